@@ -0,0 +1,144 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+#[cfg(feature = "half")]
+use half::f16;
+
+/// Handling of the alpha channel of [crate::MorphologyLayout::GrayAlpha],
+/// [crate::MorphologyLayout::Rgba] and other layouts with alpha, layouts without alpha ignore it
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)]
+pub enum AlphaMode {
+    /// Alpha is processed as any other channel
+    #[default]
+    Process,
+    /// Color is processed, alpha is copied from the source
+    Preserve,
+    /// Alpha is processed, color is copied from the source
+    AlphaOnly,
+    /// Color is multiplied by alpha, all channels are processed and color is divided by
+    /// the resulting alpha, border constants of color are premultiplied values.
+    /// Available for `u8`, `u16` and `f32` where alpha of floats is in `0..=1`.
+    /// Gradient, top-hat and black-hat divide each of their terms by its own alpha, the source
+    /// term included, and subtract them as straight color, alpha is the difference of alphas.
+    Premultiplied,
+}
+
+impl AlphaMode {
+    /// Whether the mode filters the alpha channel when `is_alpha`, or a color channel otherwise
+    pub(crate) fn processes(&self, is_alpha: bool) -> bool {
+        match self {
+            AlphaMode::Process | AlphaMode::Premultiplied => true,
+            AlphaMode::Preserve => !is_alpha,
+            AlphaMode::AlphaOnly => is_alpha,
+        }
+    }
+}
+
+/// Conversion of color to premultiplied alpha and back, required by [AlphaMode::Premultiplied].
+///
+/// Types that don't override the methods don't support premultiplied processing,
+/// plans with it return [crate::MorphologyError::UnsupportedAlphaMode].
+pub trait AlphaPremultipliable<T> {
+    /// Whether the type overrides premultiplication
+    const PREMULTIPLIABLE: bool = false;
+
+    /// Multiplies `color` by `alpha` element-wise
+    fn premultiply(_color: &mut [T], _alpha: &[T]) {}
+
+    /// Divides premultiplied `color` by `alpha` element-wise, color of transparent items is zero
+    fn unpremultiply(_color: &mut [T], _alpha: &[T]) {}
+}
+
+impl AlphaPremultipliable<u8> for u8 {
+    const PREMULTIPLIABLE: bool = true;
+
+    fn premultiply(color: &mut [u8], alpha: &[u8]) {
+        for (color, &alpha) in color.iter_mut().zip(alpha) {
+            *color = ((*color as u16 * alpha as u16 + 127) / 255) as u8;
+        }
+    }
+
+    fn unpremultiply(color: &mut [u8], alpha: &[u8]) {
+        for (color, &alpha) in color.iter_mut().zip(alpha) {
+            *color = if alpha == 0 {
+                0
+            } else {
+                ((*color as u32 * 255 + alpha as u32 / 2) / alpha as u32).min(255) as u8
+            };
+        }
+    }
+}
+
+impl AlphaPremultipliable<u16> for u16 {
+    const PREMULTIPLIABLE: bool = true;
+
+    fn premultiply(color: &mut [u16], alpha: &[u16]) {
+        for (color, &alpha) in color.iter_mut().zip(alpha) {
+            *color = ((*color as u32 * alpha as u32 + 32767) / 65535) as u16;
+        }
+    }
+
+    fn unpremultiply(color: &mut [u16], alpha: &[u16]) {
+        for (color, &alpha) in color.iter_mut().zip(alpha) {
+            *color = if alpha == 0 {
+                0
+            } else {
+                ((*color as u64 * 65535 + alpha as u64 / 2) / alpha as u64).min(65535) as u16
+            };
+        }
+    }
+}
+
+impl AlphaPremultipliable<f32> for f32 {
+    const PREMULTIPLIABLE: bool = true;
+
+    fn premultiply(color: &mut [f32], alpha: &[f32]) {
+        for (color, &alpha) in color.iter_mut().zip(alpha) {
+            *color *= alpha;
+        }
+    }
+
+    fn unpremultiply(color: &mut [f32], alpha: &[f32]) {
+        for (color, &alpha) in color.iter_mut().zip(alpha) {
+            *color = if alpha == 0. { 0. } else { *color / alpha };
+        }
+    }
+}
+
+impl AlphaPremultipliable<f64> for f64 {}
+
+impl AlphaPremultipliable<i8> for i8 {}
+
+impl AlphaPremultipliable<i16> for i16 {}
+
+impl AlphaPremultipliable<i32> for i32 {}
+
+impl AlphaPremultipliable<u32> for u32 {}
+
+#[cfg(feature = "half")]
+impl AlphaPremultipliable<f16> for f16 {}
@@ -29,143 +29,276 @@
 use crate::arena_roi::copy_roi;
 use crate::border_mode::{reflect_index, reflect_index_101, BorderMode, MorphScalar};
 use crate::filter_op_declare::Arena;
+use crate::img_size::Roi;
+use crate::morph_base::MorphNativeOp;
 use crate::structuring_element::KernelShape;
+use crate::ImageSize;
 use num_traits::AsPrimitive;
 
-/// Pads an image with chosen border strategy
-pub fn make_arena<T, const COMPONENTS: usize>(
-    image: &[T],
+/// Allocates an arena for the image padded for the structuring element
+pub(crate) fn alloc_arena<T, const COMPONENTS: usize>(
     width: u32,
     height: u32,
     kernel_size: KernelShape,
-    border_mode: BorderMode,
-    border_scalar: MorphScalar,
 ) -> Arena<T>
 where
-    T: Default + Copy + 'static,
-    f64: AsPrimitive<T>,
+    T: Default + Copy,
 {
-    let (kw, kh) = (kernel_size.width, kernel_size.height);
+    let pad_left = kernel_size.pad_left();
+    let pad_right = kernel_size.pad_right();
+    let pad_top = kernel_size.pad_top();
+    let pad_bottom = kernel_size.pad_bottom();
 
-    let pad_w = kw / 2;
-    let pad_h = kh / 2;
+    let new_height = height as usize + pad_top + pad_bottom;
+    let new_width = width as usize + pad_left + pad_right;
 
-    let new_height = height as usize + 2 * pad_h;
-    let new_width = width as usize + 2 * pad_w;
+    Arena::new(
+        vec![T::default(); new_height * new_width * COMPONENTS],
+        new_width,
+        new_height,
+        pad_left,
+        pad_right,
+        pad_top,
+        pad_bottom,
+    )
+}
 
-    let mut padded_image = vec![T::default(); new_height * new_width * COMPONENTS];
+/// Placement of the region padded into an arena in the whole image.
+///
+/// Border is applied only at edges of the whole image, pixels around the region
+/// inside the image are padded as they are.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct ArenaPlacement {
+    /// Whole image size
+    pub(crate) image_size: ImageSize,
+    /// Region padded into the arena
+    pub(crate) region: Roi,
+    /// Part of the image held by the source buffer
+    pub(crate) window: Roi,
+}
 
-    let old_stride = width as usize * COMPONENTS;
-    let new_stride = new_width * COMPONENTS;
+impl ArenaPlacement {
+    pub(crate) fn whole(image_size: ImageSize) -> Self {
+        ArenaPlacement {
+            image_size,
+            region: Roi::whole(image_size),
+            window: Roi::whole(image_size),
+        }
+    }
+}
+
+/// Maps coordinate of a border pixel into an image of `n` pixels, `None` for constant border
+#[inline]
+pub(crate) fn border_index(v: i64, n: usize, border_mode: BorderMode) -> Option<usize> {
+    if n == 1 && border_mode != BorderMode::Constant {
+        return Some(0);
+    }
+    let n = n as i64;
+    match border_mode {
+        BorderMode::Clamp => Some(v.clamp(0, n - 1) as usize),
+        BorderMode::Wrap => Some(v.rem_euclid(n - 1) as usize),
+        BorderMode::Reflect => Some(reflect_index(v, n - 1)),
+        BorderMode::Reflect101 => Some(reflect_index_101(v, n - 1)),
+        BorderMode::Constant => None,
+    }
+}
+
+/// Span of image coordinates read to pad `start..end`, inner coordinates are mapped
+/// as well when `map_inner` is set since border pixels map both of their coordinates
+fn mapped_span(
+    start: i64,
+    end: i64,
+    n: usize,
+    map_inner: bool,
+    border_mode: BorderMode,
+) -> (usize, usize) {
+    let mut min = usize::MAX;
+    let mut max = 0usize;
+    for v in start..end {
+        let inner = v >= 0 && v < n as i64;
+        if inner {
+            min = min.min(v as usize);
+            max = max.max(v as usize);
+        }
+        if !inner || map_inner {
+            if let Some(mapped) = border_index(v, n, border_mode) {
+                min = min.min(mapped);
+                max = max.max(mapped);
+            }
+        }
+    }
+    (min, max + 1)
+}
+
+/// Part of the image read to pad `region` of the image with the given paddings
+pub(crate) fn arena_window(
+    image_size: ImageSize,
+    region: Roi,
+    pad_left: usize,
+    pad_right: usize,
+    pad_top: usize,
+    pad_bottom: usize,
+    border_mode: BorderMode,
+) -> Roi {
+    let left = region.x as i64 - pad_left as i64;
+    let right = (region.x + region.width + pad_right) as i64;
+    let top = region.y as i64 - pad_top as i64;
+    let bottom = (region.y + region.height + pad_bottom) as i64;
+    let columns_outside = left < 0 || right > image_size.width as i64;
+    let rows_outside = top < 0 || bottom > image_size.height as i64;
+    let (x0, x1) = mapped_span(left, right, image_size.width, rows_outside, border_mode);
+    let (y0, y1) = mapped_span(top, bottom, image_size.height, columns_outside, border_mode);
+    Roi::new(x0, y0, x1 - x0, y1 - y0)
+}
+
+/// Pads a region of an image with chosen border strategy into an arena made by [alloc_arena],
+/// `image` holds `placement.window` of the image with rows `image_stride` items apart
+pub(crate) fn fill_arena<T, const COMPONENTS: usize>(
+    image: &[T],
+    image_stride: usize,
+    arena: &mut Arena<T>,
+    placement: ArenaPlacement,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+) where
+    T: Default + Copy + 'static + MorphNativeOp<T>,
+    f64: AsPrimitive<T>,
+{
+    let pad_left = arena.pad_left;
+    let pad_top = arena.pad_top;
+    let new_stride = arena.width * COMPONENTS;
+
+    let ArenaPlacement { region, window, .. } = placement;
+    let (x0, x1, y0, y1) = arena_inner_bounds(arena, placement);
 
     unsafe {
+        let src_x = region.x + x0 - pad_left - window.x;
+        let src_y = region.y + y0 - pad_top - window.y;
         copy_roi(
-            padded_image.get_unchecked_mut(pad_h * new_stride + (pad_w * COMPONENTS)..),
-            image,
+            arena
+                .arena
+                .get_unchecked_mut(y0 * new_stride + (x0 * COMPONENTS)..),
+            image.get_unchecked(src_y * image_stride + src_x * COMPONENTS..),
             new_stride,
-            old_stride,
-            height as usize,
+            image_stride,
+            (x1 - x0) * COMPONENTS,
+            y1 - y0,
+        );
+        fill_arena_border::<T, COMPONENTS>(
+            Some((image.as_ptr(), image_stride)),
+            arena,
+            placement,
+            border_mode,
+            border_scalar,
         );
     }
+}
+
+/// Pads an arena which inner part already holds the region, so the region
+/// must be its own placement window
+pub(crate) fn pad_arena<T, const COMPONENTS: usize>(
+    arena: &mut Arena<T>,
+    placement: ArenaPlacement,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+) where
+    T: Default + Copy + 'static + MorphNativeOp<T>,
+    f64: AsPrimitive<T>,
+{
+    debug_assert_eq!(placement.region, placement.window);
+    unsafe {
+        fill_arena_border::<T, COMPONENTS>(None, arena, placement, border_mode, border_scalar);
+    }
+}
+
+/// Arena columns `x0..x1` and rows `y0..y1` that are inside the image
+fn arena_inner_bounds<T>(
+    arena: &Arena<T>,
+    placement: ArenaPlacement,
+) -> (usize, usize, usize, usize) {
+    let ArenaPlacement {
+        image_size, region, ..
+    } = placement;
+    let x0 = arena.pad_left.saturating_sub(region.x);
+    let x1 = (image_size.width + arena.pad_left - region.x).min(arena.width);
+    let y0 = arena.pad_top.saturating_sub(region.y);
+    let y1 = (image_size.height + arena.pad_top - region.y).min(arena.height);
+    (x0, x1, y0, y1)
+}
+
+/// Fills arena items outside of the image.
+///
+/// # Safety
+///
+/// `image` must point to `placement.window` with rows `image_stride` items apart,
+/// `None` reads the window from the inner part of the arena itself.
+unsafe fn fill_arena_border<T, const COMPONENTS: usize>(
+    image: Option<(*const T, usize)>,
+    arena: &mut Arena<T>,
+    placement: ArenaPlacement,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+) where
+    T: Default + Copy + 'static + MorphNativeOp<T>,
+    f64: AsPrimitive<T>,
+{
+    let pad_left = arena.pad_left;
+    let pad_top = arena.pad_top;
+
+    let new_height = arena.height;
+    let new_width = arena.width;
+    let new_stride = new_width * COMPONENTS;
+
+    let ArenaPlacement {
+        image_size,
+        region,
+        window,
+    } = placement;
+    let (x0, x1, y0, y1) = arena_inner_bounds(arena, placement);
+
+    let padded_image = arena.arena.as_mut_ptr();
+    let (image, image_stride) = image.unwrap_or((
+        padded_image.add(pad_top * new_stride + pad_left * COMPONENTS),
+        new_stride,
+    ));
 
     let filling_ranges = [
-        (0..pad_h, 0..new_width),                              // Top outer
-        (pad_h..(new_height - pad_h), 0..pad_w),               // Left outer
-        ((height as usize + pad_h)..new_height, 0..new_width), // Bottom outer
-        (
-            pad_h..(new_height - pad_h),
-            (width as usize + pad_w)..new_width,
-        ), // Bottom outer,
+        (0..y0, 0..new_width),          // Top outer
+        (y0..y1, 0..x0),                // Left outer
+        (y1..new_height, 0..new_width), // Bottom outer
+        (y0..y1, x1..new_width),        // Right outer
     ];
 
-    match border_mode {
-        BorderMode::Clamp => {
-            for ranges in filling_ranges.iter() {
-                for i in ranges.0.clone() {
-                    for j in ranges.1.clone() {
-                        let y = i.saturating_sub(pad_h).min(height as usize - 1);
-                        let x = j.saturating_sub(pad_w).min(width as usize - 1);
-                        unsafe {
-                            let v_dst = i * new_stride + j * COMPONENTS;
-                            let v_src = y * old_stride + x * COMPONENTS;
-                            for i in 0..COMPONENTS {
-                                *padded_image.get_unchecked_mut(v_dst + i) =
-                                    *image.get_unchecked(v_src + i);
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        BorderMode::Wrap => {
-            for ranges in filling_ranges.iter() {
-                for i in ranges.0.clone() {
-                    for j in ranges.1.clone() {
-                        let y = (i as i64 - pad_h as i64).rem_euclid(height as i64 - 1) as usize;
-                        let x = (j as i64 - pad_w as i64).rem_euclid(width as i64 - 1) as usize;
-                        unsafe {
-                            let v_dst = i * new_stride + j * COMPONENTS;
-                            let v_src = y * old_stride + x * COMPONENTS;
-                            for i in 0..COMPONENTS {
-                                *padded_image.get_unchecked_mut(v_dst + i) =
-                                    *image.get_unchecked(v_src + i);
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        BorderMode::Reflect => {
-            for ranges in filling_ranges.iter() {
-                for i in ranges.0.clone() {
-                    for j in ranges.1.clone() {
-                        let y = reflect_index(i as i64 - pad_h as i64, height as i64 - 1);
-                        let x = reflect_index(j as i64 - pad_w as i64, width as i64 - 1);
-                        unsafe {
-                            let v_dst = i * new_stride + j * COMPONENTS;
-                            let v_src = y * old_stride + x * COMPONENTS;
-                            for i in 0..COMPONENTS {
-                                *padded_image.get_unchecked_mut(v_dst + i) =
-                                    *image.get_unchecked(v_src + i);
-                            }
+    for ranges in filling_ranges.iter() {
+        for i in ranges.0.clone() {
+            let y = border_index(
+                region.y as i64 + i as i64 - pad_top as i64,
+                image_size.height,
+                border_mode,
+            );
+            for j in ranges.1.clone() {
+                let x = border_index(
+                    region.x as i64 + j as i64 - pad_left as i64,
+                    image_size.width,
+                    border_mode,
+                );
+                let v_dst = i * new_stride + j * COMPONENTS;
+                match y.zip(x) {
+                    Some((y, x)) => {
+                        debug_assert!(y >= window.y && y < window.y + window.height);
+                        debug_assert!(x >= window.x && x < window.x + window.width);
+                        let v_src = (y - window.y) * image_stride + (x - window.x) * COMPONENTS;
+                        for i in 0..COMPONENTS {
+                            *padded_image.add(v_dst + i) = *image.add(v_src + i);
                         }
                     }
-                }
-            }
-        }
-        BorderMode::Reflect101 => {
-            for ranges in filling_ranges.iter() {
-                for i in ranges.0.clone() {
-                    for j in ranges.1.clone() {
-                        let y = reflect_index_101(i as i64 - pad_h as i64, height as i64 - 1);
-                        let x = reflect_index_101(j as i64 - pad_w as i64, width as i64 - 1);
-                        unsafe {
-                            let v_dst = i * new_stride + j * COMPONENTS;
-                            let v_src = y * old_stride + x * COMPONENTS;
-                            for i in 0..COMPONENTS {
-                                *padded_image.get_unchecked_mut(v_dst + i) =
-                                    *image.get_unchecked(v_src + i);
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        BorderMode::Constant => {
-            for ranges in filling_ranges.iter() {
-                for i in ranges.0.clone() {
-                    for j in ranges.1.clone() {
-                        unsafe {
-                            let v_dst = i * new_stride + j * COMPONENTS;
-                            for i in 0..COMPONENTS {
-                                *padded_image.get_unchecked_mut(v_dst + i) = border_scalar[i].as_();
-                            }
+                    None => {
+                        for i in 0..COMPONENTS {
+                            *padded_image.add(v_dst + i) = T::from_scalar(border_scalar[i]);
                         }
                     }
                 }
             }
         }
     }
-
-    Arena::new(padded_image, new_width, new_height, pad_w, pad_h)
 }
@@ -166,10 +166,16 @@ unsafe fn copy_row_avx(dst: &mut [u8], src: &[u8], start: usize, stride: usize)
     _cx
 }
 
-/// Copies ROI from one image to another
+/// Copies ROI of `height` rows of `row_length` items from one image to another
 #[allow(clippy::type_complexity)]
-pub fn copy_roi<T>(arena: &mut [T], roi: &[T], arena_stride: usize, stride: usize, height: usize)
-where
+pub fn copy_roi<T>(
+    arena: &mut [T],
+    roi: &[T],
+    arena_stride: usize,
+    roi_stride: usize,
+    row_length: usize,
+    height: usize,
+) where
     T: Copy,
 {
     if std::any::type_name::<T>() == "u8" {
@@ -194,17 +200,17 @@ where
                 let mut _cx = 0usize;
 
                 if let Some(row_handle) = _row_handle {
-                    _cx = row_handle(dst, src, _cx, stride);
+                    _cx = row_handle(dst, src, _cx, row_length);
                 }
 
-                while _cx < stride {
+                while _cx < row_length {
                     *dst.get_unchecked_mut(_cx) = *src.get_unchecked(_cx);
                     _cx += 1;
                 }
 
                 if y + 1 < height {
                     dst = dst.get_unchecked_mut(arena_stride..);
-                    src = src.get_unchecked(stride..);
+                    src = src.get_unchecked(roi_stride..);
                 }
             }
         }
@@ -215,14 +221,14 @@ where
             for y in 0..height {
                 let mut _cx = 0usize;
 
-                while _cx < stride {
+                while _cx < row_length {
                     *dst.get_unchecked_mut(_cx) = *src.get_unchecked(_cx);
                     _cx += 1;
                 }
 
                 if y + 1 < height {
                     dst = dst.get_unchecked_mut(arena_stride..);
-                    src = src.get_unchecked(stride..);
+                    src = src.get_unchecked(roi_stride..);
                 }
             }
         }
@@ -28,5 +28,17 @@
  */
 
 mod gradient_unsigned_8;
+mod morph_rows;
+#[cfg(feature = "half")]
+mod morph_rows_f16;
+mod packed_int;
 
-pub use gradient_unsigned_8::morph_gradient_avx;
\ No newline at end of file
+pub use gradient_unsigned_8::morph_gradient_avx;
+pub use morph_rows::{
+    morph_rows_f32_avx, morph_rows_f64_avx, morph_rows_u16_avx, morph_rows_u8_avx,
+};
+#[cfg(feature = "half")]
+pub use morph_rows_f16::morph_rows_f16_avx;
+#[cfg(feature = "half")]
+pub(crate) use morph_rows_f16::{_mm256_load_f16x8, _mm256_store_f16x8};
+pub use packed_int::{morph_rows_int_avx, AvxPackedInt};
@@ -0,0 +1,95 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::morph_base::MorphNativeOp;
+use crate::op_type::MorphOp;
+use half::f16;
+#[cfg(target_arch = "x86")]
+use std::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+#[inline]
+#[target_feature(enable = "avx,f16c")]
+pub(crate) unsafe fn _mm256_load_f16x8(ptr: *const f16) -> __m256 {
+    _mm256_cvtph_ps(_mm_loadu_si128(ptr as *const __m128i))
+}
+
+#[inline]
+#[target_feature(enable = "avx,f16c")]
+pub(crate) unsafe fn _mm256_store_f16x8(ptr: *mut f16, v: __m256) {
+    // Values are always read from f16, so conversion back is exact
+    _mm_storeu_si128(
+        ptr as *mut __m128i,
+        _mm256_cvtps_ph::<_MM_FROUND_TO_NEAREST_INT>(v),
+    );
+}
+
+/// Computes `dst = op(a, b)` for f16 rows using F16C conversions
+pub fn morph_rows_f16_avx<const OP_TYPE: u8>(a: &[f16], b: &[f16], dst: &mut [f16]) {
+    unsafe {
+        morph_rows_f16_avx_impl::<OP_TYPE>(a, b, dst);
+    }
+}
+
+#[inline]
+#[target_feature(enable = "avx,f16c")]
+unsafe fn morph_rows_f16_avx_impl<const OP_TYPE: u8>(a: &[f16], b: &[f16], dst: &mut [f16]) {
+    let op_type: MorphOp = OP_TYPE.into();
+    let decision = match op_type {
+        MorphOp::Dilate => _mm256_max_ps,
+        MorphOp::Erode => _mm256_min_ps,
+    };
+    let length = dst.len().min(a.len()).min(b.len());
+    let mut _cx = 0usize;
+    while _cx + 16 <= length {
+        let a_ptr = a.get_unchecked(_cx..).as_ptr();
+        let b_ptr = b.get_unchecked(_cx..).as_ptr();
+        let row0 = decision(_mm256_load_f16x8(a_ptr), _mm256_load_f16x8(b_ptr));
+        let row1 = decision(
+            _mm256_load_f16x8(a_ptr.add(8)),
+            _mm256_load_f16x8(b_ptr.add(8)),
+        );
+        let dst_ptr = dst.get_unchecked_mut(_cx..).as_mut_ptr();
+        _mm256_store_f16x8(dst_ptr, row0);
+        _mm256_store_f16x8(dst_ptr.add(8), row1);
+        _cx += 16;
+    }
+    while _cx + 8 <= length {
+        let row0 = decision(
+            _mm256_load_f16x8(a.get_unchecked(_cx..).as_ptr()),
+            _mm256_load_f16x8(b.get_unchecked(_cx..).as_ptr()),
+        );
+        _mm256_store_f16x8(dst.get_unchecked_mut(_cx..).as_mut_ptr(), row0);
+        _cx += 8;
+    }
+    while _cx < length {
+        *dst.get_unchecked_mut(_cx) = a.get_unchecked(_cx).op::<OP_TYPE>(*b.get_unchecked(_cx));
+        _cx += 1;
+    }
+}
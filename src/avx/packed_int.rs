@@ -0,0 +1,171 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::morph_base::MorphNativeOp;
+use crate::op_type::MorphOp;
+use crate::sse::SsePackedInt;
+#[cfg(target_arch = "x86")]
+use std::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+/// Lane-wise min/max of integer samples packed into 256-bit registers,
+/// a register holds `2 * LANES` samples
+pub trait AvxPackedInt: SsePackedInt {
+    unsafe fn max256(a: __m256i, b: __m256i) -> __m256i;
+
+    unsafe fn min256(a: __m256i, b: __m256i) -> __m256i;
+}
+
+impl AvxPackedInt for i8 {
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn max256(a: __m256i, b: __m256i) -> __m256i {
+        _mm256_max_epi8(a, b)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn min256(a: __m256i, b: __m256i) -> __m256i {
+        _mm256_min_epi8(a, b)
+    }
+}
+
+impl AvxPackedInt for i16 {
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn max256(a: __m256i, b: __m256i) -> __m256i {
+        _mm256_max_epi16(a, b)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn min256(a: __m256i, b: __m256i) -> __m256i {
+        _mm256_min_epi16(a, b)
+    }
+}
+
+impl AvxPackedInt for i32 {
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn max256(a: __m256i, b: __m256i) -> __m256i {
+        _mm256_max_epi32(a, b)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn min256(a: __m256i, b: __m256i) -> __m256i {
+        _mm256_min_epi32(a, b)
+    }
+}
+
+impl AvxPackedInt for u32 {
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn max256(a: __m256i, b: __m256i) -> __m256i {
+        _mm256_max_epu32(a, b)
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn min256(a: __m256i, b: __m256i) -> __m256i {
+        _mm256_min_epu32(a, b)
+    }
+}
+
+/// Computes `dst = op(a, b)` for rows of packed integers
+pub fn morph_rows_int_avx<V, const OP_TYPE: u8>(a: &[V], b: &[V], dst: &mut [V])
+where
+    V: AvxPackedInt + MorphNativeOp<V>,
+{
+    unsafe {
+        morph_rows_int_avx_impl::<V, OP_TYPE>(a, b, dst);
+    }
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+unsafe fn morph_rows_int_avx_impl<V, const OP_TYPE: u8>(a: &[V], b: &[V], dst: &mut [V])
+where
+    V: AvxPackedInt + MorphNativeOp<V>,
+{
+    let op_type: MorphOp = OP_TYPE.into();
+    let decision = match op_type {
+        MorphOp::Dilate => V::max256,
+        MorphOp::Erode => V::min256,
+    };
+    let decision_half = match op_type {
+        MorphOp::Dilate => V::max,
+        MorphOp::Erode => V::min,
+    };
+    let lanes = V::LANES;
+    let length = dst.len().min(a.len()).min(b.len());
+    let mut _cx = 0usize;
+    while _cx + lanes * 4 <= length {
+        let a_ptr = a.get_unchecked(_cx..).as_ptr();
+        let b_ptr = b.get_unchecked(_cx..).as_ptr();
+        let row0 = decision(
+            _mm256_loadu_si256(a_ptr as *const __m256i),
+            _mm256_loadu_si256(b_ptr as *const __m256i),
+        );
+        let row1 = decision(
+            _mm256_loadu_si256(a_ptr.add(lanes * 2) as *const __m256i),
+            _mm256_loadu_si256(b_ptr.add(lanes * 2) as *const __m256i),
+        );
+        let dst_ptr = dst.get_unchecked_mut(_cx..).as_mut_ptr();
+        _mm256_storeu_si256(dst_ptr as *mut __m256i, row0);
+        _mm256_storeu_si256(dst_ptr.add(lanes * 2) as *mut __m256i, row1);
+        _cx += lanes * 4;
+    }
+    while _cx + lanes * 2 <= length {
+        let row0 = decision(
+            _mm256_loadu_si256(a.get_unchecked(_cx..).as_ptr() as *const __m256i),
+            _mm256_loadu_si256(b.get_unchecked(_cx..).as_ptr() as *const __m256i),
+        );
+        _mm256_storeu_si256(
+            dst.get_unchecked_mut(_cx..).as_mut_ptr() as *mut __m256i,
+            row0,
+        );
+        _cx += lanes * 2;
+    }
+    while _cx + lanes <= length {
+        let row0 = decision_half(
+            _mm_loadu_si128(a.get_unchecked(_cx..).as_ptr() as *const __m128i),
+            _mm_loadu_si128(b.get_unchecked(_cx..).as_ptr() as *const __m128i),
+        );
+        _mm_storeu_si128(
+            dst.get_unchecked_mut(_cx..).as_mut_ptr() as *mut __m128i,
+            row0,
+        );
+        _cx += lanes;
+    }
+    while _cx < length {
+        *dst.get_unchecked_mut(_cx) = a.get_unchecked(_cx).op::<OP_TYPE>(*b.get_unchecked(_cx));
+        _cx += 1;
+    }
+}
@@ -0,0 +1,192 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::error::MorphologyError;
+use crate::op_impl::{check_image_size, check_packed_image, check_strided_image};
+use crate::ImageSize;
+
+pub(crate) const WORD_BITS: usize = 64;
+
+/// Binary image packed at 1 bit per pixel, 64 pixels per word.
+///
+/// Pixel `x` of row `y` is bit `x % 64` of word `y * words_per_row + x / 64`,
+/// bits past the image width are always clear.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinaryImage {
+    size: ImageSize,
+    words_per_row: usize,
+    data: Vec<u64>,
+}
+
+impl BinaryImage {
+    /// Creates an image with every pixel clear
+    pub fn new(image_size: ImageSize) -> Self {
+        let words_per_row = image_size.width.div_ceil(WORD_BITS);
+        BinaryImage {
+            size: image_size,
+            words_per_row,
+            data: vec![0u64; words_per_row * image_size.height],
+        }
+    }
+
+    /// Packs a tightly packed one byte per pixel mask, any non-zero value is set
+    pub fn from_mask(mask: &[u8], image_size: ImageSize) -> Result<Self, MorphologyError> {
+        check_packed_image(mask, image_size, 1)?;
+        Self::from_mask_with_stride(mask, image_size.width, image_size)
+    }
+
+    /// Packs a one byte per pixel mask with rows `stride` bytes apart, any non-zero value is set
+    pub fn from_mask_with_stride(
+        mask: &[u8],
+        stride: usize,
+        image_size: ImageSize,
+    ) -> Result<Self, MorphologyError> {
+        check_strided_image(mask, stride, image_size, 1)?;
+        let mut image = BinaryImage::new(image_size);
+        let words_per_row = image.words_per_row;
+        for (src_row, dst_row) in mask
+            .chunks(stride)
+            .zip(image.data.chunks_exact_mut(words_per_row))
+        {
+            for (chunk, word) in src_row[..image_size.width]
+                .chunks(WORD_BITS)
+                .zip(dst_row.iter_mut())
+            {
+                *word = chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0u64, |acc, (i, &v)| acc | (((v != 0) as u64) << i));
+            }
+        }
+        Ok(image)
+    }
+
+    /// Unpacks into a tightly packed one byte per pixel mask, set pixels become 255
+    pub fn to_mask(&self, dst: &mut [u8]) -> Result<(), MorphologyError> {
+        check_packed_image(dst, self.size, 1)?;
+        self.to_mask_with_stride(dst, self.size.width)
+    }
+
+    /// Unpacks into a one byte per pixel mask with rows `stride` bytes apart,
+    /// set pixels become 255, bytes between rows are left untouched
+    pub fn to_mask_with_stride(
+        &self,
+        dst: &mut [u8],
+        stride: usize,
+    ) -> Result<(), MorphologyError> {
+        check_strided_image(dst, stride, self.size, 1)?;
+        let width = self.size.width;
+        for (dst_row, src_row) in dst
+            .chunks_mut(stride)
+            .zip(self.data.chunks_exact(self.words_per_row))
+        {
+            for (chunk, &word) in dst_row[..width].chunks_mut(WORD_BITS).zip(src_row.iter()) {
+                for (i, dst) in chunk.iter_mut().enumerate() {
+                    *dst = if (word >> i) & 1 != 0 { 255 } else { 0 };
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn size(&self) -> ImageSize {
+        self.size
+    }
+
+    pub fn words_per_row(&self) -> usize {
+        self.words_per_row
+    }
+
+    /// Packed rows, `words_per_row` words each
+    pub fn words(&self) -> &[u64] {
+        &self.data
+    }
+
+    /// Packed words of the row `y`
+    pub fn row(&self, y: usize) -> &[u64] {
+        &self.data[y * self.words_per_row..(y + 1) * self.words_per_row]
+    }
+
+    /// # Panics
+    ///
+    /// When the pixel is outside of the image
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        assert!(x < self.size.width && y < self.size.height);
+        (self.data[y * self.words_per_row + x / WORD_BITS] >> (x % WORD_BITS)) & 1 != 0
+    }
+
+    /// # Panics
+    ///
+    /// When the pixel is outside of the image
+    pub fn set(&mut self, x: usize, y: usize, value: bool) {
+        assert!(x < self.size.width && y < self.size.height);
+        let word = &mut self.data[y * self.words_per_row + x / WORD_BITS];
+        let bit = 1u64 << (x % WORD_BITS);
+        if value {
+            *word |= bit;
+        } else {
+            *word &= !bit;
+        }
+    }
+
+    /// Count of set pixels
+    pub fn count_ones(&self) -> usize {
+        self.data
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Flips every pixel
+    pub fn invert(&mut self) {
+        for word in self.data.iter_mut() {
+            *word = !*word;
+        }
+        self.clear_tail();
+    }
+
+    pub(crate) fn data_mut(&mut self) -> &mut [u64] {
+        &mut self.data
+    }
+
+    /// Clears bits past the image width in the last word of every row
+    pub(crate) fn clear_tail(&mut self) {
+        let tail = self.size.width % WORD_BITS;
+        if tail == 0 || self.words_per_row == 0 {
+            return;
+        }
+        let mask = (1u64 << tail) - 1;
+        for row in self.data.chunks_exact_mut(self.words_per_row) {
+            row[self.words_per_row - 1] &= mask;
+        }
+    }
+
+    pub(crate) fn check_non_empty(&self) -> Result<(), MorphologyError> {
+        check_image_size(self.size)
+    }
+}
@@ -0,0 +1,640 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::arena::border_index;
+use crate::binary_image::{BinaryImage, WORD_BITS};
+use crate::border_mode::BorderMode;
+use crate::error::MorphologyError;
+use crate::op_impl::check_structuring_element;
+use crate::op_type::{MorphExOp, MorphOp};
+use crate::structuring_element::KernelShape;
+use crate::thread_policy::ThreadingPool;
+use crate::MorphologyThreadingPolicy;
+use rayon::prelude::*;
+
+/// Reads 64 bits of the row starting at `bit`, bits past the row are clear
+#[inline(always)]
+fn read_bits(row: &[u64], bit: usize) -> u64 {
+    let word = bit / WORD_BITS;
+    let shift = bit % WORD_BITS;
+    let low = row.get(word).copied().unwrap_or(0);
+    if shift == 0 {
+        return low;
+    }
+    let high = row.get(word + 1).copied().unwrap_or(0);
+    (low >> shift) | (high << (WORD_BITS - shift))
+}
+
+#[inline(always)]
+fn apply<const OP_TYPE: u8>(a: u64, b: u64) -> u64 {
+    let op_type: MorphOp = OP_TYPE.into();
+    match op_type {
+        MorphOp::Dilate => a | b,
+        MorphOp::Erode => a & b,
+    }
+}
+
+/// Runs `worker` for every row of `row_words` words, each task owns a scratch made by `init`
+fn for_each_row<S, I, F>(
+    buffer: &mut [u64],
+    row_words: usize,
+    pool: &ThreadingPool,
+    init: I,
+    worker: F,
+) where
+    I: Fn() -> S + Send + Sync,
+    F: Fn(&mut S, usize, &mut [u64]) + Send + Sync,
+{
+    if pool.is_parallel() {
+        pool.install(|| {
+            buffer
+                .par_chunks_exact_mut(row_words)
+                .enumerate()
+                .for_each_init(&init, |scratch, (y, row)| worker(scratch, y, row))
+        });
+    } else {
+        let mut scratch = init();
+        for (y, row) in buffer.chunks_exact_mut(row_words).enumerate() {
+            worker(&mut scratch, y, row);
+        }
+    }
+}
+
+/// Rows of the source padded for the structuring element, bit `b` of a padded row is
+/// the pixel `b - anchor_x`, every row has a spare word so runs may read past its end
+struct PaddedImage {
+    data: Vec<u64>,
+    row_words: usize,
+    height: usize,
+}
+
+impl PaddedImage {
+    fn row(&self, y: usize) -> &[u64] {
+        &self.data[y * self.row_words..(y + 1) * self.row_words]
+    }
+}
+
+fn set_bit(row: &mut [u64], bit: usize) {
+    row[bit / WORD_BITS] |= 1u64 << (bit % WORD_BITS);
+}
+
+fn get_bit(row: &[u64], bit: usize) -> bool {
+    (row[bit / WORD_BITS] >> (bit % WORD_BITS)) & 1 != 0
+}
+
+/// Copies `src` row shifted by `pad_left` bits
+fn copy_inner(src: &[u64], dst: &mut [u64], pad_left: usize) {
+    let offset = pad_left / WORD_BITS;
+    let shift = pad_left % WORD_BITS;
+    for (i, &word) in src.iter().enumerate() {
+        dst[offset + i] |= word << shift;
+        if shift != 0 {
+            dst[offset + i + 1] |= word >> (WORD_BITS - shift);
+        }
+    }
+}
+
+/// Pads the image the same way as one byte per pixel arenas: both coordinates of a pixel
+/// outside of the image are mapped by the border mode, even the one inside of the image
+fn pad_image(
+    src: &BinaryImage,
+    kernel: KernelShape,
+    border_mode: BorderMode,
+    border_value: bool,
+    pool: &ThreadingPool,
+) -> PaddedImage {
+    let size = src.size();
+    let pad_left = kernel.anchor_x;
+    let padded_width = size.width + kernel.width - 1;
+    let height = size.height + kernel.height - 1;
+    let row_words = padded_width.div_ceil(WORD_BITS) + 1;
+    let mut data = vec![0u64; row_words * height];
+    for_each_row(
+        &mut data,
+        row_words,
+        pool,
+        || (),
+        |_, y, row| {
+            let y = y as i64 - kernel.anchor_y as i64;
+            let border_row = border_index(y, size.height, border_mode).map(|y| src.row(y));
+            let mut pad_bit = |bit: usize| {
+                let x = border_index(bit as i64 - pad_left as i64, size.width, border_mode);
+                let value = match (border_row, x) {
+                    (Some(border_row), Some(x)) => get_bit(border_row, x),
+                    _ => border_value,
+                };
+                if value {
+                    set_bit(row, bit);
+                }
+            };
+            if y >= 0 && (y as usize) < size.height {
+                (0..pad_left)
+                    .chain(pad_left + size.width..padded_width)
+                    .for_each(&mut pad_bit);
+                copy_inner(src.row(y as usize), row, pad_left);
+            } else {
+                (0..padded_width).for_each(pad_bit);
+            }
+        },
+    );
+    PaddedImage {
+        data,
+        row_words,
+        height,
+    }
+}
+
+/// Runs of set points as `(start, length)`
+fn point_runs(se_row: &[u8]) -> Vec<(usize, usize)> {
+    let mut runs = Vec::new();
+    let mut x = 0usize;
+    while x < se_row.len() {
+        if se_row[x] == 0 {
+            x += 1;
+            continue;
+        }
+        let start = x;
+        while x < se_row.len() && se_row[x] != 0 {
+            x += 1;
+        }
+        runs.push((start, x - start));
+    }
+    runs
+}
+
+/// Largest power of two not greater than `length`, a window of `length` is covered by
+/// two overlapping windows of this size since min and max are idempotent
+fn doubling_window(length: usize) -> usize {
+    1usize << (usize::BITS - 1 - length.leading_zeros())
+}
+
+/// `dst[b] = op(src[b..b + window])` for every bit, built with log2(window) shifted passes
+fn reduce_bits<const OP_TYPE: u8>(
+    src: &[u64],
+    window: usize,
+    dst: &mut Vec<u64>,
+    scratch: &mut Vec<u64>,
+) {
+    dst.clear();
+    dst.extend_from_slice(src);
+    let mut step = 1usize;
+    while step < window {
+        scratch.clear();
+        scratch.extend(
+            (0..dst.len()).map(|k| apply::<OP_TYPE>(dst[k], read_bits(dst, k * WORD_BITS + step))),
+        );
+        std::mem::swap(dst, scratch);
+        step *= 2;
+    }
+}
+
+/// Every padded row reduced over the points of one structuring element row
+fn horizontal_pass<const OP_TYPE: u8>(
+    padded: &PaddedImage,
+    runs: &[(usize, usize)],
+    row_words: usize,
+    pool: &ThreadingPool,
+) -> Vec<u64> {
+    let mut rows = vec![0u64; row_words * padded.height];
+    for_each_row(
+        &mut rows,
+        row_words,
+        pool,
+        || (Vec::new(), Vec::new()),
+        |(reduced, scratch), y, row| {
+            let src = padded.row(y);
+            for (i, &(start, length)) in runs.iter().enumerate() {
+                let window = doubling_window(length);
+                let reduced: &[u64] = if window > 1 {
+                    reduce_bits::<OP_TYPE>(src, window, reduced, scratch);
+                    reduced
+                } else {
+                    src
+                };
+                let tail = start + length - window;
+                for (k, dst) in row.iter_mut().enumerate() {
+                    let bit = k * WORD_BITS;
+                    let value = apply::<OP_TYPE>(
+                        read_bits(reduced, bit + start),
+                        read_bits(reduced, bit + tail),
+                    );
+                    *dst = if i == 0 {
+                        value
+                    } else {
+                        apply::<OP_TYPE>(*dst, value)
+                    };
+                }
+            }
+        },
+    );
+    rows
+}
+
+/// `rows[y] = op(rows[y..y + window])`, built with log2(window) passes over rows
+fn vertical_pass<const OP_TYPE: u8>(
+    rows: &mut Vec<u64>,
+    row_words: usize,
+    window: usize,
+    pool: &ThreadingPool,
+) {
+    let height = rows.len() / row_words;
+    let mut scratch = vec![0u64; rows.len()];
+    let mut step = 1usize;
+    while step < window {
+        let src = &*rows;
+        for_each_row(
+            &mut scratch,
+            row_words,
+            pool,
+            || (),
+            |_, y, row| {
+                let current = &src[y * row_words..(y + 1) * row_words];
+                if y + step < height {
+                    let next = &src[(y + step) * row_words..(y + step + 1) * row_words];
+                    for ((dst, &a), &b) in row.iter_mut().zip(current).zip(next) {
+                        *dst = apply::<OP_TYPE>(a, b);
+                    }
+                } else {
+                    row.copy_from_slice(current);
+                }
+            },
+        );
+        std::mem::swap(rows, &mut scratch);
+        step *= 2;
+    }
+}
+
+fn binary_pass<const OP_TYPE: u8>(
+    src: &BinaryImage,
+    dst: &mut BinaryImage,
+    structuring_element: &[u8],
+    kernel: KernelShape,
+    border_mode: BorderMode,
+    border_value: bool,
+    pool: &ThreadingPool,
+) {
+    let row_words = src.words_per_row();
+    let mut patterns: Vec<&[u8]> = Vec::new();
+    // Consecutive structuring element rows with the same points as (pattern, first row, count)
+    let mut vertical_runs: Vec<(usize, usize, usize)> = Vec::new();
+    for (y, se_row) in structuring_element.chunks_exact(kernel.width).enumerate() {
+        if se_row.iter().all(|&v| v == 0) {
+            continue;
+        }
+        let same_points = |pattern: &&[u8]| {
+            pattern
+                .iter()
+                .zip(se_row.iter())
+                .all(|(&a, &b)| (a != 0) == (b != 0))
+        };
+        let pattern = match patterns.iter().position(same_points) {
+            Some(pattern) => pattern,
+            None => {
+                patterns.push(se_row);
+                patterns.len() - 1
+            }
+        };
+        match vertical_runs.last_mut() {
+            Some((last, start, count)) if *last == pattern && *start + *count == y => *count += 1,
+            _ => vertical_runs.push((pattern, y, 1)),
+        }
+    }
+
+    if patterns.is_empty() {
+        dst.data_mut().copy_from_slice(src.words());
+        return;
+    }
+
+    let op_type: MorphOp = OP_TYPE.into();
+    let identity = match op_type {
+        MorphOp::Dilate => 0u64,
+        MorphOp::Erode => u64::MAX,
+    };
+    dst.data_mut().fill(identity);
+
+    let padded = pad_image(src, kernel, border_mode, border_value, pool);
+
+    for (pattern, se_row) in patterns.iter().enumerate() {
+        let runs = point_runs(se_row);
+        let rows = horizontal_pass::<OP_TYPE>(&padded, &runs, row_words, pool);
+        for &(_, start, count) in vertical_runs.iter().filter(|run| run.0 == pattern) {
+            let window = doubling_window(count);
+            let mut reduced_rows = Vec::new();
+            let reduced = if window > 1 {
+                reduced_rows.extend_from_slice(&rows);
+                vertical_pass::<OP_TYPE>(&mut reduced_rows, row_words, window, pool);
+                &reduced_rows
+            } else {
+                &rows
+            };
+            let tail = start + count - window;
+            for_each_row(
+                dst.data_mut(),
+                row_words,
+                pool,
+                || (),
+                |_, y, row| {
+                    let first = &reduced[(y + start) * row_words..(y + start + 1) * row_words];
+                    let second = &reduced[(y + tail) * row_words..(y + tail + 1) * row_words];
+                    for ((dst, &a), &b) in row.iter_mut().zip(first).zip(second) {
+                        *dst = apply::<OP_TYPE>(*dst, apply::<OP_TYPE>(a, b));
+                    }
+                },
+            );
+        }
+    }
+
+    dst.clear_tail();
+}
+
+fn binary_op(
+    op: MorphOp,
+    src: &BinaryImage,
+    dst: &mut BinaryImage,
+    structuring_element: &[u8],
+    kernel: KernelShape,
+    border_mode: BorderMode,
+    border_value: bool,
+    pool: &ThreadingPool,
+) {
+    match op {
+        MorphOp::Dilate => binary_pass::<{ MorphOp::Dilate as u8 }>(
+            src,
+            dst,
+            structuring_element,
+            kernel,
+            border_mode,
+            border_value,
+            pool,
+        ),
+        MorphOp::Erode => binary_pass::<{ MorphOp::Erode as u8 }>(
+            src,
+            dst,
+            structuring_element,
+            kernel,
+            border_mode,
+            border_value,
+            pool,
+        ),
+    }
+}
+
+/// `dst = op(dst, other)` word by word
+fn combine(dst: &mut BinaryImage, other: &BinaryImage, op: impl Fn(u64, u64) -> u64) {
+    for (dst, &other) in dst.data_mut().iter_mut().zip(other.words()) {
+        *dst = op(*dst, other);
+    }
+    dst.clear_tail();
+}
+
+fn check_binary_images(src: &BinaryImage, dst: &BinaryImage) -> Result<(), MorphologyError> {
+    src.check_non_empty()?;
+    if src.size() != dst.size() {
+        return Err(MorphologyError::ImageDimensionsMismatch {
+            src_width: src.size().width,
+            src_height: src.size().height,
+            dst_width: dst.size().width,
+            dst_height: dst.size().height,
+        });
+    }
+    Ok(())
+}
+
+/// Performs morphology on a bit-packed binary image
+///
+/// Results are the same as for a one byte per pixel mask of 0 and 255 processed with
+/// [crate::morphology] and border constant 0 or 255, 64 pixels are processed with each
+/// AND/OR of words.
+///
+/// # Arguments
+///
+/// * `src`: Source image
+/// * `dst`: Destination image of the same size
+/// * `morph_op`: Requested [MorphExOp]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_value`: Value of pixels outside of the image in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn binary_morphology(
+    src: &BinaryImage,
+    dst: &mut BinaryImage,
+    morph_op: MorphExOp,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_value: bool,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    check_binary_images(src, dst)?;
+    check_structuring_element(structuring_element.len(), structuring_element_size)?;
+
+    let pool = threading_policy.threading_pool(src.size());
+    let run = |op: MorphOp, src: &BinaryImage, dst: &mut BinaryImage| {
+        binary_op(
+            op,
+            src,
+            dst,
+            structuring_element,
+            structuring_element_size,
+            border_mode,
+            border_value,
+            &pool,
+        )
+    };
+
+    match morph_op {
+        MorphExOp::Dilate => run(MorphOp::Dilate, src, dst),
+        MorphExOp::Erode => run(MorphOp::Erode, src, dst),
+        MorphExOp::Opening => {
+            let mut eroded = BinaryImage::new(src.size());
+            run(MorphOp::Erode, src, &mut eroded);
+            run(MorphOp::Dilate, &eroded, dst);
+        }
+        MorphExOp::Closing => {
+            let mut dilated = BinaryImage::new(src.size());
+            run(MorphOp::Dilate, src, &mut dilated);
+            run(MorphOp::Erode, &dilated, dst);
+        }
+        MorphExOp::Gradient => {
+            let mut eroded = BinaryImage::new(src.size());
+            run(MorphOp::Dilate, src, dst);
+            run(MorphOp::Erode, src, &mut eroded);
+            combine(dst, &eroded, |dilated, eroded| dilated & !eroded);
+        }
+        MorphExOp::TopHat => {
+            let mut eroded = BinaryImage::new(src.size());
+            run(MorphOp::Erode, src, &mut eroded);
+            run(MorphOp::Dilate, &eroded, dst);
+            combine(dst, src, |opened, src| src & !opened);
+        }
+        MorphExOp::BlackHat => {
+            let mut dilated = BinaryImage::new(src.size());
+            run(MorphOp::Dilate, src, &mut dilated);
+            run(MorphOp::Erode, &dilated, dst);
+            combine(dst, src, |closed, src| closed & !src);
+        }
+    }
+
+    Ok(())
+}
+
+/// Dilate a bit-packed binary image
+///
+/// # Arguments
+///
+/// * `src`: Source image
+/// * `dst`: Destination image of the same size
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_value`: Value of pixels outside of the image in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn binary_dilate(
+    src: &BinaryImage,
+    dst: &mut BinaryImage,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_value: bool,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    binary_morphology(
+        src,
+        dst,
+        MorphExOp::Dilate,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_value,
+        threading_policy,
+    )
+}
+
+/// Erode a bit-packed binary image
+///
+/// # Arguments
+///
+/// * `src`: Source image
+/// * `dst`: Destination image of the same size
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_value`: Value of pixels outside of the image in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn binary_erode(
+    src: &BinaryImage,
+    dst: &mut BinaryImage,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_value: bool,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    binary_morphology(
+        src,
+        dst,
+        MorphExOp::Erode,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_value,
+        threading_policy,
+    )
+}
+
+/// Hit-or-miss transform of a bit-packed binary image
+///
+/// A pixel is set when every point of `foreground` lies on set pixels and every point of
+/// `background` lies on clear pixels, empty elements put no constraint.
+///
+/// # Arguments
+///
+/// * `src`: Source image
+/// * `dst`: Destination image of the same size
+/// * `foreground`: 2D structuring element of points that must be set
+/// * `background`: 2D structuring element of points that must be clear
+/// * `structuring_element_size`: (W,H) size of both structuring elements
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_value`: Value of pixels outside of the image in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn binary_hit_or_miss(
+    src: &BinaryImage,
+    dst: &mut BinaryImage,
+    foreground: &[u8],
+    background: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_value: bool,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    check_binary_images(src, dst)?;
+    check_structuring_element(foreground.len(), structuring_element_size)?;
+    check_structuring_element(background.len(), structuring_element_size)?;
+
+    let pool = threading_policy.threading_pool(src.size());
+
+    if foreground.iter().any(|&v| v != 0) {
+        binary_op(
+            MorphOp::Erode,
+            src,
+            dst,
+            foreground,
+            structuring_element_size,
+            border_mode,
+            border_value,
+            &pool,
+        );
+    } else {
+        dst.data_mut().fill(u64::MAX);
+        dst.clear_tail();
+    }
+
+    if background.iter().any(|&v| v != 0) {
+        let mut complement = src.clone();
+        complement.invert();
+        let mut misses = BinaryImage::new(src.size());
+        binary_op(
+            MorphOp::Erode,
+            &complement,
+            &mut misses,
+            background,
+            structuring_element_size,
+            border_mode,
+            !border_value,
+            &pool,
+        );
+        combine(dst, &misses, |hits, misses| hits & misses);
+    }
+
+    Ok(())
+}
@@ -28,10 +28,13 @@
  */
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 use crate::avx::morph_gradient_avx;
+use crate::img_size::{image_rows, image_rows_mut};
 #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
 use crate::neon::morph_gradient_neon;
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 use crate::sse::morph_gradient_sse;
+#[cfg(feature = "half")]
+use half::f16;
 use num_traits::SaturatingSub;
 use std::ops::Sub;
 
@@ -39,6 +42,60 @@ pub trait MorphGradient<T> {
     fn morph_gradient(dilation: &[T], erosion: &[T], dst: &mut [T]);
 }
 
+/// Gradient of images of `width` x `height` items with independent strides
+pub(crate) fn morph_gradient_strided<T: MorphGradient<T>>(
+    dilation: &[T],
+    dilation_stride: usize,
+    erosion: &[T],
+    erosion_stride: usize,
+    dst: &mut [T],
+    dst_stride: usize,
+    width: usize,
+    height: usize,
+) {
+    if dilation_stride == width && erosion_stride == width && dst_stride == width {
+        let length = width * height;
+        T::morph_gradient(&dilation[..length], &erosion[..length], &mut dst[..length]);
+        return;
+    }
+    for ((dilation, erosion), dst) in image_rows(dilation, dilation_stride, width)
+        .zip(image_rows(erosion, erosion_stride, width))
+        .zip(image_rows_mut(dst, dst_stride, width))
+        .take(height)
+    {
+        T::morph_gradient(dilation, erosion, dst);
+    }
+}
+
+/// Gradient of images of `width` x `height` items where the result replaces `image`,
+/// which holds the dilation when `image_is_dilation` is set and the erosion otherwise
+pub(crate) fn morph_gradient_in_place<T: MorphGradient<T> + Copy + Default>(
+    image: &mut [T],
+    image_stride: usize,
+    other: &[T],
+    other_stride: usize,
+    width: usize,
+    height: usize,
+    image_is_dilation: bool,
+) {
+    const CHUNK: usize = 256;
+    let mut buffer = [T::default(); CHUNK];
+    for (image, other) in image_rows_mut(image, image_stride, width)
+        .zip(image_rows(other, other_stride, width))
+        .take(height)
+    {
+        for (image, other) in image.chunks_mut(CHUNK).zip(other.chunks(CHUNK)) {
+            let buffer = &mut buffer[..image.len()];
+            buffer.copy_from_slice(image);
+            if image_is_dilation {
+                T::morph_gradient(buffer, other, image);
+            } else {
+                T::morph_gradient(other, buffer, image);
+            }
+        }
+    }
+}
+
 fn make_morph_gradient_sat<T>(dilation: &[T], erosion: &[T], dst: &mut [T])
 where
     T: SaturatingSub + Default + Clone + Copy,
@@ -88,3 +145,40 @@ impl MorphGradient<f32> for f32 {
         make_morph_gradient(dilation, erosion, dst)
     }
 }
+
+impl MorphGradient<i8> for i8 {
+    fn morph_gradient(dilation: &[i8], erosion: &[i8], dst: &mut [i8]) {
+        make_morph_gradient_sat(dilation, erosion, dst)
+    }
+}
+
+impl MorphGradient<i16> for i16 {
+    fn morph_gradient(dilation: &[i16], erosion: &[i16], dst: &mut [i16]) {
+        make_morph_gradient_sat(dilation, erosion, dst)
+    }
+}
+
+impl MorphGradient<i32> for i32 {
+    fn morph_gradient(dilation: &[i32], erosion: &[i32], dst: &mut [i32]) {
+        make_morph_gradient_sat(dilation, erosion, dst)
+    }
+}
+
+impl MorphGradient<u32> for u32 {
+    fn morph_gradient(dilation: &[u32], erosion: &[u32], dst: &mut [u32]) {
+        make_morph_gradient_sat(dilation, erosion, dst)
+    }
+}
+
+impl MorphGradient<f64> for f64 {
+    fn morph_gradient(dilation: &[f64], erosion: &[f64], dst: &mut [f64]) {
+        make_morph_gradient(dilation, erosion, dst)
+    }
+}
+
+#[cfg(feature = "half")]
+impl MorphGradient<f16> for f16 {
+    fn morph_gradient(dilation: &[f16], erosion: &[f16], dst: &mut [f16]) {
+        make_morph_gradient(dilation, erosion, dst)
+    }
+}
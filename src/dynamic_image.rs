@@ -27,16 +27,21 @@
  * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
 use crate::border_mode::MorphScalar;
+use crate::error::MorphologyError;
+use crate::generic::{self, ChannelLayout, MorphologySample};
+use crate::morphology_options::MorphologyOptions;
 use crate::op_type::MorphExOp;
 use crate::{
-    morphology, morphology_gray_alpha_u16, morphology_gray_u16, morphology_rgb, morphology_rgb_f32,
+    morphology, morphology_gray_alpha, morphology_gray_alpha_f32, morphology_gray_alpha_u16,
+    morphology_gray_f32, morphology_gray_u16, morphology_rgb, morphology_rgb_f32,
     morphology_rgb_u16, morphology_rgba, morphology_rgba_f32, morphology_rgba_u16, BorderMode,
     ImageSize, KernelShape, MorphologyThreadingPolicy,
 };
 use image::{
-    DynamicImage, GrayAlphaImage, GrayImage, ImageBuffer, Luma, LumaA, Rgb, Rgb32FImage, RgbImage,
-    Rgba, Rgba32FImage, RgbaImage,
+    DynamicImage, GrayAlphaImage, GrayImage, ImageBuffer, Luma, LumaA, Pixel, Rgb, Rgb32FImage,
+    RgbImage, Rgba, Rgba32FImage, RgbaImage,
 };
+use num_traits::AsPrimitive;
 
 /// Performs morphology on image
 ///
@@ -59,7 +64,7 @@ pub fn morphology_image(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<DynamicImage, String> {
+) -> Result<DynamicImage, MorphologyError> {
     match image {
         DynamicImage::ImageLuma8(plane) => {
             match morph_gray_image(
@@ -201,10 +206,90 @@ pub fn morphology_image(
                 Err(err) => Err(err),
             }
         }
-        _ => Err("This type is not implemented.".parse().unwrap()),
+        _ => Err(MorphologyError::UnsupportedImageType),
     }
 }
 
+/// Performs morphology on image configured by [MorphologyOptions]
+///
+/// # Arguments
+///
+/// * `image`: Image from image crate
+/// * `morph_op`: Requested [MorphExOp]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `options`: Border, anchor, iterations, threading, backend and alpha mode, see [MorphologyOptions]
+///
+pub fn morphology_image_with_options(
+    image: DynamicImage,
+    morph_op: MorphExOp,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    options: &MorphologyOptions,
+) -> Result<DynamicImage, MorphologyError> {
+    let se = (structuring_element, structuring_element_size);
+    match image {
+        DynamicImage::ImageLuma8(img) => {
+            morph_buffer::<_, generic::Gray>(img, morph_op, se, options).map(DynamicImage::from)
+        }
+        DynamicImage::ImageLumaA8(img) => {
+            morph_buffer::<_, generic::GrayAlpha>(img, morph_op, se, options)
+                .map(DynamicImage::from)
+        }
+        DynamicImage::ImageRgb8(img) => {
+            morph_buffer::<_, generic::Rgb>(img, morph_op, se, options).map(DynamicImage::from)
+        }
+        DynamicImage::ImageRgba8(img) => {
+            morph_buffer::<_, generic::Rgba>(img, morph_op, se, options).map(DynamicImage::from)
+        }
+        DynamicImage::ImageLuma16(img) => {
+            morph_buffer::<_, generic::Gray>(img, morph_op, se, options).map(DynamicImage::from)
+        }
+        DynamicImage::ImageLumaA16(img) => {
+            morph_buffer::<_, generic::GrayAlpha>(img, morph_op, se, options)
+                .map(DynamicImage::from)
+        }
+        DynamicImage::ImageRgb16(img) => {
+            morph_buffer::<_, generic::Rgb>(img, morph_op, se, options).map(DynamicImage::from)
+        }
+        DynamicImage::ImageRgba16(img) => {
+            morph_buffer::<_, generic::Rgba>(img, morph_op, se, options).map(DynamicImage::from)
+        }
+        DynamicImage::ImageRgb32F(img) => {
+            morph_buffer::<_, generic::Rgb>(img, morph_op, se, options).map(DynamicImage::from)
+        }
+        DynamicImage::ImageRgba32F(img) => {
+            morph_buffer::<_, generic::Rgba>(img, morph_op, se, options).map(DynamicImage::from)
+        }
+        _ => Err(MorphologyError::UnsupportedImageType),
+    }
+}
+
+/// Morphology of an image buffer in place, `L` is the layout of its pixels
+fn morph_buffer<P, L>(
+    mut image: ImageBuffer<P, Vec<P::Subpixel>>,
+    morph_op: MorphExOp,
+    (structuring_element, structuring_element_size): (&[u8], KernelShape),
+    options: &MorphologyOptions,
+) -> Result<ImageBuffer<P, Vec<P::Subpixel>>, MorphologyError>
+where
+    P: Pixel,
+    P::Subpixel: MorphologySample,
+    L: ChannelLayout,
+    f64: AsPrimitive<P::Subpixel>,
+{
+    let size = ImageSize::new(image.width() as usize, image.height() as usize);
+    generic::morphology_in_place_with_options::<P::Subpixel, L>(
+        &mut image,
+        morph_op,
+        size,
+        structuring_element,
+        structuring_element_size,
+        options,
+    )?;
+    Ok(image)
+}
+
 /// Performs morphology on image
 ///
 /// # Arguments
@@ -226,7 +311,7 @@ pub fn morph_gray_image(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<GrayImage, String> {
+) -> Result<GrayImage, MorphologyError> {
     let bytes = image.as_raw();
     let mut dst_bytes = vec![0u8; bytes.len()];
     let size = ImageSize::new(image.dimensions().0 as usize, image.dimensions().1 as usize);
@@ -244,7 +329,7 @@ pub fn morph_gray_image(
     if let Some(img) = GrayImage::from_raw(size.width as u32, size.height as u32, dst_bytes) {
         Ok(img)
     } else {
-        Err("Can't create a Gray Image".parse().unwrap())
+        Err(MorphologyError::ImageBufferCreation)
     }
 }
 
@@ -269,7 +354,7 @@ pub fn morph_rgb_image(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<RgbImage, String> {
+) -> Result<RgbImage, MorphologyError> {
     let bytes = image.as_raw();
     let mut dst_bytes = vec![0u8; bytes.len()];
     let size = ImageSize::new(image.dimensions().0 as usize, image.dimensions().1 as usize);
@@ -287,7 +372,7 @@ pub fn morph_rgb_image(
     if let Some(img) = RgbImage::from_raw(size.width as u32, size.height as u32, dst_bytes) {
         Ok(img)
     } else {
-        Err("Can't create a Rgb Image".parse().unwrap())
+        Err(MorphologyError::ImageBufferCreation)
     }
 }
 
@@ -312,11 +397,11 @@ pub fn morph_gray_alpha_image(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<GrayAlphaImage, String> {
+) -> Result<GrayAlphaImage, MorphologyError> {
     let bytes = image.as_raw();
     let mut dst_bytes = vec![0u8; bytes.len()];
     let size = ImageSize::new(image.dimensions().0 as usize, image.dimensions().1 as usize);
-    morphology_rgb(
+    morphology_gray_alpha(
         bytes,
         &mut dst_bytes,
         morph_op,
@@ -330,7 +415,7 @@ pub fn morph_gray_alpha_image(
     if let Some(img) = GrayAlphaImage::from_raw(size.width as u32, size.height as u32, dst_bytes) {
         Ok(img)
     } else {
-        Err("Can't create a Rgb Image".parse().unwrap())
+        Err(MorphologyError::ImageBufferCreation)
     }
 }
 
@@ -355,7 +440,7 @@ pub fn morph_rgba_image(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<RgbaImage, String> {
+) -> Result<RgbaImage, MorphologyError> {
     let bytes = image.as_raw();
     let mut dst_bytes = vec![0u8; bytes.len()];
     let size = ImageSize::new(image.dimensions().0 as usize, image.dimensions().1 as usize);
@@ -373,7 +458,7 @@ pub fn morph_rgba_image(
     if let Some(img) = RgbaImage::from_raw(size.width as u32, size.height as u32, dst_bytes) {
         Ok(img)
     } else {
-        Err("Can't create a Rgb Image".parse().unwrap())
+        Err(MorphologyError::ImageBufferCreation)
     }
 }
 
@@ -398,7 +483,7 @@ pub fn morph_gray_alpha_16_image(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<ImageBuffer<LumaA<u16>, Vec<u16>>, String> {
+) -> Result<ImageBuffer<LumaA<u16>, Vec<u16>>, MorphologyError> {
     let bytes = image.as_raw();
     let mut dst_bytes = vec![0u16; bytes.len()];
     let size = ImageSize::new(image.dimensions().0 as usize, image.dimensions().1 as usize);
@@ -420,7 +505,7 @@ pub fn morph_gray_alpha_16_image(
     ) {
         Ok(img)
     } else {
-        Err("Can't create a Rgb Image".parse().unwrap())
+        Err(MorphologyError::ImageBufferCreation)
     }
 }
 
@@ -445,7 +530,7 @@ pub fn morph_gray_16_image(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<ImageBuffer<Luma<u16>, Vec<u16>>, String> {
+) -> Result<ImageBuffer<Luma<u16>, Vec<u16>>, MorphologyError> {
     let bytes = image.as_raw();
     let mut dst_bytes = vec![0u16; bytes.len()];
     let size = ImageSize::new(image.dimensions().0 as usize, image.dimensions().1 as usize);
@@ -467,7 +552,7 @@ pub fn morph_gray_16_image(
     ) {
         Ok(img)
     } else {
-        Err("Can't create a Rgb Image".parse().unwrap())
+        Err(MorphologyError::ImageBufferCreation)
     }
 }
 
@@ -492,7 +577,7 @@ pub fn morph_rgb_16_image(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<ImageBuffer<Rgb<u16>, Vec<u16>>, String> {
+) -> Result<ImageBuffer<Rgb<u16>, Vec<u16>>, MorphologyError> {
     let bytes = image.as_raw();
     let mut dst_bytes = vec![0u16; bytes.len()];
     let size = ImageSize::new(image.dimensions().0 as usize, image.dimensions().1 as usize);
@@ -514,7 +599,7 @@ pub fn morph_rgb_16_image(
     ) {
         Ok(img)
     } else {
-        Err("Can't create a Rgb Image".parse().unwrap())
+        Err(MorphologyError::ImageBufferCreation)
     }
 }
 
@@ -539,7 +624,7 @@ pub fn morph_rgba_16_image(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<ImageBuffer<Rgba<u16>, Vec<u16>>, String> {
+) -> Result<ImageBuffer<Rgba<u16>, Vec<u16>>, MorphologyError> {
     let bytes = image.as_raw();
     let mut dst_bytes = vec![0u16; bytes.len()];
     let size = ImageSize::new(image.dimensions().0 as usize, image.dimensions().1 as usize);
@@ -561,7 +646,7 @@ pub fn morph_rgba_16_image(
     ) {
         Ok(img)
     } else {
-        Err("Can't create a Rgb Image".parse().unwrap())
+        Err(MorphologyError::ImageBufferCreation)
     }
 }
 
@@ -586,7 +671,7 @@ pub fn morph_rgba_f32_image(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<Rgba32FImage, String> {
+) -> Result<Rgba32FImage, MorphologyError> {
     let bytes = image.as_raw();
     let mut dst_bytes = vec![0f32; bytes.len()];
     let size = ImageSize::new(image.dimensions().0 as usize, image.dimensions().1 as usize);
@@ -604,7 +689,7 @@ pub fn morph_rgba_f32_image(
     if let Some(img) = Rgba32FImage::from_raw(size.width as u32, size.height as u32, dst_bytes) {
         Ok(img)
     } else {
-        Err("Can't create a Rgb Image".parse().unwrap())
+        Err(MorphologyError::ImageBufferCreation)
     }
 }
 
@@ -629,7 +714,7 @@ pub fn morph_rgb_f32_image(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<Rgb32FImage, String> {
+) -> Result<Rgb32FImage, MorphologyError> {
     let bytes = image.as_raw();
     let mut dst_bytes = vec![0f32; bytes.len()];
     let size = ImageSize::new(image.dimensions().0 as usize, image.dimensions().1 as usize);
@@ -647,6 +732,98 @@ pub fn morph_rgb_f32_image(
     if let Some(img) = Rgb32FImage::from_raw(size.width as u32, size.height as u32, dst_bytes) {
         Ok(img)
     } else {
-        Err("Can't create a Rgb Image".parse().unwrap())
+        Err(MorphologyError::ImageBufferCreation)
+    }
+}
+
+/// Performs morphology on image
+///
+/// # Arguments
+///
+/// * `image`: Image from image crate
+/// * `morph_op`: Requested [MorphExOp]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morph_gray_f32_image(
+    image: ImageBuffer<Luma<f32>, Vec<f32>>,
+    morph_op: MorphExOp,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<ImageBuffer<Luma<f32>, Vec<f32>>, MorphologyError> {
+    let bytes = image.as_raw();
+    let mut dst_bytes = vec![0f32; bytes.len()];
+    let size = ImageSize::new(image.dimensions().0 as usize, image.dimensions().1 as usize);
+    morphology_gray_f32(
+        bytes,
+        &mut dst_bytes,
+        morph_op,
+        size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )?;
+    if let Some(img) = ImageBuffer::<Luma<f32>, Vec<f32>>::from_raw(
+        size.width as u32,
+        size.height as u32,
+        dst_bytes,
+    ) {
+        Ok(img)
+    } else {
+        Err(MorphologyError::ImageBufferCreation)
+    }
+}
+
+/// Performs morphology on image
+///
+/// # Arguments
+///
+/// * `image`: Image from image crate
+/// * `morph_op`: Requested [MorphExOp]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morph_gray_alpha_f32_image(
+    image: ImageBuffer<LumaA<f32>, Vec<f32>>,
+    morph_op: MorphExOp,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<ImageBuffer<LumaA<f32>, Vec<f32>>, MorphologyError> {
+    let bytes = image.as_raw();
+    let mut dst_bytes = vec![0f32; bytes.len()];
+    let size = ImageSize::new(image.dimensions().0 as usize, image.dimensions().1 as usize);
+    morphology_gray_alpha_f32(
+        bytes,
+        &mut dst_bytes,
+        morph_op,
+        size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )?;
+    if let Some(img) = ImageBuffer::<LumaA<f32>, Vec<f32>>::from_raw(
+        size.width as u32,
+        size.height as u32,
+        dst_bytes,
+    ) {
+        Ok(img)
+    } else {
+        Err(MorphologyError::ImageBufferCreation)
     }
 }
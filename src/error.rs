@@ -0,0 +1,161 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// Errors returned by morphology functions,
+/// new variants may be added without a breaking release
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum MorphologyError {
+    /// Source and destination slices have different lengths
+    SourceDestinationMismatch { src: usize, dst: usize },
+    /// Image slice length does not match declared image size and channels count
+    ImageSizeMismatch { expected: usize, actual: usize },
+    /// Row stride is less than the row length in items
+    StrideTooSmall { stride: usize, min_stride: usize },
+    /// Image has zero width or height
+    ZeroSizedImage { width: usize, height: usize },
+    /// Structuring element slice length does not match declared structuring element size
+    StructuringElementSizeMismatch { expected: usize, actual: usize },
+    /// Anchor lies outside of the structuring element
+    AnchorOutOfBounds {
+        anchor_x: usize,
+        anchor_y: usize,
+        width: usize,
+        height: usize,
+    },
+    /// Region of interest does not fit into the image
+    RoiOutOfBounds {
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+        image_width: usize,
+        image_height: usize,
+    },
+    /// Source and destination images have different dimensions
+    ImageDimensionsMismatch {
+        src_width: usize,
+        src_height: usize,
+        dst_width: usize,
+        dst_height: usize,
+    },
+    /// Operation is requested to be applied zero times
+    ZeroIterations,
+    /// Interleaved layout has no channels
+    ZeroChannels,
+    /// Per-channel border values count does not match the channels count of the layout
+    BorderValuesMismatch { expected: usize, actual: usize },
+    /// Sample type does not support requested alpha mode
+    UnsupportedAlphaMode,
+    /// Image type is not supported
+    UnsupportedImageType,
+    /// Result image cannot be created from the processed buffer
+    ImageBufferCreation,
+}
+
+impl Display for MorphologyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MorphologyError::SourceDestinationMismatch { src, dst } => write!(
+                f,
+                "Source slice size and destination must match, src {}, dst {}",
+                src, dst
+            ),
+            MorphologyError::ImageSizeMismatch { expected, actual } => write!(
+                f,
+                "Image size expected to be {} but it was {}",
+                expected, actual
+            ),
+            MorphologyError::StrideTooSmall { stride, min_stride } => write!(
+                f,
+                "Stride expected to be at least {} but it was {}",
+                min_stride, stride
+            ),
+            MorphologyError::ZeroSizedImage { width, height } => {
+                write!(f, "Image must not be empty but it was {}x{}", width, height)
+            }
+            MorphologyError::StructuringElementSizeMismatch { expected, actual } => write!(
+                f,
+                "Structuring element expected to be {} but it was {}",
+                expected, actual
+            ),
+            MorphologyError::AnchorOutOfBounds {
+                anchor_x,
+                anchor_y,
+                width,
+                height,
+            } => write!(
+                f,
+                "Anchor ({}, {}) must be inside structuring element {}x{}",
+                anchor_x, anchor_y, width, height
+            ),
+            MorphologyError::RoiOutOfBounds {
+                x,
+                y,
+                width,
+                height,
+                image_width,
+                image_height,
+            } => write!(
+                f,
+                "Region {}x{} at ({}, {}) must be inside image {}x{}",
+                width, height, x, y, image_width, image_height
+            ),
+            MorphologyError::ImageDimensionsMismatch {
+                src_width,
+                src_height,
+                dst_width,
+                dst_height,
+            } => write!(
+                f,
+                "Source image is {}x{} but destination is {}x{}",
+                src_width, src_height, dst_width, dst_height
+            ),
+            MorphologyError::ZeroIterations => write!(f, "Iterations count must be at least 1"),
+            MorphologyError::ZeroChannels => write!(f, "Image must have at least one channel"),
+            MorphologyError::BorderValuesMismatch { expected, actual } => write!(
+                f,
+                "Border values count {} does not match channels count {}",
+                actual, expected
+            ),
+            MorphologyError::UnsupportedAlphaMode => {
+                write!(f, "Alpha mode is not supported by this sample type")
+            }
+            MorphologyError::UnsupportedImageType => write!(f, "This type is not implemented."),
+            MorphologyError::ImageBufferCreation => {
+                write!(f, "Can't create an image from the processed buffer")
+            }
+        }
+    }
+}
+
+impl Error for MorphologyError {}
@@ -26,53 +26,294 @@
  * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
  * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
-use crate::filter_op_declare::{Arena, MorthOpFilterFlat2DRow};
-use crate::flat_se::AnalyzedSe;
+use crate::filter_op_declare::{Arena, MorthOpFilterFlat2DRow, MorthOpFilterWeighted2DRow};
+use crate::morph_base::{MorphNativeOp, MorphWeightedOp};
 use crate::op_type::MorphOp;
+#[cfg(all(feature = "half", any(target_arch = "x86", target_arch = "x86_64")))]
+use crate::ops::avx::MorphOpFilterAvx2DRowF16;
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-use crate::ops::avx::{MorphOpFilterAvx2DRow, MorphOpFilterAvx2DRowF32, MorphOpFilterAvx2DRowU16};
+use crate::ops::avx::{
+    MorphOpFilterAvx2DRow, MorphOpFilterAvx2DRowF32, MorphOpFilterAvx2DRowF64,
+    MorphOpFilterAvx2DRowInt, MorphOpFilterAvx2DRowU16, MorphOpFilterAvxWeighted2DRow,
+    MorphOpFilterAvxWeighted2DRowF32, MorphOpFilterAvxWeighted2DRowU16,
+};
+#[cfg(all(feature = "half", target_arch = "aarch64", target_feature = "neon"))]
+use crate::ops::neon::MorphOpFilterNeon2DRowF16;
 #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
 use crate::ops::neon::{
-    MorphOpFilterNeon2DRow, MorphOpFilterNeon2DRowF32, MorphOpFilterNeon2DRowU16,
+    MorphOpFilterNeon2DRow, MorphOpFilterNeon2DRowF32, MorphOpFilterNeon2DRowF64,
+    MorphOpFilterNeon2DRowInt, MorphOpFilterNeon2DRowU16, MorphOpFilterNeonWeighted2DRow,
+    MorphOpFilterNeonWeighted2DRowF32, MorphOpFilterNeonWeighted2DRowU16,
 };
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-use crate::ops::sse::{MorphOpFilterSse2DRow, MorphOpFilterSse2DRowF32, MorphOpFilterSse2DRowU16};
-use crate::ops::MorphFilterFlat2DRow;
+use crate::ops::sse::{
+    MorphOpFilterSse2DRow, MorphOpFilterSse2DRowF32, MorphOpFilterSse2DRowF64,
+    MorphOpFilterSse2DRowInt, MorphOpFilterSse2DRowU16, MorphOpFilterSseWeighted2DRow,
+    MorphOpFilterSseWeighted2DRowF32, MorphOpFilterSseWeighted2DRowU16,
+};
+use crate::ops::{MorphOpFilterScalar2DRow, MorphOpFilterScalarWeighted2DRow};
 use crate::unsafe_slice::UnsafeSlice;
 use crate::ImageSize;
+#[cfg(feature = "half")]
+use half::f16;
 
-pub struct MorthFilterFlat2DRow<T>
+pub struct MorphFilterFlat2DRow<T>
 where
     T: 'static,
 {
     pub(crate) handler: Box<dyn MorthOpFilterFlat2DRow<T> + Sync + Send>,
 }
 
-impl<T> MorthOpFilterFlat2DRow<T> for MorthFilterFlat2DRow<T> {
+impl<T> MorthOpFilterFlat2DRow<T> for MorphFilterFlat2DRow<T> {
     unsafe fn dispatch_row(
         &self,
         arena: &Arena<T>,
         dst: &UnsafeSlice<T>,
+        dst_stride: usize,
         image_size: ImageSize,
-        analyzed_se: AnalyzedSe,
+        element_offsets: &[usize],
         y: usize,
     ) {
         self.handler
-            .dispatch_row(arena, dst, image_size, analyzed_se, y)
+            .dispatch_row(arena, dst, dst_stride, image_size, element_offsets, y)
+    }
+}
+
+pub struct MorphFilterWeighted2DRow<T>
+where
+    T: 'static + MorphWeightedOp<T>,
+{
+    pub(crate) handler: Box<dyn MorthOpFilterWeighted2DRow<T> + Sync + Send>,
+}
+
+impl<T: MorphWeightedOp<T>> MorthOpFilterWeighted2DRow<T> for MorphFilterWeighted2DRow<T> {
+    unsafe fn dispatch_row(
+        &self,
+        arena: &Arena<T>,
+        dst: &UnsafeSlice<T>,
+        dst_stride: usize,
+        image_size: ImageSize,
+        element_offsets: &[usize],
+        weights: &[T::Weight],
+        y: usize,
+    ) {
+        self.handler.dispatch_row(
+            arena,
+            dst,
+            dst_stride,
+            image_size,
+            element_offsets,
+            weights,
+            y,
+        )
+    }
+}
+
+/// Dispatches row filters of the sample type, scalar filters are used unless the type
+/// provides vectorised ones
+pub trait Row2DFilter<T: MorphWeightedOp<T>> {
+    fn get_filter(op: MorphOp) -> MorphFilterFlat2DRow<T>
+    where
+        T: 'static + Copy + MorphNativeOp<T>,
+    {
+        scalar_filter(op)
+    }
+
+    /// Filter for non-flat structuring elements
+    fn get_weighted_filter(op: MorphOp) -> MorphFilterWeighted2DRow<T>
+    where
+        T: 'static + Copy + MorphNativeOp<T>,
+    {
+        scalar_weighted_filter(op)
+    }
+}
+
+/// Scalar filter for flat structuring elements, available for every sample type
+pub(crate) fn scalar_filter<T>(op: MorphOp) -> MorphFilterFlat2DRow<T>
+where
+    T: 'static + Copy + MorphNativeOp<T> + MorphWeightedOp<T>,
+{
+    MorphFilterFlat2DRow {
+        handler: match op {
+            MorphOp::Dilate => {
+                Box::new(MorphOpFilterScalar2DRow::<{ MorphOp::Dilate as u8 }>::default())
+            }
+            MorphOp::Erode => {
+                Box::new(MorphOpFilterScalar2DRow::<{ MorphOp::Erode as u8 }>::default())
+            }
+        },
+    }
+}
+
+/// Scalar filter for non-flat structuring elements, available for every sample type
+pub(crate) fn scalar_weighted_filter<T>(op: MorphOp) -> MorphFilterWeighted2DRow<T>
+where
+    T: 'static + Copy + MorphNativeOp<T> + MorphWeightedOp<T>,
+{
+    MorphFilterWeighted2DRow {
+        handler: match op {
+            MorphOp::Dilate => {
+                Box::new(MorphOpFilterScalarWeighted2DRow::<{ MorphOp::Dilate as u8 }>::default())
+            }
+            MorphOp::Erode => {
+                Box::new(MorphOpFilterScalarWeighted2DRow::<{ MorphOp::Erode as u8 }>::default())
+            }
+        },
+    }
+}
+
+fn weighted_filter_u8<const OP_TYPE: u8>() -> Box<dyn MorthOpFilterWeighted2DRow<u8> + Sync + Send>
+{
+    let mut _result: Box<dyn MorthOpFilterWeighted2DRow<u8> + Sync + Send> =
+        Box::new(MorphOpFilterScalarWeighted2DRow::<OP_TYPE>::default());
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        _result = Box::new(MorphOpFilterNeonWeighted2DRow::<OP_TYPE>::default());
+    }
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if std::arch::is_x86_feature_detected!("sse4.1") {
+            _result = Box::new(MorphOpFilterSseWeighted2DRow::<OP_TYPE>::default());
+        }
+        if std::arch::is_x86_feature_detected!("avx2") {
+            _result = Box::new(MorphOpFilterAvxWeighted2DRow::<OP_TYPE>::default());
+        }
+    }
+    _result
+}
+
+fn weighted_filter_u16<const OP_TYPE: u8>() -> Box<dyn MorthOpFilterWeighted2DRow<u16> + Sync + Send>
+{
+    let mut _result: Box<dyn MorthOpFilterWeighted2DRow<u16> + Sync + Send> =
+        Box::new(MorphOpFilterScalarWeighted2DRow::<OP_TYPE>::default());
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        _result = Box::new(MorphOpFilterNeonWeighted2DRowU16::<OP_TYPE>::default());
     }
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if std::arch::is_x86_feature_detected!("sse4.1") {
+            _result = Box::new(MorphOpFilterSseWeighted2DRowU16::<OP_TYPE>::default());
+        }
+        if std::arch::is_x86_feature_detected!("avx2") {
+            _result = Box::new(MorphOpFilterAvxWeighted2DRowU16::<OP_TYPE>::default());
+        }
+    }
+    _result
+}
+
+fn weighted_filter_f32<const OP_TYPE: u8>() -> Box<dyn MorthOpFilterWeighted2DRow<f32> + Sync + Send>
+{
+    let mut _result: Box<dyn MorthOpFilterWeighted2DRow<f32> + Sync + Send> =
+        Box::new(MorphOpFilterScalarWeighted2DRow::<OP_TYPE>::default());
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        _result = Box::new(MorphOpFilterNeonWeighted2DRowF32::<OP_TYPE>::default());
+    }
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if std::arch::is_x86_feature_detected!("sse4.1") {
+            _result = Box::new(MorphOpFilterSseWeighted2DRowF32::<OP_TYPE>::default());
+        }
+        if std::arch::is_x86_feature_detected!("avx2") {
+            _result = Box::new(MorphOpFilterAvxWeighted2DRowF32::<OP_TYPE>::default());
+        }
+    }
+    _result
 }
 
-pub trait Row2DFilter<T> {
-    fn get_filter(op: MorphOp) -> MorthFilterFlat2DRow<T>;
+fn flat_filter_i8<const OP_TYPE: u8>() -> Box<dyn MorthOpFilterFlat2DRow<i8> + Sync + Send> {
+    let mut _result: Box<dyn MorthOpFilterFlat2DRow<i8> + Sync + Send> =
+        Box::new(MorphOpFilterScalar2DRow::<OP_TYPE>::default());
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        _result = Box::new(MorphOpFilterNeon2DRowInt::<i8, OP_TYPE>::default());
+    }
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if std::arch::is_x86_feature_detected!("sse4.1") {
+            _result = Box::new(MorphOpFilterSse2DRowInt::<i8, OP_TYPE>::default());
+        }
+        if std::arch::is_x86_feature_detected!("avx2") {
+            _result = Box::new(MorphOpFilterAvx2DRowInt::<i8, OP_TYPE>::default());
+        }
+    }
+    _result
+}
+
+fn flat_filter_i16<const OP_TYPE: u8>() -> Box<dyn MorthOpFilterFlat2DRow<i16> + Sync + Send> {
+    let mut _result: Box<dyn MorthOpFilterFlat2DRow<i16> + Sync + Send> =
+        Box::new(MorphOpFilterScalar2DRow::<OP_TYPE>::default());
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        _result = Box::new(MorphOpFilterNeon2DRowInt::<i16, OP_TYPE>::default());
+    }
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if std::arch::is_x86_feature_detected!("sse4.1") {
+            _result = Box::new(MorphOpFilterSse2DRowInt::<i16, OP_TYPE>::default());
+        }
+        if std::arch::is_x86_feature_detected!("avx2") {
+            _result = Box::new(MorphOpFilterAvx2DRowInt::<i16, OP_TYPE>::default());
+        }
+    }
+    _result
+}
+
+fn flat_filter_i32<const OP_TYPE: u8>() -> Box<dyn MorthOpFilterFlat2DRow<i32> + Sync + Send> {
+    let mut _result: Box<dyn MorthOpFilterFlat2DRow<i32> + Sync + Send> =
+        Box::new(MorphOpFilterScalar2DRow::<OP_TYPE>::default());
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        _result = Box::new(MorphOpFilterNeon2DRowInt::<i32, OP_TYPE>::default());
+    }
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if std::arch::is_x86_feature_detected!("sse4.1") {
+            _result = Box::new(MorphOpFilterSse2DRowInt::<i32, OP_TYPE>::default());
+        }
+        if std::arch::is_x86_feature_detected!("avx2") {
+            _result = Box::new(MorphOpFilterAvx2DRowInt::<i32, OP_TYPE>::default());
+        }
+    }
+    _result
+}
+
+fn flat_filter_u32<const OP_TYPE: u8>() -> Box<dyn MorthOpFilterFlat2DRow<u32> + Sync + Send> {
+    let mut _result: Box<dyn MorthOpFilterFlat2DRow<u32> + Sync + Send> =
+        Box::new(MorphOpFilterScalar2DRow::<OP_TYPE>::default());
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        _result = Box::new(MorphOpFilterNeon2DRowInt::<u32, OP_TYPE>::default());
+    }
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if std::arch::is_x86_feature_detected!("sse4.1") {
+            _result = Box::new(MorphOpFilterSse2DRowInt::<u32, OP_TYPE>::default());
+        }
+        if std::arch::is_x86_feature_detected!("avx2") {
+            _result = Box::new(MorphOpFilterAvx2DRowInt::<u32, OP_TYPE>::default());
+        }
+    }
+    _result
 }
 
 impl Row2DFilter<u8> for u8 {
-    fn get_filter(op: MorphOp) -> MorthFilterFlat2DRow<u8> {
-        MorthFilterFlat2DRow {
+    fn get_weighted_filter(op: MorphOp) -> MorphFilterWeighted2DRow<u8> {
+        MorphFilterWeighted2DRow {
+            handler: match op {
+                MorphOp::Dilate => weighted_filter_u8::<{ MorphOp::Dilate as u8 }>(),
+                MorphOp::Erode => weighted_filter_u8::<{ MorphOp::Erode as u8 }>(),
+            },
+        }
+    }
+
+    fn get_filter(op: MorphOp) -> MorphFilterFlat2DRow<u8> {
+        MorphFilterFlat2DRow {
             handler: match op {
                 MorphOp::Dilate => {
                     let mut _result: Box<dyn MorthOpFilterFlat2DRow<u8> + Sync + Send> =
-                        Box::new(MorphFilterFlat2DRow::<{ MorphOp::Dilate as u8 }>::default());
+                        Box::new(MorphOpFilterScalar2DRow::<{ MorphOp::Dilate as u8 }>::default());
                     #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
                     {
                         _result = Box::new(
@@ -96,7 +337,7 @@ impl Row2DFilter<u8> for u8 {
                 }
                 MorphOp::Erode => {
                     let mut _result: Box<dyn MorthOpFilterFlat2DRow<u8> + Sync + Send> =
-                        Box::new(MorphFilterFlat2DRow::<{ MorphOp::Erode as u8 }>::default());
+                        Box::new(MorphOpFilterScalar2DRow::<{ MorphOp::Erode as u8 }>::default());
                     #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
                     {
                         _result =
@@ -123,12 +364,21 @@ impl Row2DFilter<u8> for u8 {
 }
 
 impl Row2DFilter<f32> for f32 {
-    fn get_filter(op: MorphOp) -> MorthFilterFlat2DRow<f32> {
-        MorthFilterFlat2DRow {
+    fn get_weighted_filter(op: MorphOp) -> MorphFilterWeighted2DRow<f32> {
+        MorphFilterWeighted2DRow {
+            handler: match op {
+                MorphOp::Dilate => weighted_filter_f32::<{ MorphOp::Dilate as u8 }>(),
+                MorphOp::Erode => weighted_filter_f32::<{ MorphOp::Erode as u8 }>(),
+            },
+        }
+    }
+
+    fn get_filter(op: MorphOp) -> MorphFilterFlat2DRow<f32> {
+        MorphFilterFlat2DRow {
             handler: match op {
                 MorphOp::Dilate => {
                     let mut _result: Box<dyn MorthOpFilterFlat2DRow<f32> + Sync + Send> =
-                        Box::new(MorphFilterFlat2DRow::<{ MorphOp::Dilate as u8 }>::default());
+                        Box::new(MorphOpFilterScalar2DRow::<{ MorphOp::Dilate as u8 }>::default());
                     #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
                     {
                         if std::arch::is_x86_feature_detected!("sse4.1") {
@@ -152,7 +402,7 @@ impl Row2DFilter<f32> for f32 {
                 }
                 MorphOp::Erode => {
                     let mut _result: Box<dyn MorthOpFilterFlat2DRow<f32> + Sync + Send> =
-                        Box::new(MorphFilterFlat2DRow::<{ MorphOp::Erode as u8 }>::default());
+                        Box::new(MorphOpFilterScalar2DRow::<{ MorphOp::Erode as u8 }>::default());
                     #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
                     {
                         if std::arch::is_x86_feature_detected!("sse4.1") {
@@ -179,13 +429,161 @@ impl Row2DFilter<f32> for f32 {
     }
 }
 
+#[cfg(feature = "half")]
+impl Row2DFilter<f16> for f16 {
+    fn get_weighted_filter(op: MorphOp) -> MorphFilterWeighted2DRow<f16> {
+        MorphFilterWeighted2DRow {
+            handler: match op {
+                MorphOp::Dilate => Box::new(MorphOpFilterScalarWeighted2DRow::<
+                    { MorphOp::Dilate as u8 },
+                >::default()),
+                MorphOp::Erode => Box::new(MorphOpFilterScalarWeighted2DRow::<
+                    { MorphOp::Erode as u8 },
+                >::default()),
+            },
+        }
+    }
+
+    fn get_filter(op: MorphOp) -> MorphFilterFlat2DRow<f16> {
+        MorphFilterFlat2DRow {
+            handler: match op {
+                MorphOp::Dilate => {
+                    let mut _result: Box<dyn MorthOpFilterFlat2DRow<f16> + Sync + Send> =
+                        Box::new(MorphOpFilterScalar2DRow::<{ MorphOp::Dilate as u8 }>::default());
+                    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+                    {
+                        if std::arch::is_x86_feature_detected!("avx")
+                            && std::arch::is_x86_feature_detected!("f16c")
+                        {
+                            _result = Box::new(
+                                MorphOpFilterAvx2DRowF16::<{ MorphOp::Dilate as u8 }>::default(),
+                            );
+                        }
+                    }
+                    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+                    {
+                        if std::arch::is_aarch64_feature_detected!("fp16") {
+                            _result = Box::new(MorphOpFilterNeon2DRowF16::<
+                                { MorphOp::Dilate as u8 },
+                            >::default());
+                        }
+                    }
+                    _result
+                }
+                MorphOp::Erode => {
+                    let mut _result: Box<dyn MorthOpFilterFlat2DRow<f16> + Sync + Send> =
+                        Box::new(MorphOpFilterScalar2DRow::<{ MorphOp::Erode as u8 }>::default());
+                    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+                    {
+                        if std::arch::is_x86_feature_detected!("avx")
+                            && std::arch::is_x86_feature_detected!("f16c")
+                        {
+                            _result = Box::new(
+                                MorphOpFilterAvx2DRowF16::<{ MorphOp::Erode as u8 }>::default(),
+                            );
+                        }
+                    }
+                    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+                    {
+                        if std::arch::is_aarch64_feature_detected!("fp16") {
+                            _result = Box::new(
+                                MorphOpFilterNeon2DRowF16::<{ MorphOp::Erode as u8 }>::default(),
+                            );
+                        }
+                    }
+                    _result
+                }
+            },
+        }
+    }
+}
+
+impl Row2DFilter<f64> for f64 {
+    fn get_weighted_filter(op: MorphOp) -> MorphFilterWeighted2DRow<f64> {
+        MorphFilterWeighted2DRow {
+            handler: match op {
+                MorphOp::Dilate => Box::new(MorphOpFilterScalarWeighted2DRow::<
+                    { MorphOp::Dilate as u8 },
+                >::default()),
+                MorphOp::Erode => Box::new(MorphOpFilterScalarWeighted2DRow::<
+                    { MorphOp::Erode as u8 },
+                >::default()),
+            },
+        }
+    }
+
+    fn get_filter(op: MorphOp) -> MorphFilterFlat2DRow<f64> {
+        MorphFilterFlat2DRow {
+            handler: match op {
+                MorphOp::Dilate => {
+                    let mut _result: Box<dyn MorthOpFilterFlat2DRow<f64> + Sync + Send> =
+                        Box::new(MorphOpFilterScalar2DRow::<{ MorphOp::Dilate as u8 }>::default());
+                    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+                    {
+                        if std::arch::is_x86_feature_detected!("sse4.1") {
+                            _result = Box::new(
+                                MorphOpFilterSse2DRowF64::<{ MorphOp::Dilate as u8 }>::default(),
+                            );
+                        }
+                        if std::arch::is_x86_feature_detected!("avx2") {
+                            _result = Box::new(
+                                MorphOpFilterAvx2DRowF64::<{ MorphOp::Dilate as u8 }>::default(),
+                            );
+                        }
+                    }
+                    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+                    {
+                        _result = Box::new(
+                            MorphOpFilterNeon2DRowF64::<{ MorphOp::Dilate as u8 }>::default(),
+                        );
+                    }
+                    _result
+                }
+                MorphOp::Erode => {
+                    let mut _result: Box<dyn MorthOpFilterFlat2DRow<f64> + Sync + Send> =
+                        Box::new(MorphOpFilterScalar2DRow::<{ MorphOp::Erode as u8 }>::default());
+                    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+                    {
+                        if std::arch::is_x86_feature_detected!("sse4.1") {
+                            _result = Box::new(
+                                MorphOpFilterSse2DRowF64::<{ MorphOp::Erode as u8 }>::default(),
+                            );
+                        }
+                        if std::arch::is_x86_feature_detected!("avx2") {
+                            _result = Box::new(
+                                MorphOpFilterAvx2DRowF64::<{ MorphOp::Erode as u8 }>::default(),
+                            );
+                        }
+                    }
+                    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+                    {
+                        _result = Box::new(
+                            MorphOpFilterNeon2DRowF64::<{ MorphOp::Erode as u8 }>::default(),
+                        );
+                    }
+                    _result
+                }
+            },
+        }
+    }
+}
+
 impl Row2DFilter<u16> for u16 {
-    fn get_filter(op: MorphOp) -> MorthFilterFlat2DRow<u16> {
-        MorthFilterFlat2DRow {
+    fn get_weighted_filter(op: MorphOp) -> MorphFilterWeighted2DRow<u16> {
+        MorphFilterWeighted2DRow {
+            handler: match op {
+                MorphOp::Dilate => weighted_filter_u16::<{ MorphOp::Dilate as u8 }>(),
+                MorphOp::Erode => weighted_filter_u16::<{ MorphOp::Erode as u8 }>(),
+            },
+        }
+    }
+
+    fn get_filter(op: MorphOp) -> MorphFilterFlat2DRow<u16> {
+        MorphFilterFlat2DRow {
             handler: match op {
                 MorphOp::Dilate => {
                     let mut _result: Box<dyn MorthOpFilterFlat2DRow<u16> + Sync + Send> =
-                        Box::new(MorphFilterFlat2DRow::<{ MorphOp::Dilate as u8 }>::default());
+                        Box::new(MorphOpFilterScalar2DRow::<{ MorphOp::Dilate as u8 }>::default());
                     #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
                     {
                         if std::arch::is_x86_feature_detected!("sse4.1") {
@@ -209,7 +607,7 @@ impl Row2DFilter<u16> for u16 {
                 }
                 MorphOp::Erode => {
                     let mut _result: Box<dyn MorthOpFilterFlat2DRow<u16> + Sync + Send> =
-                        Box::new(MorphFilterFlat2DRow::<{ MorphOp::Erode as u8 }>::default());
+                        Box::new(MorphOpFilterScalar2DRow::<{ MorphOp::Erode as u8 }>::default());
                     #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
                     {
                         if std::arch::is_x86_feature_detected!("sse4.1") {
@@ -235,3 +633,99 @@ impl Row2DFilter<u16> for u16 {
         }
     }
 }
+
+impl Row2DFilter<i8> for i8 {
+    fn get_weighted_filter(op: MorphOp) -> MorphFilterWeighted2DRow<i8> {
+        MorphFilterWeighted2DRow {
+            handler: match op {
+                MorphOp::Dilate => Box::new(MorphOpFilterScalarWeighted2DRow::<
+                    { MorphOp::Dilate as u8 },
+                >::default()),
+                MorphOp::Erode => Box::new(MorphOpFilterScalarWeighted2DRow::<
+                    { MorphOp::Erode as u8 },
+                >::default()),
+            },
+        }
+    }
+
+    fn get_filter(op: MorphOp) -> MorphFilterFlat2DRow<i8> {
+        MorphFilterFlat2DRow {
+            handler: match op {
+                MorphOp::Dilate => flat_filter_i8::<{ MorphOp::Dilate as u8 }>(),
+                MorphOp::Erode => flat_filter_i8::<{ MorphOp::Erode as u8 }>(),
+            },
+        }
+    }
+}
+
+impl Row2DFilter<i16> for i16 {
+    fn get_weighted_filter(op: MorphOp) -> MorphFilterWeighted2DRow<i16> {
+        MorphFilterWeighted2DRow {
+            handler: match op {
+                MorphOp::Dilate => Box::new(MorphOpFilterScalarWeighted2DRow::<
+                    { MorphOp::Dilate as u8 },
+                >::default()),
+                MorphOp::Erode => Box::new(MorphOpFilterScalarWeighted2DRow::<
+                    { MorphOp::Erode as u8 },
+                >::default()),
+            },
+        }
+    }
+
+    fn get_filter(op: MorphOp) -> MorphFilterFlat2DRow<i16> {
+        MorphFilterFlat2DRow {
+            handler: match op {
+                MorphOp::Dilate => flat_filter_i16::<{ MorphOp::Dilate as u8 }>(),
+                MorphOp::Erode => flat_filter_i16::<{ MorphOp::Erode as u8 }>(),
+            },
+        }
+    }
+}
+
+impl Row2DFilter<i32> for i32 {
+    fn get_weighted_filter(op: MorphOp) -> MorphFilterWeighted2DRow<i32> {
+        MorphFilterWeighted2DRow {
+            handler: match op {
+                MorphOp::Dilate => Box::new(MorphOpFilterScalarWeighted2DRow::<
+                    { MorphOp::Dilate as u8 },
+                >::default()),
+                MorphOp::Erode => Box::new(MorphOpFilterScalarWeighted2DRow::<
+                    { MorphOp::Erode as u8 },
+                >::default()),
+            },
+        }
+    }
+
+    fn get_filter(op: MorphOp) -> MorphFilterFlat2DRow<i32> {
+        MorphFilterFlat2DRow {
+            handler: match op {
+                MorphOp::Dilate => flat_filter_i32::<{ MorphOp::Dilate as u8 }>(),
+                MorphOp::Erode => flat_filter_i32::<{ MorphOp::Erode as u8 }>(),
+            },
+        }
+    }
+}
+
+impl Row2DFilter<u32> for u32 {
+    fn get_weighted_filter(op: MorphOp) -> MorphFilterWeighted2DRow<u32> {
+        MorphFilterWeighted2DRow {
+            handler: match op {
+                MorphOp::Dilate => Box::new(MorphOpFilterScalarWeighted2DRow::<
+                    { MorphOp::Dilate as u8 },
+                >::default()),
+                MorphOp::Erode => Box::new(MorphOpFilterScalarWeighted2DRow::<
+                    { MorphOp::Erode as u8 },
+                >::default()),
+            },
+        }
+    }
+
+    fn get_filter(op: MorphOp) -> MorphFilterFlat2DRow<u32> {
+        MorphFilterFlat2DRow {
+            handler: match op {
+                MorphOp::Dilate => flat_filter_u32::<{ MorphOp::Dilate as u8 }>(),
+                MorphOp::Erode => flat_filter_u32::<{ MorphOp::Erode as u8 }>(),
+            },
+        }
+    }
+}
@@ -26,7 +26,8 @@
  * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
  * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
-use crate::flat_se::AnalyzedSe;
+use crate::flat_se::FlatSe;
+use crate::morph_base::MorphWeightedOp;
 use crate::unsafe_slice::UnsafeSlice;
 use crate::ImageSize;
 
@@ -36,8 +37,14 @@ pub struct Arena<T> {
     pub width: usize,
     #[allow(dead_code)]
     pub height: usize,
-    pub pad_w: usize,
-    pub pad_h: usize,
+    /// Padding before the first column, it is also the anchor of the structuring element
+    pub pad_left: usize,
+    #[allow(dead_code)]
+    pub pad_right: usize,
+    /// Padding before the first row
+    pub pad_top: usize,
+    #[allow(dead_code)]
+    pub pad_bottom: usize,
 }
 
 impl<T> Arena<T> {
@@ -45,26 +52,92 @@ impl<T> Arena<T> {
         arena: Vec<T>,
         arena_width: usize,
         arena_height: usize,
-        arena_pad_w: usize,
-        arena_pad_h: usize,
+        pad_left: usize,
+        pad_right: usize,
+        pad_top: usize,
+        pad_bottom: usize,
     ) -> Arena<T> {
         Arena {
             arena,
             width: arena_width,
             height: arena_height,
-            pad_w: arena_pad_w,
-            pad_h: arena_pad_h,
+            pad_left,
+            pad_right,
+            pad_top,
+            pad_bottom,
         }
     }
 }
 
+/// Offsets of structuring element points from the start of an arena row,
+/// computed once for the arena layout so rows are addressed without allocations
+pub(crate) fn arena_offsets<T>(arena: &Arena<T>, se: &FlatSe) -> Vec<usize> {
+    se.element_offsets
+        .iter()
+        .map(|p| {
+            (p.y + arena.pad_top as i32) as usize * arena.width
+                + (p.x + arena.pad_left as i32) as usize
+        })
+        .collect()
+}
+
+/// Arena rows under each point of the structuring element for one output row
+pub struct ArenaRows<'a, T> {
+    src: &'a [T],
+    offsets: &'a [usize],
+    row_start: usize,
+}
+
+impl<'a, T> ArenaRows<'a, T> {
+    pub fn new(src: &'a [T], offsets: &'a [usize], row_start: usize) -> Self {
+        ArenaRows {
+            src,
+            offsets,
+            row_start,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// # Safety
+    ///
+    /// `i` must be less than `len()`
+    #[inline(always)]
+    pub unsafe fn get_unchecked(&self, i: usize) -> &'a [T] {
+        self.src
+            .get_unchecked(self.row_start + *self.offsets.get_unchecked(i)..)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &'a [T]> + '_ {
+        self.offsets
+            .iter()
+            .map(|&x| unsafe { self.src.get_unchecked(self.row_start + x..) })
+    }
+}
+
 pub trait MorthOpFilterFlat2DRow<T> {
     unsafe fn dispatch_row(
         &self,
         arena: &Arena<T>,
         dst: &UnsafeSlice<T>,
+        dst_stride: usize,
+        image_size: ImageSize,
+        element_offsets: &[usize],
+        y: usize,
+    );
+}
+
+pub trait MorthOpFilterWeighted2DRow<T: MorphWeightedOp<T>> {
+    unsafe fn dispatch_row(
+        &self,
+        arena: &Arena<T>,
+        dst: &UnsafeSlice<T>,
+        dst_stride: usize,
         image_size: ImageSize,
-        analyzed_se: AnalyzedSe,
+        element_offsets: &[usize],
+        weights: &[T::Weight],
         y: usize,
     );
 }
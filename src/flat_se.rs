@@ -32,32 +32,91 @@ use crate::se_scan::ScanPoint;
 pub(crate) struct FlatSe {
     /// Significant points { x, y }
     pub(crate) element_offsets: Vec<ScanPoint>,
+    /// Weights of the points for non-flat structuring elements, empty when element is flat
+    pub(crate) weights: Vec<f32>,
 }
 
 impl FlatSe {
     pub fn new(vec: Vec<ScanPoint>) -> FlatSe {
         FlatSe {
             element_offsets: vec,
+            weights: vec![],
         }
     }
 }
 
+/// Horizontal run of set elements
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Chord {
+    /// Offset of the first element { x, y } relative to the anchor
+    pub(crate) start: ScanPoint,
+    pub(crate) length: usize,
+}
+
+impl Chord {
+    pub fn new(start: ScanPoint, length: usize) -> Chord {
+        Chord { start, length }
+    }
+}
+
+/// Periodic line `{ k * step, 0 <= k < count }`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct PeriodicLine {
+    /// Always points down, or right when it is horizontal
+    pub(crate) step: ScanPoint,
+    pub(crate) count: usize,
+}
+
+impl PeriodicLine {
+    pub fn new(step: ScanPoint, count: usize) -> PeriodicLine {
+        PeriodicLine { step, count }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct AnalyzedSe {
-    #[allow(dead_code)]
-    pub(crate) original_se: Vec<u8>,
     pub(crate) left_front: FlatSe,
+    /// Same elements grouped into horizontal chords, row by row
+    pub(crate) chords: Vec<Chord>,
     pub(crate) is_empty: bool,
+    /// All elements of the kernel are set, so structuring element is separable
+    pub(crate) is_rectangle: bool,
 }
 
 impl AnalyzedSe {
-    pub fn new(original_se: Vec<u8>, left_front: FlatSe) -> AnalyzedSe {
+    pub fn new(original_se: Vec<u8>, left_front: FlatSe, chords: Vec<Chord>) -> AnalyzedSe {
         let is_empty =
             left_front.element_offsets.is_empty() && left_front.element_offsets.is_empty();
+        let is_rectangle = !is_empty && original_se.iter().all(|&x| x != 0);
         AnalyzedSe {
-            original_se,
             left_front,
+            chords,
+            is_empty,
+            is_rectangle,
+        }
+    }
+
+    /// Analyzes structuring element that has no mask, points are relative to the anchor
+    pub fn from_points(mut points: Vec<ScanPoint>) -> AnalyzedSe {
+        points.sort_by_key(|p| (p.y, p.x));
+        let mut chords: Vec<Chord> = vec![];
+        for &point in points.iter() {
+            match chords.last_mut() {
+                Some(chord)
+                    if chord.start.y == point.y
+                        && chord.start.x + chord.length as i32 == point.x =>
+                {
+                    chord.length += 1;
+                }
+                _ => chords.push(Chord::new(point, 1)),
+            }
+        }
+        let is_empty = points.is_empty();
+        AnalyzedSe {
+            left_front: FlatSe::new(points),
+            chords,
             is_empty,
+            is_rectangle: false,
         }
     }
 }
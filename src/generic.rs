@@ -0,0 +1,594 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+//! Morphology of any sample type and channel layout through a single set of functions,
+//! for example `generic::morphology::<u16, generic::Rgba>(...)`.
+//!
+//! Sample types implement [MorphologySample] through its building block traits. Built-in
+//! types have vectorised filters, other types get scalar filters from the default methods,
+//! so a downstream type needs to provide only [MorphNativeOp], [MorphWeightedOp] and
+//! [MorphGradient] with `f64: num_traits::AsPrimitive<T>`, and opt into the defaults of the other traits
+//! with empty impls. The default of [AlphaPremultipliable] has no premultiplied alpha.
+use crate::border_mode::{BorderMode, MorphScalar};
+use crate::error::MorphologyError;
+use crate::morphology_options::MorphologyOptions;
+use crate::morphology_plan::{morphology_impl, morphology_in_place_impl, MorphologyPlan};
+use crate::op_type::MorphExOp;
+use crate::structuring_element::KernelShape;
+use crate::{ImageSize, MorphologyLayout, MorphologyThreadingPolicy};
+
+pub use crate::alpha::AlphaPremultipliable;
+pub use crate::difference::MorphGradient;
+pub use crate::filter::{MorphFilterFlat2DRow, MorphFilterWeighted2DRow, Row2DFilter};
+pub use crate::morph_base::{MorphNativeOp, MorphWeightedOp};
+pub use crate::morph_rows::{MorphRows, MorphRowsHandler};
+pub use crate::op_type::MorphOp;
+pub use crate::packing::{
+    GrayAlphaPackable, InterleavedPackable, RgbPackable, RgbaPackable, UnpackedGrayAlpha,
+    UnpackedInterleaved, UnpackedRgbImage, UnpackedRgbaImage,
+};
+use num_traits::AsPrimitive;
+
+/// Sample type of images, implemented for every type that has all building block traits.
+///
+/// Border constants are converted with `f64: num_traits::AsPrimitive<T>`, which generic
+/// functions require next to this trait, `num_traits` is not re-exported.
+pub trait MorphologySample:
+    Copy
+    + Default
+    + Send
+    + Sync
+    + 'static
+    + MorphNativeOp<Self>
+    + MorphWeightedOp<Self>
+    + Row2DFilter<Self>
+    + MorphRows<Self>
+    + MorphGradient<Self>
+    + GrayAlphaPackable<Self>
+    + RgbPackable<Self>
+    + RgbaPackable<Self>
+    + InterleavedPackable<Self>
+    + AlphaPremultipliable<Self>
+{
+}
+
+impl<T> MorphologySample for T where
+    T: Copy
+        + Default
+        + Send
+        + Sync
+        + 'static
+        + MorphNativeOp<T>
+        + MorphWeightedOp<T>
+        + Row2DFilter<T>
+        + MorphRows<T>
+        + MorphGradient<T>
+        + GrayAlphaPackable<T>
+        + RgbPackable<T>
+        + RgbaPackable<T>
+        + InterleavedPackable<T>
+        + AlphaPremultipliable<T>
+{
+}
+
+/// Channels layout of images, selects [MorphologyLayout] at compile time
+pub trait ChannelLayout {
+    const LAYOUT: MorphologyLayout;
+
+    /// Count of interleaved channels
+    fn channels() -> usize {
+        Self::LAYOUT.channels()
+    }
+}
+
+/// Single plane, see [MorphologyLayout::Gray]
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Gray;
+
+/// Interleaved gray and alpha, see [MorphologyLayout::GrayAlpha]
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct GrayAlpha;
+
+/// Interleaved RGB, see [MorphologyLayout::Rgb]
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Rgb;
+
+/// Interleaved RGBA, see [MorphologyLayout::Rgba]
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Rgba;
+
+impl ChannelLayout for Gray {
+    const LAYOUT: MorphologyLayout = MorphologyLayout::Gray;
+}
+
+impl ChannelLayout for GrayAlpha {
+    const LAYOUT: MorphologyLayout = MorphologyLayout::GrayAlpha;
+}
+
+impl ChannelLayout for Rgb {
+    const LAYOUT: MorphologyLayout = MorphologyLayout::Rgb;
+}
+
+impl ChannelLayout for Rgba {
+    const LAYOUT: MorphologyLayout = MorphologyLayout::Rgba;
+}
+
+/// Interleaved BGR, see [MorphologyLayout::Bgr]
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Bgr;
+
+/// Interleaved BGRA, see [MorphologyLayout::Bgra]
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Bgra;
+
+/// Interleaved ARGB, see [MorphologyLayout::Argb]
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Argb;
+
+/// Interleaved ABGR, see [MorphologyLayout::Abgr]
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Abgr;
+
+impl ChannelLayout for Bgr {
+    const LAYOUT: MorphologyLayout = MorphologyLayout::Bgr;
+}
+
+impl ChannelLayout for Bgra {
+    const LAYOUT: MorphologyLayout = MorphologyLayout::Bgra;
+}
+
+impl ChannelLayout for Argb {
+    const LAYOUT: MorphologyLayout = MorphologyLayout::Argb;
+}
+
+impl ChannelLayout for Abgr {
+    const LAYOUT: MorphologyLayout = MorphologyLayout::Abgr;
+}
+
+/// Interleaved image with `N` channels known at compile time,
+/// see [MorphologyLayout::Interleaved]
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Channels<const N: usize>;
+
+impl<const N: usize> ChannelLayout for Channels<N> {
+    const LAYOUT: MorphologyLayout = MorphologyLayout::Interleaved(N);
+}
+
+/// Morphology of an image with samples `T` and channels layout `L`
+///
+/// # Arguments
+///
+/// * `src`: Source image slice
+/// * `dst`: Destination image slice
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant], one value per channel
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology<T, L>(
+    src: &[T],
+    dst: &mut [T],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError>
+where
+    T: MorphologySample,
+    L: ChannelLayout,
+    f64: AsPrimitive<T>,
+{
+    morphology_impl::<T>(
+        src,
+        dst,
+        None,
+        L::LAYOUT,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology of an image with samples `T` and channels layout `L` with padded rows
+///
+/// Rows are `src_stride` and `dst_stride` items apart, padding after rows is not read and not written.
+///
+/// # Arguments
+///
+/// * `src`: Source image slice
+/// * `src_stride`: Items between starts of source rows, at least `width * channels`
+/// * `dst`: Destination image slice
+/// * `dst_stride`: Items between starts of destination rows, at least `width * channels`
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant], one value per channel
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_with_stride<T, L>(
+    src: &[T],
+    src_stride: usize,
+    dst: &mut [T],
+    dst_stride: usize,
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError>
+where
+    T: MorphologySample,
+    L: ChannelLayout,
+    f64: AsPrimitive<T>,
+{
+    morphology_impl::<T>(
+        src,
+        dst,
+        Some((src_stride, dst_stride)),
+        L::LAYOUT,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology of an image with samples `T` and channels layout `L` in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Image slice, replaced by the result
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant], one value per channel
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_in_place<T, L>(
+    image: &mut [T],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError>
+where
+    T: MorphologySample,
+    L: ChannelLayout,
+    f64: AsPrimitive<T>,
+{
+    morphology_in_place_impl::<T>(
+        image,
+        L::LAYOUT,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology of an image with samples `T` and channels layout `L` with non-flat
+/// structuring element, see [crate::morphology_non_flat] for the definition
+///
+/// # Arguments
+///
+/// * `src`: Source image slice
+/// * `dst`: Destination image slice
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `weights`: 2D structuring element weights, points with non-finite weights are not part of it
+/// * `weights_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant], one value per channel
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_non_flat<T, L>(
+    src: &[T],
+    dst: &mut [T],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    weights: &[f32],
+    weights_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError>
+where
+    T: MorphologySample,
+    L: ChannelLayout,
+    f64: AsPrimitive<T>,
+{
+    MorphologyPlan::new_non_flat(
+        image_size,
+        L::LAYOUT,
+        morph_op,
+        weights,
+        weights_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )?
+    .execute(src, dst)
+}
+
+/// Morphology of an image with samples `T` and channels layout `L` configured by [MorphologyOptions]
+///
+/// # Arguments
+///
+/// * `src`: Source image slice
+/// * `dst`: Destination image slice
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `options`: Border, anchor, iterations, threading, backend and alpha mode, see [MorphologyOptions]
+///
+pub fn morphology_with_options<T, L>(
+    src: &[T],
+    dst: &mut [T],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    options: &MorphologyOptions,
+) -> Result<(), MorphologyError>
+where
+    T: MorphologySample,
+    L: ChannelLayout,
+    f64: AsPrimitive<T>,
+{
+    MorphologyPlan::new_with_options(
+        image_size,
+        L::LAYOUT,
+        morph_op,
+        structuring_element,
+        structuring_element_size,
+        options,
+    )?
+    .execute(src, dst)
+}
+
+/// Morphology of an image with samples `T` and channels layout `L` with padded rows
+/// configured by [MorphologyOptions], see [morphology_with_stride] for strides
+///
+/// # Arguments
+///
+/// * `src`: Source image slice
+/// * `src_stride`: Items between starts of source rows, at least `width * channels`
+/// * `dst`: Destination image slice
+/// * `dst_stride`: Items between starts of destination rows, at least `width * channels`
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `options`: Border, anchor, iterations, threading, backend and alpha mode, see [MorphologyOptions]
+///
+pub fn morphology_with_stride_and_options<T, L>(
+    src: &[T],
+    src_stride: usize,
+    dst: &mut [T],
+    dst_stride: usize,
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    options: &MorphologyOptions,
+) -> Result<(), MorphologyError>
+where
+    T: MorphologySample,
+    L: ChannelLayout,
+    f64: AsPrimitive<T>,
+{
+    MorphologyPlan::new_with_options(
+        image_size,
+        L::LAYOUT,
+        morph_op,
+        structuring_element,
+        structuring_element_size,
+        options,
+    )?
+    .execute_with_stride(src, src_stride, dst, dst_stride)
+}
+
+/// Morphology of an image with samples `T` and channels layout `L` in place
+/// configured by [MorphologyOptions], see [morphology_in_place] for memory usage
+///
+/// # Arguments
+///
+/// * `image`: Image slice, replaced by the result
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `options`: Border, anchor, iterations, threading, backend and alpha mode, see [MorphologyOptions]
+///
+pub fn morphology_in_place_with_options<T, L>(
+    image: &mut [T],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    options: &MorphologyOptions,
+) -> Result<(), MorphologyError>
+where
+    T: MorphologySample,
+    L: ChannelLayout,
+    f64: AsPrimitive<T>,
+{
+    MorphologyPlan::new_with_options(
+        image_size,
+        L::LAYOUT,
+        morph_op,
+        structuring_element,
+        structuring_element_size,
+        options,
+    )?
+    .execute_in_place(image)
+}
+
+/// Morphology of an image with samples `T` and channels layout `L` with non-flat
+/// structuring element configured by [MorphologyOptions]
+///
+/// # Arguments
+///
+/// * `src`: Source image slice
+/// * `dst`: Destination image slice
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `weights`: 2D structuring element weights, points with non-finite weights are not part of it
+/// * `weights_size`: (W,H) structuring element size
+/// * `options`: Border, anchor, iterations, threading, backend and alpha mode, see [MorphologyOptions]
+///
+pub fn morphology_non_flat_with_options<T, L>(
+    src: &[T],
+    dst: &mut [T],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    weights: &[f32],
+    weights_size: KernelShape,
+    options: &MorphologyOptions,
+) -> Result<(), MorphologyError>
+where
+    T: MorphologySample,
+    L: ChannelLayout,
+    f64: AsPrimitive<T>,
+{
+    MorphologyPlan::new_non_flat_with_options(
+        image_size,
+        L::LAYOUT,
+        morph_op,
+        weights,
+        weights_size,
+        options,
+    )?
+    .execute(src, dst)
+}
+
+/// Morphology of an interleaved image with samples `T` and `channels` known at runtime,
+/// configured by [MorphologyOptions]. Use [Channels] when the count is known at compile time.
+///
+/// # Arguments
+///
+/// * `src`: Source image slice
+/// * `dst`: Destination image slice
+/// * `channels`: Count of interleaved channels, at least 1
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `options`: Border, anchor, iterations, threading, backend and alpha mode, see [MorphologyOptions].
+///   Border scalar holds four values, channels past the fourth use the last one,
+///   [MorphologyOptions::with_border_values] gives a value to every channel.
+///
+pub fn morphology_interleaved<T>(
+    src: &[T],
+    dst: &mut [T],
+    channels: usize,
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    options: &MorphologyOptions,
+) -> Result<(), MorphologyError>
+where
+    T: MorphologySample,
+    f64: AsPrimitive<T>,
+{
+    MorphologyPlan::new_with_options(
+        image_size,
+        MorphologyLayout::Interleaved(channels),
+        morph_op,
+        structuring_element,
+        structuring_element_size,
+        options,
+    )?
+    .execute(src, dst)
+}
+
+/// Morphology of an interleaved image with samples `T` and `channels` known at runtime in place,
+/// see [morphology_interleaved]
+///
+/// # Arguments
+///
+/// * `image`: Image slice, replaced by the result
+/// * `channels`: Count of interleaved channels, at least 1
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `options`: Border, anchor, iterations, threading, backend and alpha mode, see [MorphologyOptions]
+///
+pub fn morphology_interleaved_in_place<T>(
+    image: &mut [T],
+    channels: usize,
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    options: &MorphologyOptions,
+) -> Result<(), MorphologyError>
+where
+    T: MorphologySample,
+    f64: AsPrimitive<T>,
+{
+    MorphologyPlan::new_with_options(
+        image_size,
+        MorphologyLayout::Interleaved(channels),
+        morph_op,
+        structuring_element,
+        structuring_element_size,
+        options,
+    )?
+    .execute_in_place(image)
+}
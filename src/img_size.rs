@@ -39,3 +39,67 @@ impl ImageSize {
         ImageSize { width, height }
     }
 }
+
+/// Rectangular region of an image, `x` and `y` are its top left corner
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Roi {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Roi {
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Roi {
+        Roi {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Region covering the whole image
+    pub fn whole(image_size: ImageSize) -> Roi {
+        Roi::new(0, 0, image_size.width, image_size.height)
+    }
+
+    pub fn size(&self) -> ImageSize {
+        ImageSize::new(self.width, self.height)
+    }
+
+    /// Smallest region containing both regions
+    pub(crate) fn union(&self, other: Roi) -> Roi {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        let right = (self.x + self.width).max(other.x + other.width);
+        let bottom = (self.y + self.height).max(other.y + other.height);
+        Roi::new(x, y, right - x, bottom - y)
+    }
+
+    /// Offset of the region's first item in an image with rows `stride` items apart
+    pub(crate) fn offset(&self, stride: usize, channels: usize) -> usize {
+        self.y * stride + self.x * channels
+    }
+}
+
+/// Rows of `width` items of an image with `stride` items between row starts,
+/// the last row may be shorter than the stride
+pub(crate) fn image_rows<T>(
+    image: &[T],
+    stride: usize,
+    width: usize,
+) -> impl Iterator<Item = &[T]> {
+    image.chunks(stride).map(move |row| &row[..width])
+}
+
+/// Mutable rows of `width` items of an image with `stride` items between row starts,
+/// the last row may be shorter than the stride
+pub(crate) fn image_rows_mut<T>(
+    image: &mut [T],
+    stride: usize,
+    width: usize,
+) -> impl Iterator<Item = &mut [T]> {
+    image.chunks_mut(stride).map(move |row| &mut row[..width])
+}
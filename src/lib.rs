@@ -29,76 +29,319 @@
 #![allow(clippy::too_many_arguments)]
 extern crate core;
 
+mod alpha;
 mod arena;
 mod arena_roi;
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 mod avx;
+mod binary_image;
+mod binary_morphology;
 mod border_mode;
 mod difference;
 #[cfg(feature = "image")]
 mod dynamic_image;
+mod error;
 mod filter;
 mod filter_op_declare;
 mod flat_se;
+pub mod generic;
 mod img_size;
 mod morph_base;
-mod morph_gray_alpha;
-mod morph_rgb;
-mod morph_rgba;
+mod morph_rows;
+mod morphology_options;
+mod morphology_plan;
 #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
 mod neon;
 mod op;
+#[cfg(feature = "half")]
+mod op_f16;
 mod op_f32;
+mod op_f64;
+mod op_i16;
+mod op_i32;
+mod op_i8;
 mod op_impl;
+mod op_non_flat;
 mod op_type;
 mod op_u16;
+mod op_u32;
 mod ops;
 mod packing;
+mod periodic_line;
+mod rle_mask;
+mod rle_morphology;
+mod se_decomposition;
 mod se_scan;
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 mod sse;
-mod structuring_element;
+pub mod structuring_element;
 mod thread_policy;
+mod transpose;
 mod unsafe_slice;
+mod urbach_wilkinson;
+mod van_herk;
 
+pub use alpha::AlphaMode;
+pub use binary_image::BinaryImage;
+pub use binary_morphology::{binary_dilate, binary_erode, binary_hit_or_miss, binary_morphology};
 pub use border_mode::{BorderMode, MorphScalar};
 #[cfg(feature = "image")]
 pub use dynamic_image::*;
-pub use img_size::ImageSize;
+pub use error::MorphologyError;
+pub use generic::{ChannelLayout, MorphologySample};
+pub use img_size::{ImageSize, Roi};
+pub use morphology_options::{MorphologyBackend, MorphologyOptions};
+pub use morphology_plan::MorphologyLayout;
+pub use morphology_plan::MorphologyPlan;
 pub use op::dilate;
 pub use op::dilate_gray_alpha;
+pub use op::dilate_gray_alpha_in_place;
+pub use op::dilate_in_place;
 pub use op::dilate_rgb;
+pub use op::dilate_rgb_in_place;
 pub use op::dilate_rgba;
+pub use op::dilate_rgba_in_place;
 pub use op::erode;
 pub use op::erode_gray_alpha;
+pub use op::erode_gray_alpha_in_place;
+pub use op::erode_in_place;
 pub use op::erode_rgb;
+pub use op::erode_rgb_in_place;
 pub use op::erode_rgba;
+pub use op::erode_rgba_in_place;
 pub use op::morphology;
 pub use op::morphology_gray_alpha;
+pub use op::morphology_gray_alpha_in_place;
+pub use op::morphology_gray_alpha_with_stride;
+pub use op::morphology_in_place;
 pub use op::morphology_rgb;
+pub use op::morphology_rgb_in_place;
+pub use op::morphology_rgb_with_stride;
 pub use op::morphology_rgba;
+pub use op::morphology_rgba_in_place;
+pub use op::morphology_rgba_with_stride;
+pub use op::morphology_with_stride;
+#[cfg(feature = "half")]
+pub use op_f16::*;
 pub use op_f32::dilate_f32;
+pub use op_f32::dilate_f32_in_place;
 pub use op_f32::dilate_gray_alpha_f32;
+pub use op_f32::dilate_gray_alpha_f32_in_place;
 pub use op_f32::dilate_rgb_f32;
+pub use op_f32::dilate_rgb_f32_in_place;
 pub use op_f32::dilate_rgba_f32;
+pub use op_f32::dilate_rgba_f32_in_place;
 pub use op_f32::erode_f32;
+pub use op_f32::erode_f32_in_place;
 pub use op_f32::erode_gray_alpha_f32;
+pub use op_f32::erode_gray_alpha_f32_in_place;
 pub use op_f32::erode_rgb_f32;
+pub use op_f32::erode_rgb_f32_in_place;
 pub use op_f32::erode_rgba_f32;
+pub use op_f32::erode_rgba_f32_in_place;
+#[allow(deprecated)]
+pub use op_f32::morphology_f32;
+#[allow(deprecated)]
+pub use op_f32::morphology_f32_in_place;
+#[allow(deprecated)]
+pub use op_f32::morphology_f32_with_stride;
+pub use op_f32::morphology_gray_alpha_f32;
+pub use op_f32::morphology_gray_alpha_f32_in_place;
+pub use op_f32::morphology_gray_alpha_f32_with_stride;
+pub use op_f32::morphology_gray_f32;
+pub use op_f32::morphology_gray_f32_in_place;
+pub use op_f32::morphology_gray_f32_with_stride;
 pub use op_f32::morphology_rgb_f32;
+pub use op_f32::morphology_rgb_f32_in_place;
+pub use op_f32::morphology_rgb_f32_with_stride;
 pub use op_f32::morphology_rgba_f32;
+pub use op_f32::morphology_rgba_f32_in_place;
+pub use op_f32::morphology_rgba_f32_with_stride;
+pub use op_f64::dilate_f64;
+pub use op_f64::dilate_f64_in_place;
+pub use op_f64::dilate_gray_alpha_f64;
+pub use op_f64::dilate_gray_alpha_f64_in_place;
+pub use op_f64::dilate_rgb_f64;
+pub use op_f64::dilate_rgb_f64_in_place;
+pub use op_f64::dilate_rgba_f64;
+pub use op_f64::dilate_rgba_f64_in_place;
+pub use op_f64::erode_f64;
+pub use op_f64::erode_f64_in_place;
+pub use op_f64::erode_gray_alpha_f64;
+pub use op_f64::erode_gray_alpha_f64_in_place;
+pub use op_f64::erode_rgb_f64;
+pub use op_f64::erode_rgb_f64_in_place;
+pub use op_f64::erode_rgba_f64;
+pub use op_f64::erode_rgba_f64_in_place;
+#[allow(deprecated)]
+pub use op_f64::morphology_f64;
+#[allow(deprecated)]
+pub use op_f64::morphology_f64_in_place;
+#[allow(deprecated)]
+pub use op_f64::morphology_f64_with_stride;
+pub use op_f64::morphology_gray_alpha_f64;
+pub use op_f64::morphology_gray_alpha_f64_in_place;
+pub use op_f64::morphology_gray_alpha_f64_with_stride;
+pub use op_f64::morphology_gray_f64;
+pub use op_f64::morphology_gray_f64_in_place;
+pub use op_f64::morphology_gray_f64_with_stride;
+pub use op_f64::morphology_rgb_f64;
+pub use op_f64::morphology_rgb_f64_in_place;
+pub use op_f64::morphology_rgb_f64_with_stride;
+pub use op_f64::morphology_rgba_f64;
+pub use op_f64::morphology_rgba_f64_in_place;
+pub use op_f64::morphology_rgba_f64_with_stride;
+pub use op_i16::dilate_gray_alpha_i16;
+pub use op_i16::dilate_gray_alpha_i16_in_place;
+pub use op_i16::dilate_i16;
+pub use op_i16::dilate_i16_in_place;
+pub use op_i16::dilate_rgb_i16;
+pub use op_i16::dilate_rgb_i16_in_place;
+pub use op_i16::dilate_rgba_i16;
+pub use op_i16::dilate_rgba_i16_in_place;
+pub use op_i16::erode_gray_alpha_i16;
+pub use op_i16::erode_gray_alpha_i16_in_place;
+pub use op_i16::erode_i16;
+pub use op_i16::erode_i16_in_place;
+pub use op_i16::erode_rgb_i16;
+pub use op_i16::erode_rgb_i16_in_place;
+pub use op_i16::erode_rgba_i16;
+pub use op_i16::erode_rgba_i16_in_place;
+pub use op_i16::morphology_gray_alpha_i16;
+pub use op_i16::morphology_gray_alpha_i16_in_place;
+pub use op_i16::morphology_gray_alpha_i16_with_stride;
+pub use op_i16::morphology_gray_i16;
+pub use op_i16::morphology_gray_i16_in_place;
+pub use op_i16::morphology_gray_i16_with_stride;
+pub use op_i16::morphology_rgb_i16;
+pub use op_i16::morphology_rgb_i16_in_place;
+pub use op_i16::morphology_rgb_i16_with_stride;
+pub use op_i16::morphology_rgba_i16;
+pub use op_i16::morphology_rgba_i16_in_place;
+pub use op_i16::morphology_rgba_i16_with_stride;
+pub use op_i32::dilate_gray_alpha_i32;
+pub use op_i32::dilate_gray_alpha_i32_in_place;
+pub use op_i32::dilate_i32;
+pub use op_i32::dilate_i32_in_place;
+pub use op_i32::dilate_rgb_i32;
+pub use op_i32::dilate_rgb_i32_in_place;
+pub use op_i32::dilate_rgba_i32;
+pub use op_i32::dilate_rgba_i32_in_place;
+pub use op_i32::erode_gray_alpha_i32;
+pub use op_i32::erode_gray_alpha_i32_in_place;
+pub use op_i32::erode_i32;
+pub use op_i32::erode_i32_in_place;
+pub use op_i32::erode_rgb_i32;
+pub use op_i32::erode_rgb_i32_in_place;
+pub use op_i32::erode_rgba_i32;
+pub use op_i32::erode_rgba_i32_in_place;
+pub use op_i32::morphology_gray_alpha_i32;
+pub use op_i32::morphology_gray_alpha_i32_in_place;
+pub use op_i32::morphology_gray_alpha_i32_with_stride;
+pub use op_i32::morphology_gray_i32;
+pub use op_i32::morphology_gray_i32_in_place;
+pub use op_i32::morphology_gray_i32_with_stride;
+pub use op_i32::morphology_rgb_i32;
+pub use op_i32::morphology_rgb_i32_in_place;
+pub use op_i32::morphology_rgb_i32_with_stride;
+pub use op_i32::morphology_rgba_i32;
+pub use op_i32::morphology_rgba_i32_in_place;
+pub use op_i32::morphology_rgba_i32_with_stride;
+pub use op_i8::dilate_gray_alpha_i8;
+pub use op_i8::dilate_gray_alpha_i8_in_place;
+pub use op_i8::dilate_i8;
+pub use op_i8::dilate_i8_in_place;
+pub use op_i8::dilate_rgb_i8;
+pub use op_i8::dilate_rgb_i8_in_place;
+pub use op_i8::dilate_rgba_i8;
+pub use op_i8::dilate_rgba_i8_in_place;
+pub use op_i8::erode_gray_alpha_i8;
+pub use op_i8::erode_gray_alpha_i8_in_place;
+pub use op_i8::erode_i8;
+pub use op_i8::erode_i8_in_place;
+pub use op_i8::erode_rgb_i8;
+pub use op_i8::erode_rgb_i8_in_place;
+pub use op_i8::erode_rgba_i8;
+pub use op_i8::erode_rgba_i8_in_place;
+pub use op_i8::morphology_gray_alpha_i8;
+pub use op_i8::morphology_gray_alpha_i8_in_place;
+pub use op_i8::morphology_gray_alpha_i8_with_stride;
+pub use op_i8::morphology_gray_i8;
+pub use op_i8::morphology_gray_i8_in_place;
+pub use op_i8::morphology_gray_i8_with_stride;
+pub use op_i8::morphology_rgb_i8;
+pub use op_i8::morphology_rgb_i8_in_place;
+pub use op_i8::morphology_rgb_i8_with_stride;
+pub use op_i8::morphology_rgba_i8;
+pub use op_i8::morphology_rgba_i8_in_place;
+pub use op_i8::morphology_rgba_i8_with_stride;
+pub use op_non_flat::dilate_non_flat;
+pub use op_non_flat::dilate_non_flat_f32;
+pub use op_non_flat::dilate_non_flat_u16;
+pub use op_non_flat::erode_non_flat;
+pub use op_non_flat::erode_non_flat_f32;
+pub use op_non_flat::erode_non_flat_u16;
+pub use op_non_flat::morphology_non_flat;
+pub use op_non_flat::morphology_non_flat_f32;
+pub use op_non_flat::morphology_non_flat_u16;
 pub use op_type::MorphExOp;
 pub use op_u16::dilate_gray_alpha_u16;
+pub use op_u16::dilate_gray_alpha_u16_in_place;
 pub use op_u16::dilate_rgb_u16;
+pub use op_u16::dilate_rgb_u16_in_place;
 pub use op_u16::dilate_rgba_u16;
+pub use op_u16::dilate_rgba_u16_in_place;
 pub use op_u16::dilate_u16;
+pub use op_u16::dilate_u16_in_place;
 pub use op_u16::erode_gray_alpha_u16;
+pub use op_u16::erode_gray_alpha_u16_in_place;
 pub use op_u16::erode_rgb_u16;
+pub use op_u16::erode_rgb_u16_in_place;
 pub use op_u16::erode_rgba_u16;
+pub use op_u16::erode_rgba_u16_in_place;
 pub use op_u16::erode_u16;
+pub use op_u16::erode_u16_in_place;
 pub use op_u16::morphology_gray_alpha_u16;
+pub use op_u16::morphology_gray_alpha_u16_in_place;
+pub use op_u16::morphology_gray_alpha_u16_with_stride;
 pub use op_u16::morphology_gray_u16;
+pub use op_u16::morphology_gray_u16_in_place;
+pub use op_u16::morphology_gray_u16_with_stride;
 pub use op_u16::morphology_rgb_u16;
+pub use op_u16::morphology_rgb_u16_in_place;
+pub use op_u16::morphology_rgb_u16_with_stride;
 pub use op_u16::morphology_rgba_u16;
+pub use op_u16::morphology_rgba_u16_in_place;
+pub use op_u16::morphology_rgba_u16_with_stride;
+pub use op_u32::dilate_gray_alpha_u32;
+pub use op_u32::dilate_gray_alpha_u32_in_place;
+pub use op_u32::dilate_rgb_u32;
+pub use op_u32::dilate_rgb_u32_in_place;
+pub use op_u32::dilate_rgba_u32;
+pub use op_u32::dilate_rgba_u32_in_place;
+pub use op_u32::dilate_u32;
+pub use op_u32::dilate_u32_in_place;
+pub use op_u32::erode_gray_alpha_u32;
+pub use op_u32::erode_gray_alpha_u32_in_place;
+pub use op_u32::erode_rgb_u32;
+pub use op_u32::erode_rgb_u32_in_place;
+pub use op_u32::erode_rgba_u32;
+pub use op_u32::erode_rgba_u32_in_place;
+pub use op_u32::erode_u32;
+pub use op_u32::erode_u32_in_place;
+pub use op_u32::morphology_gray_alpha_u32;
+pub use op_u32::morphology_gray_alpha_u32_in_place;
+pub use op_u32::morphology_gray_alpha_u32_with_stride;
+pub use op_u32::morphology_gray_u32;
+pub use op_u32::morphology_gray_u32_in_place;
+pub use op_u32::morphology_gray_u32_with_stride;
+pub use op_u32::morphology_rgb_u32;
+pub use op_u32::morphology_rgb_u32_in_place;
+pub use op_u32::morphology_rgb_u32_with_stride;
+pub use op_u32::morphology_rgba_u32;
+pub use op_u32::morphology_rgba_u32_in_place;
+pub use op_u32::morphology_rgba_u32_with_stride;
+pub use rle_mask::{MaskRun, RleMask};
+pub use rle_morphology::{rle_dilate, rle_erode, rle_morphology};
 pub use structuring_element::KernelShape;
 pub use thread_policy::MorphologyThreadingPolicy;
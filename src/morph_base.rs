@@ -27,9 +27,21 @@
  * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
 use crate::op_type::MorphOp;
+#[cfg(feature = "half")]
+use half::f16;
+use num_traits::AsPrimitive;
 
 pub trait MorphNativeOp<T> {
     fn op<const OP: u8>(&self, other: T) -> T;
+
+    /// Converts a [crate::MorphScalar] component into the border value
+    fn from_scalar(v: f64) -> T
+    where
+        T: Copy + 'static,
+        f64: AsPrimitive<T>,
+    {
+        v.as_()
+    }
 }
 
 impl MorphNativeOp<u8> for u8 {
@@ -61,3 +73,272 @@ impl MorphNativeOp<f32> for f32 {
         }
     }
 }
+
+impl MorphNativeOp<i8> for i8 {
+    fn op<const OP: u8>(&self, other: i8) -> i8 {
+        let morph_op: MorphOp = OP.into();
+        match morph_op {
+            MorphOp::Dilate => (*self).max(other),
+            MorphOp::Erode => (*self).min(other),
+        }
+    }
+}
+
+impl MorphNativeOp<i16> for i16 {
+    fn op<const OP: u8>(&self, other: i16) -> i16 {
+        let morph_op: MorphOp = OP.into();
+        match morph_op {
+            MorphOp::Dilate => (*self).max(other),
+            MorphOp::Erode => (*self).min(other),
+        }
+    }
+}
+
+impl MorphNativeOp<i32> for i32 {
+    fn op<const OP: u8>(&self, other: i32) -> i32 {
+        let morph_op: MorphOp = OP.into();
+        match morph_op {
+            MorphOp::Dilate => (*self).max(other),
+            MorphOp::Erode => (*self).min(other),
+        }
+    }
+}
+
+impl MorphNativeOp<u32> for u32 {
+    fn op<const OP: u8>(&self, other: u32) -> u32 {
+        let morph_op: MorphOp = OP.into();
+        match morph_op {
+            MorphOp::Dilate => (*self).max(other),
+            MorphOp::Erode => (*self).min(other),
+        }
+    }
+}
+
+impl MorphNativeOp<f64> for f64 {
+    fn op<const OP: u8>(&self, other: f64) -> f64 {
+        let morph_op: MorphOp = OP.into();
+        match morph_op {
+            MorphOp::Dilate => (*self).max(other),
+            MorphOp::Erode => (*self).min(other),
+        }
+    }
+}
+
+#[cfg(feature = "half")]
+impl MorphNativeOp<f16> for f16 {
+    fn op<const OP: u8>(&self, other: f16) -> f16 {
+        let morph_op: MorphOp = OP.into();
+        match morph_op {
+            MorphOp::Dilate => (*self).max(other),
+            MorphOp::Erode => (*self).min(other),
+        }
+    }
+
+    /// `f16::from_f64` goes through f32 when F16C is available and may round twice,
+    /// so the value is rounded to odd in f32 first, which makes the second rounding exact
+    fn from_scalar(v: f64) -> f16 {
+        let narrow = v as f32;
+        if !narrow.is_finite() || narrow as f64 == v || narrow.to_bits() & 1 == 1 {
+            return f16::from_f32(narrow);
+        }
+        let bits = narrow.to_bits();
+        let odd = if v.abs() > narrow.abs() as f64 {
+            bits + 1
+        } else {
+            bits - 1
+        };
+        f16::from_f32(f32::from_bits(odd))
+    }
+}
+
+/// Applies weight of non-flat structuring element, saturating for integers
+pub trait MorphWeightedOp<T> {
+    /// Weight rounded to the pixel type, erosion subtracts weights so they are negated
+    type Weight: Copy + Send + Sync;
+
+    fn make_weight<const OP: u8>(weight: f32) -> Self::Weight;
+
+    fn add_weight(&self, weight: Self::Weight) -> T;
+
+    /// Whether the weight is zero once rounded to the pixel type,
+    /// elements made of such weights are processed as flat ones
+    fn is_zero_weight(weight: f32) -> bool {
+        weight == 0.
+    }
+}
+
+impl MorphWeightedOp<u8> for u8 {
+    type Weight = i32;
+
+    fn make_weight<const OP: u8>(weight: f32) -> i32 {
+        let morph_op: MorphOp = OP.into();
+        let weight = (weight.round() as i32).clamp(-(u8::MAX as i32), u8::MAX as i32);
+        match morph_op {
+            MorphOp::Dilate => weight,
+            MorphOp::Erode => -weight,
+        }
+    }
+
+    fn add_weight(&self, weight: i32) -> u8 {
+        (*self as i32 + weight).clamp(0, u8::MAX as i32) as u8
+    }
+
+    fn is_zero_weight(weight: f32) -> bool {
+        weight.round() == 0.
+    }
+}
+
+impl MorphWeightedOp<u16> for u16 {
+    type Weight = i32;
+
+    fn make_weight<const OP: u8>(weight: f32) -> i32 {
+        let morph_op: MorphOp = OP.into();
+        let weight = (weight.round() as i32).clamp(-(u16::MAX as i32), u16::MAX as i32);
+        match morph_op {
+            MorphOp::Dilate => weight,
+            MorphOp::Erode => -weight,
+        }
+    }
+
+    fn add_weight(&self, weight: i32) -> u16 {
+        (*self as i32 + weight).clamp(0, u16::MAX as i32) as u16
+    }
+
+    fn is_zero_weight(weight: f32) -> bool {
+        weight.round() == 0.
+    }
+}
+
+impl MorphWeightedOp<f32> for f32 {
+    type Weight = f32;
+
+    fn make_weight<const OP: u8>(weight: f32) -> f32 {
+        let morph_op: MorphOp = OP.into();
+        match morph_op {
+            MorphOp::Dilate => weight,
+            MorphOp::Erode => -weight,
+        }
+    }
+
+    fn add_weight(&self, weight: f32) -> f32 {
+        *self + weight
+    }
+}
+
+impl MorphWeightedOp<i8> for i8 {
+    type Weight = i32;
+
+    fn make_weight<const OP: u8>(weight: f32) -> i32 {
+        let morph_op: MorphOp = OP.into();
+        let weight = (weight.round() as i32).clamp(-(u8::MAX as i32), u8::MAX as i32);
+        match morph_op {
+            MorphOp::Dilate => weight,
+            MorphOp::Erode => -weight,
+        }
+    }
+
+    fn add_weight(&self, weight: i32) -> i8 {
+        (*self as i32 + weight).clamp(i8::MIN as i32, i8::MAX as i32) as i8
+    }
+
+    fn is_zero_weight(weight: f32) -> bool {
+        weight.round() == 0.
+    }
+}
+
+impl MorphWeightedOp<i16> for i16 {
+    type Weight = i32;
+
+    fn make_weight<const OP: u8>(weight: f32) -> i32 {
+        let morph_op: MorphOp = OP.into();
+        let weight = (weight.round() as i32).clamp(-(u16::MAX as i32), u16::MAX as i32);
+        match morph_op {
+            MorphOp::Dilate => weight,
+            MorphOp::Erode => -weight,
+        }
+    }
+
+    fn add_weight(&self, weight: i32) -> i16 {
+        (*self as i32 + weight).clamp(i16::MIN as i32, i16::MAX as i32) as i16
+    }
+
+    fn is_zero_weight(weight: f32) -> bool {
+        weight.round() == 0.
+    }
+}
+
+impl MorphWeightedOp<i32> for i32 {
+    type Weight = i64;
+
+    fn make_weight<const OP: u8>(weight: f32) -> i64 {
+        let morph_op: MorphOp = OP.into();
+        let weight = (weight.round() as i64).clamp(-(u32::MAX as i64), u32::MAX as i64);
+        match morph_op {
+            MorphOp::Dilate => weight,
+            MorphOp::Erode => -weight,
+        }
+    }
+
+    fn add_weight(&self, weight: i64) -> i32 {
+        (*self as i64 + weight).clamp(i32::MIN as i64, i32::MAX as i64) as i32
+    }
+
+    fn is_zero_weight(weight: f32) -> bool {
+        weight.round() == 0.
+    }
+}
+
+impl MorphWeightedOp<u32> for u32 {
+    type Weight = i64;
+
+    fn make_weight<const OP: u8>(weight: f32) -> i64 {
+        let morph_op: MorphOp = OP.into();
+        let weight = (weight.round() as i64).clamp(-(u32::MAX as i64), u32::MAX as i64);
+        match morph_op {
+            MorphOp::Dilate => weight,
+            MorphOp::Erode => -weight,
+        }
+    }
+
+    fn add_weight(&self, weight: i64) -> u32 {
+        (*self as i64 + weight).clamp(0, u32::MAX as i64) as u32
+    }
+
+    fn is_zero_weight(weight: f32) -> bool {
+        weight.round() == 0.
+    }
+}
+
+impl MorphWeightedOp<f64> for f64 {
+    type Weight = f64;
+
+    fn make_weight<const OP: u8>(weight: f32) -> f64 {
+        let morph_op: MorphOp = OP.into();
+        match morph_op {
+            MorphOp::Dilate => weight as f64,
+            MorphOp::Erode => -weight as f64,
+        }
+    }
+
+    fn add_weight(&self, weight: f64) -> f64 {
+        *self + weight
+    }
+}
+
+#[cfg(feature = "half")]
+impl MorphWeightedOp<f16> for f16 {
+    /// Sum is computed in f32 and rounded once to f16
+    type Weight = f32;
+
+    fn make_weight<const OP: u8>(weight: f32) -> f32 {
+        let morph_op: MorphOp = OP.into();
+        match morph_op {
+            MorphOp::Dilate => weight,
+            MorphOp::Erode => -weight,
+        }
+    }
+
+    fn add_weight(&self, weight: f32) -> f16 {
+        f16::from_f32(self.to_f32() + weight)
+    }
+}
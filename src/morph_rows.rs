@@ -0,0 +1,376 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+#[cfg(all(feature = "half", any(target_arch = "x86", target_arch = "x86_64")))]
+use crate::avx::morph_rows_f16_avx;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use crate::avx::{
+    morph_rows_f32_avx, morph_rows_f64_avx, morph_rows_int_avx, morph_rows_u16_avx,
+    morph_rows_u8_avx,
+};
+use crate::morph_base::MorphNativeOp;
+#[cfg(all(feature = "half", target_arch = "aarch64", target_feature = "neon"))]
+use crate::neon::morph_rows_f16_neon;
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+use crate::neon::{
+    morph_rows_f32_neon, morph_rows_f64_neon, morph_rows_int_neon, morph_rows_u16_neon,
+    morph_rows_u8_neon,
+};
+use crate::op_type::MorphOp;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use crate::sse::{
+    morph_rows_f32_sse, morph_rows_f64_sse, morph_rows_int_sse, morph_rows_u16_sse,
+    morph_rows_u8_sse,
+};
+use crate::transpose::{scalar_transpose, transpose_handler, TransposeHandler};
+#[cfg(feature = "half")]
+use half::f16;
+
+/// Handler that computes `dst = op(a, b)` element-wise
+pub type MorphRowsHandler<T> = fn(&[T], &[T], &mut [T]);
+
+/// Element-wise min/max of two rows, used by separable and decomposed structuring elements,
+/// scalar loop is used unless the type provides a vectorised one
+pub trait MorphRows<T> {
+    fn get_rows_op(op: MorphOp) -> MorphRowsHandler<T>
+    where
+        T: Copy + MorphNativeOp<T>,
+    {
+        scalar_rows_op(op)
+    }
+
+    /// Transposition used to run van Herk/Gil-Werman algorithm along rows,
+    /// scalar one is used unless the type provides a vectorised one
+    fn get_transpose() -> TransposeHandler<T>
+    where
+        T: Copy + Default,
+    {
+        scalar_transpose::<T>
+    }
+}
+
+/// Scalar rows handler, available for every sample type
+pub(crate) fn scalar_rows_op<T>(op: MorphOp) -> MorphRowsHandler<T>
+where
+    T: Copy + MorphNativeOp<T>,
+{
+    match op {
+        MorphOp::Dilate => morph_rows::<T, { MorphOp::Dilate as u8 }>,
+        MorphOp::Erode => morph_rows::<T, { MorphOp::Erode as u8 }>,
+    }
+}
+
+fn morph_rows<T, const OP_TYPE: u8>(a: &[T], b: &[T], dst: &mut [T])
+where
+    T: Copy + MorphNativeOp<T>,
+{
+    for ((a, b), dst) in a.iter().zip(b.iter()).zip(dst.iter_mut()) {
+        *dst = a.op::<OP_TYPE>(*b);
+    }
+}
+
+fn rows_handler_u8<const OP_TYPE: u8>() -> MorphRowsHandler<u8> {
+    let mut _dispatcher: MorphRowsHandler<u8> = morph_rows::<u8, OP_TYPE>;
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        _dispatcher = morph_rows_u8_neon::<OP_TYPE>;
+    }
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if std::arch::is_x86_feature_detected!("sse4.1") {
+            _dispatcher = morph_rows_u8_sse::<OP_TYPE>;
+        }
+        if std::arch::is_x86_feature_detected!("avx2") {
+            _dispatcher = morph_rows_u8_avx::<OP_TYPE>;
+        }
+    }
+    _dispatcher
+}
+
+fn rows_handler_u16<const OP_TYPE: u8>() -> MorphRowsHandler<u16> {
+    let mut _dispatcher: MorphRowsHandler<u16> = morph_rows::<u16, OP_TYPE>;
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        _dispatcher = morph_rows_u16_neon::<OP_TYPE>;
+    }
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if std::arch::is_x86_feature_detected!("sse4.1") {
+            _dispatcher = morph_rows_u16_sse::<OP_TYPE>;
+        }
+        if std::arch::is_x86_feature_detected!("avx2") {
+            _dispatcher = morph_rows_u16_avx::<OP_TYPE>;
+        }
+    }
+    _dispatcher
+}
+
+fn rows_handler_f32<const OP_TYPE: u8>() -> MorphRowsHandler<f32> {
+    let mut _dispatcher: MorphRowsHandler<f32> = morph_rows::<f32, OP_TYPE>;
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        _dispatcher = morph_rows_f32_neon::<OP_TYPE>;
+    }
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if std::arch::is_x86_feature_detected!("sse4.1") {
+            _dispatcher = morph_rows_f32_sse::<OP_TYPE>;
+        }
+        if std::arch::is_x86_feature_detected!("avx2") {
+            _dispatcher = morph_rows_f32_avx::<OP_TYPE>;
+        }
+    }
+    _dispatcher
+}
+
+fn rows_handler_f64<const OP_TYPE: u8>() -> MorphRowsHandler<f64> {
+    let mut _dispatcher: MorphRowsHandler<f64> = morph_rows::<f64, OP_TYPE>;
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        _dispatcher = morph_rows_f64_neon::<OP_TYPE>;
+    }
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if std::arch::is_x86_feature_detected!("sse4.1") {
+            _dispatcher = morph_rows_f64_sse::<OP_TYPE>;
+        }
+        if std::arch::is_x86_feature_detected!("avx2") {
+            _dispatcher = morph_rows_f64_avx::<OP_TYPE>;
+        }
+    }
+    _dispatcher
+}
+
+#[cfg(feature = "half")]
+fn rows_handler_f16<const OP_TYPE: u8>() -> MorphRowsHandler<f16> {
+    let mut _dispatcher: MorphRowsHandler<f16> = morph_rows::<f16, OP_TYPE>;
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        if std::arch::is_aarch64_feature_detected!("fp16") {
+            _dispatcher = morph_rows_f16_neon::<OP_TYPE>;
+        }
+    }
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if std::arch::is_x86_feature_detected!("avx") && std::arch::is_x86_feature_detected!("f16c")
+        {
+            _dispatcher = morph_rows_f16_avx::<OP_TYPE>;
+        }
+    }
+    _dispatcher
+}
+
+fn rows_handler_i8<const OP_TYPE: u8>() -> MorphRowsHandler<i8> {
+    let mut _dispatcher: MorphRowsHandler<i8> = morph_rows::<i8, OP_TYPE>;
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        _dispatcher = morph_rows_int_neon::<i8, OP_TYPE>;
+    }
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if std::arch::is_x86_feature_detected!("sse4.1") {
+            _dispatcher = morph_rows_int_sse::<i8, OP_TYPE>;
+        }
+        if std::arch::is_x86_feature_detected!("avx2") {
+            _dispatcher = morph_rows_int_avx::<i8, OP_TYPE>;
+        }
+    }
+    _dispatcher
+}
+
+fn rows_handler_i16<const OP_TYPE: u8>() -> MorphRowsHandler<i16> {
+    let mut _dispatcher: MorphRowsHandler<i16> = morph_rows::<i16, OP_TYPE>;
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        _dispatcher = morph_rows_int_neon::<i16, OP_TYPE>;
+    }
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if std::arch::is_x86_feature_detected!("sse4.1") {
+            _dispatcher = morph_rows_int_sse::<i16, OP_TYPE>;
+        }
+        if std::arch::is_x86_feature_detected!("avx2") {
+            _dispatcher = morph_rows_int_avx::<i16, OP_TYPE>;
+        }
+    }
+    _dispatcher
+}
+
+fn rows_handler_i32<const OP_TYPE: u8>() -> MorphRowsHandler<i32> {
+    let mut _dispatcher: MorphRowsHandler<i32> = morph_rows::<i32, OP_TYPE>;
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        _dispatcher = morph_rows_int_neon::<i32, OP_TYPE>;
+    }
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if std::arch::is_x86_feature_detected!("sse4.1") {
+            _dispatcher = morph_rows_int_sse::<i32, OP_TYPE>;
+        }
+        if std::arch::is_x86_feature_detected!("avx2") {
+            _dispatcher = morph_rows_int_avx::<i32, OP_TYPE>;
+        }
+    }
+    _dispatcher
+}
+
+fn rows_handler_u32<const OP_TYPE: u8>() -> MorphRowsHandler<u32> {
+    let mut _dispatcher: MorphRowsHandler<u32> = morph_rows::<u32, OP_TYPE>;
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        _dispatcher = morph_rows_int_neon::<u32, OP_TYPE>;
+    }
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if std::arch::is_x86_feature_detected!("sse4.1") {
+            _dispatcher = morph_rows_int_sse::<u32, OP_TYPE>;
+        }
+        if std::arch::is_x86_feature_detected!("avx2") {
+            _dispatcher = morph_rows_int_avx::<u32, OP_TYPE>;
+        }
+    }
+    _dispatcher
+}
+
+impl MorphRows<u8> for u8 {
+    fn get_rows_op(op: MorphOp) -> MorphRowsHandler<u8> {
+        match op {
+            MorphOp::Dilate => rows_handler_u8::<{ MorphOp::Dilate as u8 }>(),
+            MorphOp::Erode => rows_handler_u8::<{ MorphOp::Erode as u8 }>(),
+        }
+    }
+
+    fn get_transpose() -> TransposeHandler<u8> {
+        transpose_handler::<u8>()
+    }
+}
+
+impl MorphRows<u16> for u16 {
+    fn get_rows_op(op: MorphOp) -> MorphRowsHandler<u16> {
+        match op {
+            MorphOp::Dilate => rows_handler_u16::<{ MorphOp::Dilate as u8 }>(),
+            MorphOp::Erode => rows_handler_u16::<{ MorphOp::Erode as u8 }>(),
+        }
+    }
+
+    fn get_transpose() -> TransposeHandler<u16> {
+        transpose_handler::<u16>()
+    }
+}
+
+impl MorphRows<f32> for f32 {
+    fn get_rows_op(op: MorphOp) -> MorphRowsHandler<f32> {
+        match op {
+            MorphOp::Dilate => rows_handler_f32::<{ MorphOp::Dilate as u8 }>(),
+            MorphOp::Erode => rows_handler_f32::<{ MorphOp::Erode as u8 }>(),
+        }
+    }
+
+    fn get_transpose() -> TransposeHandler<f32> {
+        transpose_handler::<f32>()
+    }
+}
+
+impl MorphRows<i8> for i8 {
+    fn get_rows_op(op: MorphOp) -> MorphRowsHandler<i8> {
+        match op {
+            MorphOp::Dilate => rows_handler_i8::<{ MorphOp::Dilate as u8 }>(),
+            MorphOp::Erode => rows_handler_i8::<{ MorphOp::Erode as u8 }>(),
+        }
+    }
+
+    fn get_transpose() -> TransposeHandler<i8> {
+        transpose_handler::<i8>()
+    }
+}
+
+impl MorphRows<i16> for i16 {
+    fn get_rows_op(op: MorphOp) -> MorphRowsHandler<i16> {
+        match op {
+            MorphOp::Dilate => rows_handler_i16::<{ MorphOp::Dilate as u8 }>(),
+            MorphOp::Erode => rows_handler_i16::<{ MorphOp::Erode as u8 }>(),
+        }
+    }
+
+    fn get_transpose() -> TransposeHandler<i16> {
+        transpose_handler::<i16>()
+    }
+}
+
+impl MorphRows<i32> for i32 {
+    fn get_rows_op(op: MorphOp) -> MorphRowsHandler<i32> {
+        match op {
+            MorphOp::Dilate => rows_handler_i32::<{ MorphOp::Dilate as u8 }>(),
+            MorphOp::Erode => rows_handler_i32::<{ MorphOp::Erode as u8 }>(),
+        }
+    }
+
+    fn get_transpose() -> TransposeHandler<i32> {
+        transpose_handler::<i32>()
+    }
+}
+
+impl MorphRows<u32> for u32 {
+    fn get_rows_op(op: MorphOp) -> MorphRowsHandler<u32> {
+        match op {
+            MorphOp::Dilate => rows_handler_u32::<{ MorphOp::Dilate as u8 }>(),
+            MorphOp::Erode => rows_handler_u32::<{ MorphOp::Erode as u8 }>(),
+        }
+    }
+
+    fn get_transpose() -> TransposeHandler<u32> {
+        transpose_handler::<u32>()
+    }
+}
+
+impl MorphRows<f64> for f64 {
+    fn get_rows_op(op: MorphOp) -> MorphRowsHandler<f64> {
+        match op {
+            MorphOp::Dilate => rows_handler_f64::<{ MorphOp::Dilate as u8 }>(),
+            MorphOp::Erode => rows_handler_f64::<{ MorphOp::Erode as u8 }>(),
+        }
+    }
+
+    fn get_transpose() -> TransposeHandler<f64> {
+        transpose_handler::<f64>()
+    }
+}
+
+#[cfg(feature = "half")]
+impl MorphRows<f16> for f16 {
+    fn get_rows_op(op: MorphOp) -> MorphRowsHandler<f16> {
+        match op {
+            MorphOp::Dilate => rows_handler_f16::<{ MorphOp::Dilate as u8 }>(),
+            MorphOp::Erode => rows_handler_f16::<{ MorphOp::Erode as u8 }>(),
+        }
+    }
+
+    fn get_transpose() -> TransposeHandler<f16> {
+        transpose_handler::<f16>()
+    }
+}
@@ -0,0 +1,184 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::alpha::AlphaMode;
+use crate::border_mode::{BorderMode, MorphScalar};
+use crate::error::MorphologyError;
+use crate::structuring_element::KernelShape;
+use crate::MorphologyThreadingPolicy;
+
+/// Instructions used by row filters
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)]
+pub enum MorphologyBackend {
+    /// Best vectorised filters available on the running CPU
+    #[default]
+    Auto,
+    /// Portable scalar filters, results are the same as with [MorphologyBackend::Auto]
+    Scalar,
+}
+
+/// Options of morphology calls, every option has a default.
+///
+/// Options are validated when they are used, see [MorphologyOptions::validate].
+#[derive(Clone, Debug)]
+pub struct MorphologyOptions {
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    border_values: Vec<f64>,
+    anchor: Option<(usize, usize)>,
+    iterations: usize,
+    threading_policy: MorphologyThreadingPolicy,
+    backend: MorphologyBackend,
+    alpha_mode: AlphaMode,
+}
+
+impl Default for MorphologyOptions {
+    fn default() -> Self {
+        MorphologyOptions {
+            border_mode: BorderMode::default(),
+            border_scalar: MorphScalar::default(),
+            border_values: vec![],
+            anchor: None,
+            iterations: 1,
+            threading_policy: MorphologyThreadingPolicy::default(),
+            backend: MorphologyBackend::default(),
+            alpha_mode: AlphaMode::default(),
+        }
+    }
+}
+
+impl MorphologyOptions {
+    /// Border handling mode, [BorderMode::Clamp] by default
+    pub fn with_border_mode(mut self, border_mode: BorderMode) -> Self {
+        self.border_mode = border_mode;
+        self
+    }
+
+    /// Values of pixels outside of the image in [BorderMode::Constant], one value per channel,
+    /// zeros by default
+    pub fn with_border_scalar(mut self, border_scalar: MorphScalar) -> Self {
+        self.border_scalar = border_scalar;
+        self
+    }
+
+    /// Values of pixels outside of the image in [BorderMode::Constant] for every channel
+    /// in memory order, replace the border scalar when not empty. Interleaved layouts with more
+    /// than four channels need them to give each channel its own value.
+    /// Must hold one value per channel of the layout.
+    pub fn with_border_values(mut self, border_values: &[f64]) -> Self {
+        self.border_values = border_values.to_vec();
+        self
+    }
+
+    /// Origin of the structuring element, replaces the anchor of its [KernelShape].
+    /// By default the anchor of the [KernelShape] is used.
+    pub fn with_anchor(mut self, anchor_x: usize, anchor_y: usize) -> Self {
+        self.anchor = Some((anchor_x, anchor_y));
+        self
+    }
+
+    /// Count of times dilations and erosions are applied, each time the border is padded again
+    /// as OpenCV does. Opening with 2 iterations is two erosions followed by two dilations.
+    /// 1 by default, must not be 0.
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Threads usage policy, [MorphologyThreadingPolicy::Adaptive] by default
+    pub fn with_threading_policy(mut self, threading_policy: MorphologyThreadingPolicy) -> Self {
+        self.threading_policy = threading_policy;
+        self
+    }
+
+    /// Instructions used by row filters, [MorphologyBackend::Auto] by default
+    pub fn with_backend(mut self, backend: MorphologyBackend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Handling of the alpha channel, [AlphaMode::Process] by default
+    pub fn with_alpha_mode(mut self, alpha_mode: AlphaMode) -> Self {
+        self.alpha_mode = alpha_mode;
+        self
+    }
+
+    pub fn border_mode(&self) -> BorderMode {
+        self.border_mode
+    }
+
+    pub fn border_scalar(&self) -> MorphScalar {
+        self.border_scalar
+    }
+
+    pub fn border_values(&self) -> &[f64] {
+        &self.border_values
+    }
+
+    pub fn anchor(&self) -> Option<(usize, usize)> {
+        self.anchor
+    }
+
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    pub fn threading_policy(&self) -> &MorphologyThreadingPolicy {
+        &self.threading_policy
+    }
+
+    pub fn backend(&self) -> MorphologyBackend {
+        self.backend
+    }
+
+    pub fn alpha_mode(&self) -> AlphaMode {
+        self.alpha_mode
+    }
+
+    /// Checks options that do not depend on the structuring element,
+    /// the anchor is checked against it by [MorphologyOptions::kernel_shape]
+    pub fn validate(&self) -> Result<(), MorphologyError> {
+        if self.iterations == 0 {
+            return Err(MorphologyError::ZeroIterations);
+        }
+        Ok(())
+    }
+
+    /// Structuring element size with the anchor of options
+    pub fn kernel_shape(&self, structuring_element_size: KernelShape) -> KernelShape {
+        match self.anchor {
+            Some((anchor_x, anchor_y)) => KernelShape::new_with_anchor(
+                structuring_element_size.width,
+                structuring_element_size.height,
+                anchor_x,
+                anchor_y,
+            ),
+            None => structuring_element_size,
+        }
+    }
+}
@@ -0,0 +1,1761 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::alpha::AlphaMode;
+use crate::arena::ArenaPlacement;
+use crate::border_mode::{BorderMode, MorphScalar};
+use crate::difference::{morph_gradient_in_place, morph_gradient_strided, MorphGradient};
+use crate::error::MorphologyError;
+use crate::filter::Row2DFilter;
+use crate::generic::MorphologySample;
+use crate::img_size::{image_rows, image_rows_mut, Roi};
+use crate::morph_base::{MorphNativeOp, MorphWeightedOp};
+use crate::morph_rows::MorphRows;
+use crate::morphology_options::{MorphologyBackend, MorphologyOptions};
+use crate::op_impl::{
+    check_image, check_layout, check_packed_image, check_roi, check_strided_image, PlanarMorphology,
+};
+use crate::op_type::{MorphExOp, MorphOp};
+use crate::packing::{
+    GrayAlphaPackable, InterleavedPackable, RgbPackable, RgbaPackable, UnpackedGrayAlpha,
+    UnpackedInterleaved, UnpackedRgbImage, UnpackedRgbaImage,
+};
+use crate::structuring_element::KernelShape;
+use crate::thread_policy::ThreadingPool;
+use crate::{ImageSize, MorphologyThreadingPolicy};
+use num_traits::AsPrimitive;
+
+/// Channels layout of images processed by [MorphologyPlan]
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Default)]
+pub enum MorphologyLayout {
+    /// Single plane
+    #[default]
+    Gray,
+    /// Interleaved gray and alpha
+    GrayAlpha,
+    /// Interleaved RGB
+    Rgb,
+    /// Interleaved RGBA
+    Rgba,
+    /// Interleaved BGR
+    Bgr,
+    /// Interleaved BGRA
+    Bgra,
+    /// Interleaved ARGB
+    Argb,
+    /// Interleaved ABGR
+    Abgr,
+    /// Interleaved image with any count of channels, for example multispectral bands.
+    /// Two, three and four channels are unpacked as fast as [MorphologyLayout::GrayAlpha],
+    /// [MorphologyLayout::Rgb] and [MorphologyLayout::Rgba], other counts by a scalar loop.
+    /// Channels past the fourth use the last value of the border scalar, a value for every
+    /// channel is given by [MorphologyOptions::with_border_values].
+    Interleaved(usize),
+}
+
+impl MorphologyLayout {
+    /// Count of interleaved channels
+    pub fn channels(&self) -> usize {
+        match self {
+            MorphologyLayout::Gray => 1,
+            MorphologyLayout::GrayAlpha => 2,
+            MorphologyLayout::Rgb | MorphologyLayout::Bgr => 3,
+            MorphologyLayout::Rgba
+            | MorphologyLayout::Bgra
+            | MorphologyLayout::Argb
+            | MorphologyLayout::Abgr => 4,
+            MorphologyLayout::Interleaved(channels) => *channels,
+        }
+    }
+
+    /// Index of the [MorphScalar] value used as border constant of the channel at `position`
+    /// in a pixel, `None` when the layout has no such channel. Color layouts read the scalar
+    /// as red, green, blue and alpha whatever the order of channels in memory is,
+    /// other layouts read it by position.
+    pub fn scalar_index(&self, position: usize) -> Option<usize> {
+        match self {
+            MorphologyLayout::Bgr => [2, 1, 0].get(position).copied(),
+            MorphologyLayout::Bgra => [2, 1, 0, 3].get(position).copied(),
+            MorphologyLayout::Argb => [3, 0, 1, 2].get(position).copied(),
+            MorphologyLayout::Abgr => [3, 2, 1, 0].get(position).copied(),
+            _ => (position < self.channels()).then_some(position.min(3)),
+        }
+    }
+
+    /// Position of the alpha channel in a pixel, `None` for layouts without alpha
+    pub fn alpha_position(&self) -> Option<usize> {
+        match self {
+            MorphologyLayout::GrayAlpha => Some(1),
+            MorphologyLayout::Rgba | MorphologyLayout::Bgra => Some(3),
+            MorphologyLayout::Argb | MorphologyLayout::Abgr => Some(0),
+            _ => None,
+        }
+    }
+}
+
+/// Planes of an interleaved image in memory order of channels.
+///
+/// Deinterleaving does not depend on what channels mean, so every three and four channel
+/// order is unpacked by the vectorised RGB and RGBA routines, and [MorphologyLayout::scalar_index]
+/// gives border constants of planes.
+enum PlanarImage<T> {
+    GrayAlpha(UnpackedGrayAlpha<T>),
+    Rgb(UnpackedRgbImage<T>),
+    Rgba(UnpackedRgbaImage<T>),
+    Interleaved(UnpackedInterleaved<T>),
+}
+
+impl<T> PlanarImage<T>
+where
+    T: Copy
+        + Default
+        + GrayAlphaPackable<T>
+        + RgbPackable<T>
+        + RgbaPackable<T>
+        + InterleavedPackable<T>,
+{
+    fn alloc(layout: MorphologyLayout, image_size: ImageSize) -> Self {
+        match layout.channels() {
+            0 | 1 => unreachable!("Single plane images are not unpacked"),
+            2 => PlanarImage::GrayAlpha(UnpackedGrayAlpha::alloc(image_size)),
+            3 => PlanarImage::Rgb(UnpackedRgbImage::alloc(image_size)),
+            4 => PlanarImage::Rgba(UnpackedRgbaImage::alloc(image_size)),
+            channels => PlanarImage::Interleaved(UnpackedInterleaved::alloc(image_size, channels)),
+        }
+    }
+
+    fn unpack(&mut self, src: &[T], src_stride: usize, image_size: ImageSize) {
+        match self {
+            PlanarImage::GrayAlpha(planes) => {
+                <T as GrayAlphaPackable<T>>::unpack(src, src_stride, planes, image_size)
+            }
+            PlanarImage::Rgb(planes) => {
+                <T as RgbPackable<T>>::unpack(src, src_stride, planes, image_size)
+            }
+            PlanarImage::Rgba(planes) => {
+                <T as RgbaPackable<T>>::unpack(src, src_stride, planes, image_size)
+            }
+            PlanarImage::Interleaved(planes) => {
+                <T as InterleavedPackable<T>>::unpack(src, src_stride, planes, image_size)
+            }
+        }
+    }
+
+    fn pack(&self, dst: &mut [T], dst_stride: usize, image_size: ImageSize) {
+        match self {
+            PlanarImage::GrayAlpha(planes) => {
+                <T as GrayAlphaPackable<T>>::pack(planes, dst, dst_stride, image_size)
+            }
+            PlanarImage::Rgb(planes) => {
+                <T as RgbPackable<T>>::pack(planes, dst, dst_stride, image_size)
+            }
+            PlanarImage::Rgba(planes) => {
+                <T as RgbaPackable<T>>::pack(planes, dst, dst_stride, image_size)
+            }
+            PlanarImage::Interleaved(planes) => {
+                <T as InterleavedPackable<T>>::pack(planes, dst, dst_stride, image_size)
+            }
+        }
+    }
+
+    fn plane(&self, channel: usize) -> &[T] {
+        match self {
+            PlanarImage::GrayAlpha(planes) => match channel {
+                0 => &planes.gray_channel,
+                _ => &planes.alpha_channel,
+            },
+            PlanarImage::Rgb(planes) => match channel {
+                0 => &planes.r_channel,
+                1 => &planes.g_channel,
+                _ => &planes.b_channel,
+            },
+            PlanarImage::Rgba(planes) => match channel {
+                0 => &planes.r_channel,
+                1 => &planes.g_channel,
+                2 => &planes.b_channel,
+                _ => &planes.a_channel,
+            },
+            PlanarImage::Interleaved(planes) => &planes.planes[channel],
+        }
+    }
+
+    fn plane_mut(&mut self, channel: usize) -> &mut Vec<T> {
+        match self {
+            PlanarImage::GrayAlpha(planes) => match channel {
+                0 => &mut planes.gray_channel,
+                _ => &mut planes.alpha_channel,
+            },
+            PlanarImage::Rgb(planes) => match channel {
+                0 => &mut planes.r_channel,
+                1 => &mut planes.g_channel,
+                _ => &mut planes.b_channel,
+            },
+            PlanarImage::Rgba(planes) => match channel {
+                0 => &mut planes.r_channel,
+                1 => &mut planes.g_channel,
+                2 => &mut planes.b_channel,
+                _ => &mut planes.a_channel,
+            },
+            PlanarImage::Interleaved(planes) => &mut planes.planes[channel],
+        }
+    }
+
+    /// Applies `f` to every color plane with the alpha plane
+    fn apply_alpha(&mut self, channels: usize, alpha: usize, f: fn(&mut [T], &[T])) {
+        let alpha_plane = std::mem::take(self.plane_mut(alpha));
+        for channel in (0..channels).filter(|&channel| channel != alpha) {
+            f(self.plane_mut(channel), &alpha_plane);
+        }
+        *self.plane_mut(alpha) = alpha_plane;
+    }
+}
+
+/// Dilation and erosion planned for one plane, composed into [MorphExOp].
+///
+/// Operation produces `region` of the whole image reading `source_window` of it.
+/// The first pass of two pass operations runs on `transient_region`, the region expanded
+/// by the second structuring element, so regions have no seams with the whole image result.
+/// When it is the region itself the first pass writes straight into the arena of the
+/// second one, otherwise into `transient`. Arenas of finished passes are reused as
+/// scratch images, so whole images need no other buffers.
+///
+/// More than one iteration is planned only for whole images, each dilation and erosion is
+/// then repeated on its own result, and results that are subtracted from another image
+/// are kept in `iterated`.
+struct CompositeMorphology<T: 'static + MorphWeightedOp<T>> {
+    morph_op: MorphExOp,
+    dilation: Option<PlanarMorphology<T>>,
+    erosion: Option<PlanarMorphology<T>>,
+    region: Roi,
+    source_window: Roi,
+    transient_region: Roi,
+    transient: Vec<T>,
+    iterations: usize,
+    iterated: Vec<T>,
+}
+
+impl<T> CompositeMorphology<T>
+where
+    T: Copy
+        + Default
+        + 'static
+        + Send
+        + Sync
+        + MorphNativeOp<T>
+        + MorphWeightedOp<T>
+        + Row2DFilter<T>
+        + MorphRows<T>
+        + MorphGradient<T>,
+    f64: AsPrimitive<T>,
+{
+    fn new(
+        morph_op: MorphExOp,
+        image_size: ImageSize,
+        region: Roi,
+        iterations: usize,
+        planner: impl Fn(MorphOp, ImageSize) -> Result<PlanarMorphology<T>, MorphologyError>,
+    ) -> Result<Self, MorphologyError> {
+        let placement = |region: Roi, window: Roi| ArenaPlacement {
+            image_size,
+            region,
+            window,
+        };
+        let (dilation, erosion, source_window, transient_region) = match morph_op {
+            MorphExOp::Dilate | MorphExOp::Erode | MorphExOp::Gradient => {
+                let mut dilation = match morph_op {
+                    MorphExOp::Erode => None,
+                    _ => Some(planner(MorphOp::Dilate, region.size())?),
+                };
+                let mut erosion = match morph_op {
+                    MorphExOp::Dilate => None,
+                    _ => Some(planner(MorphOp::Erode, region.size())?),
+                };
+                let source_window = dilation
+                    .iter()
+                    .chain(erosion.iter())
+                    .map(|planned| planned.source_window(image_size, region))
+                    .reduce(|a, b| a.union(b))
+                    .unwrap_or(region);
+                for planned in dilation.iter_mut().chain(erosion.iter_mut()) {
+                    planned.place(placement(region, source_window));
+                }
+                (dilation, erosion, source_window, region)
+            }
+            MorphExOp::Opening | MorphExOp::Closing | MorphExOp::TopHat | MorphExOp::BlackHat => {
+                let (first_op, second_op) = match morph_op {
+                    MorphExOp::Opening | MorphExOp::TopHat => (MorphOp::Erode, MorphOp::Dilate),
+                    _ => (MorphOp::Dilate, MorphOp::Erode),
+                };
+                let mut second = planner(second_op, region.size())?;
+                let transient_region = second.source_window(image_size, region);
+                let mut first = planner(first_op, transient_region.size())?;
+                let source_window = first.source_window(image_size, transient_region);
+                second.place(placement(region, transient_region));
+                first.place(placement(transient_region, source_window));
+                match first_op {
+                    MorphOp::Dilate => (Some(first), Some(second), source_window, transient_region),
+                    MorphOp::Erode => (Some(second), Some(first), source_window, transient_region),
+                }
+            }
+        };
+        let transient = if transient_region != region {
+            vec![T::default(); transient_region.width * transient_region.height]
+        } else {
+            vec![]
+        };
+        debug_assert!(iterations == 1 || region == Roi::whole(image_size));
+        let iterated = match morph_op {
+            MorphExOp::Gradient | MorphExOp::TopHat | MorphExOp::BlackHat if iterations > 1 => {
+                vec![T::default(); region.width * region.height]
+            }
+            _ => vec![],
+        };
+        Ok(CompositeMorphology {
+            morph_op,
+            dilation,
+            erosion,
+            region,
+            source_window,
+            transient_region,
+            transient,
+            iterations,
+            iterated,
+        })
+    }
+
+    /// `src` holds `source_window` with rows `src_stride` items apart
+    /// and `dst` holds `region` with rows `dst_stride` items apart
+    unsafe fn execute(
+        &mut self,
+        src: &[T],
+        src_stride: usize,
+        dst: &mut [T],
+        dst_stride: usize,
+        border_constant: MorphScalar,
+        pool: &ThreadingPool,
+    ) {
+        if self.iterations > 1 {
+            self.execute_iterated(src, src_stride, dst, dst_stride, border_constant, pool);
+            return;
+        }
+        let CompositeMorphology {
+            morph_op,
+            dilation,
+            erosion,
+            region,
+            source_window,
+            transient_region,
+            transient,
+            ..
+        } = self;
+        let ImageSize { width, height } = region.size();
+        // Source pixels of the region for top-hat and black-hat differences
+        let src_region =
+            &src[(region.y - source_window.y) * src_stride + region.x - source_window.x..];
+        match morph_op {
+            MorphExOp::Dilate | MorphExOp::Erode => {
+                let planned = dilation.as_mut().or(erosion.as_mut()).unwrap();
+                planned.execute(src, src_stride, dst, dst_stride, border_constant, pool);
+            }
+            MorphExOp::Gradient => {
+                let (dilation, erosion) = (dilation.as_mut().unwrap(), erosion.as_mut().unwrap());
+                dilation.execute(src, src_stride, dst, dst_stride, border_constant, pool);
+                erosion.load(src, src_stride, border_constant);
+                let eroded = dilation.scratch_mut();
+                erosion.run(eroded, width, pool);
+                morph_gradient_in_place(dst, dst_stride, eroded, width, width, height, true);
+            }
+            MorphExOp::Opening | MorphExOp::Closing | MorphExOp::TopHat | MorphExOp::BlackHat => {
+                let (first, second) = two_passes(*morph_op, dilation, erosion);
+                first.load(src, src_stride, border_constant);
+                transfer(
+                    first,
+                    second,
+                    transient,
+                    *transient_region,
+                    *region,
+                    border_constant,
+                    pool,
+                );
+                match morph_op {
+                    MorphExOp::TopHat => {
+                        let opened = first.scratch_mut();
+                        second.run(opened, width, pool);
+                        morph_gradient_strided(
+                            src_region, src_stride, opened, width, dst, dst_stride, width, height,
+                        );
+                    }
+                    MorphExOp::BlackHat => {
+                        let closed = first.scratch_mut();
+                        second.run(closed, width, pool);
+                        morph_gradient_strided(
+                            closed, width, src_region, src_stride, dst, dst_stride, width, height,
+                        );
+                    }
+                    _ => second.run(dst, dst_stride, pool),
+                }
+            }
+        }
+    }
+
+    /// `image` holds `source_window` with rows `stride` items apart,
+    /// `region` of it is replaced by the result
+    unsafe fn execute_in_place(
+        &mut self,
+        image: &mut [T],
+        stride: usize,
+        border_constant: MorphScalar,
+        pool: &ThreadingPool,
+    ) {
+        if self.iterations > 1 {
+            self.execute_in_place_iterated(image, stride, border_constant, pool);
+            return;
+        }
+        let CompositeMorphology {
+            morph_op,
+            dilation,
+            erosion,
+            region,
+            source_window,
+            transient_region,
+            transient,
+            ..
+        } = self;
+        let ImageSize { width, height } = region.size();
+        let region_offset = (region.y - source_window.y) * stride + region.x - source_window.x;
+        match morph_op {
+            MorphExOp::Dilate | MorphExOp::Erode => {
+                let planned = dilation.as_mut().or(erosion.as_mut()).unwrap();
+                planned.load(image, stride, border_constant);
+                planned.run(&mut image[region_offset..], stride, pool);
+            }
+            MorphExOp::Gradient => {
+                let (dilation, erosion) = (dilation.as_mut().unwrap(), erosion.as_mut().unwrap());
+                dilation.load(image, stride, border_constant);
+                erosion.load(image, stride, border_constant);
+                let image = &mut image[region_offset..];
+                dilation.run(image, stride, pool);
+                let eroded = dilation.scratch_mut();
+                erosion.run(eroded, width, pool);
+                morph_gradient_in_place(image, stride, eroded, width, width, height, true);
+            }
+            MorphExOp::Opening | MorphExOp::Closing | MorphExOp::TopHat | MorphExOp::BlackHat => {
+                let (first, second) = two_passes(*morph_op, dilation, erosion);
+                first.load(image, stride, border_constant);
+                transfer(
+                    first,
+                    second,
+                    transient,
+                    *transient_region,
+                    *region,
+                    border_constant,
+                    pool,
+                );
+                let image = &mut image[region_offset..];
+                match morph_op {
+                    MorphExOp::TopHat | MorphExOp::BlackHat => {
+                        let filtered = first.scratch_mut();
+                        second.run(filtered, width, pool);
+                        let image_is_dilation = *morph_op == MorphExOp::TopHat;
+                        morph_gradient_in_place(
+                            image,
+                            stride,
+                            filtered,
+                            width,
+                            width,
+                            height,
+                            image_is_dilation,
+                        );
+                    }
+                    _ => second.run(image, stride, pool),
+                }
+            }
+        }
+    }
+
+    /// [CompositeMorphology::execute] of the whole image with more than one iteration
+    unsafe fn execute_iterated(
+        &mut self,
+        src: &[T],
+        src_stride: usize,
+        dst: &mut [T],
+        dst_stride: usize,
+        border_constant: MorphScalar,
+        pool: &ThreadingPool,
+    ) {
+        let CompositeMorphology {
+            morph_op,
+            dilation,
+            erosion,
+            region,
+            iterations,
+            iterated,
+            ..
+        } = self;
+        let ImageSize { width, height } = region.size();
+        let repeats = *iterations - 1;
+        match morph_op {
+            MorphExOp::Dilate | MorphExOp::Erode => {
+                let planned = dilation.as_mut().or(erosion.as_mut()).unwrap();
+                planned.execute(src, src_stride, dst, dst_stride, border_constant, pool);
+                repeat(planned, dst, dst_stride, repeats, border_constant, pool);
+            }
+            MorphExOp::Gradient => {
+                let (dilation, erosion) = (dilation.as_mut().unwrap(), erosion.as_mut().unwrap());
+                dilation.execute(src, src_stride, dst, dst_stride, border_constant, pool);
+                repeat(dilation, dst, dst_stride, repeats, border_constant, pool);
+                erosion.execute(src, src_stride, iterated, width, border_constant, pool);
+                repeat(erosion, iterated, width, repeats, border_constant, pool);
+                morph_gradient_in_place(dst, dst_stride, iterated, width, width, height, true);
+            }
+            MorphExOp::Opening | MorphExOp::Closing | MorphExOp::TopHat | MorphExOp::BlackHat => {
+                let (first, second) = two_passes(*morph_op, dilation, erosion);
+                let (filtered, filtered_stride) = match morph_op {
+                    MorphExOp::TopHat | MorphExOp::BlackHat => (iterated.as_mut_slice(), width),
+                    _ => (&mut *dst, dst_stride),
+                };
+                first.execute(
+                    src,
+                    src_stride,
+                    filtered,
+                    filtered_stride,
+                    border_constant,
+                    pool,
+                );
+                repeat(
+                    first,
+                    filtered,
+                    filtered_stride,
+                    repeats,
+                    border_constant,
+                    pool,
+                );
+                repeat(
+                    second,
+                    filtered,
+                    filtered_stride,
+                    *iterations,
+                    border_constant,
+                    pool,
+                );
+                match morph_op {
+                    MorphExOp::TopHat => morph_gradient_strided(
+                        src, src_stride, iterated, width, dst, dst_stride, width, height,
+                    ),
+                    MorphExOp::BlackHat => morph_gradient_strided(
+                        iterated, width, src, src_stride, dst, dst_stride, width, height,
+                    ),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// [CompositeMorphology::execute_in_place] of the whole image with more than one iteration
+    unsafe fn execute_in_place_iterated(
+        &mut self,
+        image: &mut [T],
+        stride: usize,
+        border_constant: MorphScalar,
+        pool: &ThreadingPool,
+    ) {
+        let CompositeMorphology {
+            morph_op,
+            dilation,
+            erosion,
+            region,
+            iterations,
+            iterated,
+            ..
+        } = self;
+        let ImageSize { width, height } = region.size();
+        let iterations = *iterations;
+        match morph_op {
+            MorphExOp::Dilate | MorphExOp::Erode => {
+                let planned = dilation.as_mut().or(erosion.as_mut()).unwrap();
+                repeat(planned, image, stride, iterations, border_constant, pool);
+            }
+            MorphExOp::Gradient => {
+                let (dilation, erosion) = (dilation.as_mut().unwrap(), erosion.as_mut().unwrap());
+                copy_rows(image, stride, iterated, width, region.size());
+                repeat(erosion, iterated, width, iterations, border_constant, pool);
+                repeat(dilation, image, stride, iterations, border_constant, pool);
+                morph_gradient_in_place(image, stride, iterated, width, width, height, true);
+            }
+            MorphExOp::Opening | MorphExOp::Closing => {
+                let (first, second) = two_passes(*morph_op, dilation, erosion);
+                repeat(first, image, stride, iterations, border_constant, pool);
+                repeat(second, image, stride, iterations, border_constant, pool);
+            }
+            MorphExOp::TopHat | MorphExOp::BlackHat => {
+                let (first, second) = two_passes(*morph_op, dilation, erosion);
+                copy_rows(image, stride, iterated, width, region.size());
+                repeat(first, iterated, width, iterations, border_constant, pool);
+                repeat(second, iterated, width, iterations, border_constant, pool);
+                let image_is_dilation = *morph_op == MorphExOp::TopHat;
+                morph_gradient_in_place(
+                    image,
+                    stride,
+                    iterated,
+                    width,
+                    width,
+                    height,
+                    image_is_dilation,
+                );
+            }
+        }
+    }
+
+    /// Both images that gradient, top-hat or black-hat subtract, without subtracting them:
+    /// the minuend goes to `dst` with rows `dst_stride` items apart and the subtrahend
+    /// to `other` with rows `region.width` items apart. The source term is copied
+    /// from the region of `src`.
+    unsafe fn execute_terms(
+        &mut self,
+        src: &[T],
+        src_stride: usize,
+        dst: &mut [T],
+        dst_stride: usize,
+        other: &mut [T],
+        border_constant: MorphScalar,
+        pool: &ThreadingPool,
+    ) {
+        let CompositeMorphology {
+            morph_op,
+            dilation,
+            erosion,
+            region,
+            source_window,
+            transient_region,
+            transient,
+            iterations,
+            ..
+        } = self;
+        let width = region.width;
+        let repeats = *iterations - 1;
+        let src_region =
+            &src[(region.y - source_window.y) * src_stride + region.x - source_window.x..];
+        match morph_op {
+            MorphExOp::Gradient => {
+                let (dilation, erosion) = (dilation.as_mut().unwrap(), erosion.as_mut().unwrap());
+                dilation.execute(src, src_stride, dst, dst_stride, border_constant, pool);
+                repeat(dilation, dst, dst_stride, repeats, border_constant, pool);
+                erosion.execute(src, src_stride, other, width, border_constant, pool);
+                repeat(erosion, other, width, repeats, border_constant, pool);
+            }
+            MorphExOp::TopHat | MorphExOp::BlackHat => {
+                let (filtered, filtered_stride, source, source_stride) = match morph_op {
+                    MorphExOp::TopHat => (&mut *other, width, &mut *dst, dst_stride),
+                    _ => (&mut *dst, dst_stride, &mut *other, width),
+                };
+                let (first, second) = two_passes(*morph_op, dilation, erosion);
+                if repeats > 0 {
+                    first.execute(
+                        src,
+                        src_stride,
+                        filtered,
+                        filtered_stride,
+                        border_constant,
+                        pool,
+                    );
+                    repeat(
+                        first,
+                        filtered,
+                        filtered_stride,
+                        repeats,
+                        border_constant,
+                        pool,
+                    );
+                    repeat(
+                        second,
+                        filtered,
+                        filtered_stride,
+                        *iterations,
+                        border_constant,
+                        pool,
+                    );
+                } else {
+                    first.load(src, src_stride, border_constant);
+                    transfer(
+                        first,
+                        second,
+                        transient,
+                        *transient_region,
+                        *region,
+                        border_constant,
+                        pool,
+                    );
+                    second.run(filtered, filtered_stride, pool);
+                }
+                copy_rows(src_region, src_stride, source, source_stride, region.size());
+            }
+            _ => unreachable!("{:?} is not a difference", morph_op),
+        }
+    }
+}
+
+/// Applies planned pass `times` to the whole image, each time on the previous result
+unsafe fn repeat<T>(
+    planned: &mut PlanarMorphology<T>,
+    image: &mut [T],
+    stride: usize,
+    times: usize,
+    border_constant: MorphScalar,
+    pool: &ThreadingPool,
+) where
+    T: Copy
+        + Default
+        + 'static
+        + Send
+        + Sync
+        + MorphNativeOp<T>
+        + MorphWeightedOp<T>
+        + Row2DFilter<T>
+        + MorphRows<T>,
+    f64: AsPrimitive<T>,
+{
+    for _ in 0..times {
+        planned.load(image, stride, border_constant);
+        planned.run(image, stride, pool);
+    }
+}
+
+/// Copies an image with rows `src_stride` items apart into rows `dst_stride` items apart
+fn copy_rows<T: Copy>(
+    src: &[T],
+    src_stride: usize,
+    dst: &mut [T],
+    dst_stride: usize,
+    image_size: ImageSize,
+) {
+    for (src, dst) in image_rows(src, src_stride, image_size.width)
+        .zip(image_rows_mut(dst, dst_stride, image_size.width))
+        .take(image_size.height)
+    {
+        dst.copy_from_slice(src);
+    }
+}
+
+/// First and second passes of a two pass operation
+fn two_passes<'a, T: 'static + MorphWeightedOp<T>>(
+    morph_op: MorphExOp,
+    dilation: &'a mut Option<PlanarMorphology<T>>,
+    erosion: &'a mut Option<PlanarMorphology<T>>,
+) -> (&'a mut PlanarMorphology<T>, &'a mut PlanarMorphology<T>) {
+    let dilation = dilation.as_mut().unwrap();
+    let erosion = erosion.as_mut().unwrap();
+    match morph_op {
+        MorphExOp::Opening | MorphExOp::TopHat => (erosion, dilation),
+        _ => (dilation, erosion),
+    }
+}
+
+/// Runs loaded first pass and loads its result into the second pass
+unsafe fn transfer<T>(
+    first: &mut PlanarMorphology<T>,
+    second: &mut PlanarMorphology<T>,
+    transient: &mut [T],
+    transient_region: Roi,
+    region: Roi,
+    border_constant: MorphScalar,
+    pool: &ThreadingPool,
+) where
+    T: Copy
+        + Default
+        + 'static
+        + Send
+        + Sync
+        + MorphNativeOp<T>
+        + MorphWeightedOp<T>
+        + Row2DFilter<T>
+        + MorphRows<T>,
+    f64: AsPrimitive<T>,
+{
+    if transient_region == region {
+        let (inner, inner_stride) = second.arena_inner_mut();
+        first.run(inner, inner_stride, pool);
+        second.load_in_place(border_constant);
+    } else {
+        first.run(transient, transient_region.width, pool);
+        second.load(transient, transient_region.width, border_constant);
+    }
+}
+
+/// Morphology planned once for images of the same size and layout.
+///
+/// Structuring element analysis, SIMD dispatch, thread pool, padded arenas and transient
+/// images are made when the plan is created, planes of interleaved layouts are made on the
+/// first execution, so executing it on a sequence of frames does no heap allocation after
+/// the first one with [MorphologyThreadingPolicy::Single].
+/// With more threads the plan does not allocate either, though rayon may occasionally
+/// allocate to schedule tasks.
+///
+/// Plans are available for `u8`, `u16`, `f32`, `f64`, `i8`, `i16`, `i32` and `u32` images,
+/// `f16` with the `half` feature, and any other [MorphologySample].
+pub struct MorphologyPlan<T: 'static + MorphWeightedOp<T>> {
+    image_size: ImageSize,
+    roi: Roi,
+    layout: MorphologyLayout,
+    border_scalar: MorphScalar,
+    /// Border constants of every channel, replace `border_scalar` when not empty
+    border_values: Vec<f64>,
+    composite: CompositeMorphology<T>,
+    /// Unpacked source window and destination for interleaved layouts
+    planes: Option<(PlanarImage<T>, PlanarImage<T>)>,
+    /// Channel of the source window for in-place execution of interleaved layouts
+    channel: Vec<T>,
+    alpha_mode: AlphaMode,
+    /// Source and filtered alpha of the window for in-place premultiplied execution
+    alpha: Vec<T>,
+    alpha_filtered: Vec<T>,
+    /// Region sized terms of premultiplied gradient, top-hat and black-hat
+    terms: Vec<T>,
+    pool: ThreadingPool,
+}
+
+impl<T> MorphologyPlan<T>
+where
+    T: MorphologySample,
+    f64: AsPrimitive<T>,
+{
+    /// Plans morphology with a flat structuring element
+    ///
+    /// # Arguments
+    ///
+    /// * `image_size`: Image size declared by [ImageSize]
+    /// * `layout`: Channels layout of the image, see [MorphologyLayout]
+    /// * `morph_op`: Requested [MorphExOp]
+    /// * `structuring_element`: 2D structuring element
+    /// * `structuring_element_size`: (W,H) structuring element size
+    /// * `border_mode`: Border handling mode, for reference see [BorderMode]
+    /// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant], one value per channel, see [MorphologyLayout::scalar_index]
+    /// * `threading_policy`: Threads usage policy
+    ///
+    pub fn new(
+        image_size: ImageSize,
+        layout: MorphologyLayout,
+        morph_op: MorphExOp,
+        structuring_element: &[u8],
+        structuring_element_size: KernelShape,
+        border_mode: BorderMode,
+        border_scalar: MorphScalar,
+        threading_policy: MorphologyThreadingPolicy,
+    ) -> Result<Self, MorphologyError> {
+        Self::new_roi(
+            image_size,
+            Roi::whole(image_size),
+            layout,
+            morph_op,
+            structuring_element,
+            structuring_element_size,
+            border_mode,
+            border_scalar,
+            threading_policy,
+        )
+    }
+
+    /// Plans morphology with a flat structuring element of a region of interest.
+    ///
+    /// Source is the whole image and destination holds the region only.
+    /// Pixels around the region inside the image are used as its neighbours,
+    /// border mode is applied only at edges of the whole image, so the result is the same as
+    /// the region cut from the whole image result and adjacent tiles have no seams.
+    ///
+    /// # Arguments
+    ///
+    /// * `image_size`: Whole image size declared by [ImageSize]
+    /// * `roi`: Processed region of the image
+    /// * `layout`: Channels layout of the image, see [MorphologyLayout]
+    /// * `morph_op`: Requested [MorphExOp]
+    /// * `structuring_element`: 2D structuring element
+    /// * `structuring_element_size`: (W,H) structuring element size
+    /// * `border_mode`: Border handling mode, for reference see [BorderMode]
+    /// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant], one value per channel, see [MorphologyLayout::scalar_index]
+    /// * `threading_policy`: Threads usage policy
+    ///
+    pub fn new_roi(
+        image_size: ImageSize,
+        roi: Roi,
+        layout: MorphologyLayout,
+        morph_op: MorphExOp,
+        structuring_element: &[u8],
+        structuring_element_size: KernelShape,
+        border_mode: BorderMode,
+        border_scalar: MorphScalar,
+        threading_policy: MorphologyThreadingPolicy,
+    ) -> Result<Self, MorphologyError> {
+        check_roi(image_size, roi)?;
+        check_layout(layout)?;
+        let pool = threading_policy.threading_pool(roi.size());
+        let tasks = pool.tasks_count();
+        let composite = CompositeMorphology::new(morph_op, image_size, roi, 1, |op, size| {
+            PlanarMorphology::new(
+                op,
+                size,
+                structuring_element,
+                structuring_element_size,
+                border_mode,
+                MorphologyBackend::Auto,
+                tasks,
+            )
+        })?;
+        Ok(Self::with_composite(
+            image_size,
+            layout,
+            border_scalar,
+            composite,
+            pool,
+        ))
+    }
+
+    /// Plans morphology with a non-flat structuring element,
+    /// see [crate::morphology_non_flat] for the definition
+    ///
+    /// # Arguments
+    ///
+    /// * `image_size`: Image size declared by [ImageSize]
+    /// * `layout`: Channels layout of the image, see [MorphologyLayout]
+    /// * `morph_op`: Requested [MorphExOp]
+    /// * `weights`: 2D structuring element weights, points with non-finite weights are not part of it
+    /// * `weights_size`: (W,H) structuring element size
+    /// * `border_mode`: Border handling mode, for reference see [BorderMode]
+    /// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant], one value per channel, see [MorphologyLayout::scalar_index]
+    /// * `threading_policy`: Threads usage policy
+    ///
+    pub fn new_non_flat(
+        image_size: ImageSize,
+        layout: MorphologyLayout,
+        morph_op: MorphExOp,
+        weights: &[f32],
+        weights_size: KernelShape,
+        border_mode: BorderMode,
+        border_scalar: MorphScalar,
+        threading_policy: MorphologyThreadingPolicy,
+    ) -> Result<Self, MorphologyError> {
+        Self::new_non_flat_roi(
+            image_size,
+            Roi::whole(image_size),
+            layout,
+            morph_op,
+            weights,
+            weights_size,
+            border_mode,
+            border_scalar,
+            threading_policy,
+        )
+    }
+
+    /// Plans morphology with a non-flat structuring element of a region of interest,
+    /// see [MorphologyPlan::new_roi] for regions handling
+    ///
+    /// # Arguments
+    ///
+    /// * `image_size`: Whole image size declared by [ImageSize]
+    /// * `roi`: Processed region of the image
+    /// * `layout`: Channels layout of the image, see [MorphologyLayout]
+    /// * `morph_op`: Requested [MorphExOp]
+    /// * `weights`: 2D structuring element weights, points with non-finite weights are not part of it
+    /// * `weights_size`: (W,H) structuring element size
+    /// * `border_mode`: Border handling mode, for reference see [BorderMode]
+    /// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant], one value per channel, see [MorphologyLayout::scalar_index]
+    /// * `threading_policy`: Threads usage policy
+    ///
+    pub fn new_non_flat_roi(
+        image_size: ImageSize,
+        roi: Roi,
+        layout: MorphologyLayout,
+        morph_op: MorphExOp,
+        weights: &[f32],
+        weights_size: KernelShape,
+        border_mode: BorderMode,
+        border_scalar: MorphScalar,
+        threading_policy: MorphologyThreadingPolicy,
+    ) -> Result<Self, MorphologyError> {
+        check_roi(image_size, roi)?;
+        check_layout(layout)?;
+        let pool = threading_policy.threading_pool(roi.size());
+        let tasks = pool.tasks_count();
+        let composite = CompositeMorphology::new(morph_op, image_size, roi, 1, |op, size| {
+            PlanarMorphology::new_non_flat(
+                op,
+                size,
+                weights,
+                weights_size,
+                border_mode,
+                MorphologyBackend::Auto,
+                tasks,
+            )
+        })?;
+        Ok(Self::with_composite(
+            image_size,
+            layout,
+            border_scalar,
+            composite,
+            pool,
+        ))
+    }
+
+    /// Plans morphology of the whole image with a flat structuring element and options
+    ///
+    /// # Arguments
+    ///
+    /// * `image_size`: Image size declared by [ImageSize]
+    /// * `layout`: Channels layout of the image, see [MorphologyLayout]
+    /// * `morph_op`: Requested [MorphExOp]
+    /// * `structuring_element`: 2D structuring element
+    /// * `structuring_element_size`: (W,H) structuring element size
+    /// * `options`: Border, anchor, iterations, threading, backend and alpha mode, see [MorphologyOptions]
+    ///
+    pub fn new_with_options(
+        image_size: ImageSize,
+        layout: MorphologyLayout,
+        morph_op: MorphExOp,
+        structuring_element: &[u8],
+        structuring_element_size: KernelShape,
+        options: &MorphologyOptions,
+    ) -> Result<Self, MorphologyError> {
+        options.validate()?;
+        let structuring_element_size = options.kernel_shape(structuring_element_size);
+        Self::with_options(image_size, layout, morph_op, options, |op, size, tasks| {
+            PlanarMorphology::new(
+                op,
+                size,
+                structuring_element,
+                structuring_element_size,
+                options.border_mode(),
+                options.backend(),
+                tasks,
+            )
+        })
+    }
+
+    /// Plans morphology of the whole image with a non-flat structuring element and options,
+    /// see [crate::morphology_non_flat] for the definition
+    ///
+    /// # Arguments
+    ///
+    /// * `image_size`: Image size declared by [ImageSize]
+    /// * `layout`: Channels layout of the image, see [MorphologyLayout]
+    /// * `morph_op`: Requested [MorphExOp]
+    /// * `weights`: 2D structuring element weights, points with non-finite weights are not part of it
+    /// * `weights_size`: (W,H) structuring element size
+    /// * `options`: Border, anchor, iterations, threading, backend and alpha mode, see [MorphologyOptions]
+    ///
+    pub fn new_non_flat_with_options(
+        image_size: ImageSize,
+        layout: MorphologyLayout,
+        morph_op: MorphExOp,
+        weights: &[f32],
+        weights_size: KernelShape,
+        options: &MorphologyOptions,
+    ) -> Result<Self, MorphologyError> {
+        options.validate()?;
+        let weights_size = options.kernel_shape(weights_size);
+        Self::with_options(image_size, layout, morph_op, options, |op, size, tasks| {
+            PlanarMorphology::new_non_flat(
+                op,
+                size,
+                weights,
+                weights_size,
+                options.border_mode(),
+                options.backend(),
+                tasks,
+            )
+        })
+    }
+
+    fn with_options(
+        image_size: ImageSize,
+        layout: MorphologyLayout,
+        morph_op: MorphExOp,
+        options: &MorphologyOptions,
+        planner: impl Fn(MorphOp, ImageSize, usize) -> Result<PlanarMorphology<T>, MorphologyError>,
+    ) -> Result<Self, MorphologyError> {
+        let roi = Roi::whole(image_size);
+        check_roi(image_size, roi)?;
+        check_layout(layout)?;
+        let border_values = options.border_values();
+        if !border_values.is_empty() && border_values.len() != layout.channels() {
+            return Err(MorphologyError::BorderValuesMismatch {
+                expected: layout.channels(),
+                actual: border_values.len(),
+            });
+        }
+        if options.alpha_mode() == AlphaMode::Premultiplied && !T::PREMULTIPLIABLE {
+            return Err(MorphologyError::UnsupportedAlphaMode);
+        }
+        let pool = options.threading_policy().threading_pool(image_size);
+        let tasks = pool.tasks_count();
+        let composite = CompositeMorphology::new(
+            morph_op,
+            image_size,
+            roi,
+            options.iterations(),
+            |op, size| planner(op, size, tasks),
+        )?;
+        let mut plan =
+            Self::with_composite(image_size, layout, options.border_scalar(), composite, pool);
+        plan.alpha_mode = options.alpha_mode();
+        plan.border_values = border_values.to_vec();
+        Ok(plan)
+    }
+
+    fn with_composite(
+        image_size: ImageSize,
+        layout: MorphologyLayout,
+        border_scalar: MorphScalar,
+        composite: CompositeMorphology<T>,
+        pool: ThreadingPool,
+    ) -> Self {
+        MorphologyPlan {
+            image_size,
+            roi: composite.region,
+            layout,
+            border_scalar,
+            border_values: vec![],
+            composite,
+            planes: None,
+            channel: vec![],
+            alpha_mode: AlphaMode::Process,
+            alpha: vec![],
+            alpha_filtered: vec![],
+            terms: vec![],
+            pool,
+        }
+    }
+
+    /// Size of source images the plan is made for
+    pub fn image_size(&self) -> ImageSize {
+        self.image_size
+    }
+
+    /// Processed region of source images, destination images have its size
+    pub fn roi(&self) -> Roi {
+        self.roi
+    }
+
+    /// Channels layout of images the plan is made for
+    pub fn layout(&self) -> MorphologyLayout {
+        self.layout
+    }
+
+    /// Executes the plan on tightly packed images
+    ///
+    /// # Arguments
+    ///
+    /// * `src`: Source image slice with planned size and layout
+    /// * `dst`: Destination image slice with planned region size and layout
+    ///
+    pub fn execute(&mut self, src: &[T], dst: &mut [T]) -> Result<(), MorphologyError> {
+        let channels = self.layout.channels();
+        if self.roi == Roi::whole(self.image_size) {
+            check_image(src, dst, self.image_size, channels)?;
+        } else {
+            check_packed_image(src, self.image_size, channels)?;
+            check_packed_image(dst, self.roi.size(), channels)?;
+        }
+        unsafe {
+            self.execute_impl(
+                src,
+                self.image_size.width * channels,
+                dst,
+                self.roi.width * channels,
+            );
+        }
+        Ok(())
+    }
+
+    /// Executes the plan on images with padded rows
+    ///
+    /// # Arguments
+    ///
+    /// * `src`: Source image slice with planned size and layout
+    /// * `src_stride`: Items between starts of source rows, at least `width * channels`
+    /// * `dst`: Destination image slice with planned region size and layout
+    /// * `dst_stride`: Items between starts of destination rows, at least `width * channels`
+    ///
+    /// Slices must hold `stride * (height - 1) + width * channels` items at least,
+    /// padding after rows is not read and not written.
+    pub fn execute_with_stride(
+        &mut self,
+        src: &[T],
+        src_stride: usize,
+        dst: &mut [T],
+        dst_stride: usize,
+    ) -> Result<(), MorphologyError> {
+        let channels = self.layout.channels();
+        check_strided_image(src, src_stride, self.image_size, channels)?;
+        check_strided_image(dst, dst_stride, self.roi.size(), channels)?;
+        unsafe {
+            self.execute_impl(src, src_stride, dst, dst_stride);
+        }
+        Ok(())
+    }
+
+    /// Executes the plan replacing a tightly packed image by the result,
+    /// only the planned region of it is changed.
+    ///
+    /// Padded arenas are the only extra memory, interleaved layouts are processed
+    /// one channel at a time through a single plane.
+    ///
+    /// # Arguments
+    ///
+    /// * `image`: Image slice with planned size and layout
+    ///
+    pub fn execute_in_place(&mut self, image: &mut [T]) -> Result<(), MorphologyError> {
+        let channels = self.layout.channels();
+        check_packed_image(image, self.image_size, channels)?;
+        unsafe {
+            self.execute_in_place_impl(image, self.image_size.width * channels);
+        }
+        Ok(())
+    }
+
+    /// Executes the plan in place on an image with padded rows, see [MorphologyPlan::execute_in_place]
+    ///
+    /// # Arguments
+    ///
+    /// * `image`: Image slice with planned size and layout
+    /// * `stride`: Items between starts of rows, at least `width * channels`
+    ///
+    /// Slice must hold `stride * (height - 1) + width * channels` items at least,
+    /// padding after rows is not read and not written.
+    pub fn execute_in_place_with_stride(
+        &mut self,
+        image: &mut [T],
+        stride: usize,
+    ) -> Result<(), MorphologyError> {
+        let channels = self.layout.channels();
+        check_strided_image(image, stride, self.image_size, channels)?;
+        unsafe {
+            self.execute_in_place_impl(image, stride);
+        }
+        Ok(())
+    }
+
+    unsafe fn execute_impl(
+        &mut self,
+        src: &[T],
+        src_stride: usize,
+        dst: &mut [T],
+        dst_stride: usize,
+    ) {
+        let channels = self.layout.channels();
+        let window = self.composite.source_window;
+        let src = &src[window.offset(src_stride, channels)..];
+        if channels == 1 {
+            self.composite.execute(
+                src,
+                src_stride,
+                dst,
+                dst_stride,
+                channel_border(self.layout, self.border_scalar, &self.border_values, 0),
+                &self.pool,
+            );
+            return;
+        }
+        let (layout, roi) = (self.layout, self.roi);
+        let (src_planes, dst_planes) = self.planes.get_or_insert_with(|| {
+            (
+                PlanarImage::alloc(layout, window.size()),
+                PlanarImage::alloc(layout, roi.size()),
+            )
+        });
+        src_planes.unpack(src, src_stride, window.size());
+        let alpha = self.layout.alpha_position();
+        let alpha_mode = alpha.map_or(AlphaMode::Process, |_| self.alpha_mode);
+        if alpha_mode == AlphaMode::Premultiplied {
+            src_planes.apply_alpha(channels, alpha.unwrap(), T::premultiply);
+        }
+        if alpha_mode == AlphaMode::Premultiplied && is_difference(self.composite.morph_op) {
+            let alpha = alpha.unwrap();
+            let length = roi.width * roi.height;
+            self.terms.resize(length * 3, T::default());
+            let (alpha_minuend, rest) = self.terms.split_at_mut(length);
+            let (alpha_subtrahend, subtrahend) = rest.split_at_mut(length);
+            self.composite.execute_terms(
+                src_planes.plane(alpha),
+                window.width,
+                alpha_minuend,
+                roi.width,
+                alpha_subtrahend,
+                channel_border(self.layout, self.border_scalar, &self.border_values, alpha),
+                &self.pool,
+            );
+            for channel in (0..channels).filter(|&channel| channel != alpha) {
+                let minuend = dst_planes.plane_mut(channel);
+                self.composite.execute_terms(
+                    src_planes.plane(channel),
+                    window.width,
+                    minuend,
+                    roi.width,
+                    subtrahend,
+                    channel_border(
+                        self.layout,
+                        self.border_scalar,
+                        &self.border_values,
+                        channel,
+                    ),
+                    &self.pool,
+                );
+                subtract_premultiplied_terms(
+                    minuend,
+                    subtrahend,
+                    alpha_minuend,
+                    alpha_subtrahend,
+                    roi.size(),
+                );
+            }
+            T::morph_gradient(alpha_minuend, alpha_subtrahend, dst_planes.plane_mut(alpha));
+            dst_planes.pack(dst, dst_stride, roi.size());
+            return;
+        }
+        let plane_region = (roi.y - window.y) * window.width + roi.x - window.x;
+        for channel in 0..channels {
+            if !alpha_mode.processes(Some(channel) == alpha) {
+                copy_rows(
+                    &src_planes.plane(channel)[plane_region..],
+                    window.width,
+                    dst_planes.plane_mut(channel),
+                    roi.width,
+                    roi.size(),
+                );
+                continue;
+            }
+            self.composite.execute(
+                src_planes.plane(channel),
+                window.width,
+                dst_planes.plane_mut(channel),
+                roi.width,
+                channel_border(
+                    self.layout,
+                    self.border_scalar,
+                    &self.border_values,
+                    channel,
+                ),
+                &self.pool,
+            );
+        }
+        if alpha_mode == AlphaMode::Premultiplied {
+            dst_planes.apply_alpha(channels, alpha.unwrap(), T::unpremultiply);
+        }
+        dst_planes.pack(dst, dst_stride, roi.size());
+    }
+
+    unsafe fn execute_in_place_impl(&mut self, image: &mut [T], stride: usize) {
+        let channels = self.layout.channels();
+        let window = self.composite.source_window;
+        let image = &mut image[window.offset(stride, channels)..];
+        if channels == 1 {
+            self.composite.execute_in_place(
+                image,
+                stride,
+                channel_border(self.layout, self.border_scalar, &self.border_values, 0),
+                &self.pool,
+            );
+            return;
+        }
+        let roi = self.roi;
+        self.channel
+            .resize(window.width * window.height, T::default());
+        let plane_region = (roi.y - window.y) * window.width + roi.x - window.x;
+        let image_region = (roi.y - window.y) * stride + (roi.x - window.x) * channels;
+        let alpha = self.layout.alpha_position();
+        let alpha_mode = alpha.map_or(AlphaMode::Process, |_| self.alpha_mode);
+        if alpha_mode == AlphaMode::Premultiplied {
+            self.execute_in_place_premultiplied(image, stride, alpha.unwrap());
+            return;
+        }
+        for channel in 0..channels {
+            if !alpha_mode.processes(Some(channel) == alpha) {
+                continue;
+            }
+            extract_channel(
+                image,
+                stride,
+                channels,
+                channel,
+                &mut self.channel,
+                window.size(),
+            );
+            self.composite.execute_in_place(
+                &mut self.channel,
+                window.width,
+                channel_border(
+                    self.layout,
+                    self.border_scalar,
+                    &self.border_values,
+                    channel,
+                ),
+                &self.pool,
+            );
+            insert_channel(
+                &self.channel[plane_region..],
+                window.width,
+                &mut image[image_region..],
+                stride,
+                channels,
+                channel,
+                roi.size(),
+            );
+        }
+    }
+
+    /// In-place execution with [AlphaMode::Premultiplied], `image` starts at the source window.
+    ///
+    /// Alpha is filtered first, then every color channel is premultiplied by the source alpha,
+    /// filtered and divided by the filtered alpha.
+    unsafe fn execute_in_place_premultiplied(
+        &mut self,
+        image: &mut [T],
+        stride: usize,
+        alpha: usize,
+    ) {
+        if is_difference(self.composite.morph_op) {
+            self.execute_in_place_premultiplied_difference(image, stride, alpha);
+            return;
+        }
+        let channels = self.layout.channels();
+        let (window, roi) = (self.composite.source_window, self.roi);
+        let plane_region = (roi.y - window.y) * window.width + roi.x - window.x;
+        let image_region = (roi.y - window.y) * stride + (roi.x - window.x) * channels;
+        let window_len = window.width * window.height;
+        self.alpha.resize(window_len, T::default());
+        self.alpha_filtered.resize(window_len, T::default());
+        extract_channel(
+            image,
+            stride,
+            channels,
+            alpha,
+            &mut self.alpha,
+            window.size(),
+        );
+        self.alpha_filtered.copy_from_slice(&self.alpha);
+        self.composite.execute_in_place(
+            &mut self.alpha_filtered,
+            window.width,
+            channel_border(self.layout, self.border_scalar, &self.border_values, alpha),
+            &self.pool,
+        );
+        insert_channel(
+            &self.alpha_filtered[plane_region..],
+            window.width,
+            &mut image[image_region..],
+            stride,
+            channels,
+            alpha,
+            roi.size(),
+        );
+        for channel in (0..channels).filter(|&channel| channel != alpha) {
+            extract_channel(
+                image,
+                stride,
+                channels,
+                channel,
+                &mut self.channel,
+                window.size(),
+            );
+            T::premultiply(&mut self.channel, &self.alpha);
+            self.composite.execute_in_place(
+                &mut self.channel,
+                window.width,
+                channel_border(
+                    self.layout,
+                    self.border_scalar,
+                    &self.border_values,
+                    channel,
+                ),
+                &self.pool,
+            );
+            for (color, alpha) in
+                image_rows_mut(&mut self.channel[plane_region..], window.width, roi.width)
+                    .zip(image_rows(
+                        &self.alpha_filtered[plane_region..],
+                        window.width,
+                        roi.width,
+                    ))
+                    .take(roi.height)
+            {
+                T::unpremultiply(color, alpha);
+            }
+            insert_channel(
+                &self.channel[plane_region..],
+                window.width,
+                &mut image[image_region..],
+                stride,
+                channels,
+                channel,
+                roi.size(),
+            );
+        }
+    }
+
+    /// In-place gradient, top-hat and black-hat with [AlphaMode::Premultiplied],
+    /// `image` starts at the source window.
+    ///
+    /// Both terms of alpha are made first, then both terms of every color channel premultiplied
+    /// by the source alpha, see [subtract_premultiplied_terms].
+    unsafe fn execute_in_place_premultiplied_difference(
+        &mut self,
+        image: &mut [T],
+        stride: usize,
+        alpha: usize,
+    ) {
+        let channels = self.layout.channels();
+        let (window, roi) = (self.composite.source_window, self.roi);
+        let image_region = (roi.y - window.y) * stride + (roi.x - window.x) * channels;
+        let length = roi.width * roi.height;
+        self.alpha
+            .resize(window.width * window.height, T::default());
+        self.terms.resize(length * 4, T::default());
+        let (alpha_minuend, rest) = self.terms.split_at_mut(length);
+        let (alpha_subtrahend, rest) = rest.split_at_mut(length);
+        let (minuend, subtrahend) = rest.split_at_mut(length);
+        extract_channel(
+            image,
+            stride,
+            channels,
+            alpha,
+            &mut self.alpha,
+            window.size(),
+        );
+        self.composite.execute_terms(
+            &self.alpha,
+            window.width,
+            alpha_minuend,
+            roi.width,
+            alpha_subtrahend,
+            channel_border(self.layout, self.border_scalar, &self.border_values, alpha),
+            &self.pool,
+        );
+        for channel in (0..channels).filter(|&channel| channel != alpha) {
+            extract_channel(
+                image,
+                stride,
+                channels,
+                channel,
+                &mut self.channel,
+                window.size(),
+            );
+            T::premultiply(&mut self.channel, &self.alpha);
+            self.composite.execute_terms(
+                &self.channel,
+                window.width,
+                minuend,
+                roi.width,
+                subtrahend,
+                channel_border(
+                    self.layout,
+                    self.border_scalar,
+                    &self.border_values,
+                    channel,
+                ),
+                &self.pool,
+            );
+            subtract_premultiplied_terms(
+                minuend,
+                subtrahend,
+                alpha_minuend,
+                alpha_subtrahend,
+                roi.size(),
+            );
+            insert_channel(
+                minuend,
+                roi.width,
+                &mut image[image_region..],
+                stride,
+                channels,
+                channel,
+                roi.size(),
+            );
+        }
+        T::morph_gradient(alpha_minuend, alpha_subtrahend, minuend);
+        insert_channel(
+            minuend,
+            roi.width,
+            &mut image[image_region..],
+            stride,
+            channels,
+            alpha,
+            roi.size(),
+        );
+    }
+}
+
+/// Whether the operation is a difference of two images
+fn is_difference(morph_op: MorphExOp) -> bool {
+    matches!(
+        morph_op,
+        MorphExOp::Gradient | MorphExOp::TopHat | MorphExOp::BlackHat
+    )
+}
+
+/// Divides premultiplied color terms of a difference by their own alpha and subtracts them
+/// as straight color, the result replaces `minuend`. Images are tightly packed.
+fn subtract_premultiplied_terms<T>(
+    minuend: &mut [T],
+    subtrahend: &mut [T],
+    alpha_minuend: &[T],
+    alpha_subtrahend: &[T],
+    image_size: ImageSize,
+) where
+    T: MorphologySample,
+{
+    T::unpremultiply(minuend, alpha_minuend);
+    T::unpremultiply(subtrahend, alpha_subtrahend);
+    let width = image_size.width;
+    morph_gradient_in_place(
+        minuend,
+        width,
+        subtrahend,
+        width,
+        width,
+        image_size.height,
+        true,
+    );
+}
+
+/// Border constant of the channel at `channel` in a pixel
+fn channel_border(
+    layout: MorphologyLayout,
+    border_scalar: MorphScalar,
+    border_values: &[f64],
+    channel: usize,
+) -> MorphScalar {
+    match border_values.get(channel) {
+        Some(&value) => MorphScalar::dup(value),
+        None => MorphScalar::dup(border_scalar[layout.scalar_index(channel).unwrap_or(3)]),
+    }
+}
+
+/// Copies a channel of an interleaved image with rows `stride` items apart into a plane
+fn extract_channel<T: Copy>(
+    image: &[T],
+    stride: usize,
+    channels: usize,
+    channel: usize,
+    plane: &mut [T],
+    image_size: ImageSize,
+) {
+    for (plane_row, row) in plane.chunks_exact_mut(image_size.width).zip(image_rows(
+        image,
+        stride,
+        image_size.width * channels,
+    )) {
+        for (dst, pixel) in plane_row.iter_mut().zip(row.chunks_exact(channels)) {
+            *dst = pixel[channel];
+        }
+    }
+}
+
+/// Copies a plane with rows `plane_stride` items apart into a channel of an interleaved image
+fn insert_channel<T: Copy>(
+    plane: &[T],
+    plane_stride: usize,
+    image: &mut [T],
+    stride: usize,
+    channels: usize,
+    channel: usize,
+    image_size: ImageSize,
+) {
+    for (plane_row, row) in image_rows(plane, plane_stride, image_size.width)
+        .zip(image_rows_mut(image, stride, image_size.width * channels))
+        .take(image_size.height)
+    {
+        for (src, pixel) in plane_row.iter().zip(row.chunks_exact_mut(channels)) {
+            pixel[channel] = *src;
+        }
+    }
+}
+
+/// One-shot morphology, channels and passes of composite operations share the same plan and pool.
+///
+/// `strides` of source and destination are `None` for tightly packed images.
+pub(crate) fn morphology_impl<T>(
+    src: &[T],
+    dst: &mut [T],
+    strides: Option<(usize, usize)>,
+    layout: MorphologyLayout,
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError>
+where
+    T: MorphologySample,
+    f64: AsPrimitive<T>,
+{
+    let mut plan = MorphologyPlan::new(
+        image_size,
+        layout,
+        morph_op,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )?;
+    match strides {
+        None => plan.execute(src, dst),
+        Some((src_stride, dst_stride)) => {
+            plan.execute_with_stride(src, src_stride, dst, dst_stride)
+        }
+    }
+}
+
+/// One-shot in-place morphology, see [MorphologyPlan::execute_in_place]
+pub(crate) fn morphology_in_place_impl<T>(
+    image: &mut [T],
+    layout: MorphologyLayout,
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError>
+where
+    T: MorphologySample,
+    f64: AsPrimitive<T>,
+{
+    MorphologyPlan::new(
+        image_size,
+        layout,
+        morph_op,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )?
+    .execute_in_place(image)
+}
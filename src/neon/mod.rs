@@ -27,5 +27,19 @@
  * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
 mod gradient_unsigned_8;
+mod morph_rows;
+#[cfg(feature = "half")]
+mod morph_rows_f16;
+mod packed_int;
+mod transpose;
 
 pub use gradient_unsigned_8::*;
+pub use morph_rows::{
+    morph_rows_f32_neon, morph_rows_f64_neon, morph_rows_u16_neon, morph_rows_u8_neon,
+};
+#[cfg(feature = "half")]
+pub use morph_rows_f16::morph_rows_f16_neon;
+#[cfg(feature = "half")]
+pub(crate) use morph_rows_f16::{vmaxq_f16_u16, vminq_f16_u16};
+pub use packed_int::{morph_rows_int_neon, NeonPackedInt};
+pub(crate) use transpose::transpose_neon;
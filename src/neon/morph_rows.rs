@@ -0,0 +1,183 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::morph_base::MorphNativeOp;
+use crate::op_type::MorphOp;
+use std::arch::aarch64::*;
+
+/// Computes `dst = op(a, b)` for 8-bit rows
+pub fn morph_rows_u8_neon<const OP_TYPE: u8>(a: &[u8], b: &[u8], dst: &mut [u8]) {
+    let op_type: MorphOp = OP_TYPE.into();
+    let decision = match op_type {
+        MorphOp::Dilate => vmaxq_u8,
+        MorphOp::Erode => vminq_u8,
+    };
+    let length = dst.len().min(a.len()).min(b.len());
+    let mut _cx = 0usize;
+    unsafe {
+        while _cx + 64 <= length {
+            let a_set = vld1q_u8_x4(a.get_unchecked(_cx..).as_ptr());
+            let b_set = vld1q_u8_x4(b.get_unchecked(_cx..).as_ptr());
+            let result_set = uint8x16x4_t(
+                decision(a_set.0, b_set.0),
+                decision(a_set.1, b_set.1),
+                decision(a_set.2, b_set.2),
+                decision(a_set.3, b_set.3),
+            );
+            vst1q_u8_x4(dst.get_unchecked_mut(_cx..).as_mut_ptr(), result_set);
+            _cx += 64;
+        }
+        while _cx + 16 <= length {
+            let a_row = vld1q_u8(a.get_unchecked(_cx..).as_ptr());
+            let b_row = vld1q_u8(b.get_unchecked(_cx..).as_ptr());
+            vst1q_u8(
+                dst.get_unchecked_mut(_cx..).as_mut_ptr(),
+                decision(a_row, b_row),
+            );
+            _cx += 16;
+        }
+        while _cx < length {
+            *dst.get_unchecked_mut(_cx) = a.get_unchecked(_cx).op::<OP_TYPE>(*b.get_unchecked(_cx));
+            _cx += 1;
+        }
+    }
+}
+
+/// Computes `dst = op(a, b)` for 16-bit rows
+pub fn morph_rows_u16_neon<const OP_TYPE: u8>(a: &[u16], b: &[u16], dst: &mut [u16]) {
+    let op_type: MorphOp = OP_TYPE.into();
+    let decision = match op_type {
+        MorphOp::Dilate => vmaxq_u16,
+        MorphOp::Erode => vminq_u16,
+    };
+    let length = dst.len().min(a.len()).min(b.len());
+    let mut _cx = 0usize;
+    unsafe {
+        while _cx + 32 <= length {
+            let a_set = vld1q_u16_x4(a.get_unchecked(_cx..).as_ptr());
+            let b_set = vld1q_u16_x4(b.get_unchecked(_cx..).as_ptr());
+            let result_set = uint16x8x4_t(
+                decision(a_set.0, b_set.0),
+                decision(a_set.1, b_set.1),
+                decision(a_set.2, b_set.2),
+                decision(a_set.3, b_set.3),
+            );
+            vst1q_u16_x4(dst.get_unchecked_mut(_cx..).as_mut_ptr(), result_set);
+            _cx += 32;
+        }
+        while _cx + 8 <= length {
+            let a_row = vld1q_u16(a.get_unchecked(_cx..).as_ptr());
+            let b_row = vld1q_u16(b.get_unchecked(_cx..).as_ptr());
+            vst1q_u16(
+                dst.get_unchecked_mut(_cx..).as_mut_ptr(),
+                decision(a_row, b_row),
+            );
+            _cx += 8;
+        }
+        while _cx < length {
+            *dst.get_unchecked_mut(_cx) = a.get_unchecked(_cx).op::<OP_TYPE>(*b.get_unchecked(_cx));
+            _cx += 1;
+        }
+    }
+}
+
+/// Computes `dst = op(a, b)` for f32 rows
+pub fn morph_rows_f32_neon<const OP_TYPE: u8>(a: &[f32], b: &[f32], dst: &mut [f32]) {
+    let op_type: MorphOp = OP_TYPE.into();
+    let decision = match op_type {
+        MorphOp::Dilate => vmaxq_f32,
+        MorphOp::Erode => vminq_f32,
+    };
+    let length = dst.len().min(a.len()).min(b.len());
+    let mut _cx = 0usize;
+    unsafe {
+        while _cx + 16 <= length {
+            let a_set = vld1q_f32_x4(a.get_unchecked(_cx..).as_ptr());
+            let b_set = vld1q_f32_x4(b.get_unchecked(_cx..).as_ptr());
+            let result_set = float32x4x4_t(
+                decision(a_set.0, b_set.0),
+                decision(a_set.1, b_set.1),
+                decision(a_set.2, b_set.2),
+                decision(a_set.3, b_set.3),
+            );
+            vst1q_f32_x4(dst.get_unchecked_mut(_cx..).as_mut_ptr(), result_set);
+            _cx += 16;
+        }
+        while _cx + 4 <= length {
+            let a_row = vld1q_f32(a.get_unchecked(_cx..).as_ptr());
+            let b_row = vld1q_f32(b.get_unchecked(_cx..).as_ptr());
+            vst1q_f32(
+                dst.get_unchecked_mut(_cx..).as_mut_ptr(),
+                decision(a_row, b_row),
+            );
+            _cx += 4;
+        }
+        while _cx < length {
+            *dst.get_unchecked_mut(_cx) = a.get_unchecked(_cx).op::<OP_TYPE>(*b.get_unchecked(_cx));
+            _cx += 1;
+        }
+    }
+}
+
+/// Computes `dst = op(a, b)` for f64 rows
+pub fn morph_rows_f64_neon<const OP_TYPE: u8>(a: &[f64], b: &[f64], dst: &mut [f64]) {
+    let op_type: MorphOp = OP_TYPE.into();
+    let decision = match op_type {
+        MorphOp::Dilate => vmaxq_f64,
+        MorphOp::Erode => vminq_f64,
+    };
+    let length = dst.len().min(a.len()).min(b.len());
+    let mut _cx = 0usize;
+    unsafe {
+        while _cx + 8 <= length {
+            let a_set = vld1q_f64_x4(a.get_unchecked(_cx..).as_ptr());
+            let b_set = vld1q_f64_x4(b.get_unchecked(_cx..).as_ptr());
+            let result_set = float64x2x4_t(
+                decision(a_set.0, b_set.0),
+                decision(a_set.1, b_set.1),
+                decision(a_set.2, b_set.2),
+                decision(a_set.3, b_set.3),
+            );
+            vst1q_f64_x4(dst.get_unchecked_mut(_cx..).as_mut_ptr(), result_set);
+            _cx += 8;
+        }
+        while _cx + 2 <= length {
+            let a_row = vld1q_f64(a.get_unchecked(_cx..).as_ptr());
+            let b_row = vld1q_f64(b.get_unchecked(_cx..).as_ptr());
+            vst1q_f64(
+                dst.get_unchecked_mut(_cx..).as_mut_ptr(),
+                decision(a_row, b_row),
+            );
+            _cx += 2;
+        }
+        while _cx < length {
+            *dst.get_unchecked_mut(_cx) = a.get_unchecked(_cx).op::<OP_TYPE>(*b.get_unchecked(_cx));
+            _cx += 1;
+        }
+    }
+}
@@ -0,0 +1,110 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::morph_base::MorphNativeOp;
+use crate::op_type::MorphOp;
+use half::f16;
+use std::arch::aarch64::*;
+use std::arch::asm;
+
+/// Lane-wise maximum of eight half precision floats kept in u16 lanes
+#[inline]
+#[target_feature(enable = "fp16")]
+pub(crate) unsafe fn vmaxq_f16_u16(a: uint16x8_t, b: uint16x8_t) -> uint16x8_t {
+    let result: uint16x8_t;
+    asm!(
+        "fmax {0:v}.8h, {1:v}.8h, {2:v}.8h",
+        out(vreg) result,
+        in(vreg) a,
+        in(vreg) b,
+        options(pure, nomem, nostack)
+    );
+    result
+}
+
+/// Lane-wise minimum of eight half precision floats kept in u16 lanes
+#[inline]
+#[target_feature(enable = "fp16")]
+pub(crate) unsafe fn vminq_f16_u16(a: uint16x8_t, b: uint16x8_t) -> uint16x8_t {
+    let result: uint16x8_t;
+    asm!(
+        "fmin {0:v}.8h, {1:v}.8h, {2:v}.8h",
+        out(vreg) result,
+        in(vreg) a,
+        in(vreg) b,
+        options(pure, nomem, nostack)
+    );
+    result
+}
+
+/// Computes `dst = op(a, b)` for f16 rows, CPU must support `fp16`
+pub fn morph_rows_f16_neon<const OP_TYPE: u8>(a: &[f16], b: &[f16], dst: &mut [f16]) {
+    unsafe {
+        morph_rows_f16_neon_impl::<OP_TYPE>(a, b, dst);
+    }
+}
+
+#[inline]
+#[target_feature(enable = "fp16")]
+unsafe fn morph_rows_f16_neon_impl<const OP_TYPE: u8>(a: &[f16], b: &[f16], dst: &mut [f16]) {
+    let op_type: MorphOp = OP_TYPE.into();
+    let decision = match op_type {
+        MorphOp::Dilate => vmaxq_f16_u16,
+        MorphOp::Erode => vminq_f16_u16,
+    };
+    let length = dst.len().min(a.len()).min(b.len());
+    let mut _cx = 0usize;
+    while _cx + 32 <= length {
+        let a_set = vld1q_u16_x4(a.get_unchecked(_cx..).as_ptr() as *const u16);
+        let b_set = vld1q_u16_x4(b.get_unchecked(_cx..).as_ptr() as *const u16);
+        let result_set = uint16x8x4_t(
+            decision(a_set.0, b_set.0),
+            decision(a_set.1, b_set.1),
+            decision(a_set.2, b_set.2),
+            decision(a_set.3, b_set.3),
+        );
+        vst1q_u16_x4(
+            dst.get_unchecked_mut(_cx..).as_mut_ptr() as *mut u16,
+            result_set,
+        );
+        _cx += 32;
+    }
+    while _cx + 8 <= length {
+        let a_row = vld1q_u16(a.get_unchecked(_cx..).as_ptr() as *const u16);
+        let b_row = vld1q_u16(b.get_unchecked(_cx..).as_ptr() as *const u16);
+        vst1q_u16(
+            dst.get_unchecked_mut(_cx..).as_mut_ptr() as *mut u16,
+            decision(a_row, b_row),
+        );
+        _cx += 8;
+    }
+    while _cx < length {
+        *dst.get_unchecked_mut(_cx) = a.get_unchecked(_cx).op::<OP_TYPE>(*b.get_unchecked(_cx));
+        _cx += 1;
+    }
+}
@@ -0,0 +1,185 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::morph_base::MorphNativeOp;
+use crate::op_type::MorphOp;
+use std::arch::aarch64::*;
+
+/// Lane-wise min/max of integer samples packed into 128-bit registers
+pub trait NeonPackedInt: Copy {
+    type Vector: Copy;
+    /// Samples in one register
+    const LANES: usize;
+
+    unsafe fn load(ptr: *const Self) -> Self::Vector;
+
+    unsafe fn store(ptr: *mut Self, v: Self::Vector);
+
+    unsafe fn max(a: Self::Vector, b: Self::Vector) -> Self::Vector;
+
+    unsafe fn min(a: Self::Vector, b: Self::Vector) -> Self::Vector;
+}
+
+impl NeonPackedInt for i8 {
+    type Vector = int8x16_t;
+    const LANES: usize = 16;
+
+    #[inline(always)]
+    unsafe fn load(ptr: *const i8) -> int8x16_t {
+        vld1q_s8(ptr)
+    }
+
+    #[inline(always)]
+    unsafe fn store(ptr: *mut i8, v: int8x16_t) {
+        vst1q_s8(ptr, v)
+    }
+
+    #[inline(always)]
+    unsafe fn max(a: int8x16_t, b: int8x16_t) -> int8x16_t {
+        vmaxq_s8(a, b)
+    }
+
+    #[inline(always)]
+    unsafe fn min(a: int8x16_t, b: int8x16_t) -> int8x16_t {
+        vminq_s8(a, b)
+    }
+}
+
+impl NeonPackedInt for i16 {
+    type Vector = int16x8_t;
+    const LANES: usize = 8;
+
+    #[inline(always)]
+    unsafe fn load(ptr: *const i16) -> int16x8_t {
+        vld1q_s16(ptr)
+    }
+
+    #[inline(always)]
+    unsafe fn store(ptr: *mut i16, v: int16x8_t) {
+        vst1q_s16(ptr, v)
+    }
+
+    #[inline(always)]
+    unsafe fn max(a: int16x8_t, b: int16x8_t) -> int16x8_t {
+        vmaxq_s16(a, b)
+    }
+
+    #[inline(always)]
+    unsafe fn min(a: int16x8_t, b: int16x8_t) -> int16x8_t {
+        vminq_s16(a, b)
+    }
+}
+
+impl NeonPackedInt for i32 {
+    type Vector = int32x4_t;
+    const LANES: usize = 4;
+
+    #[inline(always)]
+    unsafe fn load(ptr: *const i32) -> int32x4_t {
+        vld1q_s32(ptr)
+    }
+
+    #[inline(always)]
+    unsafe fn store(ptr: *mut i32, v: int32x4_t) {
+        vst1q_s32(ptr, v)
+    }
+
+    #[inline(always)]
+    unsafe fn max(a: int32x4_t, b: int32x4_t) -> int32x4_t {
+        vmaxq_s32(a, b)
+    }
+
+    #[inline(always)]
+    unsafe fn min(a: int32x4_t, b: int32x4_t) -> int32x4_t {
+        vminq_s32(a, b)
+    }
+}
+
+impl NeonPackedInt for u32 {
+    type Vector = uint32x4_t;
+    const LANES: usize = 4;
+
+    #[inline(always)]
+    unsafe fn load(ptr: *const u32) -> uint32x4_t {
+        vld1q_u32(ptr)
+    }
+
+    #[inline(always)]
+    unsafe fn store(ptr: *mut u32, v: uint32x4_t) {
+        vst1q_u32(ptr, v)
+    }
+
+    #[inline(always)]
+    unsafe fn max(a: uint32x4_t, b: uint32x4_t) -> uint32x4_t {
+        vmaxq_u32(a, b)
+    }
+
+    #[inline(always)]
+    unsafe fn min(a: uint32x4_t, b: uint32x4_t) -> uint32x4_t {
+        vminq_u32(a, b)
+    }
+}
+
+/// Computes `dst = op(a, b)` for rows of packed integers
+pub fn morph_rows_int_neon<V, const OP_TYPE: u8>(a: &[V], b: &[V], dst: &mut [V])
+where
+    V: NeonPackedInt + MorphNativeOp<V>,
+{
+    let op_type: MorphOp = OP_TYPE.into();
+    let decision = match op_type {
+        MorphOp::Dilate => V::max,
+        MorphOp::Erode => V::min,
+    };
+    let lanes = V::LANES;
+    let length = dst.len().min(a.len()).min(b.len());
+    let mut _cx = 0usize;
+    unsafe {
+        while _cx + lanes * 2 <= length {
+            let a_ptr = a.get_unchecked(_cx..).as_ptr();
+            let b_ptr = b.get_unchecked(_cx..).as_ptr();
+            let row0 = decision(V::load(a_ptr), V::load(b_ptr));
+            let row1 = decision(V::load(a_ptr.add(lanes)), V::load(b_ptr.add(lanes)));
+            let dst_ptr = dst.get_unchecked_mut(_cx..).as_mut_ptr();
+            V::store(dst_ptr, row0);
+            V::store(dst_ptr.add(lanes), row1);
+            _cx += lanes * 2;
+        }
+        while _cx + lanes <= length {
+            let row0 = decision(
+                V::load(a.get_unchecked(_cx..).as_ptr()),
+                V::load(b.get_unchecked(_cx..).as_ptr()),
+            );
+            V::store(dst.get_unchecked_mut(_cx..).as_mut_ptr(), row0);
+            _cx += lanes;
+        }
+        while _cx < length {
+            *dst.get_unchecked_mut(_cx) = a.get_unchecked(_cx).op::<OP_TYPE>(*b.get_unchecked(_cx));
+            _cx += 1;
+        }
+    }
+}
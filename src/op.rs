@@ -27,15 +27,11 @@
  * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
 use crate::border_mode::{BorderMode, MorphScalar};
-use crate::difference::MorphGradient;
-use crate::morph_gray_alpha::make_morphology_gray_alpha;
-use crate::morph_rgb::make_morphology_rgb;
-use crate::morph_rgba::make_morphology_rgba;
-use crate::op_impl::make_morphology;
-use crate::op_type::{MorphExOp, MorphOp};
+use crate::error::MorphologyError;
+use crate::generic::{self, Gray, GrayAlpha, Rgb, Rgba};
+use crate::op_type::MorphExOp;
 use crate::structuring_element::KernelShape;
 use crate::{ImageSize, MorphologyThreadingPolicy};
-use std::time::Instant;
 
 /// Dilate a gray (planar) image
 ///
@@ -59,19 +55,18 @@ pub fn dilate(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    unsafe {
-        make_morphology::<u8, { MorphOp::Dilate as u8 }>(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        )
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<u8, Gray>(
+        src,
+        dst,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Dilate an RGB image
@@ -96,19 +91,18 @@ pub fn dilate_rgb(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    unsafe {
-        make_morphology_rgb::<u8, { MorphOp::Dilate as u8 }>(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        )
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<u8, Rgb>(
+        src,
+        dst,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Erode a gray (planar) image
@@ -133,19 +127,18 @@ pub fn erode(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    unsafe {
-        make_morphology::<u8, { MorphOp::Erode as u8 }>(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        )
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<u8, Gray>(
+        src,
+        dst,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Erode an RGB image
@@ -170,19 +163,18 @@ pub fn erode_rgb(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    unsafe {
-        make_morphology_rgb::<u8, { MorphOp::Erode as u8 }>(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        )
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<u8, Rgb>(
+        src,
+        dst,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Erode an RGBA image
@@ -207,19 +199,18 @@ pub fn erode_rgba(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    unsafe {
-        make_morphology_rgba::<u8, { MorphOp::Erode as u8 }>(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        )
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<u8, Rgba>(
+        src,
+        dst,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Dilate an RGBA image
@@ -244,19 +235,18 @@ pub fn dilate_rgba(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    unsafe {
-        make_morphology_rgba::<u8, { MorphOp::Dilate as u8 }>(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        )
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<u8, Rgba>(
+        src,
+        dst,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Erode Gray image with alpha
@@ -281,19 +271,18 @@ pub fn erode_gray_alpha(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    unsafe {
-        make_morphology_gray_alpha::<u8, { MorphOp::Erode as u8 }>(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        )
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<u8, GrayAlpha>(
+        src,
+        dst,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Dilate an Gray image with alpha
@@ -318,19 +307,18 @@ pub fn dilate_gray_alpha(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    unsafe {
-        make_morphology_gray_alpha::<u8, { MorphOp::Dilate as u8 }>(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        )
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<u8, GrayAlpha>(
+        src,
+        dst,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Morphology a gray (planar) image
@@ -357,133 +345,64 @@ pub fn morphology(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    match morph_op {
-        MorphExOp::Dilate => dilate(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        ),
-        MorphExOp::Erode => erode(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        ),
-        MorphExOp::Opening => {
-            let mut transient = vec![0u8; dst.len()];
-            erode(
-                src,
-                &mut transient,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            dilate(
-                &transient,
-                dst,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )
-        }
-        MorphExOp::Closing => {
-            let mut transient = vec![0u8; dst.len()];
-            dilate(
-                src,
-                &mut transient,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            erode(
-                &transient,
-                dst,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )
-        }
-        MorphExOp::Gradient => {
-            let mut dilation = vec![0u8; dst.len()];
-            dilate(
-                src,
-                &mut dilation,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            let mut erosion = vec![0u8; dst.len()];
-            erode(
-                &src,
-                &mut erosion,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            u8::morph_gradient(&dilation, &erosion, dst);
-            Ok(())
-        }
-        MorphExOp::TopHat => {
-            let mut opened = vec![0u8; dst.len()];
-            morphology(
-                src,
-                &mut opened,
-                MorphExOp::Opening,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            u8::morph_gradient(&src, &opened, dst);
-            Ok(())
-        }
-        MorphExOp::BlackHat => {
-            let mut closed = vec![0u8; dst.len()];
-            morphology(
-                src,
-                &mut closed,
-                MorphExOp::Closing,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            u8::morph_gradient(&closed, &src, dst);
-            Ok(())
-        }
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<u8, Gray>(
+        src,
+        dst,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology a gray (planar) image with padded rows
+///
+/// Rows are `src_stride` and `dst_stride` items apart, padding after rows is not read and not written.
+///
+/// # Arguments
+///
+/// * `src`: Source image slice
+/// * `src_stride`: Items between starts of source rows, at least `width * channels`
+/// * `dst`: Destination image slice
+/// * `dst_stride`: Items between starts of destination rows, at least `width * channels`
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_with_stride(
+    src: &[u8],
+    src_stride: usize,
+    dst: &mut [u8],
+    dst_stride: usize,
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_with_stride::<u8, Gray>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Morphology a RGB 8-bit image
@@ -510,135 +429,64 @@ pub fn morphology_rgb(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    match morph_op {
-        MorphExOp::Dilate => dilate_rgb(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        ),
-        MorphExOp::Erode => erode_rgb(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        ),
-        MorphExOp::Opening => {
-            let mut transient = vec![0u8; dst.len()];
-            erode_rgb(
-                src,
-                &mut transient,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            dilate_rgb(
-                &transient,
-                dst,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )
-        }
-        MorphExOp::Closing => {
-            let mut transient = vec![0u8; dst.len()];
-            dilate_rgb(
-                src,
-                &mut transient,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            erode_rgb(
-                &transient,
-                dst,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )
-        }
-        MorphExOp::Gradient => {
-            let mut dilation = vec![0u8; dst.len()];
-            dilate_rgb(
-                src,
-                &mut dilation,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            let mut erosion = vec![0u8; dst.len()];
-            erode_rgb(
-                &src,
-                &mut erosion,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            let start_time = Instant::now();
-            u8::morph_gradient(&dilation, &erosion, dst);
-            println!("end time {:?}", start_time.elapsed());
-            Ok(())
-        }
-        MorphExOp::TopHat => {
-            let mut opened = vec![0u8; dst.len()];
-            morphology_rgb(
-                src,
-                &mut opened,
-                MorphExOp::Opening,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            u8::morph_gradient(&src, &opened, dst);
-            Ok(())
-        }
-        MorphExOp::BlackHat => {
-            let mut closed = vec![0u8; dst.len()];
-            morphology_rgb(
-                src,
-                &mut closed,
-                MorphExOp::Closing,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            u8::morph_gradient(&closed, &src, dst);
-            Ok(())
-        }
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<u8, Rgb>(
+        src,
+        dst,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology a RGB 8-bit image with padded rows
+///
+/// Rows are `src_stride` and `dst_stride` items apart, padding after rows is not read and not written.
+///
+/// # Arguments
+///
+/// * `src`: Source RGB image slice
+/// * `src_stride`: Items between starts of source rows, at least `width * channels`
+/// * `dst`: Destination RGB image slice
+/// * `dst_stride`: Items between starts of destination rows, at least `width * channels`
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_rgb_with_stride(
+    src: &[u8],
+    src_stride: usize,
+    dst: &mut [u8],
+    dst_stride: usize,
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_with_stride::<u8, Rgb>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Morphology a Planar image with alpha 8-bit image
@@ -665,133 +513,64 @@ pub fn morphology_gray_alpha(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    match morph_op {
-        MorphExOp::Dilate => dilate_gray_alpha(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        ),
-        MorphExOp::Erode => erode_gray_alpha(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        ),
-        MorphExOp::Opening => {
-            let mut transient = vec![0u8; dst.len()];
-            erode_gray_alpha(
-                src,
-                &mut transient,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            dilate_gray_alpha(
-                &transient,
-                dst,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )
-        }
-        MorphExOp::Closing => {
-            let mut transient = vec![0u8; dst.len()];
-            dilate_gray_alpha(
-                src,
-                &mut transient,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            erode_gray_alpha(
-                &transient,
-                dst,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )
-        }
-        MorphExOp::Gradient => {
-            let mut dilation = vec![0u8; dst.len()];
-            dilate_gray_alpha(
-                src,
-                &mut dilation,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            let mut erosion = vec![0u8; dst.len()];
-            erode_gray_alpha(
-                &src,
-                &mut erosion,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            u8::morph_gradient(&dilation, &erosion, dst);
-            Ok(())
-        }
-        MorphExOp::TopHat => {
-            let mut opened = vec![0u8; dst.len()];
-            morphology_gray_alpha(
-                src,
-                &mut opened,
-                MorphExOp::Opening,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            u8::morph_gradient(&src, &opened, dst);
-            Ok(())
-        }
-        MorphExOp::BlackHat => {
-            let mut closed = vec![0u8; dst.len()];
-            morphology_gray_alpha(
-                src,
-                &mut closed,
-                MorphExOp::Closing,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            u8::morph_gradient(&closed, &src, dst);
-            Ok(())
-        }
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<u8, GrayAlpha>(
+        src,
+        dst,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology a Planar image with alpha 8-bit image with padded rows
+///
+/// Rows are `src_stride` and `dst_stride` items apart, padding after rows is not read and not written.
+///
+/// # Arguments
+///
+/// * `src`: Source image slice
+/// * `src_stride`: Items between starts of source rows, at least `width * channels`
+/// * `dst`: Destination image slice
+/// * `dst_stride`: Items between starts of destination rows, at least `width * channels`
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_gray_alpha_with_stride(
+    src: &[u8],
+    src_stride: usize,
+    dst: &mut [u8],
+    dst_stride: usize,
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_with_stride::<u8, GrayAlpha>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Morphology a RGBA 8-bit image
@@ -818,131 +597,490 @@ pub fn morphology_rgba(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    match morph_op {
-        MorphExOp::Dilate => dilate_rgba(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        ),
-        MorphExOp::Erode => erode_rgba(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        ),
-        MorphExOp::Opening => {
-            let mut transient = vec![0u8; dst.len()];
-            erode_rgba(
-                src,
-                &mut transient,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            dilate_rgba(
-                &transient,
-                dst,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )
-        }
-        MorphExOp::Closing => {
-            let mut transient = vec![0u8; dst.len()];
-            dilate_rgba(
-                src,
-                &mut transient,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            erode_rgba(
-                &transient,
-                dst,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )
-        }
-        MorphExOp::Gradient => {
-            let mut dilation = vec![0u8; dst.len()];
-            dilate_rgba(
-                src,
-                &mut dilation,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            let mut erosion = vec![0u8; dst.len()];
-            erode_rgba(
-                &src,
-                &mut erosion,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            u8::morph_gradient(&dilation, &erosion, dst);
-            Ok(())
-        }
-        MorphExOp::TopHat => {
-            let mut opened = vec![0u8; dst.len()];
-            morphology_rgba(
-                src,
-                &mut opened,
-                MorphExOp::Opening,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            u8::morph_gradient(&src, &opened, dst);
-            Ok(())
-        }
-        MorphExOp::BlackHat => {
-            let mut closed = vec![0u8; dst.len()];
-            morphology_rgba(
-                src,
-                &mut closed,
-                MorphExOp::Closing,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            u8::morph_gradient(&closed, &src, dst);
-            Ok(())
-        }
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<u8, Rgba>(
+        src,
+        dst,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology a RGBA 8-bit image with padded rows
+///
+/// Rows are `src_stride` and `dst_stride` items apart, padding after rows is not read and not written.
+///
+/// # Arguments
+///
+/// * `src`: Source RGBA image slice
+/// * `src_stride`: Items between starts of source rows, at least `width * channels`
+/// * `dst`: Destination RGBA image slice
+/// * `dst_stride`: Items between starts of destination rows, at least `width * channels`
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_rgba_with_stride(
+    src: &[u8],
+    src_stride: usize,
+    dst: &mut [u8],
+    dst_stride: usize,
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_with_stride::<u8, Rgba>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Dilate a gray (planar) image in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Image slice, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn dilate_in_place(
+    image: &mut [u8],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<u8, Gray>(
+        image,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Erode a gray (planar) image in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Image slice, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn erode_in_place(
+    image: &mut [u8],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<u8, Gray>(
+        image,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology a gray (planar) image in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Image slice, replaced by the result
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_in_place(
+    image: &mut [u8],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<u8, Gray>(
+        image,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Dilate a Gray image with alpha in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with Gray with alpha data, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn dilate_gray_alpha_in_place(
+    image: &mut [u8],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<u8, GrayAlpha>(
+        image,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Erode a Gray image with alpha in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with Gray with alpha data, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn erode_gray_alpha_in_place(
+    image: &mut [u8],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<u8, GrayAlpha>(
+        image,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology a Gray image with alpha in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with Gray with alpha data, replaced by the result
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_gray_alpha_in_place(
+    image: &mut [u8],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<u8, GrayAlpha>(
+        image,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Dilate an RGB image in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with RGB data, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn dilate_rgb_in_place(
+    image: &mut [u8],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<u8, Rgb>(
+        image,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Erode an RGB image in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with RGB data, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn erode_rgb_in_place(
+    image: &mut [u8],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<u8, Rgb>(
+        image,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology an RGB image in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with RGB data, replaced by the result
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_rgb_in_place(
+    image: &mut [u8],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<u8, Rgb>(
+        image,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Dilate an RGBA image in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with RGBA data, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn dilate_rgba_in_place(
+    image: &mut [u8],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<u8, Rgba>(
+        image,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Erode an RGBA image in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with RGBA data, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn erode_rgba_in_place(
+    image: &mut [u8],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<u8, Rgba>(
+        image,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology an RGBA image in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with RGBA data, replaced by the result
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_rgba_in_place(
+    image: &mut [u8],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<u8, Rgba>(
+        image,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
@@ -0,0 +1,1172 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::border_mode::{BorderMode, MorphScalar};
+use crate::error::MorphologyError;
+use crate::generic::{self, Gray, GrayAlpha, Rgb, Rgba};
+use crate::structuring_element::KernelShape;
+use crate::{ImageSize, MorphExOp, MorphologyThreadingPolicy};
+use half::f16;
+
+/// Dilate a gray (planar) stored in f16 image
+///
+/// # Arguments
+///
+/// * `src`: Source image slice
+/// * `dst`: Destination image slice
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn dilate_f16(
+    src: &[f16],
+    dst: &mut [f16],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology::<f16, Gray>(
+        src,
+        dst,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Dilate an RGB stored in f16 image
+///
+/// # Arguments
+///
+/// * `src`: Source slice with RGB data
+/// * `dst`: Destination slice for RGB data
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn dilate_rgb_f16(
+    src: &[f16],
+    dst: &mut [f16],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology::<f16, Rgb>(
+        src,
+        dst,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Erode a gray (planar) stored in f16 image
+///
+/// # Arguments
+///
+/// * `src`: Source image slice
+/// * `dst`: Destination image slice
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn erode_f16(
+    src: &[f16],
+    dst: &mut [f16],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology::<f16, Gray>(
+        src,
+        dst,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Erode an RGB image stored in f16
+///
+/// # Arguments
+///
+/// * `src`: Source slice with RGB data
+/// * `dst`: Destination slice for RGB data
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn erode_rgb_f16(
+    src: &[f16],
+    dst: &mut [f16],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology::<f16, Rgb>(
+        src,
+        dst,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Erode an RGBA image stored in f16
+///
+/// # Arguments
+///
+/// * `src`: Source slice with RGBA data
+/// * `dst`: Destination slice for RGBA data
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn erode_rgba_f16(
+    src: &[f16],
+    dst: &mut [f16],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology::<f16, Rgba>(
+        src,
+        dst,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Dilate an RGBA image stored in f16
+///
+/// # Arguments
+///
+/// * `src`: Source slice with RGBA data
+/// * `dst`: Destination slice for RGBA data
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn dilate_rgba_f16(
+    src: &[f16],
+    dst: &mut [f16],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology::<f16, Rgba>(
+        src,
+        dst,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Erode Gray image with alpha
+///
+/// # Arguments
+///
+/// * `src`: Source slice with Gray with alpha data
+/// * `dst`: Destination slice for Gray with alpha data
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn erode_gray_alpha_f16(
+    src: &[f16],
+    dst: &mut [f16],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology::<f16, GrayAlpha>(
+        src,
+        dst,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Dilate an Gray image with alpha
+///
+/// # Arguments
+///
+/// * `src`: Source slice with Gray with alpha data
+/// * `dst`: Destination slice for Gray with alpha data
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn dilate_gray_alpha_f16(
+    src: &[f16],
+    dst: &mut [f16],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology::<f16, GrayAlpha>(
+        src,
+        dst,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology an RGBA image stored in f16
+///
+/// # Arguments
+///
+/// * `src`: Source slice with RGBA data
+/// * `dst`: Destination slice for RGBA data
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_rgba_f16(
+    src: &[f16],
+    dst: &mut [f16],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology::<f16, Rgba>(
+        src,
+        dst,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology an RGBA image stored in f16 with padded rows
+///
+/// Rows are `src_stride` and `dst_stride` items apart, padding after rows is not read and not written.
+///
+/// # Arguments
+///
+/// * `src`: Source slice with RGBA data
+/// * `src_stride`: Items between starts of source rows, at least `width * channels`
+/// * `dst`: Destination slice for RGBA data
+/// * `dst_stride`: Items between starts of destination rows, at least `width * channels`
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_rgba_f16_with_stride(
+    src: &[f16],
+    src_stride: usize,
+    dst: &mut [f16],
+    dst_stride: usize,
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_with_stride::<f16, Rgba>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology an RGB image stored in f16
+///
+/// # Arguments
+///
+/// * `src`: Source slice with RGBA data
+/// * `dst`: Destination slice for RGBA data
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_rgb_f16(
+    src: &[f16],
+    dst: &mut [f16],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology::<f16, Rgb>(
+        src,
+        dst,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology an RGB image stored in f16 with padded rows
+///
+/// Rows are `src_stride` and `dst_stride` items apart, padding after rows is not read and not written.
+///
+/// # Arguments
+///
+/// * `src`: Source slice with RGBA data
+/// * `src_stride`: Items between starts of source rows, at least `width * channels`
+/// * `dst`: Destination slice for RGBA data
+/// * `dst_stride`: Items between starts of destination rows, at least `width * channels`
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_rgb_f16_with_stride(
+    src: &[f16],
+    src_stride: usize,
+    dst: &mut [f16],
+    dst_stride: usize,
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_with_stride::<f16, Rgb>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology Gray image with alpha stored in f16
+///
+/// # Arguments
+///
+/// * `src`: Source slice with Gray with alpha data
+/// * `dst`: Destination slice for Gray with alpha data
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_gray_alpha_f16(
+    src: &[f16],
+    dst: &mut [f16],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology::<f16, GrayAlpha>(
+        src,
+        dst,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology Gray image with alpha stored in f16 with padded rows
+///
+/// Rows are `src_stride` and `dst_stride` items apart, padding after rows is not read and not written.
+///
+/// # Arguments
+///
+/// * `src`: Source slice with Gray with alpha data
+/// * `src_stride`: Items between starts of source rows, at least `width * channels`
+/// * `dst`: Destination slice for Gray with alpha data
+/// * `dst_stride`: Items between starts of destination rows, at least `width * channels`
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_gray_alpha_f16_with_stride(
+    src: &[f16],
+    src_stride: usize,
+    dst: &mut [f16],
+    dst_stride: usize,
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_with_stride::<f16, GrayAlpha>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology Gray image stored in f16
+///
+/// # Arguments
+///
+/// * `src`: Source slice with Gray data
+/// * `dst`: Destination slice for Gray data
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_gray_f16(
+    src: &[f16],
+    dst: &mut [f16],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology::<f16, Gray>(
+        src,
+        dst,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology Gray image stored in f16, renamed to [morphology_gray_f16]
+#[deprecated(
+    note = "use morphology_gray_f16, planar images are handled by morphology_gray_<type> functions"
+)]
+pub fn morphology_f16(
+    src: &[f16],
+    dst: &mut [f16],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    morphology_gray_f16(
+        src,
+        dst,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology Gray image stored in f16 with padded rows
+///
+/// Rows are `src_stride` and `dst_stride` items apart, padding after rows is not read and not written.
+///
+/// # Arguments
+///
+/// * `src`: Source slice with Gray data
+/// * `src_stride`: Items between starts of source rows, at least `width * channels`
+/// * `dst`: Destination slice for Gray data
+/// * `dst_stride`: Items between starts of destination rows, at least `width * channels`
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_gray_f16_with_stride(
+    src: &[f16],
+    src_stride: usize,
+    dst: &mut [f16],
+    dst_stride: usize,
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_with_stride::<f16, Gray>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology Gray image stored in f16 with padded rows, renamed to [morphology_gray_f16_with_stride]
+#[deprecated(
+    note = "use morphology_gray_f16_with_stride, planar images are handled by morphology_gray_<type> functions"
+)]
+pub fn morphology_f16_with_stride(
+    src: &[f16],
+    src_stride: usize,
+    dst: &mut [f16],
+    dst_stride: usize,
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    morphology_gray_f16_with_stride(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Dilate a gray (planar) image stored in f16 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Image slice, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn dilate_f16_in_place(
+    image: &mut [f16],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<f16, Gray>(
+        image,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Erode a gray (planar) image stored in f16 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Image slice, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn erode_f16_in_place(
+    image: &mut [f16],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<f16, Gray>(
+        image,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology a gray (planar) image stored in f16 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Image slice, replaced by the result
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_gray_f16_in_place(
+    image: &mut [f16],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<f16, Gray>(
+        image,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology a gray (planar) image stored in f16 in place, renamed to [morphology_gray_f16_in_place]
+#[deprecated(
+    note = "use morphology_gray_f16_in_place, planar images are handled by morphology_gray_<type> functions"
+)]
+pub fn morphology_f16_in_place(
+    image: &mut [f16],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    morphology_gray_f16_in_place(
+        image,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Dilate a Gray image with alpha stored in f16 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with Gray with alpha data, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn dilate_gray_alpha_f16_in_place(
+    image: &mut [f16],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<f16, GrayAlpha>(
+        image,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Erode a Gray image with alpha stored in f16 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with Gray with alpha data, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn erode_gray_alpha_f16_in_place(
+    image: &mut [f16],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<f16, GrayAlpha>(
+        image,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology a Gray image with alpha stored in f16 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with Gray with alpha data, replaced by the result
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_gray_alpha_f16_in_place(
+    image: &mut [f16],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<f16, GrayAlpha>(
+        image,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Dilate an RGB image stored in f16 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with RGB data, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn dilate_rgb_f16_in_place(
+    image: &mut [f16],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<f16, Rgb>(
+        image,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Erode an RGB image stored in f16 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with RGB data, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn erode_rgb_f16_in_place(
+    image: &mut [f16],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<f16, Rgb>(
+        image,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology an RGB image stored in f16 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with RGB data, replaced by the result
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_rgb_f16_in_place(
+    image: &mut [f16],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<f16, Rgb>(
+        image,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Dilate an RGBA image stored in f16 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with RGBA data, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn dilate_rgba_f16_in_place(
+    image: &mut [f16],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<f16, Rgba>(
+        image,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Erode an RGBA image stored in f16 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with RGBA data, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn erode_rgba_f16_in_place(
+    image: &mut [f16],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<f16, Rgba>(
+        image,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology an RGBA image stored in f16 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with RGBA data, replaced by the result
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_rgba_f16_in_place(
+    image: &mut [f16],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<f16, Rgba>(
+        image,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
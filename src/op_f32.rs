@@ -27,12 +27,8 @@
  * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
 use crate::border_mode::{BorderMode, MorphScalar};
-use crate::difference::MorphGradient;
-use crate::morph_gray_alpha::make_morphology_gray_alpha;
-use crate::morph_rgb::make_morphology_rgb;
-use crate::morph_rgba::make_morphology_rgba;
-use crate::op_impl::make_morphology;
-use crate::op_type::MorphOp;
+use crate::error::MorphologyError;
+use crate::generic::{self, Gray, GrayAlpha, Rgb, Rgba};
 use crate::structuring_element::KernelShape;
 use crate::{ImageSize, MorphExOp, MorphologyThreadingPolicy};
 
@@ -58,19 +54,18 @@ pub fn dilate_f32(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    unsafe {
-        make_morphology::<f32, { MorphOp::Dilate as u8 }>(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        )
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<f32, Gray>(
+        src,
+        dst,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Dilate an RGB stored in f32 image
@@ -95,19 +90,18 @@ pub fn dilate_rgb_f32(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    unsafe {
-        make_morphology_rgb::<f32, { MorphOp::Dilate as u8 }>(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        )
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<f32, Rgb>(
+        src,
+        dst,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Erode a gray (planar) stored in f32 image
@@ -132,19 +126,18 @@ pub fn erode_f32(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    unsafe {
-        make_morphology::<f32, { MorphOp::Erode as u8 }>(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        )
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<f32, Gray>(
+        src,
+        dst,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Erode an RGB image stored in f32
@@ -169,19 +162,18 @@ pub fn erode_rgb_f32(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    unsafe {
-        make_morphology_rgb::<f32, { MorphOp::Erode as u8 }>(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        )
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<f32, Rgb>(
+        src,
+        dst,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Erode an RGBA image stored in f32
@@ -206,19 +198,18 @@ pub fn erode_rgba_f32(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    unsafe {
-        make_morphology_rgba::<f32, { MorphOp::Erode as u8 }>(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        )
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<f32, Rgba>(
+        src,
+        dst,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Dilate an RGBA image stored in f32
@@ -243,19 +234,18 @@ pub fn dilate_rgba_f32(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    unsafe {
-        make_morphology_rgba::<f32, { MorphOp::Dilate as u8 }>(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        )
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<f32, Rgba>(
+        src,
+        dst,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Erode Gray image with alpha
@@ -280,19 +270,18 @@ pub fn erode_gray_alpha_f32(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    unsafe {
-        make_morphology_gray_alpha::<f32, { MorphOp::Erode as u8 }>(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        )
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<f32, GrayAlpha>(
+        src,
+        dst,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Dilate an Gray image with alpha
@@ -317,19 +306,18 @@ pub fn dilate_gray_alpha_f32(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    unsafe {
-        make_morphology_gray_alpha::<f32, { MorphOp::Dilate as u8 }>(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        )
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<f32, GrayAlpha>(
+        src,
+        dst,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Morphology an RGBA image stored in f32
@@ -356,133 +344,64 @@ pub fn morphology_rgba_f32(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    match morph_op {
-        MorphExOp::Dilate => dilate_rgba_f32(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        ),
-        MorphExOp::Erode => erode_rgba_f32(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        ),
-        MorphExOp::Opening => {
-            let mut transient = vec![0f32; dst.len()];
-            erode_rgba_f32(
-                src,
-                &mut transient,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            dilate_rgba_f32(
-                &transient,
-                dst,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )
-        }
-        MorphExOp::Closing => {
-            let mut transient = vec![0f32; dst.len()];
-            dilate_rgba_f32(
-                src,
-                &mut transient,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            erode_rgba_f32(
-                &transient,
-                dst,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )
-        }
-        MorphExOp::Gradient => {
-            let mut dilation = vec![0.; dst.len()];
-            dilate_rgba_f32(
-                src,
-                &mut dilation,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            let mut erosion = vec![0.; dst.len()];
-            erode_rgba_f32(
-                &src,
-                &mut erosion,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            f32::morph_gradient(&dilation, &erosion, dst);
-            Ok(())
-        }
-        MorphExOp::TopHat => {
-            let mut opened = vec![0.; dst.len()];
-            morphology_rgba_f32(
-                src,
-                &mut opened,
-                MorphExOp::Opening,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            f32::morph_gradient(&src, &opened, dst);
-            Ok(())
-        }
-        MorphExOp::BlackHat => {
-            let mut closed = vec![0.; dst.len()];
-            morphology_rgba_f32(
-                src,
-                &mut closed,
-                MorphExOp::Closing,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            f32::morph_gradient(&closed, &src, dst);
-            Ok(())
-        }
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<f32, Rgba>(
+        src,
+        dst,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology an RGBA image stored in f32 with padded rows
+///
+/// Rows are `src_stride` and `dst_stride` items apart, padding after rows is not read and not written.
+///
+/// # Arguments
+///
+/// * `src`: Source slice with RGBA data
+/// * `src_stride`: Items between starts of source rows, at least `width * channels`
+/// * `dst`: Destination slice for RGBA data
+/// * `dst_stride`: Items between starts of destination rows, at least `width * channels`
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_rgba_f32_with_stride(
+    src: &[f32],
+    src_stride: usize,
+    dst: &mut [f32],
+    dst_stride: usize,
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_with_stride::<f32, Rgba>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Morphology an RGB image stored in f32
@@ -509,131 +428,744 @@ pub fn morphology_rgb_f32(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    match morph_op {
-        MorphExOp::Dilate => dilate_rgb_f32(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        ),
-        MorphExOp::Erode => erode_rgb_f32(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        ),
-        MorphExOp::Opening => {
-            let mut transient = vec![0f32; dst.len()];
-            erode_rgb_f32(
-                src,
-                &mut transient,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            dilate_rgb_f32(
-                &transient,
-                dst,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )
-        }
-        MorphExOp::Closing => {
-            let mut transient = vec![0f32; dst.len()];
-            dilate_rgb_f32(
-                src,
-                &mut transient,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            erode_rgb_f32(
-                &transient,
-                dst,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )
-        }
-        MorphExOp::Gradient => {
-            let mut dilation = vec![0.; dst.len()];
-            dilate_rgb_f32(
-                src,
-                &mut dilation,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            let mut erosion = vec![0.; dst.len()];
-            erode_rgb_f32(
-                &src,
-                &mut erosion,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            f32::morph_gradient(&dilation, &erosion, dst);
-            Ok(())
-        }
-        MorphExOp::TopHat => {
-            let mut opened = vec![0.; dst.len()];
-            morphology_rgb_f32(
-                src,
-                &mut opened,
-                MorphExOp::Opening,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            f32::morph_gradient(&src, &opened, dst);
-            Ok(())
-        }
-        MorphExOp::BlackHat => {
-            let mut closed = vec![0.; dst.len()];
-            morphology_rgb_f32(
-                src,
-                &mut closed,
-                MorphExOp::Closing,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            f32::morph_gradient(&closed, &src, dst);
-            Ok(())
-        }
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<f32, Rgb>(
+        src,
+        dst,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology an RGB image stored in f32 with padded rows
+///
+/// Rows are `src_stride` and `dst_stride` items apart, padding after rows is not read and not written.
+///
+/// # Arguments
+///
+/// * `src`: Source slice with RGBA data
+/// * `src_stride`: Items between starts of source rows, at least `width * channels`
+/// * `dst`: Destination slice for RGBA data
+/// * `dst_stride`: Items between starts of destination rows, at least `width * channels`
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_rgb_f32_with_stride(
+    src: &[f32],
+    src_stride: usize,
+    dst: &mut [f32],
+    dst_stride: usize,
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_with_stride::<f32, Rgb>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology Gray image with alpha stored in f32
+///
+/// # Arguments
+///
+/// * `src`: Source slice with Gray with alpha data
+/// * `dst`: Destination slice for Gray with alpha data
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_gray_alpha_f32(
+    src: &[f32],
+    dst: &mut [f32],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology::<f32, GrayAlpha>(
+        src,
+        dst,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology Gray image with alpha stored in f32 with padded rows
+///
+/// Rows are `src_stride` and `dst_stride` items apart, padding after rows is not read and not written.
+///
+/// # Arguments
+///
+/// * `src`: Source slice with Gray with alpha data
+/// * `src_stride`: Items between starts of source rows, at least `width * channels`
+/// * `dst`: Destination slice for Gray with alpha data
+/// * `dst_stride`: Items between starts of destination rows, at least `width * channels`
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_gray_alpha_f32_with_stride(
+    src: &[f32],
+    src_stride: usize,
+    dst: &mut [f32],
+    dst_stride: usize,
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_with_stride::<f32, GrayAlpha>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology Gray image stored in f32
+///
+/// # Arguments
+///
+/// * `src`: Source slice with Gray data
+/// * `dst`: Destination slice for Gray data
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_gray_f32(
+    src: &[f32],
+    dst: &mut [f32],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology::<f32, Gray>(
+        src,
+        dst,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology Gray image stored in f32, renamed to [morphology_gray_f32]
+#[deprecated(
+    note = "use morphology_gray_f32, planar images are handled by morphology_gray_<type> functions"
+)]
+pub fn morphology_f32(
+    src: &[f32],
+    dst: &mut [f32],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    morphology_gray_f32(
+        src,
+        dst,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology Gray image stored in f32 with padded rows
+///
+/// Rows are `src_stride` and `dst_stride` items apart, padding after rows is not read and not written.
+///
+/// # Arguments
+///
+/// * `src`: Source slice with Gray data
+/// * `src_stride`: Items between starts of source rows, at least `width * channels`
+/// * `dst`: Destination slice for Gray data
+/// * `dst_stride`: Items between starts of destination rows, at least `width * channels`
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_gray_f32_with_stride(
+    src: &[f32],
+    src_stride: usize,
+    dst: &mut [f32],
+    dst_stride: usize,
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_with_stride::<f32, Gray>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology Gray image stored in f32 with padded rows, renamed to [morphology_gray_f32_with_stride]
+#[deprecated(
+    note = "use morphology_gray_f32_with_stride, planar images are handled by morphology_gray_<type> functions"
+)]
+pub fn morphology_f32_with_stride(
+    src: &[f32],
+    src_stride: usize,
+    dst: &mut [f32],
+    dst_stride: usize,
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    morphology_gray_f32_with_stride(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Dilate a gray (planar) image stored in f32 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Image slice, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn dilate_f32_in_place(
+    image: &mut [f32],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<f32, Gray>(
+        image,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Erode a gray (planar) image stored in f32 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Image slice, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn erode_f32_in_place(
+    image: &mut [f32],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<f32, Gray>(
+        image,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology a gray (planar) image stored in f32 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Image slice, replaced by the result
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_gray_f32_in_place(
+    image: &mut [f32],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<f32, Gray>(
+        image,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology a gray (planar) image stored in f32 in place, renamed to [morphology_gray_f32_in_place]
+#[deprecated(
+    note = "use morphology_gray_f32_in_place, planar images are handled by morphology_gray_<type> functions"
+)]
+pub fn morphology_f32_in_place(
+    image: &mut [f32],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    morphology_gray_f32_in_place(
+        image,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Dilate a Gray image with alpha stored in f32 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with Gray with alpha data, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn dilate_gray_alpha_f32_in_place(
+    image: &mut [f32],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<f32, GrayAlpha>(
+        image,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Erode a Gray image with alpha stored in f32 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with Gray with alpha data, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn erode_gray_alpha_f32_in_place(
+    image: &mut [f32],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<f32, GrayAlpha>(
+        image,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology a Gray image with alpha stored in f32 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with Gray with alpha data, replaced by the result
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_gray_alpha_f32_in_place(
+    image: &mut [f32],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<f32, GrayAlpha>(
+        image,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Dilate an RGB image stored in f32 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with RGB data, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn dilate_rgb_f32_in_place(
+    image: &mut [f32],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<f32, Rgb>(
+        image,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Erode an RGB image stored in f32 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with RGB data, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn erode_rgb_f32_in_place(
+    image: &mut [f32],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<f32, Rgb>(
+        image,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology an RGB image stored in f32 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with RGB data, replaced by the result
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_rgb_f32_in_place(
+    image: &mut [f32],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<f32, Rgb>(
+        image,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Dilate an RGBA image stored in f32 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with RGBA data, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn dilate_rgba_f32_in_place(
+    image: &mut [f32],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<f32, Rgba>(
+        image,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Erode an RGBA image stored in f32 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with RGBA data, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn erode_rgba_f32_in_place(
+    image: &mut [f32],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<f32, Rgba>(
+        image,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology an RGBA image stored in f32 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with RGBA data, replaced by the result
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_rgba_f32_in_place(
+    image: &mut [f32],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<f32, Rgba>(
+        image,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
@@ -26,115 +26,602 @@
  * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
  * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
-use crate::arena::make_arena;
+use crate::arena::{alloc_arena, arena_window, fill_arena, pad_arena, ArenaPlacement};
 use crate::border_mode::{BorderMode, MorphScalar};
-use crate::filter::Row2DFilter;
-use crate::filter_op_declare::MorthOpFilterFlat2DRow;
-use crate::morph_base::MorphNativeOp;
+use crate::error::MorphologyError;
+use crate::filter::{
+    scalar_filter, scalar_weighted_filter, MorphFilterFlat2DRow, MorphFilterWeighted2DRow,
+    Row2DFilter,
+};
+use crate::filter_op_declare::{
+    arena_offsets, Arena, MorthOpFilterFlat2DRow, MorthOpFilterWeighted2DRow,
+};
+use crate::flat_se::{Chord, PeriodicLine};
+use crate::img_size::{image_rows, image_rows_mut, Roi};
+use crate::morph_base::{MorphNativeOp, MorphWeightedOp};
+use crate::morph_rows::{scalar_rows_op, MorphRows, MorphRowsHandler};
+use crate::morphology_options::MorphologyBackend;
 use crate::op_type::MorphOp;
-use crate::se_scan::scan_se;
+use crate::periodic_line::{apply_periodic_lines, PeriodicScratch};
+use crate::se_decomposition::decompose_periodic_lines;
+use crate::se_scan::{scan_se, scan_weighted_se};
 use crate::structuring_element::KernelShape;
+use crate::thread_policy::ThreadingPool;
+use crate::transpose::{scalar_transpose, TransposeHandler};
 use crate::unsafe_slice::UnsafeSlice;
-use crate::{ImageSize, MorphologyThreadingPolicy};
-use std::sync::Arc;
+use crate::urbach_wilkinson::{chords_are_cheaper, make_morphology_chords, ChordScratch};
+use crate::van_herk::{make_morphology_rect, RectScratch};
+use crate::{ImageSize, MorphologyLayout};
 use num_traits::AsPrimitive;
+use rayon::prelude::*;
 
-pub(crate) unsafe fn make_morphology<T, const OP_TYPE: u8>(
+pub(crate) fn check_structuring_element(
+    structuring_element_len: usize,
+    structuring_element_size: KernelShape,
+) -> Result<(), MorphologyError> {
+    let kernel_width = structuring_element_size.width;
+    let kernel_height = structuring_element_size.height;
+    if kernel_height * kernel_width != structuring_element_len {
+        return Err(MorphologyError::StructuringElementSizeMismatch {
+            expected: kernel_height * kernel_width,
+            actual: structuring_element_len,
+        });
+    }
+
+    if structuring_element_size.anchor_x >= kernel_width
+        || structuring_element_size.anchor_y >= kernel_height
+    {
+        return Err(MorphologyError::AnchorOutOfBounds {
+            anchor_x: structuring_element_size.anchor_x,
+            anchor_y: structuring_element_size.anchor_y,
+            width: kernel_width,
+            height: kernel_height,
+        });
+    }
+
+    Ok(())
+}
+
+pub(crate) fn check_image_size(image_size: ImageSize) -> Result<(), MorphologyError> {
+    if image_size.width == 0 || image_size.height == 0 {
+        return Err(MorphologyError::ZeroSizedImage {
+            width: image_size.width,
+            height: image_size.height,
+        });
+    }
+    Ok(())
+}
+
+/// Checks that images of the layout have at least one channel
+pub(crate) fn check_layout(layout: MorphologyLayout) -> Result<(), MorphologyError> {
+    if layout.channels() == 0 {
+        return Err(MorphologyError::ZeroChannels);
+    }
+    Ok(())
+}
+
+/// Checks that region of interest is not empty and fits into the image
+pub(crate) fn check_roi(image_size: ImageSize, roi: Roi) -> Result<(), MorphologyError> {
+    check_image_size(image_size)?;
+    check_image_size(roi.size())?;
+    if roi.x + roi.width > image_size.width || roi.y + roi.height > image_size.height {
+        return Err(MorphologyError::RoiOutOfBounds {
+            x: roi.x,
+            y: roi.y,
+            width: roi.width,
+            height: roi.height,
+            image_width: image_size.width,
+            image_height: image_size.height,
+        });
+    }
+    Ok(())
+}
+
+/// Checks tightly packed interleaved image with `channels` values per pixel
+pub(crate) fn check_packed_image<T>(
+    image: &[T],
+    image_size: ImageSize,
+    channels: usize,
+) -> Result<(), MorphologyError> {
+    check_image_size(image_size)?;
+
+    let expected = image_size.width * image_size.height * channels;
+    if image.len() != expected {
+        return Err(MorphologyError::ImageSizeMismatch {
+            expected,
+            actual: image.len(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Checks interleaved image with `channels` values per pixel
+pub(crate) fn check_image<T>(
     src: &[T],
-    dst: &mut [T],
+    dst: &[T],
+    image_size: ImageSize,
+    channels: usize,
+) -> Result<(), MorphologyError> {
+    check_image_size(image_size)?;
+
+    if src.len() != dst.len() {
+        return Err(MorphologyError::SourceDestinationMismatch {
+            src: src.len(),
+            dst: dst.len(),
+        });
+    }
+
+    check_packed_image(src, image_size, channels)
+}
+
+/// Minimal length of an image with rows `stride` items apart
+pub(crate) fn strided_len(image_size: ImageSize, stride: usize, channels: usize) -> usize {
+    stride * (image_size.height - 1) + image_size.width * channels
+}
+
+/// Checks interleaved image with `channels` values per pixel and rows `stride` items apart,
+/// unlike [check_image] buffers may be longer than needed
+pub(crate) fn check_strided_image<T>(
+    image: &[T],
+    stride: usize,
+    image_size: ImageSize,
+    channels: usize,
+) -> Result<(), MorphologyError> {
+    check_image_size(image_size)?;
+
+    let row_length = image_size.width * channels;
+    if stride < row_length {
+        return Err(MorphologyError::StrideTooSmall {
+            stride,
+            min_stride: row_length,
+        });
+    }
+
+    let expected = strided_len(image_size, stride, channels);
+    if image.len() < expected {
+        return Err(MorphologyError::ImageSizeMismatch {
+            expected,
+            actual: image.len(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Reflects structuring element around its anchor, dilation of non-flat element
+/// is `max(f(x - b) + w(b))` so it runs with reflected weights
+fn reflect_weights(weights: &[f32], kernel: KernelShape) -> (Vec<f32>, KernelShape) {
+    let mut reflected = weights.to_vec();
+    reflected.reverse();
+    (
+        reflected,
+        KernelShape::new_with_anchor(
+            kernel.width,
+            kernel.height,
+            kernel.width - 1 - kernel.anchor_x,
+            kernel.height - 1 - kernel.anchor_y,
+        ),
+    )
+}
+
+/// Algorithm chosen for the structuring element with its buffers
+enum MorphMethod<T: 'static + MorphWeightedOp<T>> {
+    /// Structuring element is empty, image is copied through the unpadded arena
+    Copy,
+    Rect {
+        kernel: KernelShape,
+        scratch: Vec<RectScratch<T>>,
+    },
+    Chords {
+        chords: Vec<Chord>,
+        scratch: Vec<ChordScratch<T>>,
+    },
+    Direct {
+        filter: MorphFilterFlat2DRow<T>,
+        offsets: Vec<usize>,
+    },
+    Weighted {
+        filter: MorphFilterWeighted2DRow<T>,
+        offsets: Vec<usize>,
+        weights: Vec<T::Weight>,
+    },
+}
+
+/// Dilation or erosion of a single plane prepared for one image size.
+///
+/// Structuring element is analyzed, filters are dispatched and all buffers are allocated
+/// once, so each run only pads the image and filters it.
+pub(crate) struct PlanarMorphology<T: 'static + MorphWeightedOp<T>> {
+    op: MorphOp,
     image_size: ImageSize,
-    structuring_element: &[u8],
-    structuring_element_size: KernelShape,
     border_mode: BorderMode,
-    border_constant: MorphScalar,
-    threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String>
+    backend: MorphologyBackend,
+    placement: ArenaPlacement,
+    arena: Arena<T>,
+    /// Periodic lines applied to the whole arena before the method
+    lines: Vec<PeriodicLine>,
+    lines_spare: Vec<T>,
+    lines_scratch: Vec<PeriodicScratch<T>>,
+    method: MorphMethod<T>,
+}
+
+impl<T> PlanarMorphology<T>
 where
-    T: Copy + Default + 'static + Send + Sync + MorphNativeOp<T> + Row2DFilter<T>,
+    T: Copy
+        + Default
+        + 'static
+        + Send
+        + Sync
+        + MorphNativeOp<T>
+        + MorphWeightedOp<T>
+        + Row2DFilter<T>
+        + MorphRows<T>,
     f64: AsPrimitive<T>,
 {
-    if src.len() != dst.len() {
-        return Err("Source slice size and destination must match"
-            .parse()
-            .unwrap());
+    /// Plans morphology with a flat structuring element, `tasks` is count of parallel tasks
+    pub(crate) fn new(
+        op: MorphOp,
+        image_size: ImageSize,
+        structuring_element: &[u8],
+        structuring_element_size: KernelShape,
+        border_mode: BorderMode,
+        backend: MorphologyBackend,
+        tasks: usize,
+    ) -> Result<Self, MorphologyError> {
+        check_image_size(image_size)?;
+        check_structuring_element(structuring_element.len(), structuring_element_size)?;
+
+        let analyzed_se = unsafe { scan_se(structuring_element, structuring_element_size) };
+
+        if analyzed_se.is_empty {
+            let arena = alloc_arena::<T, 1>(
+                image_size.width as u32,
+                image_size.height as u32,
+                KernelShape::new(1, 1),
+            );
+            return Ok(Self::with_method(
+                op,
+                image_size,
+                border_mode,
+                backend,
+                arena,
+                MorphMethod::Copy,
+            ));
+        }
+
+        let arena = alloc_arena::<T, 1>(
+            image_size.width as u32,
+            image_size.height as u32,
+            structuring_element_size,
+        );
+        let arena_width = arena.width;
+        let width = image_size.width;
+
+        // Direct filter is as fast for 3x3 and smaller rectangles
+        if analyzed_se.is_rectangle && analyzed_se.left_front.element_offsets.len() > 9 {
+            let method = MorphMethod::Rect {
+                kernel: structuring_element_size,
+                scratch: (0..tasks)
+                    .map(|_| RectScratch::new(arena_width, structuring_element_size))
+                    .collect(),
+            };
+            return Ok(Self::with_method(
+                op,
+                image_size,
+                border_mode,
+                backend,
+                arena,
+                method,
+            ));
+        }
+
+        let (lines, analyzed_se) = match decompose_periodic_lines(&analyzed_se) {
+            Some(decomposition) => (decomposition.lines, decomposition.residual),
+            None => (vec![], analyzed_se),
+        };
+
+        let method = if chords_are_cheaper(
+            &analyzed_se.chords,
+            analyzed_se.left_front.element_offsets.len(),
+        ) {
+            MorphMethod::Chords {
+                scratch: (0..tasks)
+                    .map(|_| ChordScratch::new(arena_width, width, &analyzed_se.chords))
+                    .collect(),
+                chords: analyzed_se.chords,
+            }
+        } else {
+            MorphMethod::Direct {
+                filter: match backend {
+                    MorphologyBackend::Auto => T::get_filter(op),
+                    MorphologyBackend::Scalar => scalar_filter(op),
+                },
+                offsets: arena_offsets(&arena, &analyzed_se.left_front),
+            }
+        };
+
+        let mut planned = Self::with_method(op, image_size, border_mode, backend, arena, method);
+        if !lines.is_empty() {
+            planned.lines_spare = vec![T::default(); planned.arena.arena.len()];
+            planned.lines_scratch = (0..tasks)
+                .map(|_| PeriodicScratch::new(arena_width, &lines))
+                .collect();
+            planned.lines = lines;
+        }
+
+        Ok(planned)
     }
 
-    let kernel_width = structuring_element_size.width;
-    let kernel_height = structuring_element_size.height;
-    if kernel_height * kernel_width != structuring_element.len() {
-        return Err(format!(
-            "Structuring element expected to be {} but it was {}",
-            kernel_height * kernel_width,
-            structuring_element.len()
-        ));
+    fn with_method(
+        op: MorphOp,
+        image_size: ImageSize,
+        border_mode: BorderMode,
+        backend: MorphologyBackend,
+        arena: Arena<T>,
+        method: MorphMethod<T>,
+    ) -> Self {
+        PlanarMorphology {
+            op,
+            image_size,
+            border_mode,
+            backend,
+            placement: ArenaPlacement::whole(image_size),
+            arena,
+            lines: vec![],
+            lines_spare: vec![],
+            lines_scratch: vec![],
+            method,
+        }
     }
 
-    let width = image_size.width;
-    let height = image_size.height;
+    /// Plans morphology with a non-flat structuring element,
+    /// points with non-finite weights are not part of it
+    pub(crate) fn new_non_flat(
+        op: MorphOp,
+        image_size: ImageSize,
+        weights: &[f32],
+        weights_size: KernelShape,
+        border_mode: BorderMode,
+        backend: MorphologyBackend,
+        tasks: usize,
+    ) -> Result<Self, MorphologyError> {
+        check_image_size(image_size)?;
+        check_structuring_element(weights.len(), weights_size)?;
+
+        let (weights, weights_size) = match op {
+            MorphOp::Dilate => reflect_weights(weights, weights_size),
+            MorphOp::Erode => (weights.to_vec(), weights_size),
+        };
+
+        // Weights that are zero for the pixel type give the same result as the flat element,
+        // which has faster paths, dilation passes it already reflected
+        if weights
+            .iter()
+            .filter(|x| x.is_finite())
+            .all(|&x| T::is_zero_weight(x))
+        {
+            let structuring_element = weights
+                .iter()
+                .map(|x| x.is_finite() as u8)
+                .collect::<Vec<u8>>();
+            return Self::new(
+                op,
+                image_size,
+                &structuring_element,
+                weights_size,
+                border_mode,
+                backend,
+                tasks,
+            );
+        }
+
+        let analyzed_se = unsafe { scan_weighted_se(&weights, weights_size) };
 
-    if src.len() != width * height {
-        return Err(format!(
-            "Image size expected to be {} but it was {}",
-            width * height,
-            src.len()
-        ));
+        let arena = alloc_arena::<T, 1>(
+            image_size.width as u32,
+            image_size.height as u32,
+            weights_size,
+        );
+
+        let weights = analyzed_se
+            .left_front
+            .weights
+            .iter()
+            .map(|&x| match op {
+                MorphOp::Dilate => T::make_weight::<{ MorphOp::Dilate as u8 }>(x),
+                MorphOp::Erode => T::make_weight::<{ MorphOp::Erode as u8 }>(x),
+            })
+            .collect();
+
+        let method = MorphMethod::Weighted {
+            filter: match backend {
+                MorphologyBackend::Auto => T::get_weighted_filter(op),
+                MorphologyBackend::Scalar => scalar_weighted_filter(op),
+            },
+            offsets: arena_offsets(&arena, &analyzed_se.left_front),
+            weights,
+        };
+        Ok(Self::with_method(
+            op,
+            image_size,
+            border_mode,
+            backend,
+            arena,
+            method,
+        ))
     }
 
-    let analyzed_se = scan_se(structuring_element, structuring_element_size);
+    /// Part of the whole image read to process `region`
+    pub(crate) fn source_window(&self, image_size: ImageSize, region: Roi) -> Roi {
+        arena_window(
+            image_size,
+            region,
+            self.arena.pad_left,
+            self.arena.pad_right,
+            self.arena.pad_top,
+            self.arena.pad_bottom,
+            self.border_mode,
+        )
+    }
 
-    if analyzed_se.is_empty {
-        for (src, dst) in src.iter().zip(dst.iter_mut()) {
-            *dst = *src;
+    /// Places planned image as a region of a larger image, see [ArenaPlacement]
+    pub(crate) fn place(&mut self, placement: ArenaPlacement) {
+        debug_assert_eq!(placement.region.size(), self.image_size);
+        self.placement = placement;
+    }
+
+    /// Runs planned operation, `pool` must have the same threads count as it was planned for.
+    ///
+    /// # Safety
+    ///
+    /// `src` must hold the placement window and `dst` the planned image with rows
+    /// `src_stride` and `dst_stride` items apart, strides must not be less than their widths.
+    pub(crate) unsafe fn execute(
+        &mut self,
+        src: &[T],
+        src_stride: usize,
+        dst: &mut [T],
+        dst_stride: usize,
+        border_constant: MorphScalar,
+        pool: &ThreadingPool,
+    ) {
+        self.load(src, src_stride, border_constant);
+        self.run(dst, dst_stride, pool);
+    }
+
+    /// Pads the placement window held by `src` into the arena,
+    /// after that `src` is not read and may be overwritten by [PlanarMorphology::run]
+    pub(crate) unsafe fn load(
+        &mut self,
+        src: &[T],
+        src_stride: usize,
+        border_constant: MorphScalar,
+    ) {
+        // Items past the last row must not be treated as more rows
+        let src = &src[..strided_len(self.placement.window.size(), src_stride, 1)];
+        fill_arena::<T, 1>(
+            src,
+            src_stride,
+            &mut self.arena,
+            self.placement,
+            self.border_mode,
+            border_constant,
+        );
+    }
+
+    /// Pads the arena after the region was written to [PlanarMorphology::arena_inner_mut],
+    /// the region must be its own placement window
+    pub(crate) fn load_in_place(&mut self, border_constant: MorphScalar) {
+        pad_arena::<T, 1>(
+            &mut self.arena,
+            self.placement,
+            self.border_mode,
+            border_constant,
+        );
+    }
+
+    /// Inner part of the arena where the region is padded, with its stride
+    pub(crate) fn arena_inner_mut(&mut self) -> (&mut [T], usize) {
+        let stride = self.arena.width;
+        let start = self.arena.pad_top * stride + self.arena.pad_left;
+        (&mut self.arena.arena[start..], stride)
+    }
+
+    /// Arena is not used after [PlanarMorphology::run] until the next load,
+    /// it holds at least the planned image
+    pub(crate) fn scratch_mut(&mut self) -> &mut [T] {
+        &mut self.arena.arena
+    }
+
+    /// Runs planned operation on the loaded arena, `pool` must have the same threads count
+    /// as it was planned for.
+    ///
+    /// # Safety
+    ///
+    /// `dst` must hold the planned image with rows `dst_stride` items apart,
+    /// stride must not be less than the image width.
+    pub(crate) unsafe fn run(&mut self, dst: &mut [T], dst_stride: usize, pool: &ThreadingPool) {
+        let dst = &mut dst[..strided_len(self.image_size, dst_stride, 1)];
+        match self.op {
+            MorphOp::Dilate => self.run_op::<{ MorphOp::Dilate as u8 }>(dst, dst_stride, pool),
+            MorphOp::Erode => self.run_op::<{ MorphOp::Erode as u8 }>(dst, dst_stride, pool),
         }
-        return Ok(());
-    }
-
-    let op_type: MorphOp = OP_TYPE.into();
-
-    let filter = Arc::new(T::get_filter(op_type));
-
-    let arena = make_arena::<T, 1>(
-        src,
-        width as u32,
-        height as u32,
-        structuring_element_size,
-        border_mode,
-        border_constant,
-    );
-    let arena_arc = Arc::new(arena);
-
-    if let Some(pool) = threading_policy.get_pool(image_size) {
-        pool.scope(|scope| {
-            let unsafe_slice = UnsafeSlice::new(dst);
-
-            for y in 0..height {
-                let cloned_se = analyzed_se.clone();
-                let cloned_filter = filter.clone();
-                let arena_clone = arena_arc.clone();
-                scope.spawn(move |_| {
-                    cloned_filter.dispatch_row(
-                        &arena_clone,
-                        &unsafe_slice,
-                        image_size,
-                        cloned_se,
-                        y,
-                    );
-                });
-            }
-        })
-    } else {
-        for y in 0..height {
-            let unsafe_slice = UnsafeSlice::new(dst);
-            let arena_clone = arena_arc.clone();
-            filter.dispatch_row(
-                &arena_clone,
-                &unsafe_slice,
-                image_size,
-                analyzed_se.clone(),
-                y,
+    }
+
+    unsafe fn run_op<const OP_TYPE: u8>(
+        &mut self,
+        dst: &mut [T],
+        dst_stride: usize,
+        pool: &ThreadingPool,
+    ) {
+        let image_size = self.image_size;
+        let rows_op: MorphRowsHandler<T> = match self.backend {
+            MorphologyBackend::Auto => T::get_rows_op(self.op),
+            MorphologyBackend::Scalar => scalar_rows_op(self.op),
+        };
+        let transpose: TransposeHandler<T> = match self.backend {
+            MorphologyBackend::Auto => T::get_transpose(),
+            MorphologyBackend::Scalar => scalar_transpose,
+        };
+
+        if !self.lines.is_empty() {
+            apply_periodic_lines::<T, OP_TYPE>(
+                &mut self.arena,
+                &mut self.lines_spare,
+                &self.lines,
+                pool,
+                &mut self.lines_scratch,
+                rows_op,
+                transpose,
             );
         }
-    }
 
-    Ok(())
+        let arena = &self.arena;
+
+        match &mut self.method {
+            MorphMethod::Copy => {
+                for (src, dst) in image_rows(&arena.arena, arena.width, image_size.width)
+                    .zip(image_rows_mut(dst, dst_stride, image_size.width))
+                {
+                    dst.copy_from_slice(src);
+                }
+            }
+            MorphMethod::Rect { kernel, scratch } => {
+                make_morphology_rect::<T, OP_TYPE>(
+                    arena, dst, dst_stride, image_size, *kernel, pool, scratch, rows_op, transpose,
+                );
+            }
+            MorphMethod::Chords { chords, scratch } => {
+                make_morphology_chords::<T, OP_TYPE>(
+                    arena, dst, dst_stride, image_size, chords, pool, scratch, rows_op,
+                );
+            }
+            MorphMethod::Direct { filter, offsets } => {
+                let dst = UnsafeSlice::new(dst);
+                let row =
+                    |y: usize| filter.dispatch_row(arena, &dst, dst_stride, image_size, offsets, y);
+                if pool.is_parallel() {
+                    pool.install(|| (0..image_size.height).into_par_iter().for_each(row));
+                } else {
+                    (0..image_size.height).for_each(row);
+                }
+            }
+            MorphMethod::Weighted {
+                filter,
+                offsets,
+                weights,
+            } => {
+                let dst = UnsafeSlice::new(dst);
+                let row = |y: usize| {
+                    filter.dispatch_row(arena, &dst, dst_stride, image_size, offsets, weights, y)
+                };
+                if pool.is_parallel() {
+                    pool.install(|| (0..image_size.height).into_par_iter().for_each(row));
+                } else {
+                    (0..image_size.height).for_each(row);
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,388 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::border_mode::{BorderMode, MorphScalar};
+use crate::error::MorphologyError;
+use crate::generic::{self, Gray};
+use crate::op_type::MorphExOp;
+use crate::structuring_element::KernelShape;
+use crate::{ImageSize, MorphologyThreadingPolicy};
+
+/// Dilate a gray (planar) image with non-flat structuring element
+///
+/// Dilation is `max(f(x - b) + w(b))`, structuring element is reflected around its anchor.
+/// Elements which weights round to zero give the same result as [crate::dilate] with the
+/// reflected element. Results are saturated.
+///
+/// # Arguments
+///
+/// * `src`: Source image slice
+/// * `dst`: Destination image slice
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `weights`: 2D structuring element weights, points with non-finite weights are not part of it, weights are rounded to integers
+/// * `weights_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn dilate_non_flat(
+    src: &[u8],
+    dst: &mut [u8],
+    image_size: ImageSize,
+    weights: &[f32],
+    weights_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_non_flat::<u8, Gray>(
+        src,
+        dst,
+        MorphExOp::Dilate,
+        image_size,
+        weights,
+        weights_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Erode a gray (planar) image with non-flat structuring element
+///
+/// Erosion is `min(f(x + b) - w(b))`, results are saturated.
+///
+/// # Arguments
+///
+/// * `src`: Source image slice
+/// * `dst`: Destination image slice
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `weights`: 2D structuring element weights, points with non-finite weights are not part of it, weights are rounded to integers
+/// * `weights_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn erode_non_flat(
+    src: &[u8],
+    dst: &mut [u8],
+    image_size: ImageSize,
+    weights: &[f32],
+    weights_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_non_flat::<u8, Gray>(
+        src,
+        dst,
+        MorphExOp::Erode,
+        image_size,
+        weights,
+        weights_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology a gray (planar) image with non-flat structuring element
+///
+/// See [dilate_non_flat] and [erode_non_flat] for definitions of the base operations.
+///
+/// # Arguments
+///
+/// * `src`: Source image slice
+/// * `dst`: Destination image slice
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `weights`: 2D structuring element weights, points with non-finite weights are not part of it, weights are rounded to integers
+/// * `weights_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_non_flat(
+    src: &[u8],
+    dst: &mut [u8],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    weights: &[f32],
+    weights_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_non_flat::<u8, Gray>(
+        src,
+        dst,
+        morph_op,
+        image_size,
+        weights,
+        weights_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Dilate a gray (planar) 16-bit image with non-flat structuring element
+///
+/// Dilation is `max(f(x - b) + w(b))`, structuring element is reflected around its anchor.
+/// Elements which weights round to zero give the same result as [crate::dilate_u16] with the
+/// reflected element. Results are saturated.
+///
+/// # Arguments
+///
+/// * `src`: Source image slice
+/// * `dst`: Destination image slice
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `weights`: 2D structuring element weights, points with non-finite weights are not part of it, weights are rounded to integers
+/// * `weights_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn dilate_non_flat_u16(
+    src: &[u16],
+    dst: &mut [u16],
+    image_size: ImageSize,
+    weights: &[f32],
+    weights_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_non_flat::<u16, Gray>(
+        src,
+        dst,
+        MorphExOp::Dilate,
+        image_size,
+        weights,
+        weights_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Erode a gray (planar) 16-bit image with non-flat structuring element
+///
+/// Erosion is `min(f(x + b) - w(b))`, results are saturated.
+///
+/// # Arguments
+///
+/// * `src`: Source image slice
+/// * `dst`: Destination image slice
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `weights`: 2D structuring element weights, points with non-finite weights are not part of it, weights are rounded to integers
+/// * `weights_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn erode_non_flat_u16(
+    src: &[u16],
+    dst: &mut [u16],
+    image_size: ImageSize,
+    weights: &[f32],
+    weights_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_non_flat::<u16, Gray>(
+        src,
+        dst,
+        MorphExOp::Erode,
+        image_size,
+        weights,
+        weights_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology a gray (planar) 16-bit image with non-flat structuring element
+///
+/// See [dilate_non_flat_u16] and [erode_non_flat_u16] for definitions of the base operations.
+///
+/// # Arguments
+///
+/// * `src`: Source image slice
+/// * `dst`: Destination image slice
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `weights`: 2D structuring element weights, points with non-finite weights are not part of it, weights are rounded to integers
+/// * `weights_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_non_flat_u16(
+    src: &[u16],
+    dst: &mut [u16],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    weights: &[f32],
+    weights_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_non_flat::<u16, Gray>(
+        src,
+        dst,
+        morph_op,
+        image_size,
+        weights,
+        weights_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Dilate a gray (planar) f32 image with non-flat structuring element
+///
+/// Dilation is `max(f(x - b) + w(b))`, structuring element is reflected around its anchor.
+/// Elements with all weights zero give the same result as [crate::dilate_f32] with the reflected
+/// element.
+///
+/// # Arguments
+///
+/// * `src`: Source image slice
+/// * `dst`: Destination image slice
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `weights`: 2D structuring element weights, points with non-finite weights are not part of it
+/// * `weights_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn dilate_non_flat_f32(
+    src: &[f32],
+    dst: &mut [f32],
+    image_size: ImageSize,
+    weights: &[f32],
+    weights_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_non_flat::<f32, Gray>(
+        src,
+        dst,
+        MorphExOp::Dilate,
+        image_size,
+        weights,
+        weights_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Erode a gray (planar) f32 image with non-flat structuring element
+///
+/// Erosion is `min(f(x + b) - w(b))`.
+///
+/// # Arguments
+///
+/// * `src`: Source image slice
+/// * `dst`: Destination image slice
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `weights`: 2D structuring element weights, points with non-finite weights are not part of it
+/// * `weights_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn erode_non_flat_f32(
+    src: &[f32],
+    dst: &mut [f32],
+    image_size: ImageSize,
+    weights: &[f32],
+    weights_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_non_flat::<f32, Gray>(
+        src,
+        dst,
+        MorphExOp::Erode,
+        image_size,
+        weights,
+        weights_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology a gray (planar) f32 image with non-flat structuring element
+///
+/// See [dilate_non_flat_f32] and [erode_non_flat_f32] for definitions of the base operations.
+///
+/// # Arguments
+///
+/// * `src`: Source image slice
+/// * `dst`: Destination image slice
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `weights`: 2D structuring element weights, points with non-finite weights are not part of it
+/// * `weights_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_non_flat_f32(
+    src: &[f32],
+    dst: &mut [f32],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    weights: &[f32],
+    weights_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_non_flat::<f32, Gray>(
+        src,
+        dst,
+        morph_op,
+        image_size,
+        weights,
+        weights_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
@@ -29,7 +29,7 @@
 
 #[repr(C)]
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
-pub(crate) enum MorphOp {
+pub enum MorphOp {
     Dilate = 0,
     Erode = 1,
 }
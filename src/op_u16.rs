@@ -27,12 +27,8 @@
  * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
 use crate::border_mode::{BorderMode, MorphScalar};
-use crate::difference::MorphGradient;
-use crate::morph_gray_alpha::make_morphology_gray_alpha;
-use crate::morph_rgb::make_morphology_rgb;
-use crate::morph_rgba::make_morphology_rgba;
-use crate::op_impl::make_morphology;
-use crate::op_type::MorphOp;
+use crate::error::MorphologyError;
+use crate::generic::{self, Gray, GrayAlpha, Rgb, Rgba};
 use crate::structuring_element::KernelShape;
 use crate::{ImageSize, MorphExOp, MorphologyThreadingPolicy};
 
@@ -58,19 +54,18 @@ pub fn dilate_u16(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    unsafe {
-        make_morphology::<u16, { MorphOp::Dilate as u8 }>(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        )
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<u16, Gray>(
+        src,
+        dst,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Dilate an RGB stored in u16 image
@@ -95,19 +90,18 @@ pub fn dilate_rgb_u16(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    unsafe {
-        make_morphology_rgb::<u16, { MorphOp::Dilate as u8 }>(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        )
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<u16, Rgb>(
+        src,
+        dst,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Erode a gray (planar) stored in u16 image
@@ -132,19 +126,18 @@ pub fn erode_u16(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    unsafe {
-        make_morphology::<u16, { MorphOp::Erode as u8 }>(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        )
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<u16, Gray>(
+        src,
+        dst,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Erode an RGB image stored in u16
@@ -169,19 +162,18 @@ pub fn erode_rgb_u16(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    unsafe {
-        make_morphology_rgb::<u16, { MorphOp::Erode as u8 }>(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        )
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<u16, Rgb>(
+        src,
+        dst,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Erode an RGBA image stored in u16
@@ -206,19 +198,18 @@ pub fn erode_rgba_u16(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    unsafe {
-        make_morphology_rgba::<u16, { MorphOp::Erode as u8 }>(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        )
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<u16, Rgba>(
+        src,
+        dst,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Dilate an RGBA image stored in u16
@@ -243,19 +234,18 @@ pub fn dilate_rgba_u16(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    unsafe {
-        make_morphology_rgba::<u16, { MorphOp::Dilate as u8 }>(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        )
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<u16, Rgba>(
+        src,
+        dst,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Erode Gray image with alpha stored in u16
@@ -280,19 +270,18 @@ pub fn erode_gray_alpha_u16(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    unsafe {
-        make_morphology_gray_alpha::<u16, { MorphOp::Erode as u8 }>(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        )
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<u16, GrayAlpha>(
+        src,
+        dst,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Dilate an Gray image with alpha stored in u16
@@ -317,19 +306,18 @@ pub fn dilate_gray_alpha_u16(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    unsafe {
-        make_morphology_gray_alpha::<u16, { MorphOp::Dilate as u8 }>(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        )
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<u16, GrayAlpha>(
+        src,
+        dst,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Morphology Gray image with alpha stored in u16
@@ -356,133 +344,64 @@ pub fn morphology_gray_alpha_u16(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    match morph_op {
-        MorphExOp::Dilate => dilate_gray_alpha_u16(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        ),
-        MorphExOp::Erode => erode_gray_alpha_u16(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        ),
-        MorphExOp::Opening => {
-            let mut transient = vec![0u16; dst.len()];
-            erode_gray_alpha_u16(
-                src,
-                &mut transient,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            dilate_gray_alpha_u16(
-                &transient,
-                dst,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )
-        }
-        MorphExOp::Closing => {
-            let mut transient = vec![0u16; dst.len()];
-            dilate_gray_alpha_u16(
-                src,
-                &mut transient,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            erode_gray_alpha_u16(
-                &transient,
-                dst,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )
-        }
-        MorphExOp::Gradient => {
-            let mut dilation = vec![0u16; dst.len()];
-            dilate_gray_alpha_u16(
-                src,
-                &mut dilation,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            let mut erosion = vec![0u16; dst.len()];
-            erode_gray_alpha_u16(
-                &src,
-                &mut erosion,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            u16::morph_gradient(&dilation, &erosion, dst);
-            Ok(())
-        }
-        MorphExOp::TopHat => {
-            let mut opened = vec![0u16; dst.len()];
-            morphology_gray_alpha_u16(
-                src,
-                &mut opened,
-                MorphExOp::Opening,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            u16::morph_gradient(&src, &opened, dst);
-            Ok(())
-        }
-        MorphExOp::BlackHat => {
-            let mut closed = vec![0u16; dst.len()];
-            morphology_gray_alpha_u16(
-                src,
-                &mut closed,
-                MorphExOp::Closing,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            u16::morph_gradient(&closed, &src, dst);
-            Ok(())
-        }
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<u16, GrayAlpha>(
+        src,
+        dst,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology Gray image with alpha stored in u16 with padded rows
+///
+/// Rows are `src_stride` and `dst_stride` items apart, padding after rows is not read and not written.
+///
+/// # Arguments
+///
+/// * `src`: Source slice with Gray with alpha data
+/// * `src_stride`: Items between starts of source rows, at least `width * channels`
+/// * `dst`: Destination slice for Gray with alpha data
+/// * `dst_stride`: Items between starts of destination rows, at least `width * channels`
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_gray_alpha_u16_with_stride(
+    src: &[u16],
+    src_stride: usize,
+    dst: &mut [u16],
+    dst_stride: usize,
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_with_stride::<u16, GrayAlpha>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Morphology Gray image stored in u16
@@ -509,133 +428,64 @@ pub fn morphology_gray_u16(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    match morph_op {
-        MorphExOp::Dilate => dilate_u16(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        ),
-        MorphExOp::Erode => erode_u16(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        ),
-        MorphExOp::Opening => {
-            let mut transient = vec![0u16; dst.len()];
-            erode_u16(
-                src,
-                &mut transient,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            dilate_u16(
-                &transient,
-                dst,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )
-        }
-        MorphExOp::Closing => {
-            let mut transient = vec![0u16; dst.len()];
-            dilate_u16(
-                src,
-                &mut transient,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            erode_u16(
-                &transient,
-                dst,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )
-        }
-        MorphExOp::Gradient => {
-            let mut dilation = vec![0u16; dst.len()];
-            dilate_u16(
-                src,
-                &mut dilation,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            let mut erosion = vec![0u16; dst.len()];
-            erode_u16(
-                &src,
-                &mut erosion,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            u16::morph_gradient(&dilation, &erosion, dst);
-            Ok(())
-        }
-        MorphExOp::TopHat => {
-            let mut opened = vec![0u16; dst.len()];
-            morphology_gray_u16(
-                src,
-                &mut opened,
-                MorphExOp::Opening,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            u16::morph_gradient(&src, &opened, dst);
-            Ok(())
-        }
-        MorphExOp::BlackHat => {
-            let mut closed = vec![0u16; dst.len()];
-            morphology_gray_u16(
-                src,
-                &mut closed,
-                MorphExOp::Closing,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            u16::morph_gradient(&closed, &src, dst);
-            Ok(())
-        }
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<u16, Gray>(
+        src,
+        dst,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology Gray image stored in u16 with padded rows
+///
+/// Rows are `src_stride` and `dst_stride` items apart, padding after rows is not read and not written.
+///
+/// # Arguments
+///
+/// * `src`: Source slice with Gray data
+/// * `src_stride`: Items between starts of source rows, at least `width * channels`
+/// * `dst`: Destination slice for Gray  data
+/// * `dst_stride`: Items between starts of destination rows, at least `width * channels`
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_gray_u16_with_stride(
+    src: &[u16],
+    src_stride: usize,
+    dst: &mut [u16],
+    dst_stride: usize,
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_with_stride::<u16, Gray>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Morphology RGB image stored in u16
@@ -662,133 +512,64 @@ pub fn morphology_rgb_u16(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    match morph_op {
-        MorphExOp::Dilate => dilate_rgb_u16(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        ),
-        MorphExOp::Erode => erode_rgb_u16(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        ),
-        MorphExOp::Opening => {
-            let mut transient = vec![0u16; dst.len()];
-            erode_rgb_u16(
-                src,
-                &mut transient,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            dilate_rgb_u16(
-                &transient,
-                dst,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )
-        }
-        MorphExOp::Closing => {
-            let mut transient = vec![0u16; dst.len()];
-            dilate_rgb_u16(
-                src,
-                &mut transient,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            erode_rgb_u16(
-                &transient,
-                dst,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )
-        }
-        MorphExOp::Gradient => {
-            let mut dilation = vec![0u16; dst.len()];
-            dilate_rgb_u16(
-                src,
-                &mut dilation,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            let mut erosion = vec![0u16; dst.len()];
-            erode_rgb_u16(
-                &src,
-                &mut erosion,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            u16::morph_gradient(&dilation, &erosion, dst);
-            Ok(())
-        }
-        MorphExOp::TopHat => {
-            let mut opened = vec![0u16; dst.len()];
-            morphology_rgb_u16(
-                src,
-                &mut opened,
-                MorphExOp::Opening,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            u16::morph_gradient(&src, &opened, dst);
-            Ok(())
-        }
-        MorphExOp::BlackHat => {
-            let mut closed = vec![0u16; dst.len()];
-            morphology_rgb_u16(
-                src,
-                &mut closed,
-                MorphExOp::Closing,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            u16::morph_gradient(&closed, &src, dst);
-            Ok(())
-        }
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<u16, Rgb>(
+        src,
+        dst,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology RGB image stored in u16 with padded rows
+///
+/// Rows are `src_stride` and `dst_stride` items apart, padding after rows is not read and not written.
+///
+/// # Arguments
+///
+/// * `src`: Source slice with RGB data
+/// * `src_stride`: Items between starts of source rows, at least `width * channels`
+/// * `dst`: Destination slice for RGB data
+/// * `dst_stride`: Items between starts of destination rows, at least `width * channels`
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_rgb_u16_with_stride(
+    src: &[u16],
+    src_stride: usize,
+    dst: &mut [u16],
+    dst_stride: usize,
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_with_stride::<u16, Rgb>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
 
 /// Morphology RGBA image stored in u16
@@ -815,131 +596,490 @@ pub fn morphology_rgba_u16(
     border_mode: BorderMode,
     border_scalar: MorphScalar,
     threading_policy: MorphologyThreadingPolicy,
-) -> Result<(), String> {
-    match morph_op {
-        MorphExOp::Dilate => dilate_rgba_u16(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        ),
-        MorphExOp::Erode => erode_rgba_u16(
-            src,
-            dst,
-            image_size,
-            structuring_element,
-            structuring_element_size,
-            border_mode,
-            border_scalar,
-            threading_policy,
-        ),
-        MorphExOp::Opening => {
-            let mut transient = vec![0u16; dst.len()];
-            erode_rgba_u16(
-                src,
-                &mut transient,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            dilate_rgba_u16(
-                &transient,
-                dst,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )
-        }
-        MorphExOp::Closing => {
-            let mut transient = vec![0u16; dst.len()];
-            dilate_rgba_u16(
-                src,
-                &mut transient,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            erode_rgba_u16(
-                &transient,
-                dst,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )
-        }
-        MorphExOp::Gradient => {
-            let mut dilation = vec![0u16; dst.len()];
-            dilate_rgba_u16(
-                src,
-                &mut dilation,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            let mut erosion = vec![0u16; dst.len()];
-            erode_rgba_u16(
-                &src,
-                &mut erosion,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            u16::morph_gradient(&dilation, &erosion, dst);
-            Ok(())
-        }
-        MorphExOp::TopHat => {
-            let mut opened = vec![0u16; dst.len()];
-            morphology_rgba_u16(
-                src,
-                &mut opened,
-                MorphExOp::Opening,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            u16::morph_gradient(&src, &opened, dst);
-            Ok(())
-        }
-        MorphExOp::BlackHat => {
-            let mut closed = vec![0u16; dst.len()];
-            morphology_rgba_u16(
-                src,
-                &mut closed,
-                MorphExOp::Closing,
-                image_size,
-                structuring_element,
-                structuring_element_size,
-                border_mode,
-                border_scalar,
-                threading_policy,
-            )?;
-            u16::morph_gradient(&closed, &src, dst);
-            Ok(())
-        }
-    }
+) -> Result<(), MorphologyError> {
+    generic::morphology::<u16, Rgba>(
+        src,
+        dst,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology RGBA image stored in u16 with padded rows
+///
+/// Rows are `src_stride` and `dst_stride` items apart, padding after rows is not read and not written.
+///
+/// # Arguments
+///
+/// * `src`: Source slice with RGBA data
+/// * `src_stride`: Items between starts of source rows, at least `width * channels`
+/// * `dst`: Destination slice for RGBA data
+/// * `dst_stride`: Items between starts of destination rows, at least `width * channels`
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_rgba_u16_with_stride(
+    src: &[u16],
+    src_stride: usize,
+    dst: &mut [u16],
+    dst_stride: usize,
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_with_stride::<u16, Rgba>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Dilate a gray (planar) image stored in u16 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Image slice, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn dilate_u16_in_place(
+    image: &mut [u16],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<u16, Gray>(
+        image,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Erode a gray (planar) image stored in u16 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Image slice, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn erode_u16_in_place(
+    image: &mut [u16],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<u16, Gray>(
+        image,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology a gray (planar) image stored in u16 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Image slice, replaced by the result
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_gray_u16_in_place(
+    image: &mut [u16],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<u16, Gray>(
+        image,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Dilate a Gray image with alpha stored in u16 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with Gray with alpha data, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn dilate_gray_alpha_u16_in_place(
+    image: &mut [u16],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<u16, GrayAlpha>(
+        image,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Erode a Gray image with alpha stored in u16 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with Gray with alpha data, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn erode_gray_alpha_u16_in_place(
+    image: &mut [u16],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<u16, GrayAlpha>(
+        image,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology a Gray image with alpha stored in u16 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with Gray with alpha data, replaced by the result
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_gray_alpha_u16_in_place(
+    image: &mut [u16],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<u16, GrayAlpha>(
+        image,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Dilate an RGB image stored in u16 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with RGB data, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn dilate_rgb_u16_in_place(
+    image: &mut [u16],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<u16, Rgb>(
+        image,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Erode an RGB image stored in u16 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with RGB data, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn erode_rgb_u16_in_place(
+    image: &mut [u16],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<u16, Rgb>(
+        image,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology an RGB image stored in u16 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with RGB data, replaced by the result
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_rgb_u16_in_place(
+    image: &mut [u16],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<u16, Rgb>(
+        image,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Dilate an RGBA image stored in u16 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with RGBA data, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn dilate_rgba_u16_in_place(
+    image: &mut [u16],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<u16, Rgba>(
+        image,
+        MorphExOp::Dilate,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Erode an RGBA image stored in u16 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with RGBA data, replaced by the result
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn erode_rgba_u16_in_place(
+    image: &mut [u16],
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<u16, Rgba>(
+        image,
+        MorphExOp::Erode,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
+}
+
+/// Morphology an RGBA image stored in u16 in place
+///
+/// The padded arena is the only extra memory, interleaved images are processed one channel at a time.
+///
+/// # Arguments
+///
+/// * `image`: Slice with RGBA data, replaced by the result
+/// * `morph_op`: Requested [MorphExOp]
+/// * `image_size`: Image size declared by [ImageSize]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_scalar`: [MorphScalar] scalar value that will be used to fill border in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn morphology_rgba_u16_in_place(
+    image: &mut [u16],
+    morph_op: MorphExOp,
+    image_size: ImageSize,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_scalar: MorphScalar,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    generic::morphology_in_place::<u16, Rgba>(
+        image,
+        morph_op,
+        image_size,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_scalar,
+        threading_policy,
+    )
 }
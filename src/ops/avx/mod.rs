@@ -28,9 +28,22 @@
  */
 
 mod morph_op;
+#[cfg(feature = "half")]
+mod morph_op_f16;
 mod morph_op_f32;
+mod morph_op_f64;
+mod morph_op_int;
 mod morph_op_u16;
+mod morph_op_weighted;
 
 pub use morph_op::MorphOpFilterAvx2DRow;
+#[cfg(feature = "half")]
+pub use morph_op_f16::MorphOpFilterAvx2DRowF16;
 pub use morph_op_f32::MorphOpFilterAvx2DRowF32;
+pub use morph_op_f64::MorphOpFilterAvx2DRowF64;
+pub use morph_op_int::MorphOpFilterAvx2DRowInt;
 pub use morph_op_u16::MorphOpFilterAvx2DRowU16;
+pub use morph_op_weighted::{
+    MorphOpFilterAvxWeighted2DRow, MorphOpFilterAvxWeighted2DRowF32,
+    MorphOpFilterAvxWeighted2DRowU16,
+};
@@ -26,8 +26,7 @@
  * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
  * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
-use crate::filter_op_declare::{Arena, MorthOpFilterFlat2DRow};
-use crate::flat_se::AnalyzedSe;
+use crate::filter_op_declare::{Arena, ArenaRows, MorthOpFilterFlat2DRow};
 use crate::morph_base::MorphNativeOp;
 use crate::op_type::MorphOp;
 use crate::unsafe_slice::UnsafeSlice;
@@ -55,14 +54,15 @@ where
         &self,
         arena: &Arena<T>,
         dst: &UnsafeSlice<T>,
+        dst_stride: usize,
         image_size: ImageSize,
-        analyzed_se: AnalyzedSe,
+        element_offsets: &[usize],
         y: usize,
     ) {
         let width = image_size.width;
 
         let op_type: MorphOp = OP_TYPE.into();
-        let stride = width;
+        let stride = dst_stride;
 
         let decision = match op_type {
             MorphOp::Dilate => _mm_max_epu16,
@@ -77,23 +77,9 @@ where
         let src: &Vec<u16> = std::mem::transmute(&arena.arena);
         let dst: &UnsafeSlice<u16> = std::mem::transmute(dst);
 
-        let dx = arena.pad_w as i32;
-        let dy = arena.pad_h as i32;
+        let offsets = ArenaRows::new(src, element_offsets, y * arena.width);
 
-        let arena_stride = arena.width;
-
-        let offsets = analyzed_se
-            .left_front
-            .element_offsets
-            .iter()
-            .map(|&x| {
-                src.get_unchecked(
-                    ((x.y + dy + y as i32) as usize * arena_stride + (x.x + dx) as usize)..,
-                )
-            })
-            .collect::<Vec<_>>();
-
-        let length = analyzed_se.left_front.element_offsets.iter().len();
+        let length = offsets.len();
 
         let mut _cx = 0usize;
 
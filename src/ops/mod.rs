@@ -29,9 +29,12 @@
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 pub mod avx;
 mod morph_row_op;
+mod morph_weighted_row_op;
 #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
 pub mod neon;
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 pub mod sse;
 
-pub use morph_row_op::MorphFilterFlat2DRow;
+pub use morph_row_op::MorphOpFilterScalar2DRow;
+pub(crate) use morph_weighted_row_op::weighted_tail;
+pub use morph_weighted_row_op::MorphOpFilterScalarWeighted2DRow;
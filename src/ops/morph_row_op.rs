@@ -26,22 +26,21 @@
  * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
  * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
-use crate::filter_op_declare::{Arena, MorthOpFilterFlat2DRow};
-use crate::flat_se::AnalyzedSe;
+use crate::filter_op_declare::{Arena, ArenaRows, MorthOpFilterFlat2DRow};
 use crate::morph_base::MorphNativeOp;
 use crate::unsafe_slice::UnsafeSlice;
 use crate::ImageSize;
 
 #[derive(Clone)]
-pub struct MorphFilterFlat2DRow<const OP_TYPE: u8> {}
+pub struct MorphOpFilterScalar2DRow<const OP_TYPE: u8> {}
 
-impl<const OP_TYPE: u8> Default for MorphFilterFlat2DRow<OP_TYPE> {
+impl<const OP_TYPE: u8> Default for MorphOpFilterScalar2DRow<OP_TYPE> {
     fn default() -> Self {
-        MorphFilterFlat2DRow {}
+        MorphOpFilterScalar2DRow {}
     }
 }
 
-impl<T, const OP_TYPE: u8> MorthOpFilterFlat2DRow<T> for MorphFilterFlat2DRow<OP_TYPE>
+impl<T, const OP_TYPE: u8> MorthOpFilterFlat2DRow<T> for MorphOpFilterScalar2DRow<OP_TYPE>
 where
     T: 'static + Copy + MorphNativeOp<T>,
 {
@@ -49,32 +48,19 @@ where
         &self,
         arena: &Arena<T>,
         dst: &UnsafeSlice<T>,
+        dst_stride: usize,
         image_size: ImageSize,
-        analyzed_se: AnalyzedSe,
+        element_offsets: &[usize],
         y: usize,
     ) {
         let width = image_size.width;
-        let stride = image_size.width;
+        let stride = dst_stride;
 
         let src = &arena.arena;
 
-        let dx = arena.pad_w as i32;
-        let dy = arena.pad_h as i32;
+        let offsets = ArenaRows::new(src, element_offsets, y * arena.width);
 
-        let arena_width = arena.width;
-
-        let offsets = analyzed_se
-            .left_front
-            .element_offsets
-            .iter()
-            .map(|&x| {
-                src.get_unchecked(
-                    ((x.y + dy + y as i32) as usize * arena_width + (x.x + dx) as usize)..,
-                )
-            })
-            .collect::<Vec<_>>();
-
-        let length = analyzed_se.left_front.element_offsets.iter().len();
+        let length = offsets.len();
 
         let mut _cx = 0usize;
 
@@ -0,0 +1,82 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::filter_op_declare::{Arena, ArenaRows, MorthOpFilterWeighted2DRow};
+use crate::morph_base::{MorphNativeOp, MorphWeightedOp};
+use crate::unsafe_slice::UnsafeSlice;
+use crate::ImageSize;
+
+/// Scalar filter of the row from `start` to the end
+pub(crate) unsafe fn weighted_tail<T, const OP_TYPE: u8>(
+    offsets: &ArenaRows<T>,
+    weights: &[T::Weight],
+    dst: &mut [T],
+    start: usize,
+) where
+    T: Copy + MorphNativeOp<T> + MorphWeightedOp<T>,
+{
+    for x in start..dst.len() {
+        let mut k0 =
+            (*offsets.get_unchecked(0).get_unchecked(x)).add_weight(*weights.get_unchecked(0));
+        for (row, &weight) in offsets.iter().zip(weights.iter()).skip(1) {
+            k0 = k0.op::<OP_TYPE>((*row.get_unchecked(x)).add_weight(weight));
+        }
+        *dst.get_unchecked_mut(x) = k0;
+    }
+}
+
+#[derive(Clone)]
+pub struct MorphOpFilterScalarWeighted2DRow<const OP_TYPE: u8> {}
+
+impl<const OP_TYPE: u8> Default for MorphOpFilterScalarWeighted2DRow<OP_TYPE> {
+    fn default() -> Self {
+        MorphOpFilterScalarWeighted2DRow {}
+    }
+}
+
+impl<T, const OP_TYPE: u8> MorthOpFilterWeighted2DRow<T>
+    for MorphOpFilterScalarWeighted2DRow<OP_TYPE>
+where
+    T: 'static + Copy + MorphNativeOp<T> + MorphWeightedOp<T>,
+{
+    unsafe fn dispatch_row(
+        &self,
+        arena: &Arena<T>,
+        dst: &UnsafeSlice<T>,
+        dst_stride: usize,
+        image_size: ImageSize,
+        element_offsets: &[usize],
+        weights: &[T::Weight],
+        y: usize,
+    ) {
+        let width = image_size.width;
+        let offsets = ArenaRows::new(&arena.arena, element_offsets, y * arena.width);
+        let dst = std::slice::from_raw_parts_mut(dst.mut_ptr().add(y * dst_stride), width);
+        weighted_tail::<T, OP_TYPE>(&offsets, weights, dst, 0);
+    }
+}
@@ -27,9 +27,22 @@
  * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
 mod morph_op;
+#[cfg(feature = "half")]
+mod morph_op_f16;
 mod morph_op_f32;
+mod morph_op_f64;
+mod morph_op_int;
 mod morph_op_u16;
+mod morph_op_weighted;
 
 pub use morph_op::MorphOpFilterNeon2DRow;
+#[cfg(feature = "half")]
+pub use morph_op_f16::MorphOpFilterNeon2DRowF16;
 pub use morph_op_f32::MorphOpFilterNeon2DRowF32;
+pub use morph_op_f64::MorphOpFilterNeon2DRowF64;
+pub use morph_op_int::MorphOpFilterNeon2DRowInt;
 pub use morph_op_u16::MorphOpFilterNeon2DRowU16;
+pub use morph_op_weighted::{
+    MorphOpFilterNeonWeighted2DRow, MorphOpFilterNeonWeighted2DRowF32,
+    MorphOpFilterNeonWeighted2DRowU16,
+};
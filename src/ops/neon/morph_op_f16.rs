@@ -0,0 +1,126 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::filter_op_declare::{Arena, ArenaRows, MorthOpFilterFlat2DRow};
+use crate::morph_base::MorphNativeOp;
+use crate::neon::{vmaxq_f16_u16, vminq_f16_u16};
+use crate::op_type::MorphOp;
+use crate::unsafe_slice::UnsafeSlice;
+use crate::ImageSize;
+use half::f16;
+use std::arch::aarch64::*;
+
+/// Flat filter for f16, CPU must support `fp16`
+#[derive(Clone)]
+pub struct MorphOpFilterNeon2DRowF16<const OP_TYPE: u8> {}
+
+impl<const OP_TYPE: u8> Default for MorphOpFilterNeon2DRowF16<OP_TYPE> {
+    fn default() -> Self {
+        MorphOpFilterNeon2DRowF16 {}
+    }
+}
+
+impl<T, const OP_TYPE: u8> MorthOpFilterFlat2DRow<T> for MorphOpFilterNeon2DRowF16<OP_TYPE>
+where
+    T: Copy + 'static,
+{
+    #[target_feature(enable = "fp16")]
+    unsafe fn dispatch_row(
+        &self,
+        arena: &Arena<T>,
+        dst: &UnsafeSlice<T>,
+        dst_stride: usize,
+        image_size: ImageSize,
+        element_offsets: &[usize],
+        y: usize,
+    ) {
+        let width = image_size.width;
+
+        let op_type: MorphOp = OP_TYPE.into();
+        let stride = dst_stride;
+
+        let decision = match op_type {
+            MorphOp::Dilate => vmaxq_f16_u16,
+            MorphOp::Erode => vminq_f16_u16,
+        };
+
+        let src: &Vec<f16> = std::mem::transmute(&arena.arena);
+        let dst: &UnsafeSlice<f16> = std::mem::transmute(dst);
+
+        let offsets = ArenaRows::new(src, element_offsets, y * arena.width);
+
+        let length = offsets.len();
+
+        let mut _cx = 0usize;
+
+        while _cx + 32 < width {
+            let mut rows = vld1q_u16_x4(
+                (*offsets.get_unchecked(0).get_unchecked(_cx..)).as_ptr() as *const u16
+            );
+
+            for i in 1..length {
+                let new_rows = vld1q_u16_x4(
+                    (*offsets.get_unchecked(i)).get_unchecked(_cx..).as_ptr() as *const u16,
+                );
+                rows.0 = decision(rows.0, new_rows.0);
+                rows.1 = decision(rows.1, new_rows.1);
+                rows.2 = decision(rows.2, new_rows.2);
+                rows.3 = decision(rows.3, new_rows.3);
+            }
+
+            vst1q_u16_x4(dst.slice.as_ptr().add(y * stride + _cx) as *mut u16, rows);
+
+            _cx += 32;
+        }
+
+        while _cx + 8 < width {
+            let mut rows =
+                vld1q_u16((*offsets.get_unchecked(0).get_unchecked(_cx..)).as_ptr() as *const u16);
+
+            for i in 1..length {
+                let new_row = vld1q_u16(
+                    (*offsets.get_unchecked(i)).get_unchecked(_cx..).as_ptr() as *const u16
+                );
+                rows = decision(rows, new_row);
+            }
+
+            vst1q_u16(dst.slice.as_ptr().add(y * stride + _cx) as *mut u16, rows);
+
+            _cx += 8;
+        }
+
+        for x in _cx..width {
+            let mut k0 = *(*offsets.get_unchecked(0)).get_unchecked(x);
+
+            for i in 1..length {
+                k0 = k0.op::<OP_TYPE>(*(*offsets.get_unchecked(i)).get_unchecked(x));
+            }
+            dst.write(y * stride + x, k0);
+        }
+    }
+}
@@ -0,0 +1,130 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::filter_op_declare::{Arena, ArenaRows, MorthOpFilterFlat2DRow};
+use crate::morph_base::MorphNativeOp;
+use crate::neon::NeonPackedInt;
+use crate::op_type::MorphOp;
+use crate::unsafe_slice::UnsafeSlice;
+use crate::ImageSize;
+use std::marker::PhantomData;
+
+/// Flat row filter for integer samples, signedness comes from [NeonPackedInt]
+#[derive(Clone)]
+pub struct MorphOpFilterNeon2DRowInt<V, const OP_TYPE: u8> {
+    _phantom: PhantomData<V>,
+}
+
+impl<V, const OP_TYPE: u8> Default for MorphOpFilterNeon2DRowInt<V, OP_TYPE> {
+    fn default() -> Self {
+        MorphOpFilterNeon2DRowInt {
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<V, const OP_TYPE: u8> MorthOpFilterFlat2DRow<V> for MorphOpFilterNeon2DRowInt<V, OP_TYPE>
+where
+    V: NeonPackedInt + MorphNativeOp<V>,
+{
+    unsafe fn dispatch_row(
+        &self,
+        arena: &Arena<V>,
+        dst: &UnsafeSlice<V>,
+        dst_stride: usize,
+        image_size: ImageSize,
+        element_offsets: &[usize],
+        y: usize,
+    ) {
+        let width = image_size.width;
+
+        let op_type: MorphOp = OP_TYPE.into();
+        let stride = dst_stride;
+
+        let decision = match op_type {
+            MorphOp::Dilate => V::max,
+            MorphOp::Erode => V::min,
+        };
+
+        let lanes = V::LANES;
+
+        let offsets = ArenaRows::new(&arena.arena, element_offsets, y * arena.width);
+
+        let length = offsets.len();
+
+        let mut _cx = 0usize;
+
+        while _cx + lanes * 4 <= width {
+            let ptr0 = (*offsets.get_unchecked(0).get_unchecked(_cx..)).as_ptr();
+            let mut row0 = V::load(ptr0);
+            let mut row1 = V::load(ptr0.add(lanes));
+            let mut row2 = V::load(ptr0.add(lanes * 2));
+            let mut row3 = V::load(ptr0.add(lanes * 3));
+
+            for i in 1..length {
+                let ptr_d = (*offsets.get_unchecked(i)).get_unchecked(_cx..).as_ptr();
+                row0 = decision(row0, V::load(ptr_d));
+                row1 = decision(row1, V::load(ptr_d.add(lanes)));
+                row2 = decision(row2, V::load(ptr_d.add(lanes * 2)));
+                row3 = decision(row3, V::load(ptr_d.add(lanes * 3)));
+            }
+
+            let v_dst = dst.slice.as_ptr().add(y * stride + _cx) as *mut V;
+
+            V::store(v_dst, row0);
+            V::store(v_dst.add(lanes), row1);
+            V::store(v_dst.add(lanes * 2), row2);
+            V::store(v_dst.add(lanes * 3), row3);
+
+            _cx += lanes * 4;
+        }
+
+        while _cx + lanes <= width {
+            let ptr0 = (*offsets.get_unchecked(0).get_unchecked(_cx..)).as_ptr();
+            let mut row0 = V::load(ptr0);
+
+            for i in 1..length {
+                let ptr_d = (*offsets.get_unchecked(i)).get_unchecked(_cx..).as_ptr();
+                row0 = decision(row0, V::load(ptr_d));
+            }
+
+            V::store(dst.slice.as_ptr().add(y * stride + _cx) as *mut V, row0);
+
+            _cx += lanes;
+        }
+
+        for x in _cx..width {
+            let mut k0 = *(*offsets.get_unchecked(0)).get_unchecked(x);
+
+            for i in 1..length {
+                k0 = k0.op::<OP_TYPE>(*(*offsets.get_unchecked(i)).get_unchecked(x));
+            }
+            dst.write(y * stride + x, k0);
+        }
+    }
+}
@@ -0,0 +1,213 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::filter_op_declare::{Arena, ArenaRows, MorthOpFilterWeighted2DRow};
+use crate::op_type::MorphOp;
+use crate::ops::weighted_tail;
+use crate::unsafe_slice::UnsafeSlice;
+use crate::ImageSize;
+#[cfg(target_arch = "aarch64")]
+use std::arch::aarch64::*;
+#[cfg(target_arch = "arm")]
+use std::arch::arm::*;
+
+#[derive(Clone)]
+pub struct MorphOpFilterNeonWeighted2DRow<const OP_TYPE: u8> {}
+
+impl<const OP_TYPE: u8> Default for MorphOpFilterNeonWeighted2DRow<OP_TYPE> {
+    fn default() -> Self {
+        MorphOpFilterNeonWeighted2DRow {}
+    }
+}
+
+impl<const OP_TYPE: u8> MorthOpFilterWeighted2DRow<u8> for MorphOpFilterNeonWeighted2DRow<OP_TYPE> {
+    unsafe fn dispatch_row(
+        &self,
+        arena: &Arena<u8>,
+        dst: &UnsafeSlice<u8>,
+        dst_stride: usize,
+        image_size: ImageSize,
+        element_offsets: &[usize],
+        weights: &[i32],
+        y: usize,
+    ) {
+        let width = image_size.width;
+        let op_type: MorphOp = OP_TYPE.into();
+        let decision = match op_type {
+            MorphOp::Dilate => vmaxq_u8,
+            MorphOp::Erode => vminq_u8,
+        };
+
+        let offsets = ArenaRows::new(&arena.arena, element_offsets, y * arena.width);
+        // Signed weight is split into saturating addition and saturating subtraction
+        let v_weight = |w: i32| {
+            (
+                vdupq_n_u8(w.clamp(0, 255) as u8),
+                vdupq_n_u8((-w).clamp(0, 255) as u8),
+            )
+        };
+
+        let dst = std::slice::from_raw_parts_mut(dst.mut_ptr().add(y * dst_stride), width);
+
+        let mut _cx = 0usize;
+
+        while _cx + 16 <= width {
+            let (add, sub) = v_weight(*weights.get_unchecked(0));
+            let ptr0 = offsets.get_unchecked(0).get_unchecked(_cx..).as_ptr();
+            let mut row0 = vqsubq_u8(vqaddq_u8(vld1q_u8(ptr0), add), sub);
+
+            for (row, &w) in offsets.iter().zip(weights.iter()).skip(1) {
+                let (add, sub) = v_weight(w);
+                let ptr_d = row.get_unchecked(_cx..).as_ptr();
+                let new_row0 = vqsubq_u8(vqaddq_u8(vld1q_u8(ptr_d), add), sub);
+                row0 = decision(row0, new_row0);
+            }
+
+            vst1q_u8(dst.get_unchecked_mut(_cx..).as_mut_ptr(), row0);
+
+            _cx += 16;
+        }
+
+        weighted_tail::<u8, OP_TYPE>(&offsets, weights, dst, _cx);
+    }
+}
+
+#[derive(Clone)]
+pub struct MorphOpFilterNeonWeighted2DRowU16<const OP_TYPE: u8> {}
+
+impl<const OP_TYPE: u8> Default for MorphOpFilterNeonWeighted2DRowU16<OP_TYPE> {
+    fn default() -> Self {
+        MorphOpFilterNeonWeighted2DRowU16 {}
+    }
+}
+
+impl<const OP_TYPE: u8> MorthOpFilterWeighted2DRow<u16>
+    for MorphOpFilterNeonWeighted2DRowU16<OP_TYPE>
+{
+    unsafe fn dispatch_row(
+        &self,
+        arena: &Arena<u16>,
+        dst: &UnsafeSlice<u16>,
+        dst_stride: usize,
+        image_size: ImageSize,
+        element_offsets: &[usize],
+        weights: &[i32],
+        y: usize,
+    ) {
+        let width = image_size.width;
+        let op_type: MorphOp = OP_TYPE.into();
+        let decision = match op_type {
+            MorphOp::Dilate => vmaxq_u16,
+            MorphOp::Erode => vminq_u16,
+        };
+
+        let offsets = ArenaRows::new(&arena.arena, element_offsets, y * arena.width);
+        let v_weight = |w: i32| {
+            (
+                vdupq_n_u16(w.clamp(0, 65535) as u16),
+                vdupq_n_u16((-w).clamp(0, 65535) as u16),
+            )
+        };
+
+        let dst = std::slice::from_raw_parts_mut(dst.mut_ptr().add(y * dst_stride), width);
+
+        let mut _cx = 0usize;
+
+        while _cx + 8 <= width {
+            let (add, sub) = v_weight(*weights.get_unchecked(0));
+            let ptr0 = offsets.get_unchecked(0).get_unchecked(_cx..).as_ptr();
+            let mut row0 = vqsubq_u16(vqaddq_u16(vld1q_u16(ptr0), add), sub);
+
+            for (row, &w) in offsets.iter().zip(weights.iter()).skip(1) {
+                let (add, sub) = v_weight(w);
+                let ptr_d = row.get_unchecked(_cx..).as_ptr();
+                let new_row0 = vqsubq_u16(vqaddq_u16(vld1q_u16(ptr_d), add), sub);
+                row0 = decision(row0, new_row0);
+            }
+
+            vst1q_u16(dst.get_unchecked_mut(_cx..).as_mut_ptr(), row0);
+
+            _cx += 8;
+        }
+
+        weighted_tail::<u16, OP_TYPE>(&offsets, weights, dst, _cx);
+    }
+}
+
+#[derive(Clone)]
+pub struct MorphOpFilterNeonWeighted2DRowF32<const OP_TYPE: u8> {}
+
+impl<const OP_TYPE: u8> Default for MorphOpFilterNeonWeighted2DRowF32<OP_TYPE> {
+    fn default() -> Self {
+        MorphOpFilterNeonWeighted2DRowF32 {}
+    }
+}
+
+impl<const OP_TYPE: u8> MorthOpFilterWeighted2DRow<f32>
+    for MorphOpFilterNeonWeighted2DRowF32<OP_TYPE>
+{
+    unsafe fn dispatch_row(
+        &self,
+        arena: &Arena<f32>,
+        dst: &UnsafeSlice<f32>,
+        dst_stride: usize,
+        image_size: ImageSize,
+        element_offsets: &[usize],
+        weights: &[f32],
+        y: usize,
+    ) {
+        let width = image_size.width;
+        let op_type: MorphOp = OP_TYPE.into();
+        let decision = match op_type {
+            MorphOp::Dilate => vmaxq_f32,
+            MorphOp::Erode => vminq_f32,
+        };
+
+        let offsets = ArenaRows::new(&arena.arena, element_offsets, y * arena.width);
+
+        let dst = std::slice::from_raw_parts_mut(dst.mut_ptr().add(y * dst_stride), width);
+
+        let mut _cx = 0usize;
+
+        while _cx + 4 <= width {
+            let ptr0 = offsets.get_unchecked(0).get_unchecked(_cx..).as_ptr();
+            let mut row0 = vaddq_f32(vld1q_f32(ptr0), vdupq_n_f32(*weights.get_unchecked(0)));
+
+            for (row, &weight) in offsets.iter().zip(weights.iter()).skip(1) {
+                let ptr_d = row.get_unchecked(_cx..).as_ptr();
+                row0 = decision(row0, vaddq_f32(vld1q_f32(ptr_d), vdupq_n_f32(weight)));
+            }
+
+            vst1q_f32(dst.get_unchecked_mut(_cx..).as_mut_ptr(), row0);
+
+            _cx += 4;
+        }
+
+        weighted_tail::<f32, OP_TYPE>(&offsets, weights, dst, _cx);
+    }
+}
@@ -28,8 +28,17 @@
  */
 mod morph_op;
 mod morph_op_f32;
+mod morph_op_f64;
+mod morph_op_int;
 mod morph_op_u16;
+mod morph_op_weighted;
 
 pub use morph_op::MorphOpFilterSse2DRow;
 pub use morph_op_f32::MorphOpFilterSse2DRowF32;
+pub use morph_op_f64::MorphOpFilterSse2DRowF64;
+pub use morph_op_int::MorphOpFilterSse2DRowInt;
 pub use morph_op_u16::MorphOpFilterSse2DRowU16;
+pub use morph_op_weighted::{
+    MorphOpFilterSseWeighted2DRow, MorphOpFilterSseWeighted2DRowF32,
+    MorphOpFilterSseWeighted2DRowU16,
+};
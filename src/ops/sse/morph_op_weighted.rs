@@ -0,0 +1,234 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::filter_op_declare::{Arena, ArenaRows, MorthOpFilterWeighted2DRow};
+use crate::op_type::MorphOp;
+use crate::ops::weighted_tail;
+use crate::unsafe_slice::UnsafeSlice;
+use crate::ImageSize;
+#[cfg(target_arch = "x86")]
+use std::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+#[derive(Clone)]
+pub struct MorphOpFilterSseWeighted2DRow<const OP_TYPE: u8> {}
+
+impl<const OP_TYPE: u8> Default for MorphOpFilterSseWeighted2DRow<OP_TYPE> {
+    fn default() -> Self {
+        MorphOpFilterSseWeighted2DRow {}
+    }
+}
+
+impl<const OP_TYPE: u8> MorthOpFilterWeighted2DRow<u8> for MorphOpFilterSseWeighted2DRow<OP_TYPE> {
+    #[target_feature(enable = "sse4.1")]
+    unsafe fn dispatch_row(
+        &self,
+        arena: &Arena<u8>,
+        dst: &UnsafeSlice<u8>,
+        dst_stride: usize,
+        image_size: ImageSize,
+        element_offsets: &[usize],
+        weights: &[i32],
+        y: usize,
+    ) {
+        let width = image_size.width;
+        let op_type: MorphOp = OP_TYPE.into();
+        let decision = match op_type {
+            MorphOp::Dilate => _mm_max_epu8,
+            MorphOp::Erode => _mm_min_epu8,
+        };
+
+        let offsets = ArenaRows::new(&arena.arena, element_offsets, y * arena.width);
+        // Signed weight is split into saturating addition and saturating subtraction
+        let v_weight = |w: i32| {
+            (
+                _mm_set1_epi8(w.clamp(0, 255) as u8 as i8),
+                _mm_set1_epi8((-w).clamp(0, 255) as u8 as i8),
+            )
+        };
+
+        let dst = std::slice::from_raw_parts_mut(dst.mut_ptr().add(y * dst_stride), width);
+
+        let mut _cx = 0usize;
+
+        while _cx + 16 <= width {
+            let (add, sub) = v_weight(*weights.get_unchecked(0));
+            let ptr0 = offsets.get_unchecked(0).get_unchecked(_cx..).as_ptr();
+            let mut row0 = _mm_subs_epu8(
+                _mm_adds_epu8(_mm_loadu_si128(ptr0 as *const __m128i), add),
+                sub,
+            );
+
+            for (row, &w) in offsets.iter().zip(weights.iter()).skip(1) {
+                let (add, sub) = v_weight(w);
+                let ptr_d = row.get_unchecked(_cx..).as_ptr();
+                let new_row0 = _mm_subs_epu8(
+                    _mm_adds_epu8(_mm_loadu_si128(ptr_d as *const __m128i), add),
+                    sub,
+                );
+                row0 = decision(row0, new_row0);
+            }
+
+            _mm_storeu_si128(
+                dst.get_unchecked_mut(_cx..).as_mut_ptr() as *mut __m128i,
+                row0,
+            );
+
+            _cx += 16;
+        }
+
+        weighted_tail::<u8, OP_TYPE>(&offsets, weights, dst, _cx);
+    }
+}
+
+#[derive(Clone)]
+pub struct MorphOpFilterSseWeighted2DRowU16<const OP_TYPE: u8> {}
+
+impl<const OP_TYPE: u8> Default for MorphOpFilterSseWeighted2DRowU16<OP_TYPE> {
+    fn default() -> Self {
+        MorphOpFilterSseWeighted2DRowU16 {}
+    }
+}
+
+impl<const OP_TYPE: u8> MorthOpFilterWeighted2DRow<u16>
+    for MorphOpFilterSseWeighted2DRowU16<OP_TYPE>
+{
+    #[target_feature(enable = "sse4.1")]
+    unsafe fn dispatch_row(
+        &self,
+        arena: &Arena<u16>,
+        dst: &UnsafeSlice<u16>,
+        dst_stride: usize,
+        image_size: ImageSize,
+        element_offsets: &[usize],
+        weights: &[i32],
+        y: usize,
+    ) {
+        let width = image_size.width;
+        let op_type: MorphOp = OP_TYPE.into();
+        let decision = match op_type {
+            MorphOp::Dilate => _mm_max_epu16,
+            MorphOp::Erode => _mm_min_epu16,
+        };
+
+        let offsets = ArenaRows::new(&arena.arena, element_offsets, y * arena.width);
+        let v_weight = |w: i32| {
+            (
+                _mm_set1_epi16(w.clamp(0, 65535) as u16 as i16),
+                _mm_set1_epi16((-w).clamp(0, 65535) as u16 as i16),
+            )
+        };
+
+        let dst = std::slice::from_raw_parts_mut(dst.mut_ptr().add(y * dst_stride), width);
+
+        let mut _cx = 0usize;
+
+        while _cx + 8 <= width {
+            let (add, sub) = v_weight(*weights.get_unchecked(0));
+            let ptr0 = offsets.get_unchecked(0).get_unchecked(_cx..).as_ptr();
+            let mut row0 = _mm_subs_epu16(
+                _mm_adds_epu16(_mm_loadu_si128(ptr0 as *const __m128i), add),
+                sub,
+            );
+
+            for (row, &w) in offsets.iter().zip(weights.iter()).skip(1) {
+                let (add, sub) = v_weight(w);
+                let ptr_d = row.get_unchecked(_cx..).as_ptr();
+                let new_row0 = _mm_subs_epu16(
+                    _mm_adds_epu16(_mm_loadu_si128(ptr_d as *const __m128i), add),
+                    sub,
+                );
+                row0 = decision(row0, new_row0);
+            }
+
+            _mm_storeu_si128(
+                dst.get_unchecked_mut(_cx..).as_mut_ptr() as *mut __m128i,
+                row0,
+            );
+
+            _cx += 8;
+        }
+
+        weighted_tail::<u16, OP_TYPE>(&offsets, weights, dst, _cx);
+    }
+}
+
+#[derive(Clone)]
+pub struct MorphOpFilterSseWeighted2DRowF32<const OP_TYPE: u8> {}
+
+impl<const OP_TYPE: u8> Default for MorphOpFilterSseWeighted2DRowF32<OP_TYPE> {
+    fn default() -> Self {
+        MorphOpFilterSseWeighted2DRowF32 {}
+    }
+}
+
+impl<const OP_TYPE: u8> MorthOpFilterWeighted2DRow<f32>
+    for MorphOpFilterSseWeighted2DRowF32<OP_TYPE>
+{
+    #[target_feature(enable = "sse4.1")]
+    unsafe fn dispatch_row(
+        &self,
+        arena: &Arena<f32>,
+        dst: &UnsafeSlice<f32>,
+        dst_stride: usize,
+        image_size: ImageSize,
+        element_offsets: &[usize],
+        weights: &[f32],
+        y: usize,
+    ) {
+        let width = image_size.width;
+        let op_type: MorphOp = OP_TYPE.into();
+        let decision = match op_type {
+            MorphOp::Dilate => _mm_max_ps,
+            MorphOp::Erode => _mm_min_ps,
+        };
+
+        let offsets = ArenaRows::new(&arena.arena, element_offsets, y * arena.width);
+
+        let dst = std::slice::from_raw_parts_mut(dst.mut_ptr().add(y * dst_stride), width);
+
+        let mut _cx = 0usize;
+
+        while _cx + 4 <= width {
+            let ptr0 = offsets.get_unchecked(0).get_unchecked(_cx..).as_ptr();
+            let mut row0 = _mm_add_ps(_mm_loadu_ps(ptr0), _mm_set1_ps(*weights.get_unchecked(0)));
+
+            for (row, &weight) in offsets.iter().zip(weights.iter()).skip(1) {
+                let ptr_d = row.get_unchecked(_cx..).as_ptr();
+                row0 = decision(row0, _mm_add_ps(_mm_loadu_ps(ptr_d), _mm_set1_ps(weight)));
+            }
+
+            _mm_storeu_ps(dst.get_unchecked_mut(_cx..).as_mut_ptr(), row0);
+
+            _cx += 4;
+        }
+
+        weighted_tail::<f32, OP_TYPE>(&offsets, weights, dst, _cx);
+    }
+}
@@ -28,7 +28,9 @@
  */
 mod deinterleave_rgb;
 mod deinterleave_rgba;
+mod interleaved;
 mod v_load;
 
 pub use deinterleave_rgb::deinterleave_rgb_avx;
 pub use deinterleave_rgba::deinterleave_rgba_avx;
+pub use interleaved::{deinterleave_avx, interleave_avx};
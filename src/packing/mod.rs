@@ -31,22 +31,29 @@ pub mod avx;
 #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
 mod neon;
 mod pack_gray_alpha;
+mod pack_interleaved;
 mod pack_rgb;
 mod pack_rgba;
+mod shuffle_masks;
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
 mod sse;
 mod traits;
 mod unpack_gray_alpha;
+mod unpack_interleaved;
 mod unpack_rgb;
 mod unpack_rgba;
 mod unpacked_gray_alpha;
+mod unpacked_interleaved;
 mod unpacked_rgb_image;
 mod unpacked_rgba_image;
 
+pub use pack_interleaved::{interleave_naive, pack_interleaved};
 pub use pack_rgb::pack_rgb;
-pub use traits::{GrayAlphaPackable, RgbPackable, RgbaPackable};
+pub use traits::{GrayAlphaPackable, InterleavedPackable, RgbPackable, RgbaPackable};
+pub use unpack_interleaved::{deinterleave_naive, unpack_interleaved};
 pub use unpack_rgb::unpack_rgb;
 pub use unpack_rgba::unpack_rgba;
 pub use unpacked_gray_alpha::UnpackedGrayAlpha;
+pub use unpacked_interleaved::{PlainItem, UnpackedInterleaved};
 pub use unpacked_rgb_image::UnpackedRgbImage;
 pub use unpacked_rgba_image::UnpackedRgbaImage;
@@ -28,33 +28,32 @@
  */
 
 use crate::packing::UnpackedRgbaImage;
-use crate::ImageSize;
 use std::arch::aarch64::*;
 
 pub fn deinterleave_rgba_neon(
     rgb_image: &[u8],
+    stride: usize,
+    unpacked_image: &mut UnpackedRgbaImage<u8>,
     width: usize,
     height: usize,
-) -> UnpackedRgbaImage<u8> {
-    if rgb_image.len() != width * height * 4 {
+) {
+    if rgb_image.len() < stride * (height - 1) + width * 4 {
         panic!(
             "Image bounds in deinterleave_rgba_neon is mismatched! Expected {} but got {}",
-            width * height * 4,
+            stride * (height - 1) + width * 4,
             rgb_image.len()
         );
     }
-    let mut unpacked_image = UnpackedRgbaImage::alloc(ImageSize::new(width, height));
-
     let mut r_dst: &mut [u8] = unpacked_image.r_channel.as_mut_slice();
     let mut g_dst: &mut [u8] = unpacked_image.g_channel.as_mut_slice();
     let mut b_dst: &mut [u8] = unpacked_image.b_channel.as_mut_slice();
     let mut a_dst: &mut [u8] = unpacked_image.a_channel.as_mut_slice();
 
-    let src_stride = width * 4;
+    let src_stride = stride;
 
     let mut src = rgb_image;
     unsafe {
-        for _ in 0..height {
+        for y in 0..height {
             let mut _cx = 0usize;
 
             while _cx + 16 < width {
@@ -87,12 +86,13 @@ pub fn deinterleave_rgba_neon(
                 _cx += 1;
             }
 
-            src = src.get_unchecked(src_stride..);
+            if y + 1 < height {
+                src = src.get_unchecked(src_stride..);
+            }
             r_dst = r_dst.get_unchecked_mut(width..);
             g_dst = g_dst.get_unchecked_mut(width..);
             b_dst = b_dst.get_unchecked_mut(width..);
             a_dst = a_dst.get_unchecked_mut(width..);
         }
     }
-    unpacked_image
 }
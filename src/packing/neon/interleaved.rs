@@ -0,0 +1,419 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::packing::shuffle_masks::{ShuffleMasks, MAX_SHUFFLED_CHANNELS};
+use crate::packing::{PlainItem, UnpackedInterleaved};
+use std::arch::aarch64::*;
+
+/// Moves a row with structure loads of `$load`, one register per plane is stored by `$store`
+macro_rules! deinterleave_structured {
+    ($src:expr, $planes:expr, $offset:expr, $width:expr, $item:ty, $load:ident, $store:ident,
+     $($channel:tt),+) => {{
+        let lanes = 16 / std::mem::size_of::<$item>();
+        let channels = [$($channel),+].len();
+        let src_ptr = $src.as_ptr() as *const $item;
+        let mut cx = 0usize;
+        while cx + lanes <= $width {
+            let pixels = $load(src_ptr.add(cx * channels));
+            $(
+                $store(
+                    $planes[$channel].as_mut_ptr().add($offset + cx) as *mut $item,
+                    pixels.$channel,
+                );
+            )+
+            cx += lanes;
+        }
+        cx
+    }};
+}
+
+/// Moves a row with structure stores of `$store` built from registers of planes
+macro_rules! interleave_structured {
+    ($planes:expr, $dst:expr, $offset:expr, $width:expr, $item:ty, $load:ident, $store:ident,
+     $set:ident, $($channel:tt),+) => {{
+        let lanes = 16 / std::mem::size_of::<$item>();
+        let channels = [$($channel),+].len();
+        let dst_ptr = $dst.as_mut_ptr() as *mut $item;
+        let mut cx = 0usize;
+        while cx + lanes <= $width {
+            let pixels = $set($($load($planes[$channel].as_ptr().add($offset + cx) as *const $item)),+);
+            $store(dst_ptr.add(cx * channels), pixels);
+            cx += lanes;
+        }
+        cx
+    }};
+}
+
+/// Deinterleaves 2, 3 and 4 channels by `vld2q`, `vld3q` and `vld4q`, other counts
+/// by table lookups of [ShuffleMasks]
+pub fn deinterleave_neon<T: PlainItem>(
+    image: &[T],
+    stride: usize,
+    unpacked_image: &mut UnpackedInterleaved<T>,
+    width: usize,
+    height: usize,
+) {
+    let channels = unpacked_image.channels();
+    assert!(channels <= MAX_SHUFFLED_CHANNELS);
+    if image.len() < stride * (height - 1) + width * channels {
+        panic!(
+            "Image bounds in deinterleave_neon is mismatched! Expected {} but got {}",
+            stride * (height - 1) + width * channels,
+            image.len()
+        );
+    }
+    let shuffles = ShuffleMasks::new(channels, std::mem::size_of::<T>(), true);
+    let planes = &mut unpacked_image.planes;
+    for (y, src) in image.chunks(stride).take(height).enumerate() {
+        let offset = y * width;
+        let cx = unsafe {
+            match (channels, std::mem::size_of::<T>()) {
+                (2, 1) => deinterleave_structured!(
+                    src, planes, offset, width, u8, vld2q_u8, vst1q_u8, 0, 1
+                ),
+                (2, 2) => deinterleave_structured!(
+                    src, planes, offset, width, u16, vld2q_u16, vst1q_u16, 0, 1
+                ),
+                (2, 4) => deinterleave_structured!(
+                    src, planes, offset, width, u32, vld2q_u32, vst1q_u32, 0, 1
+                ),
+                (2, 8) => deinterleave_structured!(
+                    src, planes, offset, width, u64, vld2q_u64, vst1q_u64, 0, 1
+                ),
+                (3, 1) => deinterleave_structured!(
+                    src, planes, offset, width, u8, vld3q_u8, vst1q_u8, 0, 1, 2
+                ),
+                (3, 2) => deinterleave_structured!(
+                    src, planes, offset, width, u16, vld3q_u16, vst1q_u16, 0, 1, 2
+                ),
+                (3, 4) => deinterleave_structured!(
+                    src, planes, offset, width, u32, vld3q_u32, vst1q_u32, 0, 1, 2
+                ),
+                (3, 8) => deinterleave_structured!(
+                    src, planes, offset, width, u64, vld3q_u64, vst1q_u64, 0, 1, 2
+                ),
+                (4, 1) => deinterleave_structured!(
+                    src, planes, offset, width, u8, vld4q_u8, vst1q_u8, 0, 1, 2, 3
+                ),
+                (4, 2) => deinterleave_structured!(
+                    src, planes, offset, width, u16, vld4q_u16, vst1q_u16, 0, 1, 2, 3
+                ),
+                (4, 4) => deinterleave_structured!(
+                    src, planes, offset, width, u32, vld4q_u32, vst1q_u32, 0, 1, 2, 3
+                ),
+                (4, 8) => deinterleave_structured!(
+                    src, planes, offset, width, u64, vld4q_u64, vst1q_u64, 0, 1, 2, 3
+                ),
+                _ => deinterleave_table(src, planes, offset, width, &shuffles),
+            }
+        };
+        for (channel, plane) in planes.iter_mut().enumerate() {
+            for (dst, src) in plane[offset + cx..offset + width]
+                .iter_mut()
+                .zip(src[cx * channels..].chunks_exact(channels))
+            {
+                *dst = src[channel];
+            }
+        }
+    }
+}
+
+/// Deinterleaves blocks of a row, returns count of moved pixels
+unsafe fn deinterleave_table<T: PlainItem>(
+    src: &[T],
+    planes: &mut [Vec<T>],
+    offset: usize,
+    width: usize,
+    shuffles: &ShuffleMasks,
+) -> usize {
+    let channels = planes.len();
+    let block = 16 / std::mem::size_of::<T>();
+    let masks = load_masks(shuffles);
+    let mut registers = [vdupq_n_u8(0); MAX_SHUFFLED_CHANNELS];
+    let mut cx = 0usize;
+    while cx + block <= width {
+        let src_ptr = src.as_ptr().add(cx * channels) as *const u8;
+        for (i, register) in registers.iter_mut().take(channels).enumerate() {
+            *register = vld1q_u8(src_ptr.add(i * 16));
+        }
+        for (to, plane) in planes.iter_mut().enumerate() {
+            let mut channel = vdupq_n_u8(0);
+            for (&from, mask) in shuffles.sources[to]
+                .iter()
+                .zip(masks[to].iter())
+                .take(shuffles.counts[to])
+            {
+                channel = vorrq_u8(channel, vqtbl1q_u8(registers[from], *mask));
+            }
+            vst1q_u8(plane.as_mut_ptr().add(offset + cx) as *mut u8, channel);
+        }
+        cx += block;
+    }
+    cx
+}
+
+/// Interleaves 2, 3 and 4 channels by `vst2q`, `vst3q` and `vst4q`, other counts
+/// by table lookups of [ShuffleMasks]
+pub fn interleave_neon<T: PlainItem>(
+    unpacked_image: &UnpackedInterleaved<T>,
+    dst_image: &mut [T],
+    stride: usize,
+    width: usize,
+    height: usize,
+) {
+    let channels = unpacked_image.channels();
+    assert!(channels <= MAX_SHUFFLED_CHANNELS);
+    if dst_image.len() < stride * (height - 1) + width * channels {
+        panic!(
+            "Image bounds in interleave_neon is mismatched! Expected {} but got {}",
+            stride * (height - 1) + width * channels,
+            dst_image.len()
+        );
+    }
+    let shuffles = ShuffleMasks::new(channels, std::mem::size_of::<T>(), false);
+    let planes = &unpacked_image.planes;
+    for (y, dst) in dst_image.chunks_mut(stride).take(height).enumerate() {
+        let offset = y * width;
+        let cx = unsafe {
+            match (channels, std::mem::size_of::<T>()) {
+                (2, 1) => interleave_structured!(
+                    planes,
+                    dst,
+                    offset,
+                    width,
+                    u8,
+                    vld1q_u8,
+                    vst2q_u8,
+                    uint8x16x2_t,
+                    0,
+                    1
+                ),
+                (2, 2) => interleave_structured!(
+                    planes,
+                    dst,
+                    offset,
+                    width,
+                    u16,
+                    vld1q_u16,
+                    vst2q_u16,
+                    uint16x8x2_t,
+                    0,
+                    1
+                ),
+                (2, 4) => interleave_structured!(
+                    planes,
+                    dst,
+                    offset,
+                    width,
+                    u32,
+                    vld1q_u32,
+                    vst2q_u32,
+                    uint32x4x2_t,
+                    0,
+                    1
+                ),
+                (2, 8) => interleave_structured!(
+                    planes,
+                    dst,
+                    offset,
+                    width,
+                    u64,
+                    vld1q_u64,
+                    vst2q_u64,
+                    uint64x2x2_t,
+                    0,
+                    1
+                ),
+                (3, 1) => interleave_structured!(
+                    planes,
+                    dst,
+                    offset,
+                    width,
+                    u8,
+                    vld1q_u8,
+                    vst3q_u8,
+                    uint8x16x3_t,
+                    0,
+                    1,
+                    2
+                ),
+                (3, 2) => interleave_structured!(
+                    planes,
+                    dst,
+                    offset,
+                    width,
+                    u16,
+                    vld1q_u16,
+                    vst3q_u16,
+                    uint16x8x3_t,
+                    0,
+                    1,
+                    2
+                ),
+                (3, 4) => interleave_structured!(
+                    planes,
+                    dst,
+                    offset,
+                    width,
+                    u32,
+                    vld1q_u32,
+                    vst3q_u32,
+                    uint32x4x3_t,
+                    0,
+                    1,
+                    2
+                ),
+                (3, 8) => interleave_structured!(
+                    planes,
+                    dst,
+                    offset,
+                    width,
+                    u64,
+                    vld1q_u64,
+                    vst3q_u64,
+                    uint64x2x3_t,
+                    0,
+                    1,
+                    2
+                ),
+                (4, 1) => interleave_structured!(
+                    planes,
+                    dst,
+                    offset,
+                    width,
+                    u8,
+                    vld1q_u8,
+                    vst4q_u8,
+                    uint8x16x4_t,
+                    0,
+                    1,
+                    2,
+                    3
+                ),
+                (4, 2) => interleave_structured!(
+                    planes,
+                    dst,
+                    offset,
+                    width,
+                    u16,
+                    vld1q_u16,
+                    vst4q_u16,
+                    uint16x8x4_t,
+                    0,
+                    1,
+                    2,
+                    3
+                ),
+                (4, 4) => interleave_structured!(
+                    planes,
+                    dst,
+                    offset,
+                    width,
+                    u32,
+                    vld1q_u32,
+                    vst4q_u32,
+                    uint32x4x4_t,
+                    0,
+                    1,
+                    2,
+                    3
+                ),
+                (4, 8) => interleave_structured!(
+                    planes,
+                    dst,
+                    offset,
+                    width,
+                    u64,
+                    vld1q_u64,
+                    vst4q_u64,
+                    uint64x2x4_t,
+                    0,
+                    1,
+                    2,
+                    3
+                ),
+                _ => interleave_table(planes, dst, offset, width, &shuffles),
+            }
+        };
+        for (channel, plane) in planes.iter().enumerate() {
+            for (dst, src) in dst[cx * channels..width * channels]
+                .chunks_exact_mut(channels)
+                .zip(plane[offset + cx..offset + width].iter())
+            {
+                dst[channel] = *src;
+            }
+        }
+    }
+}
+
+/// Interleaves blocks of a row, returns count of moved pixels
+unsafe fn interleave_table<T: PlainItem>(
+    planes: &[Vec<T>],
+    dst: &mut [T],
+    offset: usize,
+    width: usize,
+    shuffles: &ShuffleMasks,
+) -> usize {
+    let channels = planes.len();
+    let block = 16 / std::mem::size_of::<T>();
+    let masks = load_masks(shuffles);
+    let mut registers = [vdupq_n_u8(0); MAX_SHUFFLED_CHANNELS];
+    let mut cx = 0usize;
+    while cx + block <= width {
+        for (register, plane) in registers.iter_mut().zip(planes.iter()) {
+            *register = vld1q_u8(plane.as_ptr().add(offset + cx) as *const u8);
+        }
+        let dst_ptr = dst.as_mut_ptr().add(cx * channels) as *mut u8;
+        for (to, masks) in masks.iter().take(channels).enumerate() {
+            let mut interleaved = vdupq_n_u8(0);
+            for (&from, mask) in shuffles.sources[to]
+                .iter()
+                .zip(masks.iter())
+                .take(shuffles.counts[to])
+            {
+                interleaved = vorrq_u8(interleaved, vqtbl1q_u8(registers[from], *mask));
+            }
+            vst1q_u8(dst_ptr.add(to * 16), interleaved);
+        }
+        cx += block;
+    }
+    cx
+}
+
+/// Masks of [ShuffleMasks] as registers, lookups of `0x80` give zero
+unsafe fn load_masks(
+    shuffles: &ShuffleMasks,
+) -> [[uint8x16_t; MAX_SHUFFLED_CHANNELS]; MAX_SHUFFLED_CHANNELS] {
+    let mut masks = [[vdupq_n_u8(0); MAX_SHUFFLED_CHANNELS]; MAX_SHUFFLED_CHANNELS];
+    for (dst, src) in masks.iter_mut().zip(shuffles.masks.iter()) {
+        for (dst, src) in dst.iter_mut().zip(src.iter()) {
+            *dst = vld1q_u8(src.as_ptr());
+        }
+    }
+    masks
+}
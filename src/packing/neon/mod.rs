@@ -28,10 +28,12 @@
  */
 pub mod deinterleave_rgb;
 pub mod deinterleave_rgba;
+mod interleaved;
 mod pack_rgb;
 mod pack_rgba;
 
 pub use deinterleave_rgb::deinterleave_rgb_neon;
 pub use deinterleave_rgba::deinterleave_rgba_neon;
+pub use interleaved::{deinterleave_neon, interleave_neon};
 pub use pack_rgb::pack_rgb_neon;
 pub use pack_rgba::pack_rgba_neon;
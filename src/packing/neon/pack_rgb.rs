@@ -32,13 +32,14 @@ use std::arch::aarch64::*;
 pub fn pack_rgb_neon(
     unpacked_rgb_image: &UnpackedRgbImage<u8>,
     dst_image: &mut [u8],
+    stride: usize,
     width: usize,
     height: usize,
 ) {
-    if dst_image.len() != width * height * 3 {
+    if dst_image.len() < stride * (height - 1) + width * 3 {
         panic!(
             "Image bounds in pack_rgb_neon is mismatched! Expected {} but got {}",
-            width * height * 3,
+            stride * (height - 1) + width * 3,
             dst_image.len()
         );
     }
@@ -47,11 +48,11 @@ pub fn pack_rgb_neon(
     let mut g_src: &[u8] = unpacked_rgb_image.g_channel.as_slice();
     let mut b_src: &[u8] = unpacked_rgb_image.b_channel.as_slice();
 
-    let src_stride = width * 3;
+    let src_stride = stride;
 
     let mut dst = dst_image;
     unsafe {
-        for _ in 0..height {
+        for y in 0..height {
             let mut _cx = 0usize;
 
             while _cx + 16 < width {
@@ -85,7 +86,9 @@ pub fn pack_rgb_neon(
                 _cx += 1;
             }
 
-            dst = dst.get_unchecked_mut(src_stride..);
+            if y + 1 < height {
+                dst = dst.get_unchecked_mut(src_stride..);
+            }
             r_src = r_src.get_unchecked(width..);
             g_src = g_src.get_unchecked(width..);
             b_src = b_src.get_unchecked(width..);
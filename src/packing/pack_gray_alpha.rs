@@ -31,17 +31,24 @@ use crate::packing::UnpackedGrayAlpha;
 pub fn pack_gray_alpha_naive<T>(
     unpacked_rgb_image: &UnpackedGrayAlpha<T>,
     dst_image: &mut [T],
-    _: usize,
+    stride: usize,
+    width: usize,
     _: usize,
 ) where
     T: Copy,
 {
-    for ((src, r), g) in dst_image
-        .chunks_exact_mut(2)
-        .zip(unpacked_rgb_image.gray_channel.iter())
-        .zip(unpacked_rgb_image.alpha_channel.iter())
+    for ((dst, r), g) in dst_image
+        .chunks_mut(stride)
+        .zip(unpacked_rgb_image.gray_channel.chunks_exact(width))
+        .zip(unpacked_rgb_image.alpha_channel.chunks_exact(width))
     {
-        src[0] = *r;
-        src[1] = *g;
+        for ((dst, r), g) in dst[..width * 2]
+            .chunks_exact_mut(2)
+            .zip(r.iter())
+            .zip(g.iter())
+        {
+            dst[0] = *r;
+            dst[1] = *g;
+        }
     }
 }
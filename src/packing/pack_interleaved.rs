@@ -0,0 +1,101 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use crate::packing::avx::interleave_avx;
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+use crate::packing::neon::interleave_neon;
+use crate::packing::shuffle_masks::shuffles_beat_scalar;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use crate::packing::sse::interleave_sse;
+use crate::packing::{PlainItem, UnpackedInterleaved};
+use crate::ImageSize;
+use std::mem::size_of;
+
+pub fn interleave_naive<T>(
+    unpacked_image: &UnpackedInterleaved<T>,
+    dst_image: &mut [T],
+    stride: usize,
+    width: usize,
+    height: usize,
+) where
+    T: Copy,
+{
+    let channels = unpacked_image.channels();
+    for (y, dst) in dst_image.chunks_mut(stride).take(height).enumerate() {
+        let dst = &mut dst[..width * channels];
+        for (channel, plane) in unpacked_image.planes.iter().enumerate() {
+            for (dst, src) in dst
+                .chunks_exact_mut(channels)
+                .zip(plane[y * width..(y + 1) * width].iter())
+            {
+                dst[channel] = *src;
+            }
+        }
+    }
+}
+
+/// Interleaves 2, 3 and 4 channels with NEON structure loads and stores, and up to 16
+/// channels of 1 and 2 byte items with AVX2, SSE4.1 or NEON byte shuffles where they
+/// beat [interleave_naive], other counts and types use [interleave_naive]
+pub fn pack_interleaved<T: PlainItem>(
+    unpacked_image: &UnpackedInterleaved<T>,
+    dst_image: &mut [T],
+    stride: usize,
+    image_size: ImageSize,
+) {
+    let mut _dispatcher: fn(&UnpackedInterleaved<T>, &mut [T], usize, usize, usize) =
+        interleave_naive;
+    let channels = unpacked_image.channels();
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        if (2..=4).contains(&channels) || shuffles_beat_scalar(channels, size_of::<T>(), 1) {
+            _dispatcher = interleave_neon;
+        }
+    }
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if shuffles_beat_scalar(channels, size_of::<T>(), 1)
+            && std::arch::is_x86_feature_detected!("sse4.1")
+        {
+            _dispatcher = interleave_sse;
+        }
+        if shuffles_beat_scalar(channels, size_of::<T>(), 2)
+            && std::arch::is_x86_feature_detected!("avx2")
+        {
+            _dispatcher = interleave_avx;
+        }
+    }
+    _dispatcher(
+        unpacked_image,
+        dst_image,
+        stride,
+        image_size.width,
+        image_size.height,
+    )
+}
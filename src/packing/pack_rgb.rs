@@ -36,29 +36,39 @@ use crate::ImageSize;
 pub fn interleave_rgb_naive<T>(
     unpacked_rgb_image: &UnpackedRgbImage<T>,
     dst_image: &mut [T],
-    _: usize,
+    stride: usize,
+    width: usize,
     _: usize,
 ) where
     T: Copy,
 {
-    for (((src, r), g), b) in dst_image
-        .chunks_exact_mut(3)
-        .zip(unpacked_rgb_image.r_channel.iter())
-        .zip(unpacked_rgb_image.g_channel.iter())
-        .zip(unpacked_rgb_image.b_channel.iter())
+    for (((dst, r), g), b) in dst_image
+        .chunks_mut(stride)
+        .zip(unpacked_rgb_image.r_channel.chunks_exact(width))
+        .zip(unpacked_rgb_image.g_channel.chunks_exact(width))
+        .zip(unpacked_rgb_image.b_channel.chunks_exact(width))
     {
-        src[0] = *r;
-        src[1] = *g;
-        src[2] = *b;
+        for (((dst, r), g), b) in dst[..width * 3]
+            .chunks_exact_mut(3)
+            .zip(r.iter())
+            .zip(g.iter())
+            .zip(b.iter())
+        {
+            dst[0] = *r;
+            dst[1] = *g;
+            dst[2] = *b;
+        }
     }
 }
 
 pub fn pack_rgb(
     unpacked_rgb_image: &UnpackedRgbImage<u8>,
     dst_image: &mut [u8],
+    stride: usize,
     image_size: ImageSize,
 ) {
-    let mut _dispatcher: fn(&UnpackedRgbImage<u8>, &mut [u8], usize, usize) = interleave_rgb_naive;
+    let mut _dispatcher: fn(&UnpackedRgbImage<u8>, &mut [u8], usize, usize, usize) =
+        interleave_rgb_naive;
     #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
     {
         _dispatcher = pack_rgb_neon;
@@ -72,6 +82,7 @@ pub fn pack_rgb(
     _dispatcher(
         unpacked_rgb_image,
         dst_image,
+        stride,
         image_size.width,
         image_size.height,
     );
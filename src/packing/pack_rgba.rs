@@ -36,31 +36,41 @@ use crate::ImageSize;
 pub fn interleave_rgba_naive<T>(
     unpacked_rgba_image: &UnpackedRgbaImage<T>,
     dst_image: &mut [T],
-    _: usize,
+    stride: usize,
+    width: usize,
     _: usize,
 ) where
     T: Copy,
 {
-    for ((((src, r), g), b), a) in dst_image
-        .chunks_exact_mut(4)
-        .zip(unpacked_rgba_image.r_channel.iter())
-        .zip(unpacked_rgba_image.g_channel.iter())
-        .zip(unpacked_rgba_image.b_channel.iter())
-        .zip(unpacked_rgba_image.a_channel.iter())
+    for ((((dst, r), g), b), a) in dst_image
+        .chunks_mut(stride)
+        .zip(unpacked_rgba_image.r_channel.chunks_exact(width))
+        .zip(unpacked_rgba_image.g_channel.chunks_exact(width))
+        .zip(unpacked_rgba_image.b_channel.chunks_exact(width))
+        .zip(unpacked_rgba_image.a_channel.chunks_exact(width))
     {
-        src[0] = *r;
-        src[1] = *g;
-        src[2] = *b;
-        src[3] = *a;
+        for ((((dst, r), g), b), a) in dst[..width * 4]
+            .chunks_exact_mut(4)
+            .zip(r.iter())
+            .zip(g.iter())
+            .zip(b.iter())
+            .zip(a.iter())
+        {
+            dst[0] = *r;
+            dst[1] = *g;
+            dst[2] = *b;
+            dst[3] = *a;
+        }
     }
 }
 
 pub fn pack_rgba(
     unpacked_rgb_image: &UnpackedRgbaImage<u8>,
     dst_image: &mut [u8],
+    stride: usize,
     image_size: ImageSize,
 ) {
-    let mut _dispatcher: fn(&UnpackedRgbaImage<u8>, &mut [u8], usize, usize) =
+    let mut _dispatcher: fn(&UnpackedRgbaImage<u8>, &mut [u8], usize, usize, usize) =
         interleave_rgba_naive;
     #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
     {
@@ -75,6 +85,7 @@ pub fn pack_rgba(
     _dispatcher(
         unpacked_rgb_image,
         dst_image,
+        stride,
         image_size.width,
         image_size.height,
     )
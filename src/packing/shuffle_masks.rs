@@ -0,0 +1,96 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+/// Most channels moved by byte shuffles, a block of pixels fills one register per channel
+pub const MAX_SHUFFLED_CHANNELS: usize = 16;
+
+/// Byte shuffles between a block of interleaved pixels and registers of channels.
+///
+/// A block holds `16 / item_size` pixels, that is `channels` registers both interleaved
+/// and planar. Register `to` is an OR of `counts[to]` shuffles, `masks[to][i]` picks bytes
+/// of register `sources[to][i]`, registers holding no byte of `to` are skipped.
+pub(crate) struct ShuffleMasks {
+    pub(crate) masks: [[[u8; 16]; MAX_SHUFFLED_CHANNELS]; MAX_SHUFFLED_CHANNELS],
+    pub(crate) sources: [[usize; MAX_SHUFFLED_CHANNELS]; MAX_SHUFFLED_CHANNELS],
+    pub(crate) counts: [usize; MAX_SHUFFLED_CHANNELS],
+}
+
+impl ShuffleMasks {
+    /// `deinterleave` gives masks from interleaved registers to channel registers,
+    /// otherwise from channel registers to interleaved ones
+    pub(crate) fn new(channels: usize, item_size: usize, deinterleave: bool) -> ShuffleMasks {
+        assert!(channels <= MAX_SHUFFLED_CHANNELS);
+        let mut full = [[[0x80u8; 16]; MAX_SHUFFLED_CHANNELS]; MAX_SHUFFLED_CHANNELS];
+        let pixel_size = channels * item_size;
+        for offset in 0..channels * 16 {
+            let (interleaved, byte) = (offset / 16, offset % 16);
+            let (pixel, channel) = (offset / pixel_size, offset % pixel_size / item_size);
+            let planar_byte = pixel * item_size + offset % item_size;
+            if deinterleave {
+                full[channel][interleaved][planar_byte] = byte as u8;
+            } else {
+                full[interleaved][channel][byte] = planar_byte as u8;
+            }
+        }
+        let mut shuffles = ShuffleMasks {
+            masks: [[[0x80u8; 16]; MAX_SHUFFLED_CHANNELS]; MAX_SHUFFLED_CHANNELS],
+            sources: [[0usize; MAX_SHUFFLED_CHANNELS]; MAX_SHUFFLED_CHANNELS],
+            counts: [0usize; MAX_SHUFFLED_CHANNELS],
+        };
+        for (to, full) in full.iter().take(channels).enumerate() {
+            for (from, mask) in full.iter().take(channels).enumerate() {
+                if mask.iter().any(|&x| x != 0x80) {
+                    let count = shuffles.counts[to];
+                    shuffles.masks[to][count] = *mask;
+                    shuffles.sources[to][count] = from;
+                    shuffles.counts[to] += 1;
+                }
+            }
+        }
+        shuffles
+    }
+
+    /// Count of shuffles moving one block
+    pub(crate) fn shuffles_count(&self) -> usize {
+        self.counts.iter().sum()
+    }
+}
+
+/// Whether byte shuffles of `lanes` blocks per instruction move `channels` interleaved
+/// channels of `item_size` bytes faster than a scalar loop.
+///
+/// A shuffle costs about as much as two items moved by a scalar loop. Items of 4 and 8 bytes
+/// make a block of 4 or 2 pixels only, then loads and stores of registers outweigh moved
+/// items whatever the count of channels, and a single plane is a plain copy.
+pub(crate) fn shuffles_beat_scalar(channels: usize, item_size: usize, lanes: usize) -> bool {
+    if !(2..=MAX_SHUFFLED_CHANNELS).contains(&channels) || item_size > 2 {
+        return false;
+    }
+    let items = channels * 16 / item_size;
+    ShuffleMasks::new(channels, item_size, true).shuffles_count() * 2 <= items * lanes
+}
@@ -33,37 +33,42 @@ use std::arch::x86::*;
 #[cfg(target_arch = "x86_64")]
 use std::arch::x86_64::*;
 
-pub fn deinterleave_rgb_sse(rgb_image: &[u8], width: usize, height: usize) -> UnpackedRgbImage<u8> {
-    unsafe { deinterleave_rgb_sse_impl(rgb_image, width, height) }
+pub fn deinterleave_rgb_sse(
+    rgb_image: &[u8],
+    stride: usize,
+    unpacked_image: &mut UnpackedRgbImage<u8>,
+    width: usize,
+    height: usize,
+) {
+    unsafe { deinterleave_rgb_sse_impl(rgb_image, stride, unpacked_image, width, height) }
 }
 
 #[inline]
 #[target_feature(enable = "sse4.1")]
 unsafe fn deinterleave_rgb_sse_impl(
     rgb_image: &[u8],
+    stride: usize,
+    unpacked_image: &mut UnpackedRgbImage<u8>,
     width: usize,
     height: usize,
-) -> UnpackedRgbImage<u8> {
-    if rgb_image.len() != width * height * 3 {
+) {
+    if rgb_image.len() < stride * (height - 1) + width * 3 {
         panic!(
             "Image bounds in deinterleave_rgb_sse is mismatched! Expected {} but got {}",
-            width * height * 3,
+            stride * (height - 1) + width * 3,
             rgb_image.len()
         );
     }
-    let mut r_chan = vec![0u8; width * height];
-    let mut g_chan = vec![0u8; width * height];
-    let mut b_chan = vec![0u8; width * height];
 
-    let mut r_dst = r_chan.as_mut_slice();
-    let mut g_dst = g_chan.as_mut_slice();
-    let mut b_dst = b_chan.as_mut_slice();
+    let mut r_dst = unpacked_image.r_channel.as_mut_slice();
+    let mut g_dst = unpacked_image.g_channel.as_mut_slice();
+    let mut b_dst = unpacked_image.b_channel.as_mut_slice();
 
-    let src_stride = width * 3;
+    let src_stride = stride;
 
     let mut src = rgb_image;
     unsafe {
-        for _ in 0..height {
+        for y in 0..height {
             let mut _cx = 0usize;
 
             while _cx + 16 < width {
@@ -108,11 +113,12 @@ unsafe fn deinterleave_rgb_sse_impl(
                 _cx += 1;
             }
 
-            src = src.get_unchecked(src_stride..);
+            if y + 1 < height {
+                src = src.get_unchecked(src_stride..);
+            }
             r_dst = r_dst.get_unchecked_mut(width..);
             g_dst = g_dst.get_unchecked_mut(width..);
             b_dst = b_dst.get_unchecked_mut(width..);
         }
     }
-    UnpackedRgbImage::new(r_chan, g_chan, b_chan)
 }
@@ -28,7 +28,6 @@
  */
 use crate::packing::sse::{_mm_load_deinterleave_half_rgba, _mm_load_deinterleave_rgba};
 use crate::packing::UnpackedRgbaImage;
-use crate::ImageSize;
 #[cfg(target_arch = "x86")]
 use std::arch::x86::*;
 #[cfg(target_arch = "x86_64")]
@@ -36,38 +35,40 @@ use std::arch::x86_64::*;
 
 pub fn deinterleave_rgba_sse(
     rgb_image: &[u8],
+    stride: usize,
+    unpacked_image: &mut UnpackedRgbaImage<u8>,
     width: usize,
     height: usize,
-) -> UnpackedRgbaImage<u8> {
-    unsafe { deinterleave_rgba_sse_impl(rgb_image, width, height) }
+) {
+    unsafe { deinterleave_rgba_sse_impl(rgb_image, stride, unpacked_image, width, height) }
 }
 
 #[inline]
 #[target_feature(enable = "sse4.1")]
 unsafe fn deinterleave_rgba_sse_impl(
     rgb_image: &[u8],
+    stride: usize,
+    unpacked_image: &mut UnpackedRgbaImage<u8>,
     width: usize,
     height: usize,
-) -> UnpackedRgbaImage<u8> {
-    if rgb_image.len() != width * height * 4 {
+) {
+    if rgb_image.len() < stride * (height - 1) + width * 4 {
         panic!(
             "Image bounds in deinterleave_rgba_sse is mismatched! Expected {} but got {}",
-            width * height * 4,
+            stride * (height - 1) + width * 4,
             rgb_image.len()
         );
     }
-    let mut unpacked_image = UnpackedRgbaImage::alloc(ImageSize::new(width, height));
-
     let mut r_dst: &mut [u8] = unpacked_image.r_channel.as_mut_slice();
     let mut g_dst: &mut [u8] = unpacked_image.g_channel.as_mut_slice();
     let mut b_dst: &mut [u8] = unpacked_image.b_channel.as_mut_slice();
     let mut a_dst: &mut [u8] = unpacked_image.a_channel.as_mut_slice();
 
-    let src_stride = width * 4;
+    let src_stride = stride;
 
     let mut src = rgb_image;
     unsafe {
-        for _ in 0..height {
+        for y in 0..height {
             let mut _cx = 0usize;
 
             while _cx + 16 < width {
@@ -120,12 +121,13 @@ unsafe fn deinterleave_rgba_sse_impl(
                 _cx += 1;
             }
 
-            src = src.get_unchecked(src_stride..);
+            if y + 1 < height {
+                src = src.get_unchecked(src_stride..);
+            }
             r_dst = r_dst.get_unchecked_mut(width..);
             g_dst = g_dst.get_unchecked_mut(width..);
             b_dst = b_dst.get_unchecked_mut(width..);
             a_dst = a_dst.get_unchecked_mut(width..);
         }
     }
-    unpacked_image
 }
@@ -0,0 +1,179 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::packing::shuffle_masks::{ShuffleMasks, MAX_SHUFFLED_CHANNELS};
+use crate::packing::{PlainItem, UnpackedInterleaved};
+#[cfg(target_arch = "x86")]
+use std::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+/// Masks of [ShuffleMasks] as registers
+#[inline]
+#[target_feature(enable = "sse4.1")]
+unsafe fn load_masks(
+    shuffles: &ShuffleMasks,
+) -> [[__m128i; MAX_SHUFFLED_CHANNELS]; MAX_SHUFFLED_CHANNELS] {
+    let mut masks = [[_mm_setzero_si128(); MAX_SHUFFLED_CHANNELS]; MAX_SHUFFLED_CHANNELS];
+    for (dst, src) in masks.iter_mut().zip(shuffles.masks.iter()) {
+        for (dst, src) in dst.iter_mut().zip(src.iter()) {
+            *dst = _mm_loadu_si128(src.as_ptr() as *const __m128i);
+        }
+    }
+    masks
+}
+
+pub fn deinterleave_sse<T: PlainItem>(
+    image: &[T],
+    stride: usize,
+    unpacked_image: &mut UnpackedInterleaved<T>,
+    width: usize,
+    height: usize,
+) {
+    unsafe { deinterleave_sse_impl(image, stride, unpacked_image, width, height) }
+}
+
+#[inline]
+#[target_feature(enable = "sse4.1")]
+unsafe fn deinterleave_sse_impl<T: PlainItem>(
+    image: &[T],
+    stride: usize,
+    unpacked_image: &mut UnpackedInterleaved<T>,
+    width: usize,
+    height: usize,
+) {
+    let channels = unpacked_image.channels();
+    assert!(channels <= MAX_SHUFFLED_CHANNELS);
+    if image.len() < stride * (height - 1) + width * channels {
+        panic!(
+            "Image bounds in deinterleave_sse is mismatched! Expected {} but got {}",
+            stride * (height - 1) + width * channels,
+            image.len()
+        );
+    }
+    let block = 16 / std::mem::size_of::<T>();
+    let shuffles = ShuffleMasks::new(channels, std::mem::size_of::<T>(), true);
+    let masks = load_masks(&shuffles);
+    let mut registers = [_mm_setzero_si128(); MAX_SHUFFLED_CHANNELS];
+    for (y, src) in image.chunks(stride).take(height).enumerate() {
+        let mut cx = 0usize;
+        while cx + block <= width {
+            let src_ptr = src.as_ptr().add(cx * channels) as *const u8;
+            for (i, register) in registers.iter_mut().take(channels).enumerate() {
+                *register = _mm_loadu_si128(src_ptr.add(i * 16) as *const __m128i);
+            }
+            for (to, plane) in unpacked_image.planes.iter_mut().enumerate() {
+                let mut channel = _mm_setzero_si128();
+                for (&from, mask) in shuffles.sources[to]
+                    .iter()
+                    .zip(masks[to].iter())
+                    .take(shuffles.counts[to])
+                {
+                    channel = _mm_or_si128(channel, _mm_shuffle_epi8(registers[from], *mask));
+                }
+                _mm_storeu_si128(
+                    plane.as_mut_ptr().add(y * width + cx) as *mut __m128i,
+                    channel,
+                );
+            }
+            cx += block;
+        }
+        for (channel, plane) in unpacked_image.planes.iter_mut().enumerate() {
+            for (dst, src) in plane[y * width + cx..(y + 1) * width]
+                .iter_mut()
+                .zip(src[cx * channels..].chunks_exact(channels))
+            {
+                *dst = src[channel];
+            }
+        }
+    }
+}
+
+pub fn interleave_sse<T: PlainItem>(
+    unpacked_image: &UnpackedInterleaved<T>,
+    dst_image: &mut [T],
+    stride: usize,
+    width: usize,
+    height: usize,
+) {
+    unsafe { interleave_sse_impl(unpacked_image, dst_image, stride, width, height) }
+}
+
+#[inline]
+#[target_feature(enable = "sse4.1")]
+unsafe fn interleave_sse_impl<T: PlainItem>(
+    unpacked_image: &UnpackedInterleaved<T>,
+    dst_image: &mut [T],
+    stride: usize,
+    width: usize,
+    height: usize,
+) {
+    let channels = unpacked_image.channels();
+    assert!(channels <= MAX_SHUFFLED_CHANNELS);
+    if dst_image.len() < stride * (height - 1) + width * channels {
+        panic!(
+            "Image bounds in interleave_sse is mismatched! Expected {} but got {}",
+            stride * (height - 1) + width * channels,
+            dst_image.len()
+        );
+    }
+    let block = 16 / std::mem::size_of::<T>();
+    let shuffles = ShuffleMasks::new(channels, std::mem::size_of::<T>(), false);
+    let masks = load_masks(&shuffles);
+    let mut registers = [_mm_setzero_si128(); MAX_SHUFFLED_CHANNELS];
+    for (y, dst) in dst_image.chunks_mut(stride).take(height).enumerate() {
+        let mut cx = 0usize;
+        while cx + block <= width {
+            for (register, plane) in registers.iter_mut().zip(unpacked_image.planes.iter()) {
+                *register = _mm_loadu_si128(plane.as_ptr().add(y * width + cx) as *const __m128i);
+            }
+            let dst_ptr = dst.as_mut_ptr().add(cx * channels) as *mut u8;
+            for (to, masks) in masks.iter().take(channels).enumerate() {
+                let mut interleaved = _mm_setzero_si128();
+                for (&from, mask) in shuffles.sources[to]
+                    .iter()
+                    .zip(masks.iter())
+                    .take(shuffles.counts[to])
+                {
+                    interleaved =
+                        _mm_or_si128(interleaved, _mm_shuffle_epi8(registers[from], *mask));
+                }
+                _mm_storeu_si128(dst_ptr.add(to * 16) as *mut __m128i, interleaved);
+            }
+            cx += block;
+        }
+        for (channel, plane) in unpacked_image.planes.iter().enumerate() {
+            for (dst, src) in dst[cx * channels..width * channels]
+                .chunks_exact_mut(channels)
+                .zip(plane[y * width + cx..(y + 1) * width].iter())
+            {
+                dst[channel] = *src;
+            }
+        }
+    }
+}
@@ -28,6 +28,7 @@
  */
 mod deinterleave_rgb;
 mod deinterleave_rgba;
+mod interleaved;
 mod pack_rgb;
 mod pack_rgba;
 pub mod v_load;
@@ -35,6 +36,7 @@ mod v_store;
 
 pub use deinterleave_rgb::deinterleave_rgb_sse;
 pub use deinterleave_rgba::deinterleave_rgba_sse;
+pub use interleaved::{deinterleave_sse, interleave_sse};
 pub use pack_rgb::pack_rgb_sse;
 pub use pack_rgba::pack_rgba_sse;
 pub use v_load::*;
@@ -36,11 +36,12 @@ use std::arch::x86_64::*;
 pub fn pack_rgb_sse(
     unpacked_rgb_image: &UnpackedRgbImage<u8>,
     dst_image: &mut [u8],
+    stride: usize,
     width: usize,
     height: usize,
 ) {
     unsafe {
-        pack_rgb_sse_impl(unpacked_rgb_image, dst_image, width, height);
+        pack_rgb_sse_impl(unpacked_rgb_image, dst_image, stride, width, height);
     }
 }
 
@@ -49,13 +50,14 @@ pub fn pack_rgb_sse(
 unsafe fn pack_rgb_sse_impl(
     unpacked_rgb_image: &UnpackedRgbImage<u8>,
     dst_image: &mut [u8],
+    stride: usize,
     width: usize,
     height: usize,
 ) {
-    if dst_image.len() != width * height * 3 {
+    if dst_image.len() < stride * (height - 1) + width * 3 {
         panic!(
             "Image bounds in pack_rgb_neon is mismatched! Expected {} but got {}",
-            width * height * 3,
+            stride * (height - 1) + width * 3,
             dst_image.len()
         );
     }
@@ -64,11 +66,11 @@ unsafe fn pack_rgb_sse_impl(
     let mut g_src: &[u8] = unpacked_rgb_image.g_channel.as_slice();
     let mut b_src: &[u8] = unpacked_rgb_image.b_channel.as_slice();
 
-    let src_stride = width * 3;
+    let src_stride = stride;
 
     let mut dst = dst_image;
     unsafe {
-        for _ in 0..height {
+        for y in 0..height {
             let mut _cx = 0usize;
 
             while _cx + 16 < width {
@@ -102,7 +104,9 @@ unsafe fn pack_rgb_sse_impl(
                 _cx += 1;
             }
 
-            dst = dst.get_unchecked_mut(src_stride..);
+            if y + 1 < height {
+                dst = dst.get_unchecked_mut(src_stride..);
+            }
             r_src = r_src.get_unchecked(width..);
             g_src = g_src.get_unchecked(width..);
             b_src = b_src.get_unchecked(width..);
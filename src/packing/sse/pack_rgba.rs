@@ -36,11 +36,12 @@ use std::arch::x86_64::*;
 pub fn pack_rgba_sse(
     unpacked_rgb_image: &UnpackedRgbaImage<u8>,
     dst_image: &mut [u8],
+    stride: usize,
     width: usize,
     height: usize,
 ) {
     unsafe {
-        pack_rgba_impl(unpacked_rgb_image, dst_image, width, height);
+        pack_rgba_impl(unpacked_rgb_image, dst_image, stride, width, height);
     }
 }
 
@@ -49,13 +50,14 @@ pub fn pack_rgba_sse(
 unsafe fn pack_rgba_impl(
     unpacked_rgb_image: &UnpackedRgbaImage<u8>,
     dst_image: &mut [u8],
+    stride: usize,
     width: usize,
     height: usize,
 ) {
-    if dst_image.len() != width * height * 4 {
+    if dst_image.len() < stride * (height - 1) + width * 4 {
         panic!(
             "Image bounds in pack_rgba_neon is mismatched! Expected {} but got {}",
-            width * height * 4,
+            stride * (height - 1) + width * 4,
             dst_image.len()
         );
     }
@@ -65,11 +67,11 @@ unsafe fn pack_rgba_impl(
     let mut b_src: &[u8] = unpacked_rgb_image.b_channel.as_slice();
     let mut a_src: &[u8] = unpacked_rgb_image.a_channel.as_slice();
 
-    let src_stride = width * 4;
+    let src_stride = stride;
 
     let mut dst = dst_image;
     unsafe {
-        for _ in 0..height {
+        for y in 0..height {
             let mut _cx = 0usize;
 
             while _cx + 16 < width {
@@ -112,7 +114,9 @@ unsafe fn pack_rgba_impl(
                 _cx += 1;
             }
 
-            dst = dst.get_unchecked_mut(src_stride..);
+            if y + 1 < height {
+                dst = dst.get_unchecked_mut(src_stride..);
+            }
             r_src = r_src.get_unchecked(width..);
             g_src = g_src.get_unchecked(width..);
             b_src = b_src.get_unchecked(width..);
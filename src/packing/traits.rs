@@ -33,111 +33,990 @@ use crate::packing::unpack_gray_alpha::unpack_gray_alpha_naive;
 use crate::packing::unpack_rgb::deinterleave_rgb_naive;
 use crate::packing::unpack_rgba::deinterleave_rgba_naive;
 use crate::packing::{
-    pack_rgb, unpack_rgb, unpack_rgba, UnpackedGrayAlpha, UnpackedRgbImage, UnpackedRgbaImage,
+    deinterleave_naive, interleave_naive, pack_interleaved, pack_rgb, unpack_interleaved,
+    unpack_rgb, unpack_rgba, UnpackedGrayAlpha, UnpackedInterleaved, UnpackedRgbImage,
+    UnpackedRgbaImage,
 };
 use crate::ImageSize;
+#[cfg(feature = "half")]
+use half::f16;
 
 pub trait RgbPackable<T> {
-    fn unpack(src: &[T], image_size: ImageSize) -> UnpackedRgbImage<T>;
-    fn pack(unpacked_rgb_image: &UnpackedRgbImage<T>, dst: &mut [T], image_size: ImageSize);
+    fn unpack(src: &[T], src_stride: usize, dst: &mut UnpackedRgbImage<T>, image_size: ImageSize)
+    where
+        T: Copy + Default,
+    {
+        deinterleave_rgb_naive(src, src_stride, dst, image_size.width, image_size.height)
+    }
+
+    fn pack(
+        unpacked_rgb_image: &UnpackedRgbImage<T>,
+        dst: &mut [T],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) where
+        T: Copy + Default,
+    {
+        interleave_rgb_naive(
+            unpacked_rgb_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
+    }
 }
 
 impl RgbPackable<u8> for u8 {
-    fn unpack(src: &[u8], image_size: ImageSize) -> UnpackedRgbImage<u8> {
-        unpack_rgb(src, image_size)
+    fn unpack(
+        src: &[u8],
+        src_stride: usize,
+        dst: &mut UnpackedRgbImage<u8>,
+        image_size: ImageSize,
+    ) {
+        unpack_rgb(src, src_stride, dst, image_size)
     }
 
-    fn pack(unpacked_rgb_image: &UnpackedRgbImage<u8>, dst: &mut [u8], image_size: ImageSize) {
-        pack_rgb(unpacked_rgb_image, dst, image_size)
+    fn pack(
+        unpacked_rgb_image: &UnpackedRgbImage<u8>,
+        dst: &mut [u8],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        pack_rgb(unpacked_rgb_image, dst, dst_stride, image_size)
     }
 }
 
 impl RgbPackable<u16> for u16 {
-    fn unpack(src: &[u16], image_size: ImageSize) -> UnpackedRgbImage<u16> {
-        deinterleave_rgb_naive(src, image_size.width, image_size.height)
+    fn unpack(
+        src: &[u16],
+        src_stride: usize,
+        dst: &mut UnpackedRgbImage<u16>,
+        image_size: ImageSize,
+    ) {
+        deinterleave_rgb_naive(src, src_stride, dst, image_size.width, image_size.height)
     }
 
-    fn pack(unpacked_rgb_image: &UnpackedRgbImage<u16>, dst: &mut [u16], image_size: ImageSize) {
-        interleave_rgb_naive(unpacked_rgb_image, dst, image_size.width, image_size.height)
+    fn pack(
+        unpacked_rgb_image: &UnpackedRgbImage<u16>,
+        dst: &mut [u16],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        interleave_rgb_naive(
+            unpacked_rgb_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
     }
 }
 
 impl RgbPackable<f32> for f32 {
-    fn unpack(src: &[f32], image_size: ImageSize) -> UnpackedRgbImage<f32> {
-        deinterleave_rgb_naive(src, image_size.width, image_size.height)
+    fn unpack(
+        src: &[f32],
+        src_stride: usize,
+        dst: &mut UnpackedRgbImage<f32>,
+        image_size: ImageSize,
+    ) {
+        deinterleave_rgb_naive(src, src_stride, dst, image_size.width, image_size.height)
+    }
+
+    fn pack(
+        unpacked_rgb_image: &UnpackedRgbImage<f32>,
+        dst: &mut [f32],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        interleave_rgb_naive(
+            unpacked_rgb_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
+    }
+}
+
+impl RgbPackable<f64> for f64 {
+    fn unpack(
+        src: &[f64],
+        src_stride: usize,
+        dst: &mut UnpackedRgbImage<f64>,
+        image_size: ImageSize,
+    ) {
+        deinterleave_rgb_naive(src, src_stride, dst, image_size.width, image_size.height)
     }
 
-    fn pack(unpacked_rgb_image: &UnpackedRgbImage<f32>, dst: &mut [f32], image_size: ImageSize) {
-        interleave_rgb_naive(unpacked_rgb_image, dst, image_size.width, image_size.height)
+    fn pack(
+        unpacked_rgb_image: &UnpackedRgbImage<f64>,
+        dst: &mut [f64],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        interleave_rgb_naive(
+            unpacked_rgb_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
     }
 }
 
 pub trait RgbaPackable<T> {
-    fn unpack(src: &[T], image_size: ImageSize) -> UnpackedRgbaImage<T>;
-    fn pack(unpacked_rgb_image: &UnpackedRgbaImage<T>, dst: &mut [T], image_size: ImageSize);
+    fn unpack(src: &[T], src_stride: usize, dst: &mut UnpackedRgbaImage<T>, image_size: ImageSize)
+    where
+        T: Copy + Default,
+    {
+        deinterleave_rgba_naive(src, src_stride, dst, image_size.width, image_size.height)
+    }
+
+    fn pack(
+        unpacked_rgb_image: &UnpackedRgbaImage<T>,
+        dst: &mut [T],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) where
+        T: Copy + Default,
+    {
+        interleave_rgba_naive(
+            unpacked_rgb_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
+    }
 }
 
 impl RgbaPackable<u8> for u8 {
-    fn pack(unpacked_rgb_image: &UnpackedRgbaImage<u8>, dst: &mut [u8], image_size: ImageSize) {
-        pack_rgba(unpacked_rgb_image, dst, image_size)
+    fn pack(
+        unpacked_rgb_image: &UnpackedRgbaImage<u8>,
+        dst: &mut [u8],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        pack_rgba(unpacked_rgb_image, dst, dst_stride, image_size)
     }
 
-    fn unpack(src: &[u8], image_size: ImageSize) -> UnpackedRgbaImage<u8> {
-        unpack_rgba(src, image_size)
+    fn unpack(
+        src: &[u8],
+        src_stride: usize,
+        dst: &mut UnpackedRgbaImage<u8>,
+        image_size: ImageSize,
+    ) {
+        unpack_rgba(src, src_stride, dst, image_size)
     }
 }
 
 impl RgbaPackable<u16> for u16 {
-    fn pack(unpacked_rgb_image: &UnpackedRgbaImage<u16>, dst: &mut [u16], image_size: ImageSize) {
-        interleave_rgba_naive(unpacked_rgb_image, dst, image_size.width, image_size.height)
+    fn pack(
+        unpacked_rgb_image: &UnpackedRgbaImage<u16>,
+        dst: &mut [u16],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        interleave_rgba_naive(
+            unpacked_rgb_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
     }
 
-    fn unpack(src: &[u16], image_size: ImageSize) -> UnpackedRgbaImage<u16> {
-        deinterleave_rgba_naive(src, image_size.width, image_size.height)
+    fn unpack(
+        src: &[u16],
+        src_stride: usize,
+        dst: &mut UnpackedRgbaImage<u16>,
+        image_size: ImageSize,
+    ) {
+        deinterleave_rgba_naive(src, src_stride, dst, image_size.width, image_size.height)
     }
 }
 
 impl RgbaPackable<f32> for f32 {
-    fn pack(unpacked_rgb_image: &UnpackedRgbaImage<f32>, dst: &mut [f32], image_size: ImageSize) {
-        interleave_rgba_naive(unpacked_rgb_image, dst, image_size.width, image_size.height)
+    fn pack(
+        unpacked_rgb_image: &UnpackedRgbaImage<f32>,
+        dst: &mut [f32],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        interleave_rgba_naive(
+            unpacked_rgb_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
     }
 
-    fn unpack(src: &[f32], image_size: ImageSize) -> UnpackedRgbaImage<f32> {
-        deinterleave_rgba_naive(src, image_size.width, image_size.height)
+    fn unpack(
+        src: &[f32],
+        src_stride: usize,
+        dst: &mut UnpackedRgbaImage<f32>,
+        image_size: ImageSize,
+    ) {
+        deinterleave_rgba_naive(src, src_stride, dst, image_size.width, image_size.height)
+    }
+}
+
+impl RgbaPackable<f64> for f64 {
+    fn pack(
+        unpacked_rgb_image: &UnpackedRgbaImage<f64>,
+        dst: &mut [f64],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        interleave_rgba_naive(
+            unpacked_rgb_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
+    }
+
+    fn unpack(
+        src: &[f64],
+        src_stride: usize,
+        dst: &mut UnpackedRgbaImage<f64>,
+        image_size: ImageSize,
+    ) {
+        deinterleave_rgba_naive(src, src_stride, dst, image_size.width, image_size.height)
     }
 }
 
 pub trait GrayAlphaPackable<T> {
-    fn unpack(src: &[T], image_size: ImageSize) -> UnpackedGrayAlpha<T>;
-    fn pack(unpacked_rgb_image: &UnpackedGrayAlpha<T>, dst: &mut [T], image_size: ImageSize);
+    fn unpack(src: &[T], src_stride: usize, dst: &mut UnpackedGrayAlpha<T>, image_size: ImageSize)
+    where
+        T: Copy + Default,
+    {
+        unpack_gray_alpha_naive(src, src_stride, dst, image_size.width, image_size.height)
+    }
+
+    fn pack(
+        unpacked_rgb_image: &UnpackedGrayAlpha<T>,
+        dst: &mut [T],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) where
+        T: Copy + Default,
+    {
+        pack_gray_alpha_naive(
+            unpacked_rgb_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
+    }
 }
 
 impl GrayAlphaPackable<u8> for u8 {
-    fn pack(unpacked_rgb_image: &UnpackedGrayAlpha<u8>, dst: &mut [u8], image_size: ImageSize) {
-        pack_gray_alpha_naive(unpacked_rgb_image, dst, image_size.width, image_size.height)
+    fn pack(
+        unpacked_rgb_image: &UnpackedGrayAlpha<u8>,
+        dst: &mut [u8],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        pack_gray_alpha_naive(
+            unpacked_rgb_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
     }
 
-    fn unpack(src: &[u8], image_size: ImageSize) -> UnpackedGrayAlpha<u8> {
-        unpack_gray_alpha_naive(src, image_size.width, image_size.height)
+    fn unpack(
+        src: &[u8],
+        src_stride: usize,
+        dst: &mut UnpackedGrayAlpha<u8>,
+        image_size: ImageSize,
+    ) {
+        unpack_gray_alpha_naive(src, src_stride, dst, image_size.width, image_size.height)
     }
 }
 
 impl GrayAlphaPackable<u16> for u16 {
-    fn pack(unpacked_rgb_image: &UnpackedGrayAlpha<u16>, dst: &mut [u16], image_size: ImageSize) {
-        pack_gray_alpha_naive(unpacked_rgb_image, dst, image_size.width, image_size.height)
+    fn pack(
+        unpacked_rgb_image: &UnpackedGrayAlpha<u16>,
+        dst: &mut [u16],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        pack_gray_alpha_naive(
+            unpacked_rgb_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
     }
 
-    fn unpack(src: &[u16], image_size: ImageSize) -> UnpackedGrayAlpha<u16> {
-        unpack_gray_alpha_naive(src, image_size.width, image_size.height)
+    fn unpack(
+        src: &[u16],
+        src_stride: usize,
+        dst: &mut UnpackedGrayAlpha<u16>,
+        image_size: ImageSize,
+    ) {
+        unpack_gray_alpha_naive(src, src_stride, dst, image_size.width, image_size.height)
     }
 }
 
 impl GrayAlphaPackable<f32> for f32 {
-    fn pack(unpacked_rgb_image: &UnpackedGrayAlpha<f32>, dst: &mut [f32], image_size: ImageSize) {
-        pack_gray_alpha_naive(unpacked_rgb_image, dst, image_size.width, image_size.height)
+    fn pack(
+        unpacked_rgb_image: &UnpackedGrayAlpha<f32>,
+        dst: &mut [f32],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        pack_gray_alpha_naive(
+            unpacked_rgb_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
+    }
+
+    fn unpack(
+        src: &[f32],
+        src_stride: usize,
+        dst: &mut UnpackedGrayAlpha<f32>,
+        image_size: ImageSize,
+    ) {
+        unpack_gray_alpha_naive(src, src_stride, dst, image_size.width, image_size.height)
+    }
+}
+
+impl GrayAlphaPackable<f64> for f64 {
+    fn pack(
+        unpacked_rgb_image: &UnpackedGrayAlpha<f64>,
+        dst: &mut [f64],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        pack_gray_alpha_naive(
+            unpacked_rgb_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
+    }
+
+    fn unpack(
+        src: &[f64],
+        src_stride: usize,
+        dst: &mut UnpackedGrayAlpha<f64>,
+        image_size: ImageSize,
+    ) {
+        unpack_gray_alpha_naive(src, src_stride, dst, image_size.width, image_size.height)
+    }
+}
+
+impl RgbPackable<i8> for i8 {
+    fn unpack(
+        src: &[i8],
+        src_stride: usize,
+        dst: &mut UnpackedRgbImage<i8>,
+        image_size: ImageSize,
+    ) {
+        deinterleave_rgb_naive(src, src_stride, dst, image_size.width, image_size.height)
+    }
+
+    fn pack(
+        unpacked_rgb_image: &UnpackedRgbImage<i8>,
+        dst: &mut [i8],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        interleave_rgb_naive(
+            unpacked_rgb_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
+    }
+}
+
+impl RgbaPackable<i8> for i8 {
+    fn pack(
+        unpacked_rgb_image: &UnpackedRgbaImage<i8>,
+        dst: &mut [i8],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        interleave_rgba_naive(
+            unpacked_rgb_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
+    }
+
+    fn unpack(
+        src: &[i8],
+        src_stride: usize,
+        dst: &mut UnpackedRgbaImage<i8>,
+        image_size: ImageSize,
+    ) {
+        deinterleave_rgba_naive(src, src_stride, dst, image_size.width, image_size.height)
+    }
+}
+
+impl GrayAlphaPackable<i8> for i8 {
+    fn pack(
+        unpacked_rgb_image: &UnpackedGrayAlpha<i8>,
+        dst: &mut [i8],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        pack_gray_alpha_naive(
+            unpacked_rgb_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
+    }
+
+    fn unpack(
+        src: &[i8],
+        src_stride: usize,
+        dst: &mut UnpackedGrayAlpha<i8>,
+        image_size: ImageSize,
+    ) {
+        unpack_gray_alpha_naive(src, src_stride, dst, image_size.width, image_size.height)
+    }
+}
+
+impl RgbPackable<i16> for i16 {
+    fn unpack(
+        src: &[i16],
+        src_stride: usize,
+        dst: &mut UnpackedRgbImage<i16>,
+        image_size: ImageSize,
+    ) {
+        deinterleave_rgb_naive(src, src_stride, dst, image_size.width, image_size.height)
+    }
+
+    fn pack(
+        unpacked_rgb_image: &UnpackedRgbImage<i16>,
+        dst: &mut [i16],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        interleave_rgb_naive(
+            unpacked_rgb_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
+    }
+}
+
+impl RgbaPackable<i16> for i16 {
+    fn pack(
+        unpacked_rgb_image: &UnpackedRgbaImage<i16>,
+        dst: &mut [i16],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        interleave_rgba_naive(
+            unpacked_rgb_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
+    }
+
+    fn unpack(
+        src: &[i16],
+        src_stride: usize,
+        dst: &mut UnpackedRgbaImage<i16>,
+        image_size: ImageSize,
+    ) {
+        deinterleave_rgba_naive(src, src_stride, dst, image_size.width, image_size.height)
+    }
+}
+
+impl GrayAlphaPackable<i16> for i16 {
+    fn pack(
+        unpacked_rgb_image: &UnpackedGrayAlpha<i16>,
+        dst: &mut [i16],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        pack_gray_alpha_naive(
+            unpacked_rgb_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
+    }
+
+    fn unpack(
+        src: &[i16],
+        src_stride: usize,
+        dst: &mut UnpackedGrayAlpha<i16>,
+        image_size: ImageSize,
+    ) {
+        unpack_gray_alpha_naive(src, src_stride, dst, image_size.width, image_size.height)
+    }
+}
+
+impl RgbPackable<i32> for i32 {
+    fn unpack(
+        src: &[i32],
+        src_stride: usize,
+        dst: &mut UnpackedRgbImage<i32>,
+        image_size: ImageSize,
+    ) {
+        deinterleave_rgb_naive(src, src_stride, dst, image_size.width, image_size.height)
+    }
+
+    fn pack(
+        unpacked_rgb_image: &UnpackedRgbImage<i32>,
+        dst: &mut [i32],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        interleave_rgb_naive(
+            unpacked_rgb_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
+    }
+}
+
+impl RgbaPackable<i32> for i32 {
+    fn pack(
+        unpacked_rgb_image: &UnpackedRgbaImage<i32>,
+        dst: &mut [i32],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        interleave_rgba_naive(
+            unpacked_rgb_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
+    }
+
+    fn unpack(
+        src: &[i32],
+        src_stride: usize,
+        dst: &mut UnpackedRgbaImage<i32>,
+        image_size: ImageSize,
+    ) {
+        deinterleave_rgba_naive(src, src_stride, dst, image_size.width, image_size.height)
+    }
+}
+
+impl GrayAlphaPackable<i32> for i32 {
+    fn pack(
+        unpacked_rgb_image: &UnpackedGrayAlpha<i32>,
+        dst: &mut [i32],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        pack_gray_alpha_naive(
+            unpacked_rgb_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
+    }
+
+    fn unpack(
+        src: &[i32],
+        src_stride: usize,
+        dst: &mut UnpackedGrayAlpha<i32>,
+        image_size: ImageSize,
+    ) {
+        unpack_gray_alpha_naive(src, src_stride, dst, image_size.width, image_size.height)
+    }
+}
+
+impl RgbPackable<u32> for u32 {
+    fn unpack(
+        src: &[u32],
+        src_stride: usize,
+        dst: &mut UnpackedRgbImage<u32>,
+        image_size: ImageSize,
+    ) {
+        deinterleave_rgb_naive(src, src_stride, dst, image_size.width, image_size.height)
+    }
+
+    fn pack(
+        unpacked_rgb_image: &UnpackedRgbImage<u32>,
+        dst: &mut [u32],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        interleave_rgb_naive(
+            unpacked_rgb_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
+    }
+}
+
+impl RgbaPackable<u32> for u32 {
+    fn pack(
+        unpacked_rgb_image: &UnpackedRgbaImage<u32>,
+        dst: &mut [u32],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        interleave_rgba_naive(
+            unpacked_rgb_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
+    }
+
+    fn unpack(
+        src: &[u32],
+        src_stride: usize,
+        dst: &mut UnpackedRgbaImage<u32>,
+        image_size: ImageSize,
+    ) {
+        deinterleave_rgba_naive(src, src_stride, dst, image_size.width, image_size.height)
+    }
+}
+
+impl GrayAlphaPackable<u32> for u32 {
+    fn pack(
+        unpacked_rgb_image: &UnpackedGrayAlpha<u32>,
+        dst: &mut [u32],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        pack_gray_alpha_naive(
+            unpacked_rgb_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
+    }
+
+    fn unpack(
+        src: &[u32],
+        src_stride: usize,
+        dst: &mut UnpackedGrayAlpha<u32>,
+        image_size: ImageSize,
+    ) {
+        unpack_gray_alpha_naive(src, src_stride, dst, image_size.width, image_size.height)
+    }
+}
+
+#[cfg(feature = "half")]
+impl RgbPackable<f16> for f16 {
+    fn unpack(
+        src: &[f16],
+        src_stride: usize,
+        dst: &mut UnpackedRgbImage<f16>,
+        image_size: ImageSize,
+    ) {
+        deinterleave_rgb_naive(src, src_stride, dst, image_size.width, image_size.height)
+    }
+
+    fn pack(
+        unpacked_rgb_image: &UnpackedRgbImage<f16>,
+        dst: &mut [f16],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        interleave_rgb_naive(
+            unpacked_rgb_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
+    }
+}
+
+#[cfg(feature = "half")]
+impl RgbaPackable<f16> for f16 {
+    fn pack(
+        unpacked_rgb_image: &UnpackedRgbaImage<f16>,
+        dst: &mut [f16],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        interleave_rgba_naive(
+            unpacked_rgb_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
+    }
+
+    fn unpack(
+        src: &[f16],
+        src_stride: usize,
+        dst: &mut UnpackedRgbaImage<f16>,
+        image_size: ImageSize,
+    ) {
+        deinterleave_rgba_naive(src, src_stride, dst, image_size.width, image_size.height)
+    }
+}
+
+#[cfg(feature = "half")]
+impl GrayAlphaPackable<f16> for f16 {
+    fn pack(
+        unpacked_rgb_image: &UnpackedGrayAlpha<f16>,
+        dst: &mut [f16],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        pack_gray_alpha_naive(
+            unpacked_rgb_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
+    }
+
+    fn unpack(
+        src: &[f16],
+        src_stride: usize,
+        dst: &mut UnpackedGrayAlpha<f16>,
+        image_size: ImageSize,
+    ) {
+        unpack_gray_alpha_naive(src, src_stride, dst, image_size.width, image_size.height)
+    }
+}
+
+/// Packing of interleaved images with any count of channels, built-in types move
+/// channels by SIMD where it beats a scalar loop
+pub trait InterleavedPackable<T> {
+    fn unpack(src: &[T], src_stride: usize, dst: &mut UnpackedInterleaved<T>, image_size: ImageSize)
+    where
+        T: Copy + Default,
+    {
+        deinterleave_naive(src, src_stride, dst, image_size.width, image_size.height)
+    }
+
+    fn pack(
+        unpacked_image: &UnpackedInterleaved<T>,
+        dst: &mut [T],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) where
+        T: Copy + Default,
+    {
+        interleave_naive(
+            unpacked_image,
+            dst,
+            dst_stride,
+            image_size.width,
+            image_size.height,
+        )
+    }
+}
+
+impl InterleavedPackable<u8> for u8 {
+    fn unpack(
+        src: &[u8],
+        src_stride: usize,
+        dst: &mut UnpackedInterleaved<u8>,
+        image_size: ImageSize,
+    ) {
+        unpack_interleaved(src, src_stride, dst, image_size)
+    }
+
+    fn pack(
+        unpacked_image: &UnpackedInterleaved<u8>,
+        dst: &mut [u8],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        pack_interleaved(unpacked_image, dst, dst_stride, image_size)
+    }
+}
+
+impl InterleavedPackable<i8> for i8 {
+    fn unpack(
+        src: &[i8],
+        src_stride: usize,
+        dst: &mut UnpackedInterleaved<i8>,
+        image_size: ImageSize,
+    ) {
+        unpack_interleaved(src, src_stride, dst, image_size)
+    }
+
+    fn pack(
+        unpacked_image: &UnpackedInterleaved<i8>,
+        dst: &mut [i8],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        pack_interleaved(unpacked_image, dst, dst_stride, image_size)
+    }
+}
+
+impl InterleavedPackable<u16> for u16 {
+    fn unpack(
+        src: &[u16],
+        src_stride: usize,
+        dst: &mut UnpackedInterleaved<u16>,
+        image_size: ImageSize,
+    ) {
+        unpack_interleaved(src, src_stride, dst, image_size)
+    }
+
+    fn pack(
+        unpacked_image: &UnpackedInterleaved<u16>,
+        dst: &mut [u16],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        pack_interleaved(unpacked_image, dst, dst_stride, image_size)
+    }
+}
+
+impl InterleavedPackable<i16> for i16 {
+    fn unpack(
+        src: &[i16],
+        src_stride: usize,
+        dst: &mut UnpackedInterleaved<i16>,
+        image_size: ImageSize,
+    ) {
+        unpack_interleaved(src, src_stride, dst, image_size)
+    }
+
+    fn pack(
+        unpacked_image: &UnpackedInterleaved<i16>,
+        dst: &mut [i16],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        pack_interleaved(unpacked_image, dst, dst_stride, image_size)
+    }
+}
+
+impl InterleavedPackable<u32> for u32 {
+    fn unpack(
+        src: &[u32],
+        src_stride: usize,
+        dst: &mut UnpackedInterleaved<u32>,
+        image_size: ImageSize,
+    ) {
+        unpack_interleaved(src, src_stride, dst, image_size)
+    }
+
+    fn pack(
+        unpacked_image: &UnpackedInterleaved<u32>,
+        dst: &mut [u32],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        pack_interleaved(unpacked_image, dst, dst_stride, image_size)
+    }
+}
+
+impl InterleavedPackable<i32> for i32 {
+    fn unpack(
+        src: &[i32],
+        src_stride: usize,
+        dst: &mut UnpackedInterleaved<i32>,
+        image_size: ImageSize,
+    ) {
+        unpack_interleaved(src, src_stride, dst, image_size)
+    }
+
+    fn pack(
+        unpacked_image: &UnpackedInterleaved<i32>,
+        dst: &mut [i32],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        pack_interleaved(unpacked_image, dst, dst_stride, image_size)
+    }
+}
+
+impl InterleavedPackable<f32> for f32 {
+    fn unpack(
+        src: &[f32],
+        src_stride: usize,
+        dst: &mut UnpackedInterleaved<f32>,
+        image_size: ImageSize,
+    ) {
+        unpack_interleaved(src, src_stride, dst, image_size)
+    }
+
+    fn pack(
+        unpacked_image: &UnpackedInterleaved<f32>,
+        dst: &mut [f32],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        pack_interleaved(unpacked_image, dst, dst_stride, image_size)
+    }
+}
+
+impl InterleavedPackable<f64> for f64 {
+    fn unpack(
+        src: &[f64],
+        src_stride: usize,
+        dst: &mut UnpackedInterleaved<f64>,
+        image_size: ImageSize,
+    ) {
+        unpack_interleaved(src, src_stride, dst, image_size)
+    }
+
+    fn pack(
+        unpacked_image: &UnpackedInterleaved<f64>,
+        dst: &mut [f64],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        pack_interleaved(unpacked_image, dst, dst_stride, image_size)
+    }
+}
+
+#[cfg(feature = "half")]
+impl InterleavedPackable<f16> for f16 {
+    fn unpack(
+        src: &[f16],
+        src_stride: usize,
+        dst: &mut UnpackedInterleaved<f16>,
+        image_size: ImageSize,
+    ) {
+        unpack_interleaved(src, src_stride, dst, image_size)
     }
 
-    fn unpack(src: &[f32], image_size: ImageSize) -> UnpackedGrayAlpha<f32> {
-        unpack_gray_alpha_naive(src, image_size.width, image_size.height)
+    fn pack(
+        unpacked_image: &UnpackedInterleaved<f16>,
+        dst: &mut [f16],
+        dst_stride: usize,
+        image_size: ImageSize,
+    ) {
+        pack_interleaved(unpacked_image, dst, dst_stride, image_size)
     }
 }
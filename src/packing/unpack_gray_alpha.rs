@@ -30,30 +30,33 @@ use crate::packing::UnpackedGrayAlpha;
 
 pub fn unpack_gray_alpha_naive<T>(
     rgb_image: &[T],
+    stride: usize,
+    unpacked_image: &mut UnpackedGrayAlpha<T>,
     width: usize,
     height: usize,
-) -> UnpackedGrayAlpha<T>
-where
+) where
     T: Copy + Default,
 {
-    if rgb_image.len() != width * height * 2 {
+    if rgb_image.len() < stride * (height - 1) + width * 2 {
         panic!(
             "Image bounds in deinterleave_gray_alpha_naive is mismatched! Expected {} but got {}",
-            width * height * 2,
+            stride * (height - 1) + width * 2,
             rgb_image.len()
         );
     }
-    let mut r_chan = vec![T::default(); width * height];
-    let mut g_chan = vec![T::default(); width * height];
 
     for ((src, r), g) in rgb_image
-        .chunks_exact(2)
-        .zip(r_chan.iter_mut())
-        .zip(g_chan.iter_mut())
+        .chunks(stride)
+        .zip(unpacked_image.gray_channel.chunks_exact_mut(width))
+        .zip(unpacked_image.alpha_channel.chunks_exact_mut(width))
     {
-        *r = src[0];
-        *g = src[1];
+        for ((src, r), g) in src[..width * 2]
+            .chunks_exact(2)
+            .zip(r.iter_mut())
+            .zip(g.iter_mut())
+        {
+            *r = src[0];
+            *g = src[1];
+        }
     }
-
-    UnpackedGrayAlpha::new(r_chan, g_chan)
 }
@@ -0,0 +1,109 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use crate::packing::avx::deinterleave_avx;
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+use crate::packing::neon::deinterleave_neon;
+use crate::packing::shuffle_masks::shuffles_beat_scalar;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use crate::packing::sse::deinterleave_sse;
+use crate::packing::{PlainItem, UnpackedInterleaved};
+use crate::ImageSize;
+use std::mem::size_of;
+
+pub fn deinterleave_naive<T>(
+    image: &[T],
+    stride: usize,
+    unpacked_image: &mut UnpackedInterleaved<T>,
+    width: usize,
+    height: usize,
+) where
+    T: Copy,
+{
+    let channels = unpacked_image.channels();
+    if image.len() < stride * (height - 1) + width * channels {
+        panic!(
+            "Image bounds in deinterleave_naive is mismatched! Expected {} but got {}",
+            stride * (height - 1) + width * channels,
+            image.len()
+        );
+    }
+
+    for (y, src) in image.chunks(stride).take(height).enumerate() {
+        let src = &src[..width * channels];
+        for (channel, plane) in unpacked_image.planes.iter_mut().enumerate() {
+            for (dst, src) in plane[y * width..(y + 1) * width]
+                .iter_mut()
+                .zip(src.chunks_exact(channels))
+            {
+                *dst = src[channel];
+            }
+        }
+    }
+}
+
+/// Deinterleaves 2, 3 and 4 channels with NEON structure loads and stores, and up to 16
+/// channels of 1 and 2 byte items with AVX2, SSE4.1 or NEON byte shuffles where they
+/// beat [deinterleave_naive], other counts and types use [deinterleave_naive]
+pub fn unpack_interleaved<T: PlainItem>(
+    image: &[T],
+    stride: usize,
+    unpacked_image: &mut UnpackedInterleaved<T>,
+    image_size: ImageSize,
+) {
+    let mut _dispatcher: fn(&[T], usize, &mut UnpackedInterleaved<T>, usize, usize) =
+        deinterleave_naive;
+    let channels = unpacked_image.channels();
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        if (2..=4).contains(&channels) || shuffles_beat_scalar(channels, size_of::<T>(), 1) {
+            _dispatcher = deinterleave_neon;
+        }
+    }
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if shuffles_beat_scalar(channels, size_of::<T>(), 1)
+            && std::arch::is_x86_feature_detected!("sse4.1")
+        {
+            _dispatcher = deinterleave_sse;
+        }
+        if shuffles_beat_scalar(channels, size_of::<T>(), 2)
+            && std::arch::is_x86_feature_detected!("avx2")
+        {
+            _dispatcher = deinterleave_avx;
+        }
+    }
+    _dispatcher(
+        image,
+        stride,
+        unpacked_image,
+        image_size.width,
+        image_size.height,
+    )
+}
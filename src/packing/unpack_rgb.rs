@@ -37,39 +37,48 @@ use crate::ImageSize;
 
 pub fn deinterleave_rgb_naive<T>(
     rgb_image: &[T],
+    stride: usize,
+    unpacked_image: &mut UnpackedRgbImage<T>,
     width: usize,
     height: usize,
-) -> UnpackedRgbImage<T>
-where
+) where
     T: Copy + Default,
 {
-    if rgb_image.len() != width * height * 3 {
+    if rgb_image.len() < stride * (height - 1) + width * 3 {
         panic!(
-            "Image bounds in deinterleave_rgba_neon is mismatched! Expected {} but got {}",
-            width * height * 3,
+            "Image bounds in deinterleave_rgb_naive is mismatched! Expected {} but got {}",
+            stride * (height - 1) + width * 3,
             rgb_image.len()
         );
     }
-    let mut r_chan = vec![T::default(); width * height];
-    let mut g_chan = vec![T::default(); width * height];
-    let mut b_chan = vec![T::default(); width * height];
 
     for (((src, r), g), b) in rgb_image
-        .chunks_exact(3)
-        .zip(r_chan.iter_mut())
-        .zip(g_chan.iter_mut())
-        .zip(b_chan.iter_mut())
+        .chunks(stride)
+        .zip(unpacked_image.r_channel.chunks_exact_mut(width))
+        .zip(unpacked_image.g_channel.chunks_exact_mut(width))
+        .zip(unpacked_image.b_channel.chunks_exact_mut(width))
     {
-        *r = src[0];
-        *g = src[1];
-        *b = src[2];
+        for (((src, r), g), b) in src[..width * 3]
+            .chunks_exact(3)
+            .zip(r.iter_mut())
+            .zip(g.iter_mut())
+            .zip(b.iter_mut())
+        {
+            *r = src[0];
+            *g = src[1];
+            *b = src[2];
+        }
     }
-
-    UnpackedRgbImage::new(r_chan, g_chan, b_chan)
 }
 
-pub fn unpack_rgb(rgb_image: &[u8], image_size: ImageSize) -> UnpackedRgbImage<u8> {
-    let mut _dispatcher: fn(&[u8], usize, usize) -> UnpackedRgbImage<u8> = deinterleave_rgb_naive;
+pub fn unpack_rgb(
+    rgb_image: &[u8],
+    stride: usize,
+    unpacked_image: &mut UnpackedRgbImage<u8>,
+    image_size: ImageSize,
+) {
+    let mut _dispatcher: fn(&[u8], usize, &mut UnpackedRgbImage<u8>, usize, usize) =
+        deinterleave_rgb_naive;
     #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
     {
         _dispatcher = deinterleave_rgb_neon;
@@ -83,5 +92,11 @@ pub fn unpack_rgb(rgb_image: &[u8], image_size: ImageSize) -> UnpackedRgbImage<u
             _dispatcher = deinterleave_rgb_avx;
         }
     }
-    _dispatcher(rgb_image, image_size.width, image_size.height)
+    _dispatcher(
+        rgb_image,
+        stride,
+        unpacked_image,
+        image_size.width,
+        image_size.height,
+    )
 }
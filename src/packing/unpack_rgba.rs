@@ -37,42 +37,51 @@ use crate::ImageSize;
 
 pub fn deinterleave_rgba_naive<T>(
     rgb_image: &[T],
+    stride: usize,
+    unpacked_image: &mut UnpackedRgbaImage<T>,
     width: usize,
     height: usize,
-) -> UnpackedRgbaImage<T>
-where
+) where
     T: Copy + Default,
 {
-    if rgb_image.len() != width * height * 4 {
+    if rgb_image.len() < stride * (height - 1) + width * 4 {
         panic!(
-            "Image bounds in deinterleave_rgba_neon is mismatched! Expected {} but got {}",
-            width * height * 4,
+            "Image bounds in deinterleave_rgba_naive is mismatched! Expected {} but got {}",
+            stride * (height - 1) + width * 4,
             rgb_image.len()
         );
     }
-    let mut r_chan = vec![T::default(); width * height];
-    let mut g_chan = vec![T::default(); width * height];
-    let mut b_chan = vec![T::default(); width * height];
-    let mut a_chan = vec![T::default(); width * height];
 
     for ((((src, r), g), b), a) in rgb_image
-        .chunks_exact(4)
-        .zip(r_chan.iter_mut())
-        .zip(g_chan.iter_mut())
-        .zip(b_chan.iter_mut())
-        .zip(a_chan.iter_mut())
+        .chunks(stride)
+        .zip(unpacked_image.r_channel.chunks_exact_mut(width))
+        .zip(unpacked_image.g_channel.chunks_exact_mut(width))
+        .zip(unpacked_image.b_channel.chunks_exact_mut(width))
+        .zip(unpacked_image.a_channel.chunks_exact_mut(width))
     {
-        *r = src[0];
-        *g = src[1];
-        *b = src[2];
-        *a = src[3];
+        for ((((src, r), g), b), a) in src[..width * 4]
+            .chunks_exact(4)
+            .zip(r.iter_mut())
+            .zip(g.iter_mut())
+            .zip(b.iter_mut())
+            .zip(a.iter_mut())
+        {
+            *r = src[0];
+            *g = src[1];
+            *b = src[2];
+            *a = src[3];
+        }
     }
-
-    UnpackedRgbaImage::new(r_chan, g_chan, b_chan, a_chan)
 }
 
-pub fn unpack_rgba(rgb_image: &[u8], image_size: ImageSize) -> UnpackedRgbaImage<u8> {
-    let mut _dispatcher: fn(&[u8], usize, usize) -> UnpackedRgbaImage<u8> = deinterleave_rgba_naive;
+pub fn unpack_rgba(
+    rgb_image: &[u8],
+    stride: usize,
+    unpacked_image: &mut UnpackedRgbaImage<u8>,
+    image_size: ImageSize,
+) {
+    let mut _dispatcher: fn(&[u8], usize, &mut UnpackedRgbaImage<u8>, usize, usize) =
+        deinterleave_rgba_naive;
     #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
     {
         _dispatcher = deinterleave_rgba_neon;
@@ -86,5 +95,11 @@ pub fn unpack_rgba(rgb_image: &[u8], image_size: ImageSize) -> UnpackedRgbaImage
             _dispatcher = deinterleave_rgba_avx;
         }
     }
-    _dispatcher(rgb_image, image_size.width, image_size.height)
+    _dispatcher(
+        rgb_image,
+        stride,
+        unpacked_image,
+        image_size.width,
+        image_size.height,
+    )
 }
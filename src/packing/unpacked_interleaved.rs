@@ -0,0 +1,80 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::ImageSize;
+#[cfg(feature = "half")]
+use half::f16;
+
+/// Number types without padding bytes, their interleaved images are moved as bytes by SIMD
+pub trait PlainItem: Copy + Default {}
+
+impl PlainItem for u8 {}
+
+impl PlainItem for i8 {}
+
+impl PlainItem for u16 {}
+
+impl PlainItem for i16 {}
+
+impl PlainItem for u32 {}
+
+impl PlainItem for i32 {}
+
+impl PlainItem for f32 {}
+
+impl PlainItem for f64 {}
+
+#[cfg(feature = "half")]
+impl PlainItem for f16 {}
+
+/// Planes of an interleaved image with any count of channels
+#[derive(Clone, Debug)]
+pub struct UnpackedInterleaved<T> {
+    pub planes: Vec<Vec<T>>,
+}
+
+impl<T> UnpackedInterleaved<T> {
+    pub fn new(planes: Vec<Vec<T>>) -> UnpackedInterleaved<T> {
+        UnpackedInterleaved { planes }
+    }
+
+    /// Count of interleaved channels
+    pub fn channels(&self) -> usize {
+        self.planes.len()
+    }
+}
+
+impl<T> UnpackedInterleaved<T>
+where
+    T: Default + Clone,
+{
+    pub fn alloc(image_size: ImageSize, channels: usize) -> UnpackedInterleaved<T> {
+        let plane_size = image_size.height * image_size.width;
+        UnpackedInterleaved::new(vec![vec![T::default(); plane_size]; channels])
+    }
+}
@@ -0,0 +1,322 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::filter_op_declare::Arena;
+use crate::flat_se::PeriodicLine;
+use crate::morph_base::MorphNativeOp;
+use crate::morph_rows::MorphRowsHandler;
+use crate::thread_policy::ThreadingPool;
+use crate::transpose::TransposeHandler;
+use crate::unsafe_slice::UnsafeSlice;
+use crate::van_herk::{morph_rows_window, RowScratch};
+use rayon::prelude::*;
+
+/// Computes `dst[x] = op(a[x], b[x + shift])`, where `b[x + shift]` is out of the row
+/// `a[x]` is taken, such items only appear in windows that leave the arena.
+fn shifted_rows_op<T: Copy>(
+    a: &[T],
+    b: &[T],
+    shift: isize,
+    dst: &mut [T],
+    rows_op: MorphRowsHandler<T>,
+) {
+    let width = a.len() as isize;
+    let start = (-shift).clamp(0, width) as usize;
+    let end = (width - shift).clamp(0, width) as usize;
+    if start < end {
+        let b_start = (start as isize + shift) as usize;
+        rows_op(
+            &a[start..end],
+            &b[b_start..b_start + end - start],
+            &mut dst[start..end],
+        );
+    } else {
+        dst.copy_from_slice(a);
+        return;
+    }
+    dst[..start].copy_from_slice(&a[..start]);
+    dst[end..].copy_from_slice(&a[end..]);
+}
+
+/// Rows that belong to the same periodic line, every `step` rows
+#[derive(Copy, Clone)]
+struct RowsSequence {
+    first: usize,
+    step: usize,
+    length: usize,
+}
+
+impl RowsSequence {
+    fn row(&self, i: usize) -> usize {
+        self.first + i * self.step
+    }
+}
+
+/// Van Herk/Gil-Werman blocks of `count` rows in all sequences of a buffer
+fn line_blocks(
+    height: usize,
+    step: usize,
+    count: usize,
+) -> impl Iterator<Item = (RowsSequence, usize)> {
+    (0..step.min(height)).flat_map(move |first| {
+        let sequence = RowsSequence {
+            first,
+            step,
+            length: (height - first).div_ceil(step),
+        };
+        (0..sequence.length.div_ceil(count)).map(move |block| (sequence, block))
+    })
+}
+
+/// Buffers of one task, allocated once and reused between runs
+pub(crate) struct PeriodicScratch<T> {
+    row: RowScratch<T>,
+    prefix: Vec<T>,
+    suffix: Vec<T>,
+}
+
+impl<T: Copy + Default> PeriodicScratch<T> {
+    /// Prefix and suffix hold `count` rows of the arena for the longest line across rows,
+    /// so their size grows with the line length.
+    pub(crate) fn new(arena_width: usize, lines: &[PeriodicLine]) -> Self {
+        let count = lines
+            .iter()
+            .filter(|x| x.step.y != 0)
+            .map(|x| x.count)
+            .max()
+            .unwrap_or(0);
+        let window = lines
+            .iter()
+            .filter(|x| x.step.y == 0)
+            .map(|x| x.count.min(arena_width))
+            .max()
+            .unwrap_or(1);
+        PeriodicScratch {
+            row: RowScratch::new(arena_width, window),
+            prefix: vec![T::default(); count * arena_width],
+            suffix: vec![T::default(); count * arena_width],
+        }
+    }
+}
+
+/// Processes one van Herk/Gil-Werman block of the sequence, rows `block * count..`.
+///
+/// Suffix of the block is stored in the frame of its own row, prefix of the next block
+/// in the frame of the last row of the window, so each step shifts previous row by the
+/// line step, and output row `i` is `op(suffix[i], prefix[i + count - 1])`.
+///
+/// # Safety
+///
+/// Only this call writes to rows of the block in `dst`.
+#[allow(clippy::too_many_arguments)]
+unsafe fn periodic_line_block<T: Copy>(
+    src: &[T],
+    dst: &UnsafeSlice<T>,
+    width: usize,
+    sequence: RowsSequence,
+    block: usize,
+    line: PeriodicLine,
+    prefix: &mut [T],
+    suffix: &mut [T],
+    rows_op: MorphRowsHandler<T>,
+) {
+    let count = line.count;
+    let shift = line.step.x as isize;
+    let src_row = |i: usize| &src[sequence.row(i) * width..(sequence.row(i) + 1) * width];
+
+    let start = block * count;
+    let end = (start + count).min(sequence.length);
+    let next_end = (end + count).min(sequence.length);
+
+    let last = (end - start - 1) * width;
+    suffix[last..last + width].copy_from_slice(src_row(end - 1));
+    for i in (start..end - 1).rev() {
+        let (current, previous) = suffix.split_at_mut((i - start + 1) * width);
+        shifted_rows_op(
+            src_row(i),
+            &previous[..width],
+            shift,
+            &mut current[(i - start) * width..],
+            rows_op,
+        );
+    }
+
+    if end < next_end {
+        prefix[..width].copy_from_slice(src_row(end));
+    }
+    for i in end + 1..next_end {
+        let (previous, current) = prefix.split_at_mut((i - end) * width);
+        shifted_rows_op(
+            src_row(i),
+            &previous[(i - end - 1) * width..],
+            -shift,
+            &mut current[..width],
+            rows_op,
+        );
+    }
+
+    let last_shift = shift * (count as isize - 1);
+    for i in start..end {
+        let dst = std::slice::from_raw_parts_mut(dst.mut_ptr().add(sequence.row(i) * width), width);
+        let suffix = &suffix[(i - start) * width..(i - start + 1) * width];
+        let window_end = i + count - 1;
+        // Window that starts the block is its suffix, window that leaves the buffer is unused
+        if i == start || window_end >= sequence.length {
+            dst.copy_from_slice(suffix);
+        } else {
+            let prefix = &prefix[(window_end - end) * width..(window_end - end + 1) * width];
+            shifted_rows_op(suffix, prefix, last_shift, dst, rows_op);
+        }
+    }
+}
+
+/// Computes `op(src[p + k * step]), 0 <= k < count` for each point `p` of the buffer.
+///
+/// Lines that cross rows use van Herk/Gil-Werman algorithm over rows, horizontal ones over
+/// transposed tiles of rows with [morph_rows_window], so count of comparisons per point of both
+/// does not depend on `count`. Lines across rows go through `count` rows of prefix and suffix
+/// per block, so for long lines these leave the cache and time still grows with `count`.
+///
+/// Points which window leaves the buffer receive values that must not be used.
+fn periodic_line_pass<T, const OP_TYPE: u8>(
+    src: &[T],
+    dst: &mut [T],
+    width: usize,
+    line: PeriodicLine,
+    rows_op: MorphRowsHandler<T>,
+    transpose: TransposeHandler<T>,
+    pool: &ThreadingPool,
+    scratch: &mut [PeriodicScratch<T>],
+) where
+    T: Copy + Default + Send + Sync + MorphNativeOp<T>,
+{
+    let height = src.len() / width;
+    let count = line.count;
+
+    if line.step.y == 0 {
+        // Horizontal lines are reduced along the rows of the whole chunk at once, only unit step
+        // exists after decomposition
+        let window = count.min(width);
+        let rows = height.div_ceil(scratch.len()).max(1);
+        let valid = width - window + 1;
+        let worker = |((src, dst), scratch): ((&[T], &mut [T]), &mut PeriodicScratch<T>)| {
+            morph_rows_window(
+                src,
+                width,
+                dst,
+                width,
+                valid,
+                window,
+                &mut scratch.row,
+                rows_op,
+                transpose,
+            );
+            for (src, dst) in src.chunks_exact(width).zip(dst.chunks_exact_mut(width)) {
+                dst[valid..].copy_from_slice(&src[valid..]);
+            }
+        };
+        if pool.is_parallel() {
+            pool.install(|| {
+                src.par_chunks(rows * width)
+                    .zip(dst.par_chunks_mut(rows * width))
+                    .zip(scratch.par_iter_mut())
+                    .for_each(worker);
+            });
+        } else {
+            src.chunks(rows * width)
+                .zip(dst.chunks_mut(rows * width))
+                .zip(scratch.iter_mut())
+                .for_each(worker);
+        }
+        return;
+    }
+
+    let step_y = line.step.y as usize;
+
+    let blocks = line_blocks(height, step_y, count).count();
+    let per_task = blocks.div_ceil(scratch.len()).max(1);
+
+    let dst = UnsafeSlice::new(dst);
+    let worker = |(task, scratch): (usize, &mut PeriodicScratch<T>)| {
+        for (sequence, block) in line_blocks(height, step_y, count)
+            .skip(task * per_task)
+            .take(per_task)
+        {
+            unsafe {
+                periodic_line_block(
+                    src,
+                    &dst,
+                    width,
+                    sequence,
+                    block,
+                    line,
+                    &mut scratch.prefix,
+                    &mut scratch.suffix,
+                    rows_op,
+                );
+            }
+        }
+    };
+
+    if pool.is_parallel() {
+        pool.install(|| scratch.par_iter_mut().enumerate().for_each(worker));
+    } else {
+        scratch.iter_mut().enumerate().for_each(worker);
+    }
+}
+
+/// Applies periodic lines one after another over the whole arena,
+/// so the remaining part of the structuring element can be applied to the result
+/// as to the usual arena.
+///
+/// `spare` must have the size of the arena, it receives the previous content.
+pub(crate) fn apply_periodic_lines<T, const OP_TYPE: u8>(
+    arena: &mut Arena<T>,
+    spare: &mut Vec<T>,
+    lines: &[PeriodicLine],
+    pool: &ThreadingPool,
+    scratch: &mut [PeriodicScratch<T>],
+    rows_op: MorphRowsHandler<T>,
+    transpose: TransposeHandler<T>,
+) where
+    T: Copy + Default + Send + Sync + MorphNativeOp<T>,
+{
+    for &line in lines {
+        periodic_line_pass::<T, OP_TYPE>(
+            &arena.arena,
+            spare,
+            arena.width,
+            line,
+            rows_op,
+            transpose,
+            pool,
+            scratch,
+        );
+        std::mem::swap(&mut arena.arena, spare);
+    }
+}
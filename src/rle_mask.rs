@@ -0,0 +1,235 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::error::MorphologyError;
+use crate::op_impl::{check_image_size, check_packed_image, check_strided_image};
+use crate::ImageSize;
+
+/// Set pixels `start..end` of a mask row
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct MaskRun {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl MaskRun {
+    pub fn new(start: usize, end: usize) -> MaskRun {
+        MaskRun { start, end }
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+}
+
+/// Binary mask stored as runs of set pixels.
+///
+/// Runs of every row are sorted, non-empty and separated by at least one clear pixel,
+/// so storage grows with the count of runs rather than with the image area.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RleMask {
+    size: ImageSize,
+    runs: Vec<MaskRun>,
+    /// Runs of row `y` are `runs[row_offsets[y]..row_offsets[y + 1]]`
+    row_offsets: Vec<usize>,
+}
+
+impl RleMask {
+    /// Creates a mask with every pixel clear
+    pub fn new(image_size: ImageSize) -> Self {
+        RleMask {
+            size: image_size,
+            runs: Vec::new(),
+            row_offsets: vec![0; image_size.height + 1],
+        }
+    }
+
+    /// Encodes a tightly packed one byte per pixel mask, any non-zero value is set
+    pub fn from_mask(mask: &[u8], image_size: ImageSize) -> Result<Self, MorphologyError> {
+        check_packed_image(mask, image_size, 1)?;
+        Self::from_mask_with_stride(mask, image_size.width, image_size)
+    }
+
+    /// Encodes a one byte per pixel mask with rows `stride` bytes apart, any non-zero value is set
+    pub fn from_mask_with_stride(
+        mask: &[u8],
+        stride: usize,
+        image_size: ImageSize,
+    ) -> Result<Self, MorphologyError> {
+        check_strided_image(mask, stride, image_size, 1)?;
+        let mut runs = Vec::new();
+        let mut row_offsets = Vec::with_capacity(image_size.height + 1);
+        row_offsets.push(0);
+        for row in mask.chunks(stride).take(image_size.height) {
+            let row = &row[..image_size.width];
+            let mut x = 0usize;
+            while let Some(start) = row[x..].iter().position(|&v| v != 0) {
+                let start = x + start;
+                let end = row[start..]
+                    .iter()
+                    .position(|&v| v == 0)
+                    .map_or(row.len(), |end| start + end);
+                runs.push(MaskRun::new(start, end));
+                x = end;
+            }
+            row_offsets.push(runs.len());
+        }
+        Ok(RleMask {
+            size: image_size,
+            runs,
+            row_offsets,
+        })
+    }
+
+    /// Decodes into a tightly packed one byte per pixel mask, set pixels become 255
+    pub fn to_mask(&self, dst: &mut [u8]) -> Result<(), MorphologyError> {
+        check_packed_image(dst, self.size, 1)?;
+        self.to_mask_with_stride(dst, self.size.width)
+    }
+
+    /// Decodes into a one byte per pixel mask with rows `stride` bytes apart,
+    /// set pixels become 255, bytes between rows are left untouched
+    pub fn to_mask_with_stride(
+        &self,
+        dst: &mut [u8],
+        stride: usize,
+    ) -> Result<(), MorphologyError> {
+        check_strided_image(dst, stride, self.size, 1)?;
+        for (y, dst_row) in dst.chunks_mut(stride).take(self.size.height).enumerate() {
+            let dst_row = &mut dst_row[..self.size.width];
+            dst_row.fill(0);
+            for run in self.row(y) {
+                dst_row[run.start..run.end].fill(255);
+            }
+        }
+        Ok(())
+    }
+
+    /// Builds a mask from the runs of every row, runs may overlap or touch
+    /// and are merged, runs past the image width are clipped
+    ///
+    /// # Panics
+    ///
+    /// When count of rows is not equal to the image height
+    pub fn from_rows<R>(image_size: ImageSize, rows: impl IntoIterator<Item = R>) -> Self
+    where
+        R: AsRef<[MaskRun]>,
+    {
+        let mut runs = Vec::new();
+        let mut row_offsets = vec![0];
+        let mut row_runs = Vec::new();
+        for row in rows {
+            row_runs.clear();
+            row_runs.extend(row.as_ref().iter().filter_map(|run| {
+                let end = run.end.min(image_size.width);
+                (run.start < end).then(|| MaskRun::new(run.start, end))
+            }));
+            merge_runs(&mut row_runs);
+            runs.extend_from_slice(&row_runs);
+            row_offsets.push(runs.len());
+        }
+        assert_eq!(row_offsets.len(), image_size.height + 1);
+        RleMask {
+            size: image_size,
+            runs,
+            row_offsets,
+        }
+    }
+
+    pub fn size(&self) -> ImageSize {
+        self.size
+    }
+
+    /// Runs of the row `y`
+    pub fn row(&self, y: usize) -> &[MaskRun] {
+        &self.runs[self.row_offsets[y]..self.row_offsets[y + 1]]
+    }
+
+    /// Runs of every row, row after row
+    pub fn runs(&self) -> &[MaskRun] {
+        &self.runs
+    }
+
+    /// # Panics
+    ///
+    /// When the pixel is outside of the image
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        assert!(x < self.size.width && y < self.size.height);
+        run_contains(self.row(y), x)
+    }
+
+    /// Count of set pixels
+    pub fn count_ones(&self) -> usize {
+        self.runs.iter().map(|run| run.len()).sum()
+    }
+
+    pub(crate) fn from_row_runs(image_size: ImageSize, rows: Vec<Vec<MaskRun>>) -> Self {
+        let mut row_offsets = Vec::with_capacity(rows.len() + 1);
+        row_offsets.push(0);
+        let mut runs = Vec::with_capacity(rows.iter().map(|row| row.len()).sum());
+        for row in rows {
+            runs.extend(row);
+            row_offsets.push(runs.len());
+        }
+        RleMask {
+            size: image_size,
+            runs,
+            row_offsets,
+        }
+    }
+
+    pub(crate) fn check_non_empty(&self) -> Result<(), MorphologyError> {
+        check_image_size(self.size)
+    }
+}
+
+/// Checks if sorted disjoint `runs` contain `x`
+pub(crate) fn run_contains(runs: &[MaskRun], x: usize) -> bool {
+    let index = runs.partition_point(|run| run.end <= x);
+    runs.get(index).is_some_and(|run| run.start <= x)
+}
+
+/// Sorts runs and merges the ones that overlap or touch
+pub(crate) fn merge_runs(runs: &mut Vec<MaskRun>) {
+    runs.sort_unstable_by_key(|run| run.start);
+    let mut merged = 0usize;
+    for i in 0..runs.len() {
+        let run = runs[i];
+        if merged > 0 && runs[merged - 1].end >= run.start {
+            runs[merged - 1].end = runs[merged - 1].end.max(run.end);
+        } else {
+            runs[merged] = run;
+            merged += 1;
+        }
+    }
+    runs.truncate(merged);
+}
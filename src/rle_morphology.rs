@@ -0,0 +1,390 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::arena::border_index;
+use crate::border_mode::BorderMode;
+use crate::error::MorphologyError;
+use crate::flat_se::Chord;
+use crate::op_impl::check_structuring_element;
+use crate::op_type::{MorphExOp, MorphOp};
+use crate::rle_mask::{merge_runs, run_contains, MaskRun, RleMask};
+use crate::se_scan::scan_se;
+use crate::structuring_element::KernelShape;
+use crate::thread_policy::ThreadingPool;
+use crate::MorphologyThreadingPolicy;
+use rayon::prelude::*;
+
+/// Set pixels `start..end` of a padded row in image coordinates, may be negative
+#[derive(Debug, Copy, Clone)]
+struct PaddedRun {
+    start: i64,
+    end: i64,
+}
+
+/// Collects runs of increasing pixels, joining the ones that touch
+#[derive(Default)]
+struct RunsBuilder {
+    runs: Vec<PaddedRun>,
+}
+
+impl RunsBuilder {
+    fn push_run(&mut self, start: i64, end: i64) {
+        if start >= end {
+            return;
+        }
+        match self.runs.last_mut() {
+            Some(last) if last.end == start => last.end = end,
+            _ => self.runs.push(PaddedRun { start, end }),
+        }
+    }
+
+    fn push_pixel(&mut self, x: i64, value: bool) {
+        if value {
+            self.push_run(x, x + 1);
+        }
+    }
+}
+
+/// Rows of the source padded for the structuring element, the same way as one byte per
+/// pixel arenas: both coordinates of a pixel outside of the image are mapped by the border
+/// mode, even the one inside of the image. Row `y` is the image row `y - anchor_y`.
+fn pad_rows(
+    src: &RleMask,
+    kernel: KernelShape,
+    border_mode: BorderMode,
+    border_value: bool,
+    pool: &ThreadingPool,
+) -> Vec<Vec<PaddedRun>> {
+    let size = src.size();
+    let width = size.width as i64;
+    let pad_left = kernel.anchor_x as i64;
+    let pad_right = (kernel.width - 1 - kernel.anchor_x) as i64;
+    let pad_row = |y: usize| {
+        let y = y as i64 - kernel.anchor_y as i64;
+        let border_row = border_index(y, size.height, border_mode).map(|y| src.row(y));
+        let value_at = |x: i64| match (border_row, border_index(x, size.width, border_mode)) {
+            (Some(border_row), Some(x)) => run_contains(border_row, x),
+            _ => border_value,
+        };
+        let mut builder = RunsBuilder::default();
+        (-pad_left..0).for_each(|x| builder.push_pixel(x, value_at(x)));
+        if y >= 0 && (y as usize) < size.height {
+            for run in src.row(y as usize) {
+                builder.push_run(run.start as i64, run.end as i64);
+            }
+        } else if let Some(border_row) = border_row {
+            // Last pixel is mapped as well, wrapping sends it to the first one
+            for run in border_row {
+                builder.push_run(run.start as i64, (run.end as i64).min(width - 1));
+            }
+            builder.push_pixel(width - 1, value_at(width - 1));
+        } else {
+            builder.push_run(0, if border_value { width } else { 0 });
+        }
+        (width..width + pad_right).for_each(|x| builder.push_pixel(x, value_at(x)));
+        builder.runs
+    };
+
+    let rows = size.height + kernel.height - 1;
+    if pool.is_parallel() {
+        pool.install(|| (0..rows).into_par_iter().map(pad_row).collect())
+    } else {
+        (0..rows).map(pad_row).collect()
+    }
+}
+
+/// Dilates a row by the chords: every run is spread by the chord it is seen through
+fn dilate_row(
+    padded: &[Vec<PaddedRun>],
+    chords: &[Chord],
+    y: usize,
+    anchor_y: usize,
+    width: usize,
+) -> Vec<MaskRun> {
+    let width = width as i64;
+    let mut runs = Vec::new();
+    for chord in chords {
+        let row = &padded[(y as i64 + (chord.start.y as i64) + anchor_y as i64) as usize];
+        let first = chord.start.x as i64;
+        let last = first + chord.length as i64 - 1;
+        runs.extend(row.iter().filter_map(|run| {
+            let start = (run.start - last).max(0);
+            let end = (run.end - first).min(width);
+            (start < end).then(|| MaskRun::new(start as usize, end as usize))
+        }));
+    }
+    merge_runs(&mut runs);
+    runs
+}
+
+/// Erodes a row by the chords: a chord fits at the positions where it lies entirely within
+/// a run, result is the intersection of those positions across chords
+fn erode_row(
+    padded: &[Vec<PaddedRun>],
+    chords: &[Chord],
+    y: usize,
+    anchor_y: usize,
+    width: usize,
+) -> Vec<MaskRun> {
+    let mut runs = vec![MaskRun::new(0, width)];
+    let mut fits = Vec::new();
+    for chord in chords {
+        let row = &padded[(y as i64 + (chord.start.y as i64) + anchor_y as i64) as usize];
+        let first = chord.start.x as i64;
+        let last = first + chord.length as i64 - 1;
+        fits.clear();
+        fits.extend(row.iter().filter_map(|run| {
+            let start = (run.start - first).max(0);
+            let end = (run.end - last).min(width as i64);
+            (start < end).then(|| MaskRun::new(start as usize, end as usize))
+        }));
+        runs = combine_runs(&runs, &fits, |a, b| a && b);
+        if runs.is_empty() {
+            break;
+        }
+    }
+    runs
+}
+
+/// Applies `op` pixel by pixel to two sorted disjoint sets of runs, `op(false, false)`
+/// must be false
+fn combine_runs(a: &[MaskRun], b: &[MaskRun], op: impl Fn(bool, bool) -> bool) -> Vec<MaskRun> {
+    let mut bounds = a
+        .iter()
+        .chain(b.iter())
+        .flat_map(|run| [run.start, run.end])
+        .collect::<Vec<usize>>();
+    bounds.sort_unstable();
+    bounds.dedup();
+    let mut runs: Vec<MaskRun> = Vec::new();
+    let (mut i, mut j) = (0usize, 0usize);
+    for span in bounds.windows(2) {
+        let (start, end) = (span[0], span[1]);
+        while i < a.len() && a[i].end <= start {
+            i += 1;
+        }
+        while j < b.len() && b[j].end <= start {
+            j += 1;
+        }
+        let in_a = i < a.len() && a[i].start <= start;
+        let in_b = j < b.len() && b[j].start <= start;
+        if !op(in_a, in_b) {
+            continue;
+        }
+        match runs.last_mut() {
+            Some(last) if last.end == start => last.end = end,
+            _ => runs.push(MaskRun::new(start, end)),
+        }
+    }
+    runs
+}
+
+fn rle_op(
+    op: MorphOp,
+    src: &RleMask,
+    structuring_element: &[u8],
+    kernel: KernelShape,
+    border_mode: BorderMode,
+    border_value: bool,
+    pool: &ThreadingPool,
+) -> RleMask {
+    let analyzed_se = unsafe { scan_se(structuring_element, kernel) };
+    if analyzed_se.chords.is_empty() {
+        return src.clone();
+    }
+    let chords = analyzed_se.chords;
+
+    let size = src.size();
+    let padded = pad_rows(src, kernel, border_mode, border_value, pool);
+    let row_op = match op {
+        MorphOp::Dilate => dilate_row,
+        MorphOp::Erode => erode_row,
+    };
+    let filter_row = |y: usize| row_op(&padded, &chords, y, kernel.anchor_y, size.width);
+    let rows = if pool.is_parallel() {
+        pool.install(|| (0..size.height).into_par_iter().map(filter_row).collect())
+    } else {
+        (0..size.height).map(filter_row).collect()
+    };
+    RleMask::from_row_runs(size, rows)
+}
+
+/// Applies `op` pixel by pixel to two masks of the same size
+fn combine(a: &RleMask, b: &RleMask, op: impl Fn(bool, bool) -> bool) -> RleMask {
+    let rows = (0..a.size().height)
+        .map(|y| combine_runs(a.row(y), b.row(y), &op))
+        .collect();
+    RleMask::from_row_runs(a.size(), rows)
+}
+
+fn check_rle_masks(src: &RleMask, dst: &RleMask) -> Result<(), MorphologyError> {
+    src.check_non_empty()?;
+    if src.size() != dst.size() {
+        return Err(MorphologyError::ImageDimensionsMismatch {
+            src_width: src.size().width,
+            src_height: src.size().height,
+            dst_width: dst.size().width,
+            dst_height: dst.size().height,
+        });
+    }
+    Ok(())
+}
+
+/// Performs morphology on a run-length encoded mask
+///
+/// Runs are spread or shrunk by every chord of the structuring element and joined with
+/// union or intersection, pixels are never expanded. Results are the same as for a one byte
+/// per pixel mask of 0 and 255 processed with [crate::morphology] and border constant 0 or 255.
+///
+/// # Arguments
+///
+/// * `src`: Source mask
+/// * `dst`: Destination mask of the same size, its content is replaced
+/// * `morph_op`: Requested [MorphExOp]
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_value`: Value of pixels outside of the image in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn rle_morphology(
+    src: &RleMask,
+    dst: &mut RleMask,
+    morph_op: MorphExOp,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_value: bool,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    check_rle_masks(src, dst)?;
+    check_structuring_element(structuring_element.len(), structuring_element_size)?;
+
+    let pool = threading_policy.threading_pool(src.size());
+    let run = |op: MorphOp, src: &RleMask| {
+        rle_op(
+            op,
+            src,
+            structuring_element,
+            structuring_element_size,
+            border_mode,
+            border_value,
+            &pool,
+        )
+    };
+
+    *dst = match morph_op {
+        MorphExOp::Dilate => run(MorphOp::Dilate, src),
+        MorphExOp::Erode => run(MorphOp::Erode, src),
+        MorphExOp::Opening => run(MorphOp::Dilate, &run(MorphOp::Erode, src)),
+        MorphExOp::Closing => run(MorphOp::Erode, &run(MorphOp::Dilate, src)),
+        MorphExOp::Gradient => combine(
+            &run(MorphOp::Dilate, src),
+            &run(MorphOp::Erode, src),
+            |dilated, eroded| dilated && !eroded,
+        ),
+        MorphExOp::TopHat => combine(
+            src,
+            &run(MorphOp::Dilate, &run(MorphOp::Erode, src)),
+            |src, opened| src && !opened,
+        ),
+        MorphExOp::BlackHat => combine(
+            &run(MorphOp::Erode, &run(MorphOp::Dilate, src)),
+            src,
+            |closed, src| closed && !src,
+        ),
+    };
+
+    Ok(())
+}
+
+/// Dilate a run-length encoded mask
+///
+/// # Arguments
+///
+/// * `src`: Source mask
+/// * `dst`: Destination mask of the same size, its content is replaced
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_value`: Value of pixels outside of the image in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn rle_dilate(
+    src: &RleMask,
+    dst: &mut RleMask,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_value: bool,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    rle_morphology(
+        src,
+        dst,
+        MorphExOp::Dilate,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_value,
+        threading_policy,
+    )
+}
+
+/// Erode a run-length encoded mask
+///
+/// # Arguments
+///
+/// * `src`: Source mask
+/// * `dst`: Destination mask of the same size, its content is replaced
+/// * `structuring_element`: 2D structuring element
+/// * `structuring_element_size`: (W,H) structuring element size
+/// * `border_mode`: Border handling mode, for reference see [BorderMode]
+/// * `border_value`: Value of pixels outside of the image in [BorderMode::Constant]
+/// * `threading_policy`: Threads usage policy
+///
+pub fn rle_erode(
+    src: &RleMask,
+    dst: &mut RleMask,
+    structuring_element: &[u8],
+    structuring_element_size: KernelShape,
+    border_mode: BorderMode,
+    border_value: bool,
+    threading_policy: MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError> {
+    rle_morphology(
+        src,
+        dst,
+        MorphExOp::Erode,
+        structuring_element,
+        structuring_element_size,
+        border_mode,
+        border_value,
+        threading_policy,
+    )
+}
@@ -0,0 +1,242 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::flat_se::{AnalyzedSe, PeriodicLine};
+use crate::se_scan::ScanPoint;
+use crate::urbach_wilkinson::chords_cost;
+
+/// Longest step of periodic lines that are looked for
+const MAX_LINE_STEP: i32 = 16;
+/// Bounds count of points visited on each round of looking for periodic lines
+const SEARCH_BUDGET: usize = 1 << 17;
+/// Steps up to 3 pixels long are always tried, sums of such lines make disks and polygons
+const ALWAYS_SEARCHED_STEPS: usize = 16;
+/// Each line costs a pass over the whole arena, long chains of them rarely pay off
+const MAX_PERIODIC_LINES: usize = 16;
+/// Approximate cost of one periodic line pass, in units of one direct filter element
+const PERIODIC_LINE_COST: usize = 8;
+
+/// Structuring element split as `residual ⊕ P(n1, v1) ⊕ ... ⊕ P(nk, vk)`
+pub(crate) struct SeDecomposition {
+    pub(crate) lines: Vec<PeriodicLine>,
+    pub(crate) residual: AnalyzedSe,
+}
+
+/// Dense lookup of points, keeps index of each point
+struct PointsMask {
+    left: i32,
+    top: i32,
+    width: i32,
+    height: i32,
+    indices: Vec<u32>,
+}
+
+impl PointsMask {
+    fn new(points: &[ScanPoint]) -> PointsMask {
+        let left = points.iter().map(|p| p.x).min().unwrap_or(0);
+        let top = points.iter().map(|p| p.y).min().unwrap_or(0);
+        let right = points.iter().map(|p| p.x).max().unwrap_or(0);
+        let bottom = points.iter().map(|p| p.y).max().unwrap_or(0);
+        let width = right - left + 1;
+        let height = bottom - top + 1;
+        let mut indices = vec![0u32; (width * height) as usize];
+        for (i, p) in points.iter().enumerate() {
+            indices[((p.y - top) * width + p.x - left) as usize] = i as u32 + 1;
+        }
+        PointsMask {
+            left,
+            top,
+            width,
+            height,
+            indices,
+        }
+    }
+
+    fn index(&self, p: ScanPoint) -> Option<usize> {
+        let x = p.x - self.left;
+        let y = p.y - self.top;
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return None;
+        }
+        match self.indices[(y * self.width + x) as usize] {
+            0 => None,
+            i => Some(i as usize - 1),
+        }
+    }
+
+    fn contains(&self, p: ScanPoint) -> bool {
+        self.index(p).is_some()
+    }
+}
+
+fn gcd(a: i32, b: i32) -> i32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Primitive steps pointing down or right, shortest first
+fn line_steps() -> Vec<ScanPoint> {
+    let mut steps = vec![];
+    for y in 0..=MAX_LINE_STEP {
+        for x in -MAX_LINE_STEP..=MAX_LINE_STEP {
+            if (y > 0 || x > 0) && gcd(x.abs(), y) == 1 {
+                steps.push(ScanPoint::new(x, y));
+            }
+        }
+    }
+    steps.sort_by_key(|p| (p.x.abs().max(p.y), p.x.abs() + p.y));
+    steps
+}
+
+/// Looks for the longest periodic line along the step that fits all chains of points.
+///
+/// Every chain of points along the step must be at least `count` long, then splitting
+/// removes `count - 1` points from each chain. Returns `count` and removed points count.
+fn fit_periodic_line(
+    points: &[ScanPoint],
+    mask: &PointsMask,
+    step: ScanPoint,
+) -> Option<(usize, usize)> {
+    let back = ScanPoint::new(-step.x, -step.y);
+    let mut count = usize::MAX;
+    let mut chains = 0usize;
+    for &start in points.iter() {
+        if mask.contains(start + back) {
+            continue;
+        }
+        let mut length = 1;
+        let mut p = start + step;
+        while mask.contains(p) && length < count {
+            length += 1;
+            p = p + step;
+        }
+        if length == 1 {
+            return None;
+        }
+        count = count.min(length);
+        chains += 1;
+    }
+    if chains == 0 {
+        return None;
+    }
+    Some((count, chains * (count - 1)))
+}
+
+/// Splits points as `residual ⊕ P(count, step)`, points that start at least `count`
+/// long run form the residual, it keeps points order.
+fn split_periodic_line(
+    points: &[ScanPoint],
+    mask: &PointsMask,
+    line: PeriodicLine,
+) -> Vec<ScanPoint> {
+    let step = line.step;
+    let back = ScanPoint::new(-step.x, -step.y);
+    let mut keep = vec![false; points.len()];
+    for &start in points.iter().filter(|&&p| !mask.contains(p + back)) {
+        let mut length = 1;
+        while mask.contains(ScanPoint::new(
+            start.x + step.x * length,
+            start.y + step.y * length,
+        )) {
+            length += 1;
+        }
+        let mut p = start;
+        for _ in 0..=length as usize - line.count {
+            if let Some(index) = mask.index(p) {
+                keep[index] = true;
+            }
+            p = p + step;
+        }
+    }
+
+    points
+        .iter()
+        .zip(keep.iter())
+        .filter(|(_, &keep)| keep)
+        .map(|(&p, _)| p)
+        .collect()
+}
+
+fn estimate_cost(analyzed_se: &AnalyzedSe) -> usize {
+    let direct = analyzed_se.left_front.element_offsets.len();
+    chords_cost(&analyzed_se.chords).map_or(direct, |cost| cost.min(direct))
+}
+
+/// Greedily looks for periodic lines that make the structuring element cheaper.
+///
+/// Splitting a line may make the residual costlier before the next lines pay off,
+/// so lines are looked for until none fits, and the cheapest prefix of them is kept.
+/// Each split is exact, so the result does not depend on what was found,
+/// `None` is returned when nothing makes the element cheaper.
+pub(crate) fn decompose_periodic_lines(analyzed_se: &AnalyzedSe) -> Option<SeDecomposition> {
+    let mut cost = estimate_cost(analyzed_se);
+    if cost <= PERIODIC_LINE_COST * 2 {
+        return None;
+    }
+
+    let steps = line_steps();
+    let mut points = analyzed_se.left_front.element_offsets.clone();
+    points.sort_by_key(|p| (p.y, p.x));
+    let mut lines = vec![];
+    let mut best: Option<SeDecomposition> = None;
+    while lines.len() < MAX_PERIODIC_LINES {
+        let mut budget = SEARCH_BUDGET;
+        let mask = PointsMask::new(&points);
+        let mut best_line: Option<(usize, PeriodicLine)> = None;
+        for (i, &step) in steps.iter().enumerate() {
+            if budget < points.len() && i >= ALWAYS_SEARCHED_STEPS {
+                break;
+            }
+            budget = budget.saturating_sub(points.len());
+            if let Some((count, gain)) = fit_periodic_line(&points, &mask, step) {
+                if best_line.is_none_or(|x| x.0 < gain) {
+                    best_line = Some((gain, PeriodicLine::new(step, count)));
+                }
+            }
+        }
+        let Some((_, line)) = best_line else {
+            break;
+        };
+        let rest = AnalyzedSe::from_points(split_periodic_line(&points, &mask, line));
+        lines.push(line);
+        points = rest.left_front.element_offsets.clone();
+        let new_cost = estimate_cost(&rest) + PERIODIC_LINE_COST * lines.len();
+        if new_cost < cost {
+            cost = new_cost;
+            best = Some(SeDecomposition {
+                lines: lines.clone(),
+                residual: rest,
+            });
+        }
+    }
+
+    best
+}
@@ -26,7 +26,7 @@
  * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
  * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
-use crate::flat_se::{AnalyzedSe, FlatSe};
+use crate::flat_se::{AnalyzedSe, Chord, FlatSe};
 use crate::structuring_element::KernelShape;
 use std::ops::Add;
 
@@ -35,28 +35,56 @@ pub(crate) unsafe fn scan_se(
     structuring_element_size: KernelShape,
 ) -> AnalyzedSe {
     let mut left_front = vec![];
+    let mut chords = vec![];
 
     let kernel_width = structuring_element_size.width;
     let kernel_height = structuring_element_size.height;
 
-    let half_kernel_width = kernel_width as i32 / 2;
-    let half_kernel_height = kernel_height as i32 / 2;
+    let anchor_x = structuring_element_size.anchor_x as i32;
+    let anchor_y = structuring_element_size.anchor_y as i32;
 
     for y in 0..kernel_height {
+        let mut chord_start: Option<usize> = None;
         for x in 0..kernel_width {
-            let item = *structuring_element.get_unchecked(y * kernel_height + x);
+            let item = *structuring_element.get_unchecked(y * kernel_width + x);
             if item != 0 {
-                left_front.push(ScanPoint::new(
-                    y as i32 - half_kernel_height,
-                    x as i32 - half_kernel_width,
+                left_front.push(ScanPoint::new(x as i32 - anchor_x, y as i32 - anchor_y));
+                if chord_start.is_none() {
+                    chord_start = Some(x);
+                }
+            } else if let Some(start) = chord_start.take() {
+                chords.push(Chord::new(
+                    ScanPoint::new(start as i32 - anchor_x, y as i32 - anchor_y),
+                    x - start,
                 ));
             }
         }
+        if let Some(start) = chord_start {
+            chords.push(Chord::new(
+                ScanPoint::new(start as i32 - anchor_x, y as i32 - anchor_y),
+                kernel_width - start,
+            ));
+        }
     }
 
     let iv_left: Vec<ScanPoint> = left_front.to_vec();
 
-    AnalyzedSe::new(structuring_element.to_vec(), FlatSe::new(iv_left))
+    AnalyzedSe::new(structuring_element.to_vec(), FlatSe::new(iv_left), chords)
+}
+
+/// Points of non-flat structuring element are the ones with finite weights
+pub(crate) unsafe fn scan_weighted_se(
+    weights: &[f32],
+    structuring_element_size: KernelShape,
+) -> AnalyzedSe {
+    let mask = weights
+        .iter()
+        .map(|x| x.is_finite() as u8)
+        .collect::<Vec<u8>>();
+    let mut analyzed_se = scan_se(&mask, structuring_element_size);
+    // Points are scanned row by row, so weights follow the same order
+    analyzed_se.left_front.weights = weights.iter().filter(|x| x.is_finite()).copied().collect();
+    analyzed_se
 }
 
 #[repr(C)]
@@ -74,28 +102,6 @@ impl Add<ScanPoint> for ScanPoint {
     }
 }
 
-#[repr(C)]
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
-pub struct FilterBounds {
-    pub x: i32,
-    pub y: i32,
-    pub size: u16,
-}
-
-impl FilterBounds {
-    pub fn new(x: i32, y: i32, size: u16) -> FilterBounds {
-        FilterBounds { x, y, size }
-    }
-}
-
-impl Add<ScanPoint> for FilterBounds {
-    type Output = FilterBounds;
-
-    fn add(self, rhs: ScanPoint) -> Self::Output {
-        FilterBounds::new(self.x + rhs.x, self.y + rhs.y, self.size)
-    }
-}
-
 impl ScanPoint {
     pub fn new(x: i32, y: i32) -> ScanPoint {
         ScanPoint { x, y }
@@ -27,5 +27,13 @@
  * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
  */
 mod gradient_unsigned_8;
+mod morph_rows;
+mod packed_int;
+mod transpose;
 
 pub use gradient_unsigned_8::morph_gradient_sse;
+pub use morph_rows::{
+    morph_rows_f32_sse, morph_rows_f64_sse, morph_rows_u16_sse, morph_rows_u8_sse,
+};
+pub use packed_int::{morph_rows_int_sse, SsePackedInt};
+pub(crate) use transpose::transpose_sse;
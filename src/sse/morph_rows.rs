@@ -0,0 +1,216 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::morph_base::MorphNativeOp;
+use crate::op_type::MorphOp;
+#[cfg(target_arch = "x86")]
+use std::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+/// Computes `dst = op(a, b)` for 8-bit rows
+pub fn morph_rows_u8_sse<const OP_TYPE: u8>(a: &[u8], b: &[u8], dst: &mut [u8]) {
+    unsafe {
+        morph_rows_u8_sse_impl::<OP_TYPE>(a, b, dst);
+    }
+}
+
+#[inline]
+#[target_feature(enable = "sse4.1")]
+unsafe fn morph_rows_u8_sse_impl<const OP_TYPE: u8>(a: &[u8], b: &[u8], dst: &mut [u8]) {
+    let op_type: MorphOp = OP_TYPE.into();
+    let decision = match op_type {
+        MorphOp::Dilate => _mm_max_epu8,
+        MorphOp::Erode => _mm_min_epu8,
+    };
+    let length = dst.len().min(a.len()).min(b.len());
+    let mut _cx = 0usize;
+    while _cx + 32 <= length {
+        let a_ptr = a.get_unchecked(_cx..).as_ptr();
+        let b_ptr = b.get_unchecked(_cx..).as_ptr();
+        let row0 = decision(
+            _mm_loadu_si128(a_ptr as *const __m128i),
+            _mm_loadu_si128(b_ptr as *const __m128i),
+        );
+        let row1 = decision(
+            _mm_loadu_si128(a_ptr.add(16) as *const __m128i),
+            _mm_loadu_si128(b_ptr.add(16) as *const __m128i),
+        );
+        let dst_ptr = dst.get_unchecked_mut(_cx..).as_mut_ptr();
+        _mm_storeu_si128(dst_ptr as *mut __m128i, row0);
+        _mm_storeu_si128(dst_ptr.add(16) as *mut __m128i, row1);
+        _cx += 32;
+    }
+    while _cx + 16 <= length {
+        let row0 = decision(
+            _mm_loadu_si128(a.get_unchecked(_cx..).as_ptr() as *const __m128i),
+            _mm_loadu_si128(b.get_unchecked(_cx..).as_ptr() as *const __m128i),
+        );
+        _mm_storeu_si128(
+            dst.get_unchecked_mut(_cx..).as_mut_ptr() as *mut __m128i,
+            row0,
+        );
+        _cx += 16;
+    }
+    while _cx < length {
+        *dst.get_unchecked_mut(_cx) = a.get_unchecked(_cx).op::<OP_TYPE>(*b.get_unchecked(_cx));
+        _cx += 1;
+    }
+}
+
+/// Computes `dst = op(a, b)` for 16-bit rows
+pub fn morph_rows_u16_sse<const OP_TYPE: u8>(a: &[u16], b: &[u16], dst: &mut [u16]) {
+    unsafe {
+        morph_rows_u16_sse_impl::<OP_TYPE>(a, b, dst);
+    }
+}
+
+#[inline]
+#[target_feature(enable = "sse4.1")]
+unsafe fn morph_rows_u16_sse_impl<const OP_TYPE: u8>(a: &[u16], b: &[u16], dst: &mut [u16]) {
+    let op_type: MorphOp = OP_TYPE.into();
+    let decision = match op_type {
+        MorphOp::Dilate => _mm_max_epu16,
+        MorphOp::Erode => _mm_min_epu16,
+    };
+    let length = dst.len().min(a.len()).min(b.len());
+    let mut _cx = 0usize;
+    while _cx + 16 <= length {
+        let a_ptr = a.get_unchecked(_cx..).as_ptr();
+        let b_ptr = b.get_unchecked(_cx..).as_ptr();
+        let row0 = decision(
+            _mm_loadu_si128(a_ptr as *const __m128i),
+            _mm_loadu_si128(b_ptr as *const __m128i),
+        );
+        let row1 = decision(
+            _mm_loadu_si128(a_ptr.add(8) as *const __m128i),
+            _mm_loadu_si128(b_ptr.add(8) as *const __m128i),
+        );
+        let dst_ptr = dst.get_unchecked_mut(_cx..).as_mut_ptr();
+        _mm_storeu_si128(dst_ptr as *mut __m128i, row0);
+        _mm_storeu_si128(dst_ptr.add(8) as *mut __m128i, row1);
+        _cx += 16;
+    }
+    while _cx + 8 <= length {
+        let row0 = decision(
+            _mm_loadu_si128(a.get_unchecked(_cx..).as_ptr() as *const __m128i),
+            _mm_loadu_si128(b.get_unchecked(_cx..).as_ptr() as *const __m128i),
+        );
+        _mm_storeu_si128(
+            dst.get_unchecked_mut(_cx..).as_mut_ptr() as *mut __m128i,
+            row0,
+        );
+        _cx += 8;
+    }
+    while _cx < length {
+        *dst.get_unchecked_mut(_cx) = a.get_unchecked(_cx).op::<OP_TYPE>(*b.get_unchecked(_cx));
+        _cx += 1;
+    }
+}
+
+/// Computes `dst = op(a, b)` for f32 rows
+pub fn morph_rows_f32_sse<const OP_TYPE: u8>(a: &[f32], b: &[f32], dst: &mut [f32]) {
+    unsafe {
+        morph_rows_f32_sse_impl::<OP_TYPE>(a, b, dst);
+    }
+}
+
+#[inline]
+#[target_feature(enable = "sse4.1")]
+unsafe fn morph_rows_f32_sse_impl<const OP_TYPE: u8>(a: &[f32], b: &[f32], dst: &mut [f32]) {
+    let op_type: MorphOp = OP_TYPE.into();
+    let decision = match op_type {
+        MorphOp::Dilate => _mm_max_ps,
+        MorphOp::Erode => _mm_min_ps,
+    };
+    let length = dst.len().min(a.len()).min(b.len());
+    let mut _cx = 0usize;
+    while _cx + 8 <= length {
+        let a_ptr = a.get_unchecked(_cx..).as_ptr();
+        let b_ptr = b.get_unchecked(_cx..).as_ptr();
+        let row0 = decision(_mm_loadu_ps(a_ptr), _mm_loadu_ps(b_ptr));
+        let row1 = decision(_mm_loadu_ps(a_ptr.add(4)), _mm_loadu_ps(b_ptr.add(4)));
+        let dst_ptr = dst.get_unchecked_mut(_cx..).as_mut_ptr();
+        _mm_storeu_ps(dst_ptr, row0);
+        _mm_storeu_ps(dst_ptr.add(4), row1);
+        _cx += 8;
+    }
+    while _cx + 4 <= length {
+        let row0 = decision(
+            _mm_loadu_ps(a.get_unchecked(_cx..).as_ptr()),
+            _mm_loadu_ps(b.get_unchecked(_cx..).as_ptr()),
+        );
+        _mm_storeu_ps(dst.get_unchecked_mut(_cx..).as_mut_ptr(), row0);
+        _cx += 4;
+    }
+    while _cx < length {
+        *dst.get_unchecked_mut(_cx) = a.get_unchecked(_cx).op::<OP_TYPE>(*b.get_unchecked(_cx));
+        _cx += 1;
+    }
+}
+
+/// Computes `dst = op(a, b)` for f64 rows
+pub fn morph_rows_f64_sse<const OP_TYPE: u8>(a: &[f64], b: &[f64], dst: &mut [f64]) {
+    unsafe {
+        morph_rows_f64_sse_impl::<OP_TYPE>(a, b, dst);
+    }
+}
+
+#[inline]
+#[target_feature(enable = "sse4.1")]
+unsafe fn morph_rows_f64_sse_impl<const OP_TYPE: u8>(a: &[f64], b: &[f64], dst: &mut [f64]) {
+    let op_type: MorphOp = OP_TYPE.into();
+    let decision = match op_type {
+        MorphOp::Dilate => _mm_max_pd,
+        MorphOp::Erode => _mm_min_pd,
+    };
+    let length = dst.len().min(a.len()).min(b.len());
+    let mut _cx = 0usize;
+    while _cx + 4 <= length {
+        let a_ptr = a.get_unchecked(_cx..).as_ptr();
+        let b_ptr = b.get_unchecked(_cx..).as_ptr();
+        let row0 = decision(_mm_loadu_pd(a_ptr), _mm_loadu_pd(b_ptr));
+        let row1 = decision(_mm_loadu_pd(a_ptr.add(2)), _mm_loadu_pd(b_ptr.add(2)));
+        let dst_ptr = dst.get_unchecked_mut(_cx..).as_mut_ptr();
+        _mm_storeu_pd(dst_ptr, row0);
+        _mm_storeu_pd(dst_ptr.add(2), row1);
+        _cx += 4;
+    }
+    while _cx + 2 <= length {
+        let row0 = decision(
+            _mm_loadu_pd(a.get_unchecked(_cx..).as_ptr()),
+            _mm_loadu_pd(b.get_unchecked(_cx..).as_ptr()),
+        );
+        _mm_storeu_pd(dst.get_unchecked_mut(_cx..).as_mut_ptr(), row0);
+        _cx += 2;
+    }
+    while _cx < length {
+        *dst.get_unchecked_mut(_cx) = a.get_unchecked(_cx).op::<OP_TYPE>(*b.get_unchecked(_cx));
+        _cx += 1;
+    }
+}
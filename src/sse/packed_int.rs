@@ -0,0 +1,165 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::morph_base::MorphNativeOp;
+use crate::op_type::MorphOp;
+#[cfg(target_arch = "x86")]
+use std::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+/// Lane-wise min/max of integer samples packed into 128-bit registers
+pub trait SsePackedInt: Copy {
+    /// Samples in one register
+    const LANES: usize;
+
+    unsafe fn max(a: __m128i, b: __m128i) -> __m128i;
+
+    unsafe fn min(a: __m128i, b: __m128i) -> __m128i;
+}
+
+impl SsePackedInt for i8 {
+    const LANES: usize = 16;
+
+    #[inline]
+    #[target_feature(enable = "sse4.1")]
+    unsafe fn max(a: __m128i, b: __m128i) -> __m128i {
+        _mm_max_epi8(a, b)
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse4.1")]
+    unsafe fn min(a: __m128i, b: __m128i) -> __m128i {
+        _mm_min_epi8(a, b)
+    }
+}
+
+impl SsePackedInt for i16 {
+    const LANES: usize = 8;
+
+    #[inline]
+    #[target_feature(enable = "sse4.1")]
+    unsafe fn max(a: __m128i, b: __m128i) -> __m128i {
+        _mm_max_epi16(a, b)
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse4.1")]
+    unsafe fn min(a: __m128i, b: __m128i) -> __m128i {
+        _mm_min_epi16(a, b)
+    }
+}
+
+impl SsePackedInt for i32 {
+    const LANES: usize = 4;
+
+    #[inline]
+    #[target_feature(enable = "sse4.1")]
+    unsafe fn max(a: __m128i, b: __m128i) -> __m128i {
+        _mm_max_epi32(a, b)
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse4.1")]
+    unsafe fn min(a: __m128i, b: __m128i) -> __m128i {
+        _mm_min_epi32(a, b)
+    }
+}
+
+impl SsePackedInt for u32 {
+    const LANES: usize = 4;
+
+    #[inline]
+    #[target_feature(enable = "sse4.1")]
+    unsafe fn max(a: __m128i, b: __m128i) -> __m128i {
+        _mm_max_epu32(a, b)
+    }
+
+    #[inline]
+    #[target_feature(enable = "sse4.1")]
+    unsafe fn min(a: __m128i, b: __m128i) -> __m128i {
+        _mm_min_epu32(a, b)
+    }
+}
+
+/// Computes `dst = op(a, b)` for rows of packed integers
+pub fn morph_rows_int_sse<V, const OP_TYPE: u8>(a: &[V], b: &[V], dst: &mut [V])
+where
+    V: SsePackedInt + MorphNativeOp<V>,
+{
+    unsafe {
+        morph_rows_int_sse_impl::<V, OP_TYPE>(a, b, dst);
+    }
+}
+
+#[inline]
+#[target_feature(enable = "sse4.1")]
+unsafe fn morph_rows_int_sse_impl<V, const OP_TYPE: u8>(a: &[V], b: &[V], dst: &mut [V])
+where
+    V: SsePackedInt + MorphNativeOp<V>,
+{
+    let op_type: MorphOp = OP_TYPE.into();
+    let decision = match op_type {
+        MorphOp::Dilate => V::max,
+        MorphOp::Erode => V::min,
+    };
+    let lanes = V::LANES;
+    let length = dst.len().min(a.len()).min(b.len());
+    let mut _cx = 0usize;
+    while _cx + lanes * 2 <= length {
+        let a_ptr = a.get_unchecked(_cx..).as_ptr();
+        let b_ptr = b.get_unchecked(_cx..).as_ptr();
+        let row0 = decision(
+            _mm_loadu_si128(a_ptr as *const __m128i),
+            _mm_loadu_si128(b_ptr as *const __m128i),
+        );
+        let row1 = decision(
+            _mm_loadu_si128(a_ptr.add(lanes) as *const __m128i),
+            _mm_loadu_si128(b_ptr.add(lanes) as *const __m128i),
+        );
+        let dst_ptr = dst.get_unchecked_mut(_cx..).as_mut_ptr();
+        _mm_storeu_si128(dst_ptr as *mut __m128i, row0);
+        _mm_storeu_si128(dst_ptr.add(lanes) as *mut __m128i, row1);
+        _cx += lanes * 2;
+    }
+    while _cx + lanes <= length {
+        let row0 = decision(
+            _mm_loadu_si128(a.get_unchecked(_cx..).as_ptr() as *const __m128i),
+            _mm_loadu_si128(b.get_unchecked(_cx..).as_ptr() as *const __m128i),
+        );
+        _mm_storeu_si128(
+            dst.get_unchecked_mut(_cx..).as_mut_ptr() as *mut __m128i,
+            row0,
+        );
+        _cx += lanes;
+    }
+    while _cx < length {
+        *dst.get_unchecked_mut(_cx) = a.get_unchecked(_cx).op::<OP_TYPE>(*b.get_unchecked(_cx));
+        _cx += 1;
+    }
+}
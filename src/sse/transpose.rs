@@ -0,0 +1,128 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::transpose::{network_row, scalar_transpose, transpose_blocks};
+#[cfg(target_arch = "x86")]
+use std::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+/// Transposes rows of 1, 2, 4 or 8 byte samples by blocks of one register per row,
+/// other sizes are transposed with scalar code.
+///
+/// Samples are moved as raw bytes, so `T` must be plain data as built-in samples are.
+pub(crate) fn transpose_sse<T: Copy + Default>(
+    src: &[T],
+    src_stride: usize,
+    dst: &mut [T],
+    dst_stride: usize,
+    width: usize,
+    height: usize,
+) {
+    unsafe {
+        match std::mem::size_of::<T>() {
+            1 => transpose_sse_impl::<T, 16>(src, src_stride, dst, dst_stride, width, height),
+            2 => transpose_sse_impl::<T, 8>(src, src_stride, dst, dst_stride, width, height),
+            4 => transpose_sse_impl::<T, 4>(src, src_stride, dst, dst_stride, width, height),
+            8 => transpose_sse_impl::<T, 2>(src, src_stride, dst, dst_stride, width, height),
+            _ => scalar_transpose(src, src_stride, dst, dst_stride, width, height),
+        }
+    }
+}
+
+#[target_feature(enable = "sse4.1")]
+unsafe fn transpose_sse_impl<T: Copy + Default, const N: usize>(
+    src: &[T],
+    src_stride: usize,
+    dst: &mut [T],
+    dst_stride: usize,
+    width: usize,
+    height: usize,
+) {
+    transpose_blocks::<T, N>(
+        src,
+        src_stride,
+        dst,
+        dst_stride,
+        width,
+        height,
+        |src, src_stride, dst, dst_stride| {
+            transpose_block::<T, N>(src, src_stride, dst, dst_stride)
+        },
+    );
+}
+
+#[inline(always)]
+unsafe fn unpack_lo(a: __m128i, b: __m128i, unit: usize) -> __m128i {
+    match unit {
+        1 => _mm_unpacklo_epi8(a, b),
+        2 => _mm_unpacklo_epi16(a, b),
+        4 => _mm_unpacklo_epi32(a, b),
+        _ => _mm_unpacklo_epi64(a, b),
+    }
+}
+
+#[inline(always)]
+unsafe fn unpack_hi(a: __m128i, b: __m128i, unit: usize) -> __m128i {
+    match unit {
+        1 => _mm_unpackhi_epi8(a, b),
+        2 => _mm_unpackhi_epi16(a, b),
+        4 => _mm_unpackhi_epi32(a, b),
+        _ => _mm_unpackhi_epi64(a, b),
+    }
+}
+
+/// Transposes `N` rows of `N` samples, each row is one register.
+///
+/// Rows are loaded in [network_row] order, then every step interleaves register `i`
+/// with register `i + N / 2` by units twice as wide as the previous step.
+#[inline(always)]
+unsafe fn transpose_block<T: Copy, const N: usize>(
+    src: *const T,
+    src_stride: usize,
+    dst: *mut T,
+    dst_stride: usize,
+) {
+    let mut rows = [_mm_setzero_si128(); N];
+    for (i, row) in rows.iter_mut().enumerate() {
+        *row = _mm_loadu_si128(src.add(network_row(i, N) * src_stride) as *const __m128i);
+    }
+    let mut unit = std::mem::size_of::<T>();
+    while unit < 16 {
+        let mut next = [_mm_setzero_si128(); N];
+        for i in 0..N / 2 {
+            next[2 * i] = unpack_lo(rows[i], rows[i + N / 2], unit);
+            next[2 * i + 1] = unpack_hi(rows[i], rows[i + N / 2], unit);
+        }
+        rows = next;
+        unit *= 2;
+    }
+    for (i, row) in rows.iter().enumerate() {
+        _mm_storeu_si128(dst.add(i * dst_stride) as *mut __m128i, *row);
+    }
+}
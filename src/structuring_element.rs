@@ -31,10 +31,671 @@
 pub struct KernelShape {
     pub width: usize,
     pub height: usize,
+    /// Horizontal position of the structuring element origin
+    pub anchor_x: usize,
+    /// Vertical position of the structuring element origin
+    pub anchor_y: usize,
 }
 
 impl KernelShape {
+    /// Creates kernel shape with anchor at the center, `(width / 2, height / 2)`
     pub fn new(width: usize, height: usize) -> KernelShape {
-        KernelShape { width, height }
+        KernelShape {
+            width,
+            height,
+            anchor_x: width / 2,
+            anchor_y: height / 2,
+        }
     }
+
+    /// Creates kernel shape with the origin at `(anchor_x, anchor_y)`,
+    /// same as OpenCV `anchor` argument
+    pub fn new_with_anchor(
+        width: usize,
+        height: usize,
+        anchor_x: usize,
+        anchor_y: usize,
+    ) -> KernelShape {
+        KernelShape {
+            width,
+            height,
+            anchor_x,
+            anchor_y,
+        }
+    }
+
+    pub(crate) fn pad_left(&self) -> usize {
+        self.anchor_x
+    }
+
+    pub(crate) fn pad_right(&self) -> usize {
+        self.width.saturating_sub(self.anchor_x + 1)
+    }
+
+    pub(crate) fn pad_top(&self) -> usize {
+        self.anchor_y
+    }
+
+    pub(crate) fn pad_bottom(&self) -> usize {
+        self.height.saturating_sub(self.anchor_y + 1)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum ElementShape {
+    Rect,
+    Cross,
+    Ellipse,
+}
+
+/// Same fill rules as OpenCV `getStructuringElement` with anchor at the center
+fn make_element(shape: ElementShape, width: usize, height: usize) -> (Vec<u8>, KernelShape) {
+    let width = width.max(1);
+    let height = height.max(1);
+    let shape = if width == 1 && height == 1 {
+        ElementShape::Rect
+    } else {
+        shape
+    };
+
+    let anchor_x = width / 2;
+    let anchor_y = height / 2;
+
+    let r = (height / 2) as f64;
+    let c = (width / 2) as f64;
+    let inv_r2 = if r != 0. { 1. / (r * r) } else { 0. };
+
+    let mut element = vec![0u8; width * height];
+
+    for (y, row) in element.chunks_exact_mut(width).enumerate() {
+        let (j1, j2) = match shape {
+            ElementShape::Rect => (0, width),
+            ElementShape::Cross => {
+                if y == anchor_y {
+                    (0, width)
+                } else {
+                    (anchor_x, anchor_x + 1)
+                }
+            }
+            ElementShape::Ellipse => {
+                let dy = y as f64 - r;
+                if dy.abs() <= r {
+                    let dx = (c * ((r * r - dy * dy) * inv_r2).sqrt()).round_ties_even() as i64;
+                    let j1 = (c as i64 - dx).max(0) as usize;
+                    let j2 = (c as i64 + dx + 1).min(width as i64) as usize;
+                    (j1, j2)
+                } else {
+                    (0, 0)
+                }
+            }
+        };
+        for dst in row.iter_mut().take(j2).skip(j1) {
+            *dst = 1;
+        }
+    }
+
+    (element, KernelShape::new(width, height))
+}
+
+/// Creates rectangular structuring element with all elements set
+///
+/// # Arguments
+///
+/// * `width`: Structuring element width
+/// * `height`: Structuring element height
+///
+pub fn rect(width: usize, height: usize) -> (Vec<u8>, KernelShape) {
+    make_element(ElementShape::Rect, width, height)
+}
+
+/// Creates cross-shaped structuring element, equivalent of OpenCV `MORPH_CROSS`
+///
+/// # Arguments
+///
+/// * `width`: Structuring element width
+/// * `height`: Structuring element height
+///
+pub fn cross(width: usize, height: usize) -> (Vec<u8>, KernelShape) {
+    make_element(ElementShape::Cross, width, height)
+}
+
+/// Creates elliptic structuring element, equivalent of OpenCV `MORPH_ELLIPSE`
+/// with kernel size `(2 * radius_x + 1, 2 * radius_y + 1)`
+///
+/// # Arguments
+///
+/// * `radius_x`: Horizontal radius of the ellipse
+/// * `radius_y`: Vertical radius of the ellipse
+///
+pub fn ellipse(radius_x: usize, radius_y: usize) -> (Vec<u8>, KernelShape) {
+    make_element(ElementShape::Ellipse, 2 * radius_x + 1, 2 * radius_y + 1)
+}
+
+/// Creates circular structuring element, same as [ellipse] with equal radii
+///
+/// # Arguments
+///
+/// * `radius`: Circle radius
+///
+pub fn circle(radius: usize) -> (Vec<u8>, KernelShape) {
+    ellipse(radius, radius)
+}
+
+/// Creates diamond-shaped structuring element, point is set when `|dx| + |dy| <= radius`
+///
+/// # Arguments
+///
+/// * `radius`: Diamond radius, kernel size will be `2 * radius + 1`
+///
+pub fn diamond(radius: usize) -> (Vec<u8>, KernelShape) {
+    let size = 2 * radius + 1;
+    let mut element = vec![0u8; size * size];
+    for (y, row) in element.chunks_exact_mut(size).enumerate() {
+        for (x, dst) in row.iter_mut().enumerate() {
+            if x.abs_diff(radius) + y.abs_diff(radius) <= radius {
+                *dst = 1;
+            }
+        }
+    }
+    (element, KernelShape::new(size, size))
+}
+
+/// Creates octagon-shaped structuring element
+///
+/// Kernel size will be `m + 2 * n`, horizontal and vertical sides have `m` points,
+/// and diagonal sides have `n` points each.
+///
+/// # Arguments
+///
+/// * `m`: Size of horizontal and vertical sides
+/// * `n`: Height or width of the slanted sides
+///
+pub fn octagon(m: usize, n: usize) -> (Vec<u8>, KernelShape) {
+    let size = (m + 2 * n).max(1);
+    let last = size - 1;
+    let mut element = vec![0u8; size * size];
+    for (y, row) in element.chunks_exact_mut(size).enumerate() {
+        for (x, dst) in row.iter_mut().enumerate() {
+            if x + y >= n
+                && (last - x) + y >= n
+                && x + (last - y) >= n
+                && (last - x) + (last - y) >= n
+            {
+                *dst = 1;
+            }
+        }
+    }
+    (element, KernelShape::new(size, size))
+}
+
+/// Creates rectangular structuring element rotated around its center
+///
+/// Element is always centered, so it has odd size, and even sides are rounded down
+/// when pixels centers lay exactly on the edge of the rectangle.
+///
+/// # Arguments
+///
+/// * `width`: Rectangle width before rotation
+/// * `height`: Rectangle height before rotation
+/// * `angle`: Rotation angle in degrees, counter-clockwise
+///
+pub fn rotated_rect(width: usize, height: usize, angle: f64) -> (Vec<u8>, KernelShape) {
+    const EPS: f64 = 1e-9;
+
+    let width = width.max(1);
+    let height = height.max(1);
+    let (sin, cos) = angle.to_radians().sin_cos();
+    let half_w = width as f64 / 2.;
+    let half_h = height as f64 / 2.;
+
+    let bound_x = (half_w * cos.abs() + half_h * sin.abs()).ceil() as i64;
+    let bound_y = (half_w * sin.abs() + half_h * cos.abs()).ceil() as i64;
+
+    let is_inside = |dx: i64, dy: i64| {
+        // Image rows go down, so y axis is flipped to keep rotation counter-clockwise
+        let (dx, dy) = (dx as f64, -dy as f64);
+        let u = dx * cos + dy * sin;
+        let v = -dx * sin + dy * cos;
+        u.abs() < half_w - EPS && v.abs() < half_h - EPS
+    };
+
+    let mut radius_x = 0i64;
+    let mut radius_y = 0i64;
+    for dy in -bound_y..=bound_y {
+        for dx in -bound_x..=bound_x {
+            if is_inside(dx, dy) {
+                radius_x = radius_x.max(dx.abs());
+                radius_y = radius_y.max(dy.abs());
+            }
+        }
+    }
+
+    let kernel_width = 2 * radius_x as usize + 1;
+    let kernel_height = 2 * radius_y as usize + 1;
+
+    let mut element = vec![0u8; kernel_width * kernel_height];
+    for (y, row) in element.chunks_exact_mut(kernel_width).enumerate() {
+        for (x, dst) in row.iter_mut().enumerate() {
+            if is_inside(x as i64 - radius_x, y as i64 - radius_y) {
+                *dst = 1;
+            }
+        }
+    }
+
+    (element, KernelShape::new(kernel_width, kernel_height))
+}
+
+/// Longest period of [line], longer periods approximate angles better
+const MAX_LINE_PERIOD: usize = 16;
+
+/// Creates line of `length` points at arbitrary angle
+///
+/// Line is periodic: one period is a Bresenham segment towards the closest direction
+/// with period up to 16 points, and it is repeated along the line. Such element is
+/// processed as a short segment and a periodic line, so count of comparisons per pixel does
+/// not depend on length, horizontal lines included. Time still grows with length, as the image
+/// is padded by it and long lines across rows leave the cache.
+///
+/// Length is counted along the major axis, anchor is the middle point of the line.
+///
+/// # Arguments
+///
+/// * `length`: Count of points in the line
+/// * `angle`: Line angle in degrees, counter-clockwise from the horizontal axis
+///
+pub fn line(length: usize, angle: f64) -> (Vec<u8>, KernelShape) {
+    let length = length.max(1);
+    let (sin, cos) = angle.to_radians().sin_cos();
+    let x_major = cos.abs() >= sin.abs();
+    let slope = if x_major {
+        (sin / cos).abs()
+    } else {
+        (cos / sin).abs()
+    };
+
+    // Best approximation of the slope by `rise / period`, the shortest period on ties
+    let mut period = 1usize;
+    let mut rise = slope.round() as usize;
+    for q in 2..=length.min(MAX_LINE_PERIOD) {
+        let p = (slope * q as f64).round();
+        if (slope - p / q as f64).abs() < (slope - rise as f64 / period as f64).abs() - 1e-12 {
+            period = q;
+            rise = p as usize;
+        }
+    }
+
+    // Image rows go down, so y axis is flipped to keep angle counter-clockwise
+    let sign_x: i64 = if cos < 0. { -1 } else { 1 };
+    let sign_y: i64 = if sin > 0. { -1 } else { 1 };
+
+    let points = (0..length)
+        .map(|i| {
+            let (k, j) = (i / period, i % period);
+            let major = (k * period + j) as i64;
+            let minor = (k * rise + (2 * j * rise + period) / (2 * period)) as i64;
+            if x_major {
+                (major * sign_x, minor * sign_y)
+            } else {
+                (minor * sign_x, major * sign_y)
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let left = points.iter().map(|p| p.0).min().unwrap_or(0);
+    let top = points.iter().map(|p| p.1).min().unwrap_or(0);
+    let right = points.iter().map(|p| p.0).max().unwrap_or(0);
+    let bottom = points.iter().map(|p| p.1).max().unwrap_or(0);
+    let kernel_width = (right - left + 1) as usize;
+    let kernel_height = (bottom - top + 1) as usize;
+
+    let mut element = vec![0u8; kernel_width * kernel_height];
+    for &(x, y) in points.iter() {
+        element[(y - top) as usize * kernel_width + (x - left) as usize] = 1;
+    }
+
+    let anchor = points[(length - 1) / 2];
+    (
+        element,
+        KernelShape::new_with_anchor(
+            kernel_width,
+            kernel_height,
+            (anchor.0 - left) as usize,
+            (anchor.1 - top) as usize,
+        ),
+    )
+}
+
+/// Steps of periodic lines in polygons are limited, so the sum stays solid on small radii
+const MAX_POLYGON_STEP: i64 = 3;
+
+/// Primitive step closest to the angle, in degrees from the horizontal axis,
+/// shorter step wins on ties so mirrored angles get mirrored steps
+fn closest_step(angle: f64) -> (i64, i64) {
+    let mut best = (1i64, 0i64);
+    let mut best_error = f64::MAX;
+    for y in 0..=MAX_POLYGON_STEP {
+        for x in -MAX_POLYGON_STEP..=MAX_POLYGON_STEP {
+            if (y == 0 && x <= 0) || gcd(x.abs(), y) != 1 {
+                continue;
+            }
+            // Image rows go down, so y axis is flipped to keep angle counter-clockwise
+            let step_angle = (-y as f64).atan2(x as f64).to_degrees();
+            let error = (step_angle - angle).rem_euclid(180.);
+            let error = error.min(180. - error);
+            let is_shorter = x * x + y * y < best.0 * best.0 + best.1 * best.1;
+            if error < best_error - 1e-6 || (error < best_error + 1e-6 && is_shorter) {
+                best_error = error;
+                best = (x, y);
+            }
+        }
+    }
+    best
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Sum of periodic lines `{ k * step, -count <= k <= count }` as a mask
+fn sum_of_periodic_lines(lines: &[((i64, i64), i64)]) -> (Vec<u8>, KernelShape) {
+    let radius_x = lines.iter().map(|x| x.0 .0.abs() * x.1).sum::<i64>();
+    let radius_y = lines.iter().map(|x| x.0 .1.abs() * x.1).sum::<i64>();
+    let kernel_width = 2 * radius_x as usize + 1;
+    let kernel_height = 2 * radius_y as usize + 1;
+
+    let mut element = vec![0u8; kernel_width * kernel_height];
+    element[radius_y as usize * kernel_width + radius_x as usize] = 1;
+    let mut spare = vec![0u8; element.len()];
+    for &((step_x, step_y), count) in lines.iter() {
+        spare.fill(0);
+        for y in 0..kernel_height as i64 {
+            for x in 0..kernel_width as i64 {
+                if element[y as usize * kernel_width + x as usize] == 0 {
+                    continue;
+                }
+                for k in -count..=count {
+                    let (nx, ny) = (x + k * step_x, y + k * step_y);
+                    spare[ny as usize * kernel_width + nx as usize] = 1;
+                }
+            }
+        }
+        std::mem::swap(&mut element, &mut spare);
+    }
+
+    (element, KernelShape::new(kernel_width, kernel_height))
+}
+
+/// Rounds counts of periodic lines, lines in a group share the count
+///
+/// Support function of the sum in direction `u` is `sum(count * |step . u|)`,
+/// counts are adjusted one by one while the largest difference from `target` decreases.
+fn round_line_counts(
+    groups: &[&[(i64, i64)]],
+    counts: &[f64],
+    target: impl Fn((f64, f64)) -> f64,
+) -> Vec<((i64, i64), i64)> {
+    let directions = (0..180)
+        .map(|i| (i as f64).to_radians().sin_cos())
+        .collect::<Vec<_>>();
+    let error = |counts: &[f64]| {
+        directions
+            .iter()
+            .map(|&u| {
+                let support = groups
+                    .iter()
+                    .zip(counts.iter())
+                    .map(|(steps, c)| {
+                        steps
+                            .iter()
+                            .map(|s| c * (s.0 as f64 * u.1 + s.1 as f64 * u.0).abs())
+                            .sum::<f64>()
+                    })
+                    .sum::<f64>();
+                (support - target(u)).abs()
+            })
+            .fold(0f64, f64::max)
+    };
+
+    let mut counts = counts.iter().map(|x| x.round()).collect::<Vec<_>>();
+    let mut best_error = error(&counts);
+    loop {
+        let mut improved = false;
+        for j in 0..counts.len() {
+            for delta in [-1., 1.] {
+                if counts[j] + delta < 0. {
+                    continue;
+                }
+                counts[j] += delta;
+                let new_error = error(&counts);
+                if new_error < best_error - 1e-9 {
+                    best_error = new_error;
+                    improved = true;
+                } else {
+                    counts[j] -= delta;
+                }
+            }
+        }
+        if !improved {
+            break;
+        }
+    }
+
+    groups
+        .iter()
+        .zip(counts)
+        .filter(|x| x.1 > 0.)
+        .flat_map(|(steps, c)| steps.iter().map(move |&s| (s, c as i64)))
+        .collect()
+}
+
+/// Counts of periodic lines which sum is the closest to a regular polygon
+/// with lines as sides and with vertices at `radius`
+fn fit_polygon_lines(radius: f64, sides: usize) -> Vec<((i64, i64), i64)> {
+    let orientations = sides / 2;
+    let half_angle = std::f64::consts::PI / sides as f64;
+    let steps = (0..orientations)
+        .map(|j| [closest_step(j as f64 * 180. / orientations as f64)])
+        .collect::<Vec<_>>();
+    let ideal = steps
+        .iter()
+        .map(|s| radius * half_angle.sin() / ((s[0].0 * s[0].0 + s[0].1 * s[0].1) as f64).sqrt())
+        .collect::<Vec<_>>();
+    let groups = steps.iter().map(|x| x.as_slice()).collect::<Vec<_>>();
+    let support = |u: (f64, f64)| {
+        steps
+            .iter()
+            .zip(ideal.iter())
+            .map(|(s, c)| c * (s[0].0 as f64 * u.1 + s[0].1 as f64 * u.0).abs())
+            .sum::<f64>()
+    };
+    round_line_counts(&groups, &ideal, support)
+}
+
+/// Creates regular polygon which is a sum of periodic lines
+///
+/// Polygon is a sum of `sides / 2` periodic lines, with steps closest to evenly spread
+/// angles, each line is one pass over the image, see [disk] for how the cost grows
+/// with the radius.
+/// Sums of lines are centrally symmetric, so odd count of sides is rounded up,
+/// from 4 to 16 sides are supported. Vertices are at `radius` from the center within
+/// two pixels, lines are steps of up to 3 pixels, so sides are not exactly regular.
+///
+/// # Arguments
+///
+/// * `radius`: Distance from the center to the polygon vertices
+/// * `sides`: Count of polygon sides
+///
+pub fn regular_polygon(radius: usize, sides: usize) -> (Vec<u8>, KernelShape) {
+    let mut sides = sides.div_ceil(2).clamp(2, 8) * 2;
+    // On small radii slanted lines are too sparse to fill the gaps between their points,
+    // then fewer sides are used, square is always solid
+    loop {
+        let lines = fit_polygon_lines(radius as f64, sides);
+        let (element, kernel) = sum_of_periodic_lines(&lines);
+        if sides <= 4 || is_solid(&element, kernel) {
+            return (element, kernel);
+        }
+        sides -= 2;
+    }
+}
+
+/// Every row and column is a single run of points
+fn is_solid(element: &[u8], kernel: KernelShape) -> bool {
+    let is_single_run = |items: &mut dyn Iterator<Item = u8>| {
+        let mut runs = 0;
+        let mut previous = 0;
+        for item in items {
+            if item != 0 && previous == 0 {
+                runs += 1;
+            }
+            previous = item;
+        }
+        runs == 1
+    };
+    let rows_solid = (0..kernel.height).all(|y| {
+        is_single_run(
+            &mut element[y * kernel.width..(y + 1) * kernel.width]
+                .iter()
+                .copied(),
+        )
+    });
+    let columns_solid = (0..kernel.width)
+        .all(|x| is_single_run(&mut element.iter().skip(x).step_by(kernel.width).copied()));
+    rows_solid && columns_solid
+}
+
+/// Groups of periodic line steps used by disks, mirrored steps share the count
+const DISK_LINE_GROUPS: [&[(i64, i64)]; 4] = [
+    &[(1, 0), (0, 1)],
+    &[(1, 1), (-1, 1)],
+    &[(2, 1), (-2, 1), (1, 2), (-1, 2)],
+    &[(3, 1), (-3, 1), (1, 3), (-1, 3)],
+];
+
+/// Accuracy of disks that are made of periodic lines
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Default)]
+pub enum DiskAccuracy {
+    /// Octagon of 4 lines, boundary is within 4% of the radius plus 2 pixels from the circle
+    Low,
+    /// 16-gon of 8 lines, boundary is within 1.4% of the radius plus 2 pixels from the circle
+    Medium,
+    /// 24-gon of 12 lines, boundary is within 0.7% of the radius plus 2 pixels from the circle
+    #[default]
+    High,
+}
+
+impl DiskAccuracy {
+    /// Counts of lines in [DISK_LINE_GROUPS] per unit of radius, chosen so
+    /// the polygon boundary deviates from the circle the least in both directions
+    fn counts_per_radius(self) -> &'static [f64] {
+        match self {
+            DiskAccuracy::Low => &[0.39782, 0.28130],
+            DiskAccuracy::Medium => &[0.23229, 0.09812, 0.09300],
+            DiskAccuracy::High => &[0.16141, 0.11352, 0.05221, 0.03647],
+        }
+    }
+
+    fn lower(self) -> Option<DiskAccuracy> {
+        match self {
+            DiskAccuracy::Low => None,
+            DiskAccuracy::Medium => Some(DiskAccuracy::Low),
+            DiskAccuracy::High => Some(DiskAccuracy::Medium),
+        }
+    }
+}
+
+/// Creates disk approximated by a sum of periodic lines
+///
+/// Disk is a polygon which boundary goes both inside and outside of the circle,
+/// see [DiskAccuracy] for the tolerance. On small radii slanted lines leave gaps
+/// between their points, then lower accuracy is used, and [circle] is the last resort,
+/// such radii are small enough to be processed directly.
+///
+/// Disk is split into periodic lines, one pass over the image each, and a residual of
+/// a few dozen points that is processed by chords. Count of lines grows with the radius
+/// up to the count of the accuracy, [DiskAccuracy::High] reaches its 12 lines at about
+/// radius 60. Past that each line costs the same whatever its length is, so cost only grows
+/// with the image padded by the radius on each side that lines run over.
+///
+/// # Arguments
+///
+/// * `radius`: Disk radius
+/// * `accuracy`: How close to the circle the polygon is
+///
+pub fn disk(radius: usize, accuracy: DiskAccuracy) -> (Vec<u8>, KernelShape) {
+    let mut accuracy = accuracy;
+    loop {
+        let counts = accuracy
+            .counts_per_radius()
+            .iter()
+            .map(|x| x * radius as f64)
+            .collect::<Vec<_>>();
+        let groups = &DISK_LINE_GROUPS[..counts.len()];
+        let lines = round_line_counts(groups, &counts, |_| radius as f64);
+        let (element, kernel) = sum_of_periodic_lines(&lines);
+        if is_solid(&element, kernel) {
+            return (element, kernel);
+        }
+        match accuracy.lower() {
+            Some(lower) => accuracy = lower,
+            None => return circle(radius),
+        }
+    }
+}
+
+/// Weights of non-flat element over the circle, points outside are `f32::NEG_INFINITY`
+fn circle_weights(radius: usize, weight: impl Fn(f64) -> f64) -> (Vec<f32>, KernelShape) {
+    let (element, kernel) = circle(radius);
+    let weights = element
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| {
+            if x == 0 {
+                return f32::NEG_INFINITY;
+            }
+            let dx = (i % kernel.width) as f64 - kernel.anchor_x as f64;
+            let dy = (i / kernel.width) as f64 - kernel.anchor_y as f64;
+            weight((dx * dx + dy * dy).sqrt()) as f32
+        })
+        .collect();
+    (weights, kernel)
+}
+
+/// Creates non-flat paraboloid structuring element
+///
+/// Weights are `-(dx^2 + dy^2) / (2 * scale)` over the circle of `radius`, points outside
+/// of the circle are `f32::NEG_INFINITY`.
+///
+/// # Arguments
+///
+/// * `radius`: Radius of the circle that bounds the paraboloid
+/// * `scale`: Paraboloid scale, larger scale makes it flatter
+///
+pub fn paraboloid(radius: usize, scale: f32) -> (Vec<f32>, KernelShape) {
+    let scale = scale as f64;
+    circle_weights(radius, |d| -d * d / (2. * scale))
+}
+
+/// Creates non-flat ball-shaped structuring element, as used by rolling ball filters
+///
+/// Weights are a spherical cap `height * (sqrt(1 - d^2 / radius^2) - 1)` over the circle
+/// of `radius`, so the center has zero weight and the rim is `-height`, points outside
+/// of the circle are `f32::NEG_INFINITY`.
+///
+/// # Arguments
+///
+/// * `radius`: Ball radius
+/// * `height`: Ball height, in pixel values
+///
+pub fn ball(radius: usize, height: f32) -> (Vec<f32>, KernelShape) {
+    let radius_f = radius.max(1) as f64;
+    let height = height as f64;
+    circle_weights(radius, |d| {
+        let t = (d / radius_f).min(1.);
+        height * ((1. - t * t).sqrt() - 1.)
+    })
 }
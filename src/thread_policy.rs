@@ -29,15 +29,47 @@
 
 use crate::ImageSize;
 use rayon::ThreadPool;
+use std::sync::Arc;
 
-#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Default)]
+/// Threads usage policy
+///
+/// [MorphologyThreadingPolicy::Fixed] and [MorphologyThreadingPolicy::Adaptive] build a new
+/// thread pool on each call, when morphology is called often prefer
+/// [MorphologyThreadingPolicy::Global] or [MorphologyThreadingPolicy::Pool] with a pool
+/// the application already manages.
+#[derive(Clone, Debug, Default)]
 pub enum MorphologyThreadingPolicy {
+    /// Everything is done on the calling thread
     Single,
+    /// New pool with the fixed number of threads
     Fixed(u8),
+    /// New pool with the number of threads depending on the image size
     #[default]
     Adaptive,
+    /// Rayon pool the call is made from, that is the global pool unless called
+    /// inside of [ThreadPool::install]
+    Global,
+    /// Caller-supplied pool
+    Pool(Arc<ThreadPool>),
 }
 
+impl PartialEq for MorphologyThreadingPolicy {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (MorphologyThreadingPolicy::Single, MorphologyThreadingPolicy::Single) => true,
+            (MorphologyThreadingPolicy::Fixed(a), MorphologyThreadingPolicy::Fixed(b)) => a == b,
+            (MorphologyThreadingPolicy::Adaptive, MorphologyThreadingPolicy::Adaptive) => true,
+            (MorphologyThreadingPolicy::Global, MorphologyThreadingPolicy::Global) => true,
+            (MorphologyThreadingPolicy::Pool(a), MorphologyThreadingPolicy::Pool(b)) => {
+                Arc::ptr_eq(a, b)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Eq for MorphologyThreadingPolicy {}
+
 impl MorphologyThreadingPolicy {
     pub fn get_threads_count(&self, for_size: ImageSize) -> usize {
         match self {
@@ -48,20 +80,73 @@ impl MorphologyThreadingPolicy {
                 let new_box_size = for_size.height * for_size.width;
                 (new_box_size / box_size).clamp(1, 16)
             }
+            MorphologyThreadingPolicy::Global => rayon::current_num_threads(),
+            MorphologyThreadingPolicy::Pool(pool) => pool.current_num_threads(),
         }
     }
 
+    /// Builds a new pool with [MorphologyThreadingPolicy::get_threads_count] threads,
+    /// `None` for [MorphologyThreadingPolicy::Single] or when the pool can't be built
+    #[deprecated(
+        note = "morphology functions resolve the policy themselves, Global and Pool policies reuse existing pools"
+    )]
     pub fn get_pool(&self, for_size: ImageSize) -> Option<ThreadPool> {
         if *self == MorphologyThreadingPolicy::Single {
             return None;
         }
-        let threads_count = self.get_threads_count(for_size);
-        match rayon::ThreadPoolBuilder::new()
-            .num_threads(threads_count)
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(self.get_threads_count(for_size))
             .build()
-        {
-            Ok(pool) => Some(pool),
-            Err(_) => None,
+            .ok()
+    }
+
+    pub(crate) fn threading_pool(&self, for_size: ImageSize) -> ThreadingPool {
+        match self {
+            MorphologyThreadingPolicy::Single => ThreadingPool::Single,
+            MorphologyThreadingPolicy::Global => ThreadingPool::Global,
+            MorphologyThreadingPolicy::Pool(pool) => ThreadingPool::Pool(pool.clone()),
+            MorphologyThreadingPolicy::Fixed(_) | MorphologyThreadingPolicy::Adaptive => {
+                let threads_count = self.get_threads_count(for_size);
+                rayon::ThreadPoolBuilder::new()
+                    .num_threads(threads_count)
+                    .build()
+                    .map_or(ThreadingPool::Single, |pool| {
+                        ThreadingPool::Pool(Arc::new(pool))
+                    })
+            }
+        }
+    }
+}
+
+/// Pool resolved from [MorphologyThreadingPolicy]
+pub(crate) enum ThreadingPool {
+    Single,
+    Global,
+    Pool(Arc<ThreadPool>),
+}
+
+impl ThreadingPool {
+    pub(crate) fn is_parallel(&self) -> bool {
+        !matches!(self, ThreadingPool::Single)
+    }
+
+    /// Count of parallel tasks, each of them owns its scratch buffers
+    pub(crate) fn tasks_count(&self) -> usize {
+        match self {
+            ThreadingPool::Single => 1,
+            ThreadingPool::Global => rayon::current_num_threads(),
+            ThreadingPool::Pool(pool) => pool.current_num_threads(),
+        }
+    }
+
+    pub(crate) fn install<OP, R>(&self, op: OP) -> R
+    where
+        OP: FnOnce() -> R + Send,
+        R: Send,
+    {
+        match self {
+            ThreadingPool::Pool(pool) => pool.install(op),
+            ThreadingPool::Single | ThreadingPool::Global => op(),
         }
     }
 }
@@ -0,0 +1,145 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+use crate::neon::transpose_neon;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use crate::sse::transpose_sse;
+
+/// Handler that writes `width` items of `height` rows of `src` as `height` items of `width`
+/// rows of `dst`, arguments are `src`, `src_stride`, `dst`, `dst_stride`, `width`, `height`
+pub(crate) type TransposeHandler<T> = fn(&[T], usize, &mut [T], usize, usize, usize);
+
+/// Side of the square tiles scalar transposition goes by
+const SCALAR_TILE: usize = 8;
+
+/// Scalar transposition, available for every sample type
+pub(crate) fn scalar_transpose<T: Copy>(
+    src: &[T],
+    src_stride: usize,
+    dst: &mut [T],
+    dst_stride: usize,
+    width: usize,
+    height: usize,
+) {
+    for y0 in (0..height).step_by(SCALAR_TILE) {
+        for x0 in (0..width).step_by(SCALAR_TILE) {
+            for y in y0..(y0 + SCALAR_TILE).min(height) {
+                let src = &src[y * src_stride..];
+                for x in x0..(x0 + SCALAR_TILE).min(width) {
+                    dst[x * dst_stride + y] = src[x];
+                }
+            }
+        }
+    }
+}
+
+/// Transposes square blocks of `N` items with `block`, blocks on the edges that are only
+/// partly inside the image go through a padded copy.
+///
+/// # Safety
+///
+/// `block` must read `N` rows of `N` items from its source with rows of the given stride apart,
+/// and write `N` rows of `N` items to its destination with rows of the given stride apart.
+#[inline(always)]
+pub(crate) unsafe fn transpose_blocks<T: Copy + Default, const N: usize>(
+    src: &[T],
+    src_stride: usize,
+    dst: &mut [T],
+    dst_stride: usize,
+    width: usize,
+    height: usize,
+    block: impl Fn(*const T, usize, *mut T, usize),
+) {
+    if width == 0 || height == 0 {
+        return;
+    }
+    assert!(src.len() >= (height - 1) * src_stride + width);
+    assert!(dst.len() >= (width - 1) * dst_stride + height);
+
+    // Destination rows are filled one group after another, so only `N` of them are written
+    // at once whatever the destination stride is
+    for x in (0..width).step_by(N) {
+        let block_width = N.min(width - x);
+        for y in (0..height).step_by(N) {
+            let block_height = N.min(height - y);
+            if block_width == N && block_height == N {
+                block(
+                    src.as_ptr().add(y * src_stride + x),
+                    src_stride,
+                    dst.as_mut_ptr().add(x * dst_stride + y),
+                    dst_stride,
+                );
+                continue;
+            }
+            let mut padded = [[T::default(); N]; N];
+            for (i, row) in padded.iter_mut().take(block_height).enumerate() {
+                let start = (y + i) * src_stride + x;
+                row[..block_width].copy_from_slice(&src[start..start + block_width]);
+            }
+            let mut transposed = [[T::default(); N]; N];
+            block(
+                padded.as_ptr() as *const T,
+                N,
+                transposed.as_mut_ptr() as *mut T,
+                N,
+            );
+            for (i, row) in transposed.iter().take(block_width).enumerate() {
+                let start = (x + i) * dst_stride + y;
+                dst[start..start + block_height].copy_from_slice(&row[..block_height]);
+            }
+        }
+    }
+}
+
+/// Index of the row loaded into register `i` of the block transposition network,
+/// bits of the index are reversed, so `log2(N)` interleaving steps give rows in order
+#[inline(always)]
+pub(crate) const fn network_row(i: usize, n: usize) -> usize {
+    if n <= 1 {
+        return i;
+    }
+    i.reverse_bits() >> (usize::BITS - n.trailing_zeros())
+}
+
+/// Vectorised transposition of sample types made of 1, 2, 4 or 8 bytes of plain data,
+/// only built-in sample types use it
+pub(crate) fn transpose_handler<T: Copy + Default>() -> TransposeHandler<T> {
+    let mut _dispatcher: TransposeHandler<T> = scalar_transpose::<T>;
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    {
+        _dispatcher = transpose_neon::<T>;
+    }
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if std::arch::is_x86_feature_detected!("sse4.1") {
+            _dispatcher = transpose_sse::<T>;
+        }
+    }
+    _dispatcher
+}
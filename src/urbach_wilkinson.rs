@@ -0,0 +1,277 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::filter_op_declare::Arena;
+use crate::flat_se::Chord;
+use crate::img_size::image_rows_mut;
+use crate::morph_base::MorphNativeOp;
+use crate::morph_rows::MorphRowsHandler;
+use crate::thread_policy::ThreadingPool;
+use crate::ImageSize;
+use rayon::prelude::*;
+
+/// Level of the table that covers a chord with two overlapping reads
+fn chord_level(length: usize) -> usize {
+    length.ilog2() as usize
+}
+
+/// Rough count of row passes per output row, in units of one direct filter element.
+///
+/// Table levels are built once per arena row and reused by all chords that cross it,
+/// `None` when all chords are single points and tables give nothing.
+pub(crate) fn chords_cost(chords: &[Chord]) -> Option<usize> {
+    let max_level = chords
+        .iter()
+        .map(|x| chord_level(x.length))
+        .max()
+        .unwrap_or(0);
+    if max_level == 0 {
+        return None;
+    }
+    let passes = chords
+        .iter()
+        .map(|x| if x.length.is_power_of_two() { 1 } else { 2 })
+        .sum::<usize>();
+    // Direct filter keeps accumulator in registers, so it is about twice as cheap per element
+    Some((passes + max_level) * 2)
+}
+
+pub(crate) fn chords_are_cheaper(chords: &[Chord], elements: usize) -> bool {
+    chords_cost(chords).is_some_and(|cost| cost < elements)
+}
+
+/// Highest table level and count of arena rows that chords cover
+fn chords_window(chords: &[Chord]) -> (usize, usize) {
+    let levels = chords
+        .iter()
+        .map(|x| chord_level(x.length))
+        .max()
+        .unwrap_or(0);
+    // Chords may not span the whole kernel when element was decomposed before
+    let top = chords.iter().map(|x| x.start.y).min().unwrap_or(0);
+    let bottom = chords.iter().map(|x| x.start.y).max().unwrap_or(0);
+    (levels, (bottom - top) as usize + 1)
+}
+
+/// Buffers of one stripe, allocated once and reused between runs
+pub(crate) struct ChordScratch<T> {
+    tables: Vec<T>,
+    accumulator: Vec<T>,
+    spare: Vec<T>,
+}
+
+impl<T: Copy + Default> ChordScratch<T> {
+    pub(crate) fn new(arena_width: usize, width: usize, chords: &[Chord]) -> Self {
+        let (levels, window_height) = chords_window(chords);
+        ChordScratch {
+            tables: vec![T::default(); levels * window_height * arena_width],
+            accumulator: vec![T::default(); width],
+            spare: vec![T::default(); width],
+        }
+    }
+}
+
+/// Running min/max tables of power-of-two lengths for the last `kernel_height` arena rows.
+///
+/// Level `k` of a row holds `op(row[x..x + 2^k])`, level 0 is the arena row itself.
+struct ChordTables<'a, T> {
+    arena: &'a Arena<T>,
+    levels: usize,
+    rows: usize,
+    tables: &'a mut [T],
+}
+
+impl<'a, T> ChordTables<'a, T>
+where
+    T: Copy + Default,
+{
+    fn new(arena: &'a Arena<T>, levels: usize, rows: usize, tables: &'a mut [T]) -> Self {
+        ChordTables {
+            arena,
+            levels,
+            rows,
+            tables,
+        }
+    }
+
+    fn arena_row(&self, y: usize) -> &[T] {
+        let width = self.arena.width;
+        &self.arena.arena[y * width..(y + 1) * width]
+    }
+
+    fn level_offset(&self, y: usize, level: usize) -> usize {
+        ((y % self.rows) * self.levels + level - 1) * self.arena.width
+    }
+
+    fn fill(&mut self, y: usize, rows_op: MorphRowsHandler<T>) {
+        let arena = self.arena;
+        let width = arena.width;
+        for level in 1..=self.levels {
+            let span = 1usize << (level - 1);
+            let valid = width + 1 - span * 2;
+            let offset = self.level_offset(y, level);
+            let previous = if level > 1 {
+                self.level_offset(y, level - 1)
+            } else {
+                0
+            };
+            let (head, tail) = self.tables.split_at_mut(offset);
+            let previous = if level == 1 {
+                &arena.arena[y * width..(y + 1) * width]
+            } else {
+                &head[previous..previous + width]
+            };
+            rows_op(
+                &previous[..valid],
+                &previous[span..span + valid],
+                &mut tail[..valid],
+            );
+        }
+    }
+
+    fn level(&self, y: usize, level: usize) -> &[T] {
+        if level == 0 {
+            self.arena_row(y)
+        } else {
+            let offset = self.level_offset(y, level);
+            &self.tables[offset..offset + self.arena.width]
+        }
+    }
+}
+
+/// Processes rows `start_row..` of the output, `dst` rows are `dst_stride` items apart,
+/// tables are filled for each arena row once when it enters the kernel window.
+fn make_morphology_chords_stripe<T>(
+    arena: &Arena<T>,
+    dst: &mut [T],
+    dst_stride: usize,
+    start_row: usize,
+    width: usize,
+    chords: &[Chord],
+    scratch: &mut ChordScratch<T>,
+    rows_op: MorphRowsHandler<T>,
+) where
+    T: Copy + Default,
+{
+    let (levels, window_height) = chords_window(chords);
+    let top = chords.iter().map(|x| x.start.y).min().unwrap_or(0);
+    let first_row = (arena.pad_top as i32 + top) as usize;
+
+    let ChordScratch {
+        tables,
+        accumulator,
+        spare,
+    } = scratch;
+
+    let mut tables = ChordTables::new(arena, levels, window_height, tables);
+    for y in start_row..start_row + window_height - 1 {
+        tables.fill(first_row + y, rows_op);
+    }
+
+    let pad_left = arena.pad_left as i32;
+    let pad_top = arena.pad_top as i32;
+
+    for (y, dst_row) in image_rows_mut(dst, dst_stride, width).enumerate() {
+        let y = start_row + y;
+        tables.fill(first_row + y + window_height - 1, rows_op);
+
+        for (i, chord) in chords.iter().enumerate() {
+            let level = chord_level(chord.length);
+            let span = 1usize << level;
+            let row = tables.level((y as i32 + pad_top + chord.start.y) as usize, level);
+            let start = (pad_left + chord.start.x) as usize;
+            let head = &row[start..start + width];
+            let tail_start = start + chord.length - span;
+            let tail = &row[tail_start..tail_start + width];
+
+            if i == 0 {
+                rows_op(head, tail, accumulator);
+            } else {
+                rows_op(accumulator, head, spare);
+                if span == chord.length {
+                    std::mem::swap(accumulator, spare);
+                } else {
+                    rows_op(spare, tail, accumulator);
+                }
+            }
+        }
+
+        dst_row.copy_from_slice(accumulator);
+    }
+}
+
+/// Morphology with a flat structuring element decomposed into horizontal chords
+/// as in Urbach & Wilkinson "Efficient 2-D grayscale morphological transformations
+/// with arbitrary flat structuring elements".
+///
+/// Each chord costs at most two reads of precomputed power-of-two tables,
+/// so the cost scales with the number of chords instead of the area.
+///
+/// Output is split into one stripe of rows per scratch.
+pub(crate) fn make_morphology_chords<T, const OP_TYPE: u8>(
+    arena: &Arena<T>,
+    dst: &mut [T],
+    dst_stride: usize,
+    image_size: ImageSize,
+    chords: &[Chord],
+    pool: &ThreadingPool,
+    scratch: &mut [ChordScratch<T>],
+    rows_op: MorphRowsHandler<T>,
+) where
+    T: Copy + Default + Send + Sync + MorphNativeOp<T>,
+{
+    let width = image_size.width;
+    let rows_per_task = image_size.height.div_ceil(scratch.len()).max(1);
+
+    let stripe = |(i, (dst, scratch)): (usize, (&mut [T], &mut ChordScratch<T>))| {
+        make_morphology_chords_stripe(
+            arena,
+            dst,
+            dst_stride,
+            i * rows_per_task,
+            width,
+            chords,
+            scratch,
+            rows_op,
+        );
+    };
+
+    if pool.is_parallel() {
+        pool.install(|| {
+            dst.par_chunks_mut(rows_per_task * dst_stride)
+                .zip(scratch.par_iter_mut())
+                .enumerate()
+                .for_each(stripe);
+        });
+    } else {
+        dst.chunks_mut(rows_per_task * dst_stride)
+            .zip(scratch.iter_mut())
+            .enumerate()
+            .for_each(stripe);
+    }
+}
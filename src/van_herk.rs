@@ -0,0 +1,462 @@
+/*
+ * Copyright (c) Radzivon Bartoshyk. All rights reserved.
+ *
+ * Redistribution and use in source and binary forms, with or without modification,
+ * are permitted provided that the following conditions are met:
+ *
+ * 1.  Redistributions of source code must retain the above copyright notice, this
+ * list of conditions and the following disclaimer.
+ *
+ * 2.  Redistributions in binary form must reproduce the above copyright notice,
+ * this list of conditions and the following disclaimer in the documentation
+ * and/or other materials provided with the distribution.
+ *
+ * 3.  Neither the name of the copyright holder nor the names of its
+ * contributors may be used to endorse or promote products derived from
+ * this software without specific prior written permission.
+ *
+ * THIS SOFTWARE IS PROVIDED BY THE COPYRIGHT HOLDERS AND CONTRIBUTORS "AS IS"
+ * AND ANY EXPRESS OR IMPLIED WARRANTIES, INCLUDING, BUT NOT LIMITED TO, THE
+ * IMPLIED WARRANTIES OF MERCHANTABILITY AND FITNESS FOR A PARTICULAR PURPOSE ARE
+ * DISCLAIMED. IN NO EVENT SHALL THE COPYRIGHT HOLDER OR CONTRIBUTORS BE LIABLE
+ * FOR ANY DIRECT, INDIRECT, INCIDENTAL, SPECIAL, EXEMPLARY, OR CONSEQUENTIAL
+ * DAMAGES (INCLUDING, BUT NOT LIMITED TO, PROCUREMENT OF SUBSTITUTE GOODS OR
+ * SERVICES; LOSS OF USE, DATA, OR PROFITS; OR BUSINESS INTERRUPTION) HOWEVER
+ * CAUSED AND ON ANY THEORY OF LIABILITY, WHETHER IN CONTRACT, STRICT LIABILITY,
+ * OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE USE
+ * OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
+ */
+use crate::filter_op_declare::Arena;
+use crate::img_size::image_rows_mut;
+use crate::morph_base::MorphNativeOp;
+use crate::morph_rows::MorphRowsHandler;
+use crate::structuring_element::KernelShape;
+use crate::thread_policy::ThreadingPool;
+use crate::transpose::TransposeHandler;
+use crate::ImageSize;
+use rayon::prelude::*;
+
+/// Windows up to this size are reduced with `ceil(log2(window))` shifted passes, at most 3,
+/// longer ones with van Herk/Gil-Werman blocks at 3 comparisons per item
+const SHIFTED_WINDOW_LIMIT: usize = 8;
+
+/// Bytes of one item of all rows transposed together, so each van Herk/Gil-Werman step
+/// is vectorised across them
+const TILE_BYTES: usize = 64;
+
+/// Least columns of rows transposed at once
+const GROUP_COLUMNS: usize = 64;
+
+/// Rows transposed together for items of `T`
+const fn tile_rows<T>() -> usize {
+    let rows = TILE_BYTES / std::mem::size_of::<T>();
+    if rows > 1 {
+        rows
+    } else {
+        1
+    }
+}
+
+pub(crate) struct RowScratch<T> {
+    first: Vec<T>,
+    second: Vec<T>,
+    /// Transposed blocks of a tile, then its output, with prefix and suffix of the blocks
+    tile: Vec<T>,
+    prefix: Vec<T>,
+    suffix: Vec<T>,
+}
+
+impl<T: Copy + Default> RowScratch<T> {
+    /// Scratch for rows of up to `length` items reduced over windows of up to `window` items
+    pub(crate) fn new(length: usize, window: usize) -> Self {
+        let (row_size, span_size, tile_size) = if window > SHIFTED_WINDOW_LIMIT {
+            let span = group_span(window) * tile_rows::<T>();
+            (0, span, span + window * tile_rows::<T>())
+        } else {
+            (length, 0, 0)
+        };
+        Self {
+            first: vec![T::default(); row_size],
+            second: vec![T::default(); row_size],
+            tile: vec![T::default(); tile_size],
+            prefix: vec![T::default(); tile_size],
+            suffix: vec![T::default(); span_size],
+        }
+    }
+}
+
+/// Running min/max over `window` consecutive items of each row of `src`.
+///
+/// Output rows are `width` items of `dst` that are `dst_stride` items apart, `src` rows
+/// are `src_stride` items apart and must have at least `width + window - 1` items.
+///
+/// Windows up to [SHIFTED_WINDOW_LIMIT] take at most 3 shifted passes over each row.
+/// Longer windows are done with van Herk/Gil-Werman algorithm on tiles of [TILE_BYTES] wide rows
+/// transposed block by block, so prefix and suffix scans step along the row with each
+/// comparison vectorised across the tile. Every item then costs 3 comparisons and two
+/// transpositions, whatever the window is.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn morph_rows_window<T>(
+    src: &[T],
+    src_stride: usize,
+    dst: &mut [T],
+    dst_stride: usize,
+    width: usize,
+    window: usize,
+    scratch: &mut RowScratch<T>,
+    rows_op: MorphRowsHandler<T>,
+    transpose: TransposeHandler<T>,
+) where
+    T: Copy + Default,
+{
+    if window > SHIFTED_WINDOW_LIMIT {
+        let tile_rows = tile_rows::<T>();
+        for (i, dst) in dst.chunks_mut(tile_rows * dst_stride).enumerate() {
+            let src = &src[i * tile_rows * src_stride..];
+            van_herk_rows(
+                src, src_stride, dst, dst_stride, width, window, scratch, rows_op, transpose,
+            );
+        }
+        return;
+    }
+    for (y, dst) in image_rows_mut(dst, dst_stride, width).enumerate() {
+        let src = &src[y * src_stride..];
+        if window == 1 {
+            dst.copy_from_slice(&src[..width]);
+        } else {
+            shifted_row(src, dst, window, scratch, rows_op);
+        }
+    }
+}
+
+/// Columns of rows transposed at once for windows of `window` items, whole blocks
+/// of at least [GROUP_COLUMNS] columns, so few transposed blocks are cut by the edges.
+const fn group_span(window: usize) -> usize {
+    GROUP_COLUMNS.div_ceil(window) * window
+}
+
+/// Van Herk/Gil-Werman blocks of `window` items along up to [TILE_BYTES] wide rows.
+///
+/// Rows are transposed by groups of whole blocks, so item `x` of every row is contiguous,
+/// and scanned with [column_prefix] and [column_suffix] the same way as columns are.
+/// Output at `x` is the suffix of its block at `x` combined with the prefix of the next block
+/// at `x + window - 1`, or of its own block when `x` starts it. The first block after a group
+/// is transposed with it, and moved to the start of the tile for the next group.
+#[allow(clippy::too_many_arguments)]
+fn van_herk_rows<T>(
+    src: &[T],
+    src_stride: usize,
+    dst: &mut [T],
+    dst_stride: usize,
+    width: usize,
+    window: usize,
+    scratch: &mut RowScratch<T>,
+    rows_op: MorphRowsHandler<T>,
+    transpose: TransposeHandler<T>,
+) where
+    T: Copy + Default,
+{
+    let rows = dst.len().div_ceil(dst_stride);
+    let length = width + window - 1;
+    let span = group_span(window);
+    let block_size = window * rows;
+
+    let mut carried = 0;
+    for start in (0..width).step_by(span) {
+        let columns = (span + window).min(length - start);
+        let outputs = span.min(width - start);
+        let tile = &mut scratch.tile[..columns * rows];
+        let prefix = &mut scratch.prefix[..columns * rows];
+        let suffix = &mut scratch.suffix[..outputs.div_ceil(window) * block_size];
+
+        transpose(
+            &src[start + carried..],
+            src_stride,
+            &mut tile[carried * rows..],
+            rows,
+            columns - carried,
+            rows,
+        );
+
+        for (tile, prefix) in tile.chunks(block_size).zip(prefix.chunks_mut(block_size)) {
+            column_prefix(tile, prefix, rows, rows_op);
+        }
+        for (tile, suffix) in tile.chunks(block_size).zip(suffix.chunks_mut(block_size)) {
+            column_suffix(tile, suffix, rows, rows_op);
+        }
+
+        let shift = (window - 1) * rows;
+        let tile_out = &mut tile[..outputs * rows];
+        rows_op(
+            &suffix[..outputs * rows],
+            &prefix[shift..shift + outputs * rows],
+            tile_out,
+        );
+        transpose(tile_out, rows, &mut dst[start..], dst_stride, rows, outputs);
+
+        if columns == span + window {
+            tile.copy_within(span * rows.., 0);
+            carried = window;
+        }
+    }
+}
+
+/// Doubles covered span on each pass: `acc[x] = op(acc[x], acc[x + span])`,
+/// then the last pass overlaps two spans to cover exactly `window` items.
+fn shifted_row<T>(
+    src: &[T],
+    dst: &mut [T],
+    window: usize,
+    scratch: &mut RowScratch<T>,
+    rows_op: MorphRowsHandler<T>,
+) where
+    T: Copy,
+{
+    let width = dst.len();
+    let length = width + window - 1;
+
+    let valid = length - 1;
+    rows_op(
+        &src[..valid],
+        &src[1..1 + valid],
+        &mut scratch.first[..valid],
+    );
+    let mut span = 2;
+    let mut current = &mut scratch.first;
+    let mut spare = &mut scratch.second;
+
+    while span * 2 <= window {
+        let valid = length - span * 2 + 1;
+        rows_op(
+            &current[..valid],
+            &current[span..span + valid],
+            &mut spare[..valid],
+        );
+        std::mem::swap(&mut current, &mut spare);
+        span *= 2;
+    }
+
+    let shift = window - span;
+    rows_op(&current[..width], &current[shift..shift + width], dst);
+}
+
+/// Running op down the columns of a block, row by row
+fn column_prefix<T>(src: &[T], prefix: &mut [T], width: usize, rows_op: MorphRowsHandler<T>)
+where
+    T: Copy,
+{
+    let rows = src.len() / width;
+    prefix[..width].copy_from_slice(&src[..width]);
+    for y in 1..rows {
+        let (previous, current) = prefix.split_at_mut(y * width);
+        rows_op(
+            &previous[(y - 1) * width..],
+            &src[y * width..(y + 1) * width],
+            &mut current[..width],
+        );
+    }
+}
+
+/// Running op up the columns of a block, row by row
+fn column_suffix<T>(src: &[T], suffix: &mut [T], width: usize, rows_op: MorphRowsHandler<T>)
+where
+    T: Copy,
+{
+    let rows = src.len() / width;
+    let last = (rows - 1) * width;
+    suffix[last..last + width].copy_from_slice(&src[last..]);
+    for y in (0..rows - 1).rev() {
+        let (current, next) = suffix.split_at_mut((y + 1) * width);
+        rows_op(
+            &next[..width],
+            &src[y * width..(y + 1) * width],
+            &mut current[y * width..],
+        );
+    }
+}
+
+/// Buffers of one stripe, allocated once and reused between runs
+pub(crate) struct RectScratch<T> {
+    row: RowScratch<T>,
+    /// Arena rows reduced down the columns, waiting to be reduced along the rows by tiles
+    columns: Vec<T>,
+    prefix: Vec<T>,
+    suffix: Vec<T>,
+}
+
+impl<T: Copy + Default> RectScratch<T> {
+    pub(crate) fn new(arena_width: usize, kernel_size: KernelShape) -> Self {
+        let (batch_size, block_size) = if kernel_size.height > 1 {
+            // Less than a tile of rows waits while the next block is added
+            let batch_rows = tile_rows::<T>() - 1 + kernel_size.height;
+            (batch_rows * arena_width, kernel_size.height * arena_width)
+        } else {
+            (0, 0)
+        };
+        RectScratch {
+            row: RowScratch::new(arena_width, kernel_size.width),
+            columns: vec![T::default(); batch_size],
+            prefix: vec![T::default(); block_size],
+            suffix: vec![T::default(); block_size],
+        }
+    }
+}
+
+/// Processes rows `start_row..` of the output, `dst` rows are `dst_stride` items apart.
+///
+/// Arena columns are reduced first, block by block of `kernel_height` arena rows: output
+/// row `y` inside a block is the suffix of its own block at `y` combined with the prefix
+/// of the next block at `y + kernel_height - 1`. Such rows are then reduced along the rows
+/// into the output by whole tiles of [morph_rows_window].
+fn make_morphology_rect_stripe<T>(
+    arena: &Arena<T>,
+    dst: &mut [T],
+    dst_stride: usize,
+    start_row: usize,
+    width: usize,
+    kernel_size: KernelShape,
+    scratch: &mut RectScratch<T>,
+    rows_op: MorphRowsHandler<T>,
+    transpose: TransposeHandler<T>,
+) where
+    T: Copy + Default,
+{
+    let arena_width = arena.width;
+    let kernel_width = kernel_size.width;
+    let kernel_height = kernel_size.height;
+
+    let RectScratch {
+        row,
+        columns,
+        prefix,
+        suffix,
+    } = scratch;
+
+    if kernel_height == 1 {
+        morph_rows_window(
+            &arena.arena[start_row * arena_width..],
+            arena_width,
+            dst,
+            dst_stride,
+            width,
+            kernel_width,
+            row,
+            rows_op,
+            transpose,
+        );
+        return;
+    }
+
+    let rows = dst.len().div_ceil(dst_stride);
+    let tile_rows = tile_rows::<T>();
+    // Rows reduced down the columns and not yet along the rows, and rows done
+    let mut ready = 0;
+    let mut done = 0;
+
+    for block_start in (0..rows).step_by(kernel_height) {
+        let y = start_row + block_start;
+        let block_rows = kernel_height.min(rows - block_start);
+        let block = &arena.arena[y * arena_width..(y + kernel_height) * arena_width];
+        column_suffix(block, suffix, arena_width, rows_op);
+
+        // Only rows of the next block that windows of this one reach are needed
+        let tail = (block_rows - 1) * arena_width;
+        let next = (y + kernel_height) * arena_width;
+        if tail > 0 {
+            column_prefix(
+                &arena.arena[next..next + tail],
+                &mut prefix[..tail],
+                arena_width,
+                rows_op,
+            );
+        }
+
+        let output = &mut columns[ready * arena_width..][..tail + arena_width];
+        let (first, output) = output.split_at_mut(arena_width);
+        first.copy_from_slice(&suffix[..arena_width]);
+        rows_op(
+            &suffix[arena_width..tail + arena_width],
+            &prefix[..tail],
+            output,
+        );
+        ready += block_rows;
+
+        // Rows pass takes whole tiles, the rest waits for the next block
+        let last = block_start + block_rows == rows;
+        let take = if last {
+            ready
+        } else {
+            ready - ready % tile_rows
+        };
+        if take > 0 {
+            let end = ((done + take) * dst_stride).min(dst.len());
+            morph_rows_window(
+                columns,
+                arena_width,
+                &mut dst[done * dst_stride..end],
+                dst_stride,
+                width,
+                kernel_width,
+                row,
+                rows_op,
+                transpose,
+            );
+            columns.copy_within(take * arena_width..ready * arena_width, 0);
+            ready -= take;
+            done += take;
+        }
+    }
+}
+
+/// Morphology with a rectangular structuring element that has all elements set.
+///
+/// Rectangle is separable, so it is done as vertical then horizontal running min/max,
+/// both with van Herk/Gil-Werman algorithm that costs 3 comparisons per pixel whatever
+/// the kernel size is. Columns are scanned with comparisons vectorised across the row,
+/// rows are transposed by tiles first, see [morph_rows_window].
+///
+/// Output is split into one stripe of rows per scratch.
+pub(crate) fn make_morphology_rect<T, const OP_TYPE: u8>(
+    arena: &Arena<T>,
+    dst: &mut [T],
+    dst_stride: usize,
+    image_size: ImageSize,
+    kernel_size: KernelShape,
+    pool: &ThreadingPool,
+    scratch: &mut [RectScratch<T>],
+    rows_op: MorphRowsHandler<T>,
+    transpose: TransposeHandler<T>,
+) where
+    T: Copy + Default + Send + Sync + MorphNativeOp<T>,
+{
+    let width = image_size.width;
+    let rows_per_task = image_size.height.div_ceil(scratch.len()).max(1);
+
+    let stripe = |(i, (dst, scratch)): (usize, (&mut [T], &mut RectScratch<T>))| {
+        make_morphology_rect_stripe(
+            arena,
+            dst,
+            dst_stride,
+            i * rows_per_task,
+            width,
+            kernel_size,
+            scratch,
+            rows_op,
+            transpose,
+        );
+    };
+
+    if pool.is_parallel() {
+        pool.install(|| {
+            dst.par_chunks_mut(rows_per_task * dst_stride)
+                .zip(scratch.par_iter_mut())
+                .enumerate()
+                .for_each(stripe);
+        });
+    } else {
+        dst.chunks_mut(rows_per_task * dst_stride)
+            .zip(scratch.iter_mut())
+            .enumerate()
+            .for_each(stripe);
+    }
+}
@@ -0,0 +1,348 @@
+mod common;
+
+use common::*;
+use fast_morphology::generic::{self, AlphaPremultipliable, ChannelLayout};
+use fast_morphology::*;
+
+fn alpha_kernels() -> Vec<(KernelShape, Vec<u8>)> {
+    vec![
+        (KernelShape::new(3, 3), vec![1; 9]),
+        (KernelShape::new(9, 5), make_se((9, 5), 7)),
+        (KernelShape::new_with_anchor(6, 4, 0, 3), make_se((6, 4), 5)),
+    ]
+}
+
+fn run<T, L>(
+    src: &[T],
+    op: MorphExOp,
+    size: ImageSize,
+    se: &[u8],
+    kernel: KernelShape,
+    options: &MorphologyOptions,
+) -> (Vec<T>, Vec<T>)
+where
+    T: MorphologySample,
+    L: ChannelLayout,
+    f64: num_traits::AsPrimitive<T>,
+{
+    let mut dst = vec![T::default(); src.len()];
+    generic::morphology_with_options::<T, L>(src, &mut dst, op, size, se, kernel, options).unwrap();
+    let mut image = src.to_vec();
+    generic::morphology_in_place_with_options::<T, L>(&mut image, op, size, se, kernel, options)
+        .unwrap();
+    (dst, image)
+}
+
+/// Images subtracted by differences, `None` is the source
+fn difference_terms(op: MorphExOp) -> Option<(Option<MorphExOp>, Option<MorphExOp>)> {
+    match op {
+        MorphExOp::Gradient => Some((Some(MorphExOp::Dilate), Some(MorphExOp::Erode))),
+        MorphExOp::TopHat => Some((None, Some(MorphExOp::Opening))),
+        MorphExOp::BlackHat => Some((Some(MorphExOp::Closing), None)),
+        _ => None,
+    }
+}
+
+/// Preserve and alpha-only modes are processed channels of the plain result
+/// and the source elsewhere
+fn check_copied_channels<T, L>(max: u32, alpha: usize)
+where
+    T: Sample + MorphologySample + PartialEq,
+    L: ChannelLayout,
+    f64: num_traits::AsPrimitive<T>,
+{
+    let size = ImageSize::new(43, 27);
+    let channels = L::channels();
+    let src = make_image(size.width * size.height * channels, 13, max)
+        .iter()
+        .map(|&v| T::from_f64(v))
+        .collect::<Vec<T>>();
+    let options = MorphologyOptions::default()
+        .with_border_mode(BorderMode::Constant)
+        .with_border_scalar(MorphScalar::new(1., 2., 3., 4.));
+    for (kernel, se) in alpha_kernels() {
+        for op in MORPH_OPS {
+            let (processed, _) = run::<T, L>(&src, op, size, &se, kernel, &options);
+            for mode in [AlphaMode::Preserve, AlphaMode::AlphaOnly] {
+                let expected = processed
+                    .iter()
+                    .zip(&src)
+                    .enumerate()
+                    .map(|(i, (&processed, &src))| {
+                        let is_alpha = i % channels == alpha;
+                        match (mode, is_alpha) {
+                            (AlphaMode::Preserve, false) | (AlphaMode::AlphaOnly, true) => {
+                                processed
+                            }
+                            _ => src,
+                        }
+                    })
+                    .collect::<Vec<T>>();
+                let options = options.clone().with_alpha_mode(mode);
+                let (dst, image) = run::<T, L>(&src, op, size, &se, kernel, &options);
+                assert!(
+                    dst == expected,
+                    "{:?} op {:?}, kernel {:?}",
+                    mode,
+                    op,
+                    kernel
+                );
+                assert!(
+                    image == expected,
+                    "in place {:?} op {:?}, kernel {:?}",
+                    mode,
+                    op,
+                    kernel
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn alpha_preserve_and_alpha_only_copy_channels() {
+    check_copied_channels::<u8, generic::Rgba>(256, 3);
+    check_copied_channels::<u16, generic::GrayAlpha>(65536, 1);
+    check_copied_channels::<f32, generic::Argb>(1000, 0);
+    check_copied_channels::<i16, generic::Bgra>(30000, 3);
+}
+
+/// Premultiplied mode is plain morphology of the premultiplied image
+/// divided by the resulting alpha, differences subtract such terms
+fn check_premultiplied<T, L>(src: &[T], size: ImageSize, alpha: usize)
+where
+    T: MorphologySample + PartialEq,
+    L: ChannelLayout,
+    f64: num_traits::AsPrimitive<T>,
+{
+    let channels = L::channels();
+    let apply = |image: &mut [T], f: fn(&mut [T], &[T])| {
+        for pixel in image.chunks_exact_mut(channels) {
+            let a = [pixel[alpha]];
+            for (channel, value) in pixel.iter_mut().enumerate() {
+                if channel != alpha {
+                    f(std::slice::from_mut(value), &a);
+                }
+            }
+        }
+    };
+    let mut premultiplied = src.to_vec();
+    apply(&mut premultiplied, T::premultiply);
+    for iterations in [1, 2] {
+        let options = MorphologyOptions::default()
+            .with_threading_policy(MorphologyThreadingPolicy::Fixed(2))
+            .with_iterations(iterations);
+        let premultiplied_options = options.clone().with_alpha_mode(AlphaMode::Premultiplied);
+        for (kernel, se) in alpha_kernels() {
+            let term = |op: Option<MorphExOp>| {
+                let mut term = match op {
+                    Some(op) => run::<T, L>(&premultiplied, op, size, &se, kernel, &options).0,
+                    None => premultiplied.clone(),
+                };
+                apply(&mut term, T::unpremultiply);
+                term
+            };
+            for op in MORPH_OPS {
+                let expected = match difference_terms(op) {
+                    Some((minuend, subtrahend)) => {
+                        let mut expected = vec![T::default(); src.len()];
+                        T::morph_gradient(&term(minuend), &term(subtrahend), &mut expected);
+                        expected
+                    }
+                    None => term(Some(op)),
+                };
+                let (dst, image) = run::<T, L>(src, op, size, &se, kernel, &premultiplied_options);
+                assert!(
+                    dst == expected,
+                    "op {:?}, kernel {:?}, iterations {}",
+                    op,
+                    kernel,
+                    iterations
+                );
+                assert!(
+                    image == expected,
+                    "in place op {:?}, kernel {:?}, iterations {}",
+                    op,
+                    kernel,
+                    iterations
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn alpha_premultiplied_matches_manual_premultiplication() {
+    let size = ImageSize::new(37, 29);
+    let image =
+        |channels: usize, max: u32| make_image(size.width * size.height * channels, 31, max);
+    let src = image(4, 256).iter().map(|&v| v as u8).collect::<Vec<u8>>();
+    check_premultiplied::<u8, generic::Rgba>(&src, size, 3);
+    check_premultiplied::<u8, generic::Abgr>(&src, size, 0);
+    let src = image(2, 65536)
+        .iter()
+        .map(|&v| v as u16)
+        .collect::<Vec<u16>>();
+    check_premultiplied::<u16, generic::GrayAlpha>(&src, size, 1);
+    let src = image(4, 1001)
+        .iter()
+        .map(|&v| (v / 1000.) as f32)
+        .collect::<Vec<f32>>();
+    check_premultiplied::<f32, generic::Bgra>(&src, size, 3);
+}
+
+/// Premultiplication changes nothing where alpha is the maximum
+fn check_premultiplied_opaque<T, L>(max: u32, alpha: usize, opaque: T)
+where
+    T: Sample + MorphologySample + PartialEq,
+    L: ChannelLayout,
+    f64: num_traits::AsPrimitive<T>,
+{
+    let size = ImageSize::new(41, 23);
+    let channels = L::channels();
+    let src = make_image(size.width * size.height * channels, 17, max)
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| {
+            if i % channels == alpha {
+                opaque
+            } else {
+                T::from_f64(v)
+            }
+        })
+        .collect::<Vec<T>>();
+    let options = MorphologyOptions::default();
+    let premultiplied_options = options.clone().with_alpha_mode(AlphaMode::Premultiplied);
+    for (kernel, se) in alpha_kernels() {
+        for op in MORPH_OPS {
+            let expected = run::<T, L>(&src, op, size, &se, kernel, &options);
+            assert!(
+                run::<T, L>(&src, op, size, &se, kernel, &premultiplied_options) == expected,
+                "op {:?}, kernel {:?}",
+                op,
+                kernel
+            );
+        }
+    }
+}
+
+#[test]
+fn alpha_premultiplied_opaque_image_matches_process() {
+    check_premultiplied_opaque::<u8, generic::Rgba>(256, 3, u8::MAX);
+    check_premultiplied_opaque::<u16, generic::GrayAlpha>(65536, 1, u16::MAX);
+    check_premultiplied_opaque::<f32, generic::Argb>(1000, 0, 1.);
+}
+
+#[test]
+fn alpha_premultiplied_transparent_color_does_not_bleed() {
+    // Opaque red, transparent blue and half transparent green
+    let src = [200u8, 0, 0, 255, 0, 0, 200, 0, 0, 100, 0, 128];
+    let size = ImageSize::new(3, 1);
+    let kernel = KernelShape::new(3, 1);
+    let options = MorphologyOptions::default()
+        .with_border_mode(BorderMode::Clamp)
+        .with_alpha_mode(AlphaMode::Premultiplied);
+    let (dst, image) =
+        run::<u8, generic::Rgba>(&src, MorphExOp::Dilate, size, &[1; 3], kernel, &options);
+    // Premultiplied green is 50, it stays 50 next to opaque red and is divided
+    // back to 100 where alpha is 128, blue never shows up as its alpha is zero
+    let expected = [200u8, 0, 0, 255, 200, 50, 0, 255, 0, 100, 0, 128];
+    assert_eq!(dst, expected);
+    assert_eq!(image, expected);
+}
+
+#[test]
+fn alpha_premultiplied_differences_ignore_transparent_color() {
+    // Opaque red, transparent blue and half transparent green
+    let src = [200u8, 0, 0, 255, 0, 0, 200, 0, 0, 100, 0, 128];
+    let size = ImageSize::new(3, 1);
+    let kernel = KernelShape::new(3, 1);
+    let options = MorphologyOptions::default()
+        .with_border_mode(BorderMode::Clamp)
+        .with_alpha_mode(AlphaMode::Premultiplied);
+    // Erosion and opening are transparent, so gradient is the dilation and top-hat the source,
+    // which transparent blue is cleared. Closing keeps half of alpha in the middle only.
+    for (op, expected) in [
+        (
+            MorphExOp::Gradient,
+            [200u8, 0, 0, 255, 200, 50, 0, 255, 0, 100, 0, 128],
+        ),
+        (
+            MorphExOp::TopHat,
+            [200u8, 0, 0, 255, 0, 0, 0, 0, 0, 100, 0, 128],
+        ),
+        (
+            MorphExOp::BlackHat,
+            [0u8, 0, 0, 0, 0, 0, 0, 128, 0, 0, 0, 0],
+        ),
+    ] {
+        let (dst, image) = run::<u8, generic::Rgba>(&src, op, size, &[1; 3], kernel, &options);
+        assert_eq!(dst, expected, "op {:?}", op);
+        assert_eq!(image, expected, "in place op {:?}", op);
+    }
+}
+
+#[test]
+fn alpha_premultiplication_rounds_and_clears_transparent_color() {
+    let mut color = [255u8, 0, 200, 100];
+    u8::premultiply(&mut color, &[128, 255, 0, 255]);
+    assert_eq!(color, [128, 0, 0, 100]);
+    let mut color = [128u8, 10, 5, 200];
+    u8::unpremultiply(&mut color, &[128, 0, 255, 100]);
+    assert_eq!(color, [255, 0, 5, 255]);
+    let mut color = [65535u16, 1000];
+    u16::premultiply(&mut color, &[32768, 0]);
+    assert_eq!(color, [32768, 0]);
+    let mut color = [0.25f32, 0.5];
+    f32::unpremultiply(&mut color, &[0.5, 0.]);
+    assert_eq!(color, [0.5, 0.]);
+}
+
+#[test]
+fn alpha_mode_is_ignored_without_alpha() {
+    let size = ImageSize::new(31, 17);
+    let src = make_image(size.width * size.height * 3, 3, 256)
+        .iter()
+        .map(|&v| v as u8)
+        .collect::<Vec<u8>>();
+    let se = make_se((5, 5), 2);
+    let kernel = KernelShape::new(5, 5);
+    let options = MorphologyOptions::default();
+    for op in MORPH_OPS {
+        let expected = run::<u8, generic::Rgb>(&src, op, size, &se, kernel, &options);
+        for mode in [
+            AlphaMode::Preserve,
+            AlphaMode::AlphaOnly,
+            AlphaMode::Premultiplied,
+        ] {
+            let options = options.clone().with_alpha_mode(mode);
+            assert_eq!(
+                run::<u8, generic::Rgb>(&src, op, size, &se, kernel, &options),
+                expected,
+                "{:?} op {:?}",
+                mode,
+                op
+            );
+        }
+    }
+}
+
+#[test]
+fn alpha_premultiplied_requires_premultipliable_type() {
+    let size = ImageSize::new(8, 8);
+    let src = vec![0i16; 8 * 8 * 4];
+    let mut dst = vec![0i16; 8 * 8 * 4];
+    let options = MorphologyOptions::default().with_alpha_mode(AlphaMode::Premultiplied);
+    assert_eq!(
+        generic::morphology_with_options::<i16, generic::Rgba>(
+            &src,
+            &mut dst,
+            MorphExOp::Dilate,
+            size,
+            &[1; 9],
+            KernelShape::new(3, 3),
+            &options,
+        ),
+        Err(MorphologyError::UnsupportedAlphaMode)
+    );
+}
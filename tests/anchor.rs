@@ -0,0 +1,77 @@
+mod common;
+
+use common::*;
+use fast_morphology::*;
+
+fn anchored_kernels() -> Vec<KernelShape> {
+    vec![
+        KernelShape::new_with_anchor(4, 4, 0, 0),
+        KernelShape::new_with_anchor(6, 2, 5, 1),
+        KernelShape::new_with_anchor(2, 2, 1, 1),
+        KernelShape::new_with_anchor(5, 3, 0, 2),
+        KernelShape::new_with_anchor(1, 5, 0, 4),
+        KernelShape::new_with_anchor(8, 3, 3, 0),
+        KernelShape::new_with_anchor(16, 9, 2, 7),
+    ]
+}
+
+#[test]
+fn anchored_morphology() {
+    let kernels = anchored_kernels();
+    let ops = &MORPH_OPS;
+    check_morphology::<u8, _>(&kernels, 1, 256, ops, morph_fn!(morphology));
+    check_morphology::<u8, _>(&kernels, 4, 256, ops, morph_fn!(morphology_rgba));
+    check_morphology::<u16, _>(&kernels, 1, 65536, ops, morph_fn!(morphology_gray_u16));
+    check_morphology::<f32, _>(&kernels, 3, 1000, ops, morph_fn!(morphology_rgb_f32));
+}
+
+#[test]
+fn anchored_constant_border() {
+    let size = ImageSize::new(71, 15);
+    let src_f64 = make_image(size.width * size.height, 9, 256);
+    let src = src_f64.iter().map(|&v| v as u8).collect::<Vec<u8>>();
+    for (i, kernel) in anchored_kernels().into_iter().enumerate() {
+        let se = make_se((kernel.width, kernel.height), 100 + i as u64);
+        let mut dst = vec![0u8; src.len()];
+        dilate(
+            &src,
+            &mut dst,
+            size,
+            &se,
+            kernel,
+            BorderMode::Constant,
+            MorphScalar::dup(255.),
+            MorphologyThreadingPolicy::Single,
+        )
+        .unwrap();
+        let expected = reference_op(&src_f64, size, 1, &se, kernel, true, Some(255.));
+        let dst = dst.iter().map(|&v| v as f64).collect::<Vec<f64>>();
+        assert_eq!(dst, expected, "kernel {:?}", kernel);
+    }
+}
+
+#[test]
+fn anchor_outside_kernel_is_rejected() {
+    let size = ImageSize::new(8, 8);
+    let src = vec![0u8; 64];
+    let mut dst = vec![0u8; 64];
+    let result = dilate(
+        &src,
+        &mut dst,
+        size,
+        &[1u8; 9],
+        KernelShape::new_with_anchor(3, 3, 3, 0),
+        BorderMode::Clamp,
+        MorphScalar::default(),
+        MorphologyThreadingPolicy::Single,
+    );
+    assert_eq!(
+        result,
+        Err(MorphologyError::AnchorOutOfBounds {
+            anchor_x: 3,
+            anchor_y: 0,
+            width: 3,
+            height: 3
+        })
+    );
+}
@@ -0,0 +1,282 @@
+mod common;
+
+use common::*;
+use fast_morphology::*;
+
+const BORDER_MODES: [BorderMode; 5] = [
+    BorderMode::Clamp,
+    BorderMode::Wrap,
+    BorderMode::Reflect,
+    BorderMode::Reflect101,
+    BorderMode::Constant,
+];
+
+fn make_mask(size: ImageSize, seed: u64, density: u32) -> Vec<u8> {
+    make_image(size.width * size.height, seed, 10)
+        .iter()
+        .map(|&v| if (v as u32) < density { 255 } else { 0 })
+        .collect()
+}
+
+fn binary_kernels() -> Vec<(Vec<u8>, KernelShape)> {
+    let mut random_se = make_image(5 * 4, 3, 2)
+        .iter()
+        .map(|&v| v as u8)
+        .collect::<Vec<u8>>();
+    random_se[7] = 1;
+    vec![
+        structuring_element::rect(3, 3),
+        structuring_element::rect(1, 1),
+        structuring_element::rect(15, 5),
+        structuring_element::rect(70, 3),
+        structuring_element::rect(1, 9),
+        structuring_element::ellipse(7, 5),
+        structuring_element::cross(5, 5),
+        (vec![1u8; 24], KernelShape::new_with_anchor(6, 4, 0, 3)),
+        (random_se, KernelShape::new_with_anchor(5, 4, 2, 1)),
+    ]
+}
+
+fn byte_morphology(
+    mask: &[u8],
+    size: ImageSize,
+    op: MorphExOp,
+    se: &[u8],
+    kernel: KernelShape,
+    border_mode: BorderMode,
+    border_value: bool,
+) -> Vec<u8> {
+    let mut dst = vec![0u8; mask.len()];
+    morphology(
+        mask,
+        &mut dst,
+        op,
+        size,
+        se,
+        kernel,
+        border_mode,
+        MorphScalar::dup(if border_value { 255. } else { 0. }),
+        MorphologyThreadingPolicy::Single,
+    )
+    .unwrap();
+    dst
+}
+
+#[test]
+fn binary_matches_byte_masks() {
+    for (i, size) in [
+        ImageSize::new(1, 7),
+        ImageSize::new(63, 9),
+        ImageSize::new(64, 5),
+        ImageSize::new(65, 11),
+        ImageSize::new(200, 13),
+    ]
+    .iter()
+    .enumerate()
+    {
+        let mask = make_mask(*size, i as u64 + 1, 7);
+        let src = BinaryImage::from_mask(&mask, *size).unwrap();
+        for (se, kernel) in binary_kernels() {
+            for op in MORPH_OPS {
+                for border_mode in BORDER_MODES {
+                    for border_value in [false, true] {
+                        let expected = byte_morphology(
+                            &mask,
+                            *size,
+                            op,
+                            &se,
+                            kernel,
+                            border_mode,
+                            border_value,
+                        );
+                        let mut dst = BinaryImage::new(*size);
+                        binary_morphology(
+                            &src,
+                            &mut dst,
+                            op,
+                            &se,
+                            kernel,
+                            border_mode,
+                            border_value,
+                            MorphologyThreadingPolicy::Fixed(3),
+                        )
+                        .unwrap();
+                        let mut result = vec![0u8; mask.len()];
+                        dst.to_mask(&mut result).unwrap();
+                        assert_eq!(
+                            result, expected,
+                            "size {:?}, kernel {:?}, op {:?}, border {:?} {}",
+                            size, kernel, op, border_mode, border_value
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn binary_hit_or_miss_matches_erosions() {
+    let size = ImageSize::new(131, 17);
+    let mask = make_mask(size, 11, 5);
+    let src = BinaryImage::from_mask(&mask, size).unwrap();
+    let complement = mask.iter().map(|&v| 255 - v).collect::<Vec<u8>>();
+    let kernel = KernelShape::new(3, 3);
+    // Isolated corner: set pixel with clear right and bottom neighbours
+    let foreground = [0, 0, 0, 0, 1, 0, 0, 0, 0];
+    let background = [0, 0, 0, 0, 0, 1, 0, 1, 1];
+    for border_mode in BORDER_MODES {
+        for border_value in [false, true] {
+            let hits = byte_morphology(
+                &mask,
+                size,
+                MorphExOp::Erode,
+                &foreground,
+                kernel,
+                border_mode,
+                border_value,
+            );
+            let misses = byte_morphology(
+                &complement,
+                size,
+                MorphExOp::Erode,
+                &background,
+                kernel,
+                border_mode,
+                !border_value,
+            );
+            let expected = hits
+                .iter()
+                .zip(misses.iter())
+                .map(|(&a, &b)| a & b)
+                .collect::<Vec<u8>>();
+            let mut dst = BinaryImage::new(size);
+            binary_hit_or_miss(
+                &src,
+                &mut dst,
+                &foreground,
+                &background,
+                kernel,
+                border_mode,
+                border_value,
+                MorphologyThreadingPolicy::Single,
+            )
+            .unwrap();
+            let mut result = vec![0u8; mask.len()];
+            dst.to_mask(&mut result).unwrap();
+            assert_eq!(
+                result, expected,
+                "border {:?} {}",
+                border_mode, border_value
+            );
+        }
+    }
+
+    // Empty elements put no constraint
+    let mut dst = BinaryImage::new(size);
+    binary_hit_or_miss(
+        &src,
+        &mut dst,
+        &[0; 9],
+        &[0; 9],
+        kernel,
+        BorderMode::Clamp,
+        false,
+        MorphologyThreadingPolicy::Single,
+    )
+    .unwrap();
+    assert_eq!(dst.count_ones(), size.width * size.height);
+}
+
+#[test]
+fn binary_mask_conversion() {
+    let size = ImageSize::new(70, 3);
+    let stride = 75;
+    let mut mask = vec![7u8; stride * (size.height - 1) + size.width];
+    for y in 0..size.height {
+        for x in 0..size.width {
+            mask[y * stride + x] = if (x * 7 + y) % 3 == 0 { 1 } else { 0 };
+        }
+    }
+    let image = BinaryImage::from_mask_with_stride(&mask, stride, size).unwrap();
+    assert_eq!(image.words_per_row(), 2);
+    for y in 0..size.height {
+        for x in 0..size.width {
+            assert_eq!(image.get(x, y), (x * 7 + y) % 3 == 0);
+        }
+    }
+    // Bits past the width stay clear
+    assert_eq!(image.row(0)[1] >> 6, 0);
+
+    let mut inverted = image.clone();
+    inverted.invert();
+    assert_eq!(
+        inverted.count_ones() + image.count_ones(),
+        size.width * size.height
+    );
+    inverted.set(69, 2, true);
+    assert!(inverted.get(69, 2));
+
+    let mut unpacked = vec![7u8; mask.len()];
+    image.to_mask_with_stride(&mut unpacked, stride).unwrap();
+    for y in 0..size.height - 1 {
+        // Bytes between rows are left untouched
+        assert!(unpacked[y * stride + size.width..(y + 1) * stride]
+            .iter()
+            .all(|&v| v == 7));
+    }
+    for y in 0..size.height {
+        for x in 0..size.width {
+            let expected = if mask[y * stride + x] != 0 { 255 } else { 0 };
+            assert_eq!(unpacked[y * stride + x], expected);
+        }
+    }
+}
+
+#[test]
+fn binary_errors() {
+    let src = BinaryImage::new(ImageSize::new(4, 3));
+    let mut dst = BinaryImage::new(ImageSize::new(3, 4));
+    let result = binary_dilate(
+        &src,
+        &mut dst,
+        &[1; 9],
+        KernelShape::new(3, 3),
+        BorderMode::Clamp,
+        false,
+        MorphologyThreadingPolicy::Single,
+    );
+    assert_eq!(
+        result,
+        Err(MorphologyError::ImageDimensionsMismatch {
+            src_width: 4,
+            src_height: 3,
+            dst_width: 3,
+            dst_height: 4
+        })
+    );
+    let mut dst = BinaryImage::new(ImageSize::new(4, 3));
+    let result = binary_erode(
+        &src,
+        &mut dst,
+        &[1; 8],
+        KernelShape::new(3, 3),
+        BorderMode::Clamp,
+        false,
+        MorphologyThreadingPolicy::Single,
+    );
+    assert_eq!(
+        result,
+        Err(MorphologyError::StructuringElementSizeMismatch {
+            expected: 9,
+            actual: 8
+        })
+    );
+    assert_eq!(
+        BinaryImage::from_mask(&[0u8; 11], ImageSize::new(4, 3)),
+        Err(MorphologyError::ImageSizeMismatch {
+            expected: 12,
+            actual: 11
+        })
+    );
+}
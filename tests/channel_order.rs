@@ -0,0 +1,106 @@
+mod common;
+
+use common::*;
+use fast_morphology::generic::{self, ChannelLayout};
+use fast_morphology::*;
+
+/// Checks layout `L` whose channels in memory are `order` of red, green, blue and alpha
+fn check_order<T, L>(order: &[usize], max: u32)
+where
+    T: Sample + MorphologySample + PartialEq,
+    L: ChannelLayout,
+    f64: num_traits::AsPrimitive<T>,
+{
+    let size = ImageSize::new(43, 17);
+    let channels = L::channels();
+    assert_eq!(channels, order.len());
+    let src = make_image(size.width * size.height * channels, 12, max)
+        .iter()
+        .map(|&v| T::from_f64(v))
+        .collect::<Vec<T>>();
+    let kernel = KernelShape::new(7, 5);
+    let se = make_se((7, 5), 6);
+    let rgba = [3., 9., 27., 81.];
+    let options = MorphologyOptions::default()
+        .with_border_mode(BorderMode::Constant)
+        .with_border_scalar(MorphScalar::new(rgba[0], rgba[1], rgba[2], rgba[3]))
+        .with_threading_policy(MorphologyThreadingPolicy::Single);
+    for op in MORPH_OPS {
+        let mut expected = vec![T::default(); src.len()];
+        for (position, &semantic) in order.iter().enumerate() {
+            let plane = src
+                .iter()
+                .skip(position)
+                .step_by(channels)
+                .copied()
+                .collect::<Vec<T>>();
+            let mut filtered = vec![T::default(); plane.len()];
+            generic::morphology::<T, generic::Gray>(
+                &plane,
+                &mut filtered,
+                op,
+                size,
+                &se,
+                kernel,
+                BorderMode::Constant,
+                MorphScalar::dup(rgba[semantic]),
+                MorphologyThreadingPolicy::Single,
+            )
+            .unwrap();
+            for (dst, v) in expected
+                .iter_mut()
+                .skip(position)
+                .step_by(channels)
+                .zip(filtered)
+            {
+                *dst = v;
+            }
+        }
+
+        let mut dst = vec![T::default(); src.len()];
+        generic::morphology_with_options::<T, L>(&src, &mut dst, op, size, &se, kernel, &options)
+            .unwrap();
+        assert!(dst == expected, "{:?} op {:?}", L::LAYOUT, op);
+
+        let mut image = src.clone();
+        generic::morphology_in_place_with_options::<T, L>(
+            &mut image, op, size, &se, kernel, &options,
+        )
+        .unwrap();
+        assert!(image == expected, "in place {:?} op {:?}", L::LAYOUT, op);
+    }
+}
+
+#[test]
+fn channel_order_border_constants() {
+    check_order::<u8, generic::Rgb>(&[0, 1, 2], 256);
+    check_order::<u8, generic::Bgr>(&[2, 1, 0], 256);
+    check_order::<u8, generic::Rgba>(&[0, 1, 2, 3], 256);
+    check_order::<u8, generic::Bgra>(&[2, 1, 0, 3], 256);
+    check_order::<u8, generic::Argb>(&[3, 0, 1, 2], 256);
+    check_order::<u8, generic::Abgr>(&[3, 2, 1, 0], 256);
+    check_order::<u16, generic::Bgr>(&[2, 1, 0], 65536);
+    check_order::<u16, generic::Argb>(&[3, 0, 1, 2], 65536);
+    check_order::<f32, generic::Bgra>(&[2, 1, 0, 3], 1000);
+    check_order::<f32, generic::Abgr>(&[3, 2, 1, 0], 1000);
+}
+
+#[test]
+fn channel_order_scalar_index() {
+    let indices = |layout: MorphologyLayout| {
+        (0..layout.channels())
+            .map(|position| layout.scalar_index(position).unwrap())
+            .collect::<Vec<usize>>()
+    };
+    assert_eq!(indices(MorphologyLayout::Rgba), [0, 1, 2, 3]);
+    assert_eq!(indices(MorphologyLayout::Bgra), [2, 1, 0, 3]);
+    assert_eq!(indices(MorphologyLayout::Argb), [3, 0, 1, 2]);
+    assert_eq!(indices(MorphologyLayout::Abgr), [3, 2, 1, 0]);
+    assert_eq!(
+        indices(MorphologyLayout::Interleaved(6)),
+        [0, 1, 2, 3, 3, 3]
+    );
+    assert_eq!(MorphologyLayout::Bgra.scalar_index(4), None);
+    assert_eq!(MorphologyLayout::Rgb.scalar_index(3), None);
+    assert_eq!(MorphologyLayout::Interleaved(6).scalar_index(6), None);
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use common::*;
+use fast_morphology::*;
+
+fn ring(outer: usize, inner: usize) -> (Vec<u8>, KernelShape) {
+    let (mut se, kernel) = structuring_element::circle(outer);
+    let (hole, hole_kernel) = structuring_element::circle(inner);
+    let shift = outer - inner;
+    for y in 0..hole_kernel.height {
+        for x in 0..hole_kernel.width {
+            if hole[y * hole_kernel.width + x] != 0 {
+                se[(y + shift) * kernel.width + x + shift] = 0;
+            }
+        }
+    }
+    (se, kernel)
+}
+
+fn corner(width: usize, height: usize) -> (Vec<u8>, KernelShape) {
+    let mut se = vec![0u8; width * height];
+    for y in 0..height {
+        for x in 0..width {
+            if x < 3 || y + 4 >= height || x + y == width {
+                se[y * width + x] = 1;
+            }
+        }
+    }
+    (
+        se,
+        KernelShape::new_with_anchor(width, height, 0, height - 1),
+    )
+}
+
+fn shapes() -> Vec<(Vec<u8>, KernelShape)> {
+    vec![
+        structuring_element::circle(7),
+        structuring_element::ellipse(9, 4),
+        structuring_element::diamond(6),
+        structuring_element::octagon(3, 2),
+        structuring_element::rotated_rect(21, 5, 30.),
+        structuring_element::cross(15, 11),
+        ring(8, 4),
+        corner(12, 10),
+    ]
+}
+
+#[test]
+fn chord_morphology_u8() {
+    let shapes = shapes();
+    let ops = &MORPH_OPS;
+    check_morphology_with_shapes::<u8, _>(&shapes, 1, 256, ops, morph_fn!(morphology));
+    check_morphology_with_shapes::<u8, _>(&shapes, 3, 256, ops, morph_fn!(morphology_rgb));
+    check_morphology_with_shapes::<u8, _>(&shapes, 2, 256, ops, morph_fn!(morphology_gray_alpha));
+}
+
+#[test]
+fn chord_morphology_u16() {
+    let shapes = shapes();
+    let ops = &MORPH_OPS;
+    check_morphology_with_shapes::<u16, _>(&shapes, 1, 65536, ops, morph_fn!(morphology_gray_u16));
+    check_morphology_with_shapes::<u16, _>(&shapes, 4, 65536, ops, morph_fn!(morphology_rgba_u16));
+}
+
+#[test]
+fn chord_morphology_f32() {
+    let shapes = shapes();
+    let ops = &MORPH_OPS;
+    check_morphology_with_shapes::<f32, _>(&shapes, 3, 1000, ops, morph_fn!(morphology_rgb_f32));
+}
+
+#[test]
+fn large_disk_morphology() {
+    let shapes = vec![structuring_element::circle(24), ring(20, 12)];
+    let ops = &[MorphExOp::Dilate, MorphExOp::Erode];
+    check_morphology_with_shapes::<u8, _>(&shapes, 1, 256, ops, morph_fn!(morphology));
+}
+
+#[test]
+fn chord_constant_border() {
+    let size = ImageSize::new(77, 31);
+    let src_f64 = make_image(size.width * size.height, 17, 256);
+    let src = src_f64.iter().map(|&v| v as u8).collect::<Vec<u8>>();
+    for (se, kernel) in shapes() {
+        for (dilate_op, border) in [(true, 255.), (false, 0.)] {
+            let mut dst = vec![0u8; src.len()];
+            let op = if dilate_op { dilate } else { erode };
+            op(
+                &src,
+                &mut dst,
+                size,
+                &se,
+                kernel,
+                BorderMode::Constant,
+                MorphScalar::dup(border),
+                MorphologyThreadingPolicy::Fixed(4),
+            )
+            .unwrap();
+            let expected = reference_op(&src_f64, size, 1, &se, kernel, dilate_op, Some(border));
+            let dst = dst.iter().map(|&v| v as f64).collect::<Vec<f64>>();
+            assert_eq!(dst, expected, "kernel {:?}", kernel);
+        }
+    }
+}
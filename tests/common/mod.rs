@@ -0,0 +1,368 @@
+#![allow(dead_code, unused_macros)]
+
+use fast_morphology::*;
+
+/// Wraps a `morphology_*` function into the closure the checks below run,
+/// border defaults to clamp and threading to the policy the check picks
+macro_rules! morph_fn {
+    ($func: ident) => {
+        morph_fn!($func, fast_morphology::BorderMode::Clamp)
+    };
+    ($func: ident, $border: expr) => {
+        |src, dst, op, size, se, kernel, policy| {
+            $func(
+                src,
+                dst,
+                op,
+                size,
+                se,
+                kernel,
+                $border,
+                fast_morphology::MorphScalar::default(),
+                policy,
+            )
+        }
+    };
+    ($func: ident, $border: expr, $policy: expr) => {
+        |src, dst, op, size, se, kernel, _| {
+            $func(
+                src,
+                dst,
+                op,
+                size,
+                se,
+                kernel,
+                $border,
+                fast_morphology::MorphScalar::default(),
+                $policy,
+            )
+        }
+    };
+}
+
+/// Same as `morph_fn` for `dilate_*` and `erode_*` which take no operation
+macro_rules! op_fn {
+    ($func: ident) => {
+        op_fn!($func, fast_morphology::BorderMode::Clamp)
+    };
+    ($func: ident, $border: expr) => {
+        |src, dst, _, size, se, kernel, policy| {
+            $func(
+                src,
+                dst,
+                size,
+                se,
+                kernel,
+                $border,
+                fast_morphology::MorphScalar::default(),
+                policy,
+            )
+        }
+    };
+}
+
+#[allow(unused_imports)]
+pub(crate) use {morph_fn, op_fn};
+
+pub const MORPH_OPS: [MorphExOp; 7] = [
+    MorphExOp::Dilate,
+    MorphExOp::Erode,
+    MorphExOp::Opening,
+    MorphExOp::Closing,
+    MorphExOp::Gradient,
+    MorphExOp::TopHat,
+    MorphExOp::BlackHat,
+];
+
+pub trait Sample: Copy + Default + Into<f64> + std::fmt::Debug {
+    /// Results are clamped into `MIN..=MAX`
+    const MIN: f64;
+    const MAX: f64;
+    /// Shifts generated images so signed types get negative values
+    const OFFSET: f64 = 0.;
+    fn from_f64(v: f64) -> Self;
+}
+
+impl Sample for u8 {
+    const MIN: f64 = u8::MIN as f64;
+    const MAX: f64 = u8::MAX as f64;
+    fn from_f64(v: f64) -> Self {
+        v as u8
+    }
+}
+
+impl Sample for u16 {
+    const MIN: f64 = u16::MIN as f64;
+    const MAX: f64 = u16::MAX as f64;
+    fn from_f64(v: f64) -> Self {
+        v as u16
+    }
+}
+
+impl Sample for f32 {
+    const MIN: f64 = f64::NEG_INFINITY;
+    const MAX: f64 = f64::INFINITY;
+    fn from_f64(v: f64) -> Self {
+        v as f32
+    }
+}
+
+#[cfg(feature = "half")]
+impl Sample for half::f16 {
+    const MIN: f64 = f64::NEG_INFINITY;
+    const MAX: f64 = f64::INFINITY;
+    fn from_f64(v: f64) -> Self {
+        half::f16::from_f64(v)
+    }
+}
+
+impl Sample for f64 {
+    const MIN: f64 = f64::NEG_INFINITY;
+    const MAX: f64 = f64::INFINITY;
+    fn from_f64(v: f64) -> Self {
+        v
+    }
+}
+
+impl Sample for i8 {
+    const MIN: f64 = i8::MIN as f64;
+    const MAX: f64 = i8::MAX as f64;
+    const OFFSET: f64 = i8::MIN as f64;
+    fn from_f64(v: f64) -> Self {
+        v as i8
+    }
+}
+
+impl Sample for i16 {
+    const MIN: f64 = i16::MIN as f64;
+    const MAX: f64 = i16::MAX as f64;
+    const OFFSET: f64 = i16::MIN as f64;
+    fn from_f64(v: f64) -> Self {
+        v as i16
+    }
+}
+
+impl Sample for i32 {
+    const MIN: f64 = i32::MIN as f64;
+    const MAX: f64 = i32::MAX as f64;
+    const OFFSET: f64 = i32::MIN as f64 / 2.;
+    fn from_f64(v: f64) -> Self {
+        v as i32
+    }
+}
+
+impl Sample for u32 {
+    const MIN: f64 = u32::MIN as f64;
+    const MAX: f64 = u32::MAX as f64;
+    fn from_f64(v: f64) -> Self {
+        v as u32
+    }
+}
+
+pub struct Lcg(pub u64);
+
+impl Lcg {
+    pub fn next(&mut self) -> u32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) as u32
+    }
+}
+
+pub fn make_se(kernel: (usize, usize), seed: u64) -> Vec<u8> {
+    let mut rng = Lcg(seed);
+    let mut se = (0..kernel.0 * kernel.1)
+        .map(|_| (rng.next() % 3).min(1) as u8)
+        .collect::<Vec<u8>>();
+    // Keeps the element non-empty and asymmetric
+    se[0] = 1;
+    if se.len() > 1 {
+        se[1] = 0;
+    }
+    se
+}
+
+pub fn make_image(len: usize, seed: u64, max: u32) -> Vec<f64> {
+    let mut rng = Lcg(seed);
+    (0..len).map(|_| (rng.next() % max) as f64).collect()
+}
+
+pub fn reference_op(
+    src: &[f64],
+    size: ImageSize,
+    channels: usize,
+    se: &[u8],
+    kernel: KernelShape,
+    dilate: bool,
+    border: Option<f64>,
+) -> Vec<f64> {
+    let mut dst = vec![0f64; src.len()];
+    let (w, h) = (size.width as i64, size.height as i64);
+    for y in 0..h {
+        for x in 0..w {
+            for c in 0..channels {
+                let mut acc = if dilate { f64::MIN } else { f64::MAX };
+                for ky in 0..kernel.height {
+                    for kx in 0..kernel.width {
+                        if se[ky * kernel.width + kx] == 0 {
+                            continue;
+                        }
+                        let sy = y + ky as i64 - kernel.anchor_y as i64;
+                        let sx = x + kx as i64 - kernel.anchor_x as i64;
+                        let v = if sy < 0 || sy >= h || sx < 0 || sx >= w {
+                            match border {
+                                Some(v) => v,
+                                None => {
+                                    let sy = sy.clamp(0, h - 1);
+                                    let sx = sx.clamp(0, w - 1);
+                                    src[(sy * w + sx) as usize * channels + c]
+                                }
+                            }
+                        } else {
+                            src[(sy * w + sx) as usize * channels + c]
+                        };
+                        acc = if dilate { acc.max(v) } else { acc.min(v) };
+                    }
+                }
+                dst[(y * w + x) as usize * channels + c] = acc;
+            }
+        }
+    }
+    dst
+}
+
+pub fn reference_morphology(
+    src: &[f64],
+    size: ImageSize,
+    channels: usize,
+    se: &[u8],
+    kernel: KernelShape,
+    op: MorphExOp,
+    saturating: bool,
+) -> Vec<f64> {
+    let dilate = |v: &[f64]| reference_op(v, size, channels, se, kernel, true, None);
+    let erode = |v: &[f64]| reference_op(v, size, channels, se, kernel, false, None);
+    // SE is not necessarily symmetric, so opening is not guaranteed to be below the source
+    let sub = |a: &[f64], b: &[f64]| {
+        a.iter()
+            .zip(b)
+            .map(|(a, b)| if saturating { (a - b).max(0.) } else { a - b })
+            .collect::<Vec<_>>()
+    };
+    match op {
+        MorphExOp::Dilate => dilate(src),
+        MorphExOp::Erode => erode(src),
+        MorphExOp::Opening => dilate(&erode(src)),
+        MorphExOp::Closing => erode(&dilate(src)),
+        MorphExOp::Gradient => sub(&dilate(src), &erode(src)),
+        MorphExOp::TopHat => sub(src, &dilate(&erode(src))),
+        MorphExOp::BlackHat => sub(&erode(&dilate(src)), src),
+    }
+}
+
+pub fn check_morphology<T, F>(
+    kernels: &[KernelShape],
+    channels: usize,
+    max: u32,
+    ops: &[MorphExOp],
+    func: F,
+) where
+    T: Sample,
+    F: Fn(
+        &[T],
+        &mut [T],
+        MorphExOp,
+        ImageSize,
+        &[u8],
+        KernelShape,
+        MorphologyThreadingPolicy,
+    ) -> Result<(), MorphologyError>,
+{
+    let random_se = |kernel: KernelShape, seed: u64| make_se((kernel.width, kernel.height), seed);
+    check_morphology_with_se(kernels, channels, max, ops, random_se, func);
+}
+
+/// Runs the checks over ready made structuring elements
+pub fn check_morphology_with_shapes<T, F>(
+    shapes: &[(Vec<u8>, KernelShape)],
+    channels: usize,
+    max: u32,
+    ops: &[MorphExOp],
+    func: F,
+) where
+    T: Sample,
+    F: Fn(
+        &[T],
+        &mut [T],
+        MorphExOp,
+        ImageSize,
+        &[u8],
+        KernelShape,
+        MorphologyThreadingPolicy,
+    ) -> Result<(), MorphologyError>,
+{
+    let kernels = shapes.iter().map(|x| x.1).collect::<Vec<_>>();
+    let se_factory = |_, seed: u64| shapes[seed as usize - 1].0.clone();
+    check_morphology_with_se(&kernels, channels, max, ops, se_factory, func);
+}
+
+pub fn check_morphology_with_se<T, S, F>(
+    kernels: &[KernelShape],
+    channels: usize,
+    max: u32,
+    ops: &[MorphExOp],
+    se_factory: S,
+    func: F,
+) where
+    T: Sample,
+    S: Fn(KernelShape, u64) -> Vec<u8>,
+    F: Fn(
+        &[T],
+        &mut [T],
+        MorphExOp,
+        ImageSize,
+        &[u8],
+        KernelShape,
+        MorphologyThreadingPolicy,
+    ) -> Result<(), MorphologyError>,
+{
+    let size = ImageSize::new(131, 19);
+    for (i, &kernel) in kernels.iter().enumerate() {
+        let se = se_factory(kernel, i as u64 + 1);
+        let reference_src = make_image(size.width * size.height * channels, 42 + i as u64, max)
+            .iter()
+            .map(|&v| v + T::OFFSET)
+            .collect::<Vec<f64>>();
+        let src = reference_src
+            .iter()
+            .map(|&v| T::from_f64(v))
+            .collect::<Vec<T>>();
+        for &op in ops {
+            let expected =
+                reference_morphology(&reference_src, size, channels, &se, kernel, op, false)
+                    .iter()
+                    .map(|&v| v.clamp(T::MIN, T::MAX))
+                    .collect::<Vec<f64>>();
+            for policy in [
+                MorphologyThreadingPolicy::Single,
+                MorphologyThreadingPolicy::Fixed(3),
+            ] {
+                let mut dst = vec![T::default(); src.len()];
+                func(&src, &mut dst, op, size, &se, kernel, policy).unwrap();
+                for (idx, (&v, &e)) in dst.iter().zip(expected.iter()).enumerate() {
+                    assert_eq!(
+                        v.into(),
+                        e,
+                        "Mismatch at {} for kernel {:?}, op {:?}, channels {}",
+                        idx,
+                        kernel,
+                        op,
+                        channels
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,181 @@
+mod common;
+
+use common::*;
+use fast_morphology::structuring_element::DiskAccuracy;
+use fast_morphology::*;
+
+fn shapes() -> Vec<(Vec<u8>, KernelShape)> {
+    vec![
+        structuring_element::disk(1, DiskAccuracy::High),
+        structuring_element::disk(4, DiskAccuracy::Low),
+        structuring_element::disk(9, DiskAccuracy::Medium),
+        structuring_element::disk(12, DiskAccuracy::High),
+        structuring_element::regular_polygon(7, 6),
+        structuring_element::regular_polygon(10, 5),
+    ]
+}
+
+fn large_shapes() -> Vec<(Vec<u8>, KernelShape)> {
+    vec![
+        structuring_element::disk(17, DiskAccuracy::High),
+        structuring_element::disk(30, DiskAccuracy::Low),
+        structuring_element::disk(26, DiskAccuracy::Medium),
+        structuring_element::regular_polygon(24, 12),
+    ]
+}
+
+fn is_set(se: &[u8], kernel: KernelShape, x: i64, y: i64) -> bool {
+    x >= 0
+        && y >= 0
+        && x < kernel.width as i64
+        && y < kernel.height as i64
+        && se[y as usize * kernel.width + x as usize] != 0
+}
+
+/// Rows and columns have no gaps, and shape is centrally symmetric around the anchor
+fn assert_solid_symmetric(se: &[u8], kernel: KernelShape) {
+    assert_eq!(se.len(), kernel.width * kernel.height);
+    assert_eq!(kernel.anchor_x * 2 + 1, kernel.width);
+    assert_eq!(kernel.anchor_y * 2 + 1, kernel.height);
+    for y in 0..kernel.height as i64 {
+        let row = (0..kernel.width as i64)
+            .map(|x| is_set(se, kernel, x, y))
+            .collect::<Vec<_>>();
+        let first = row.iter().position(|&x| x).unwrap();
+        let last = row.iter().rposition(|&x| x).unwrap();
+        assert!(row[first..=last].iter().all(|&x| x), "kernel {:?}", kernel);
+        for x in 0..kernel.width as i64 {
+            let mirrored = is_set(
+                se,
+                kernel,
+                kernel.width as i64 - 1 - x,
+                kernel.height as i64 - 1 - y,
+            );
+            assert_eq!(is_set(se, kernel, x, y), mirrored, "kernel {:?}", kernel);
+        }
+    }
+    for x in 0..kernel.width as i64 {
+        let column = (0..kernel.height as i64)
+            .map(|y| is_set(se, kernel, x, y))
+            .collect::<Vec<_>>();
+        let first = column.iter().position(|&x| x).unwrap();
+        let last = column.iter().rposition(|&x| x).unwrap();
+        assert!(
+            column[first..=last].iter().all(|&x| x),
+            "kernel {:?}",
+            kernel
+        );
+    }
+}
+
+#[test]
+fn disk_shape() {
+    for (accuracy, tolerance) in [
+        (DiskAccuracy::Low, 0.04),
+        (DiskAccuracy::Medium, 0.014),
+        (DiskAccuracy::High, 0.007),
+    ] {
+        for radius in (1usize..=60).chain([100, 150]) {
+            let (se, kernel) = structuring_element::disk(radius, accuracy);
+            assert_solid_symmetric(&se, kernel);
+            // Boundary points are within the documented tolerance from the circle
+            let (cx, cy) = (kernel.anchor_x as i64, kernel.anchor_y as i64);
+            for y in 0..kernel.height as i64 {
+                for x in 0..kernel.width as i64 {
+                    let is_boundary = is_set(&se, kernel, x, y)
+                        && [(-1, 0), (1, 0), (0, -1), (0, 1)]
+                            .iter()
+                            .any(|&(dx, dy)| !is_set(&se, kernel, x + dx, y + dy));
+                    if !is_boundary {
+                        continue;
+                    }
+                    let distance = (((x - cx).pow(2) + (y - cy).pow(2)) as f64).sqrt();
+                    let allowed = radius as f64 * tolerance + 2.;
+                    assert!(
+                        (distance - radius as f64).abs() <= allowed,
+                        "disk {} {:?} at ({}, {})",
+                        radius,
+                        accuracy,
+                        x,
+                        y
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn regular_polygon_shape() {
+    for radius in [1usize, 3, 8, 20, 45] {
+        for sides in 3..=18 {
+            let (se, kernel) = structuring_element::regular_polygon(radius, sides);
+            assert_solid_symmetric(&se, kernel);
+            let (cx, cy) = (kernel.anchor_x as i64, kernel.anchor_y as i64);
+            let farthest = (0..kernel.height as i64)
+                .flat_map(|y| (0..kernel.width as i64).map(move |x| (x, y)))
+                .filter(|&(x, y)| is_set(&se, kernel, x, y))
+                .map(|(x, y)| (((x - cx).pow(2) + (y - cy).pow(2)) as f64).sqrt())
+                .fold(0f64, f64::max);
+            assert!(
+                (farthest - radius as f64).abs() <= 2. + radius as f64 * 0.1,
+                "polygon {} {}",
+                radius,
+                sides
+            );
+        }
+    }
+}
+
+#[test]
+fn disk_morphology_u8() {
+    let shapes = shapes();
+    let ops = &MORPH_OPS;
+    check_morphology_with_shapes::<u8, _>(&shapes, 1, 256, ops, morph_fn!(morphology));
+    check_morphology_with_shapes::<u8, _>(&shapes, 4, 256, ops, morph_fn!(morphology_rgba));
+}
+
+#[test]
+fn disk_morphology_u16() {
+    let shapes = shapes();
+    let ops = &MORPH_OPS;
+    check_morphology_with_shapes::<u16, _>(&shapes, 1, 65536, ops, morph_fn!(morphology_gray_u16));
+}
+
+#[test]
+fn disk_morphology_f32() {
+    let shapes = shapes();
+    let ops = &MORPH_OPS;
+    check_morphology_with_shapes::<f32, _>(&shapes, 3, 1000, ops, morph_fn!(morphology_rgb_f32));
+}
+
+#[test]
+fn disk_border_modes() {
+    let size = ImageSize::new(91, 57);
+    let src_f64 = make_image(size.width * size.height, 29, 256);
+    let src = src_f64.iter().map(|&v| v as u8).collect::<Vec<u8>>();
+    for (se, kernel) in shapes().into_iter().chain(large_shapes()) {
+        for (dilate_op, border) in [(true, Some(255.)), (false, Some(0.)), (false, None)] {
+            let mut dst = vec![0u8; src.len()];
+            let op = if dilate_op { dilate } else { erode };
+            let (border_mode, scalar) = match border {
+                Some(v) => (BorderMode::Constant, MorphScalar::dup(v)),
+                None => (BorderMode::Clamp, MorphScalar::default()),
+            };
+            op(
+                &src,
+                &mut dst,
+                size,
+                &se,
+                kernel,
+                border_mode,
+                scalar,
+                MorphologyThreadingPolicy::Fixed(4),
+            )
+            .unwrap();
+            let expected = reference_op(&src_f64, size, 1, &se, kernel, dilate_op, border);
+            let dst = dst.iter().map(|&v| v as f64).collect::<Vec<f64>>();
+            assert_eq!(dst, expected, "kernel {:?}", kernel);
+        }
+    }
+}
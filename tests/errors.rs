@@ -0,0 +1,112 @@
+use fast_morphology::*;
+
+fn run_rgb(src: &[u8], dst: &mut [u8], size: ImageSize, se: &[u8]) -> Result<(), MorphologyError> {
+    morphology_rgb(
+        src,
+        dst,
+        MorphExOp::Opening,
+        size,
+        se,
+        KernelShape::new(3, 3),
+        BorderMode::Clamp,
+        MorphScalar::default(),
+        MorphologyThreadingPolicy::Single,
+    )
+}
+
+#[test]
+fn source_destination_mismatch() {
+    let size = ImageSize::new(4, 4);
+    let src = vec![0u8; 48];
+    let mut dst = vec![0u8; 47];
+    assert_eq!(
+        run_rgb(&src, &mut dst, size, &[1; 9]),
+        Err(MorphologyError::SourceDestinationMismatch { src: 48, dst: 47 })
+    );
+}
+
+#[test]
+fn image_size_mismatch() {
+    let size = ImageSize::new(4, 4);
+    let src = vec![0u8; 16];
+    let mut dst = vec![0u8; 16];
+    assert_eq!(
+        run_rgb(&src, &mut dst, size, &[1; 9]),
+        Err(MorphologyError::ImageSizeMismatch {
+            expected: 48,
+            actual: 16
+        })
+    );
+    let result = erode_rgba_u16(
+        &[0u16; 16],
+        &mut [0u16; 16],
+        size,
+        &[1; 9],
+        KernelShape::new(3, 3),
+        BorderMode::Clamp,
+        MorphScalar::default(),
+        MorphologyThreadingPolicy::Single,
+    );
+    assert_eq!(
+        result,
+        Err(MorphologyError::ImageSizeMismatch {
+            expected: 64,
+            actual: 16
+        })
+    );
+}
+
+#[test]
+fn structuring_element_mismatch() {
+    let size = ImageSize::new(4, 4);
+    let src = vec![0u8; 48];
+    let mut dst = vec![0u8; 48];
+    assert_eq!(
+        run_rgb(&src, &mut dst, size, &[1; 8]),
+        Err(MorphologyError::StructuringElementSizeMismatch {
+            expected: 9,
+            actual: 8
+        })
+    );
+}
+
+#[test]
+fn zero_sized_image() {
+    let size = ImageSize::new(0, 4);
+    assert_eq!(
+        run_rgb(&[], &mut [], size, &[1; 9]),
+        Err(MorphologyError::ZeroSizedImage {
+            width: 0,
+            height: 4
+        })
+    );
+    let plan = MorphologyPlan::<f32>::new(
+        ImageSize::new(3, 0),
+        MorphologyLayout::Gray,
+        MorphExOp::Dilate,
+        &[1; 9],
+        KernelShape::new(3, 3),
+        BorderMode::Clamp,
+        MorphScalar::default(),
+        MorphologyThreadingPolicy::Single,
+    );
+    assert!(matches!(
+        plan,
+        Err(MorphologyError::ZeroSizedImage {
+            width: 3,
+            height: 0
+        })
+    ));
+}
+
+#[test]
+fn error_is_std_error() {
+    let error: Box<dyn std::error::Error> = Box::new(MorphologyError::ImageSizeMismatch {
+        expected: 48,
+        actual: 16,
+    });
+    assert_eq!(
+        error.to_string(),
+        "Image size expected to be 48 but it was 16"
+    );
+}
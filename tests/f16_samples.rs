@@ -0,0 +1,107 @@
+#![cfg(feature = "half")]
+
+mod common;
+
+use common::*;
+use fast_morphology::*;
+use half::f16;
+
+fn f16_kernels() -> Vec<KernelShape> {
+    vec![
+        KernelShape::new(3, 3),
+        KernelShape::new(1, 1),
+        KernelShape::new(15, 5),
+        KernelShape::new(33, 3),
+        KernelShape::new_with_anchor(6, 4, 0, 3),
+    ]
+}
+
+fn full_se(kernel: KernelShape, _: u64) -> Vec<u8> {
+    vec![1u8; kernel.width * kernel.height]
+}
+
+#[test]
+fn f16_morphology() {
+    // Integers up to 2048 are exact in f16
+    let kernels = f16_kernels();
+    let ops = &MORPH_OPS;
+    check_morphology::<f16, _>(&kernels, 1, 1000, ops, morph_fn!(morphology_gray_f16));
+    check_morphology::<f16, _>(&kernels, 2, 1000, ops, morph_fn!(morphology_gray_alpha_f16));
+    check_morphology::<f16, _>(&kernels, 3, 1000, ops, morph_fn!(morphology_rgb_f16));
+    check_morphology::<f16, _>(&kernels, 4, 1000, ops, morph_fn!(morphology_rgba_f16));
+    check_morphology_with_se::<f16, _, _>(
+        &kernels,
+        1,
+        1000,
+        ops,
+        full_se,
+        morph_fn!(morphology_gray_f16),
+    );
+}
+
+#[test]
+fn f16_fractional_values() {
+    let size = ImageSize::new(71, 13);
+    let src_f64 = make_image(size.width * size.height, 5, 1000)
+        .iter()
+        .map(|&v| f16::from_f64(v / 1000. - 0.5).to_f64())
+        .collect::<Vec<f64>>();
+    let src = src_f64
+        .iter()
+        .map(|&v| f16::from_f64(v))
+        .collect::<Vec<f16>>();
+    for (se, kernel) in [
+        structuring_element::rect(5, 3),
+        structuring_element::ellipse(7, 5),
+    ] {
+        for dilate_op in [true, false] {
+            let mut dst = vec![f16::ZERO; src.len()];
+            let op = if dilate_op { dilate_f16 } else { erode_f16 };
+            op(
+                &src,
+                &mut dst,
+                size,
+                &se,
+                kernel,
+                BorderMode::Clamp,
+                MorphScalar::default(),
+                MorphologyThreadingPolicy::Fixed(2),
+            )
+            .unwrap();
+            let expected = reference_op(&src_f64, size, 1, &se, kernel, dilate_op, None);
+            let dst = dst.iter().map(|&v| v.to_f64()).collect::<Vec<f64>>();
+            assert_eq!(dst, expected, "kernel {:?}, dilate {}", kernel, dilate_op);
+        }
+    }
+}
+
+#[test]
+fn f16_constant_border() {
+    let size = ImageSize::new(37, 11);
+    let src = vec![f16::from_f32(-1.); size.width * size.height * 4];
+    let (se, kernel) = structuring_element::rect(3, 3);
+    // Rounding through f32 first would tie to 1 instead of rounding up
+    let half_ulp_and_more = 1. + 2f64.powi(-11) + 2f64.powi(-40);
+    let scalar = MorphScalar::new(0.1, half_ulp_and_more, 65504., 1e6);
+    let mut dst = vec![f16::ZERO; src.len()];
+    dilate_rgba_f16(
+        &src,
+        &mut dst,
+        size,
+        &se,
+        kernel,
+        BorderMode::Constant,
+        scalar,
+        MorphologyThreadingPolicy::Single,
+    )
+    .unwrap();
+    assert_eq!(
+        &dst[..4],
+        &[
+            f16::from_f64(0.1),
+            f16::from_f64(1. + 2f64.powi(-10)),
+            f16::MAX,
+            f16::INFINITY
+        ]
+    );
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use common::*;
+use fast_morphology::*;
+
+fn f64_kernels() -> Vec<KernelShape> {
+    vec![
+        KernelShape::new(3, 3),
+        KernelShape::new(1, 1),
+        KernelShape::new(15, 5),
+        KernelShape::new(33, 3),
+        KernelShape::new_with_anchor(6, 4, 0, 3),
+    ]
+}
+
+fn full_se(kernel: KernelShape, _: u64) -> Vec<u8> {
+    vec![1u8; kernel.width * kernel.height]
+}
+
+#[test]
+fn f64_morphology() {
+    let kernels = f64_kernels();
+    let ops = &MORPH_OPS;
+    check_morphology::<f64, _>(&kernels, 1, 1000, ops, morph_fn!(morphology_gray_f64));
+    check_morphology::<f64, _>(&kernels, 2, 1000, ops, morph_fn!(morphology_gray_alpha_f64));
+    check_morphology::<f64, _>(&kernels, 3, 1000, ops, morph_fn!(morphology_rgb_f64));
+    check_morphology::<f64, _>(&kernels, 4, 1000, ops, morph_fn!(morphology_rgba_f64));
+    check_morphology_with_se::<f64, _, _>(
+        &kernels,
+        1,
+        1000,
+        ops,
+        full_se,
+        morph_fn!(morphology_gray_f64),
+    );
+}
+
+#[test]
+fn f64_keeps_precision() {
+    // Neighbouring values collapse into one when rounded to f32
+    let size = ImageSize::new(71, 13);
+    let src = make_image(size.width * size.height, 5, 1000)
+        .iter()
+        .map(|&v| 16777216. + v * 1e-6)
+        .collect::<Vec<f64>>();
+    for (se, kernel) in [
+        structuring_element::rect(5, 3),
+        structuring_element::ellipse(7, 5),
+    ] {
+        for dilate_op in [true, false] {
+            let mut dst = vec![0f64; src.len()];
+            let op = if dilate_op { dilate_f64 } else { erode_f64 };
+            op(
+                &src,
+                &mut dst,
+                size,
+                &se,
+                kernel,
+                BorderMode::Clamp,
+                MorphScalar::default(),
+                MorphologyThreadingPolicy::Fixed(2),
+            )
+            .unwrap();
+            let expected = reference_op(&src, size, 1, &se, kernel, dilate_op, None);
+            assert_eq!(dst, expected, "kernel {:?}, dilate {}", kernel, dilate_op);
+        }
+    }
+}
+
+#[test]
+fn f64_constant_border() {
+    let size = ImageSize::new(37, 11);
+    let src = vec![0.5f64; size.width * size.height * 3];
+    let (se, kernel) = structuring_element::rect(3, 3);
+    let scalar = MorphScalar::new(0.1, 1. + 1e-12, 1e300, 0.);
+    let mut dst = vec![0f64; src.len()];
+    dilate_rgb_f64(
+        &src,
+        &mut dst,
+        size,
+        &se,
+        kernel,
+        BorderMode::Constant,
+        scalar,
+        MorphologyThreadingPolicy::Single,
+    )
+    .unwrap();
+    // The first pixel touches the border, the border values must not pass through f32
+    assert_eq!(&dst[..3], &[0.5, 1. + 1e-12, 1e300]);
+}
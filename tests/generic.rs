@@ -0,0 +1,243 @@
+mod common;
+
+use common::*;
+use fast_morphology::generic::{
+    self, AlphaPremultipliable, ChannelLayout, GrayAlphaPackable, InterleavedPackable,
+    MorphGradient, MorphNativeOp, MorphOp, MorphRows, MorphRowsHandler, MorphWeightedOp,
+    RgbPackable, RgbaPackable, Row2DFilter,
+};
+use fast_morphology::*;
+use num_traits::AsPrimitive;
+use std::cell::Cell;
+
+/// Sample type defined outside of the crate, it gets scalar filters and packing
+#[derive(Debug, Copy, Clone, Default, PartialEq, PartialOrd)]
+struct Level(u16);
+
+impl From<Level> for f64 {
+    fn from(value: Level) -> Self {
+        value.0 as f64
+    }
+}
+
+impl AsPrimitive<Level> for f64 {
+    fn as_(self) -> Level {
+        Level(self as u16)
+    }
+}
+
+impl Sample for Level {
+    const MIN: f64 = u16::MIN as f64;
+    const MAX: f64 = u16::MAX as f64;
+    fn from_f64(v: f64) -> Self {
+        Level(v as u16)
+    }
+}
+
+impl MorphNativeOp<Level> for Level {
+    fn op<const OP: u8>(&self, other: Level) -> Level {
+        match OP.into() {
+            MorphOp::Dilate => Level(self.0.max(other.0)),
+            MorphOp::Erode => Level(self.0.min(other.0)),
+        }
+    }
+}
+
+impl MorphWeightedOp<Level> for Level {
+    type Weight = i32;
+
+    fn make_weight<const OP: u8>(weight: f32) -> i32 {
+        match OP.into() {
+            MorphOp::Dilate => weight.round() as i32,
+            MorphOp::Erode => -weight.round() as i32,
+        }
+    }
+
+    fn add_weight(&self, weight: i32) -> Level {
+        Level((self.0 as i32 + weight).clamp(0, u16::MAX as i32) as u16)
+    }
+}
+
+impl MorphGradient<Level> for Level {
+    fn morph_gradient(dilation: &[Level], erosion: &[Level], dst: &mut [Level]) {
+        for ((dilation, erosion), dst) in dilation.iter().zip(erosion).zip(dst.iter_mut()) {
+            *dst = Level(dilation.0.saturating_sub(erosion.0));
+        }
+    }
+}
+
+thread_local! {
+    /// Items that rows handlers of [Level] wrote on this thread
+    static ROWS_ITEMS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn counted_rows_op<const OP: u8>(a: &[Level], b: &[Level], dst: &mut [Level]) {
+    ROWS_ITEMS.with(|x| x.set(x.get() + dst.len()));
+    for ((a, b), dst) in a.iter().zip(b).zip(dst.iter_mut()) {
+        *dst = a.op::<OP>(*b);
+    }
+}
+
+impl MorphRows<Level> for Level {
+    fn get_rows_op(op: MorphOp) -> MorphRowsHandler<Level> {
+        match op {
+            MorphOp::Dilate => counted_rows_op::<{ MorphOp::Dilate as u8 }>,
+            MorphOp::Erode => counted_rows_op::<{ MorphOp::Erode as u8 }>,
+        }
+    }
+}
+
+impl Row2DFilter<Level> for Level {}
+impl GrayAlphaPackable<Level> for Level {}
+impl RgbPackable<Level> for Level {}
+impl RgbaPackable<Level> for Level {}
+impl InterleavedPackable<Level> for Level {}
+impl AlphaPremultipliable<Level> for Level {}
+
+fn generic_kernels() -> Vec<KernelShape> {
+    vec![
+        KernelShape::new(3, 3),
+        KernelShape::new(15, 5),
+        KernelShape::new(1, 9),
+        KernelShape::new_with_anchor(6, 4, 0, 3),
+    ]
+}
+
+fn check_generic<T, L>(max: u32)
+where
+    T: Sample + MorphologySample,
+    L: ChannelLayout,
+    f64: AsPrimitive<T>,
+{
+    check_morphology::<T, _>(
+        &generic_kernels(),
+        L::channels(),
+        max,
+        &MORPH_OPS,
+        |src, dst, op, size, se, kernel, policy| {
+            generic::morphology::<T, L>(
+                src,
+                dst,
+                op,
+                size,
+                se,
+                kernel,
+                BorderMode::Clamp,
+                MorphScalar::default(),
+                policy,
+            )
+        },
+    );
+}
+
+#[test]
+fn generic_morphology_builtin_samples() {
+    check_generic::<u8, generic::Gray>(256);
+    check_generic::<u16, generic::Rgba>(65536);
+    check_generic::<f32, generic::GrayAlpha>(1000);
+    check_generic::<i16, generic::Rgb>(65536);
+}
+
+#[test]
+fn generic_morphology_downstream_sample() {
+    check_generic::<Level, generic::Gray>(65536);
+    check_generic::<Level, generic::GrayAlpha>(65536);
+    check_generic::<Level, generic::Rgb>(65536);
+    check_generic::<Level, generic::Rgba>(65536);
+}
+
+#[test]
+fn generic_non_flat_interleaved() {
+    let size = ImageSize::new(45, 17);
+    let src = make_image(size.width * size.height * 3, 7, 65536)
+        .iter()
+        .map(|&v| v as u16)
+        .collect::<Vec<u16>>();
+    let kernel = KernelShape::new(5, 3);
+    let weights = (0..15).map(|i| (i % 4) as f32 * 300.).collect::<Vec<f32>>();
+    let border = MorphScalar::new(1., 2., 3., 4.);
+    for op in MORPH_OPS {
+        let mut dst = vec![0u16; src.len()];
+        generic::morphology_non_flat::<u16, generic::Rgb>(
+            &src,
+            &mut dst,
+            op,
+            size,
+            &weights,
+            kernel,
+            BorderMode::Constant,
+            border,
+            MorphologyThreadingPolicy::Single,
+        )
+        .unwrap();
+        for channel in 0..3 {
+            let plane = src
+                .iter()
+                .skip(channel)
+                .step_by(3)
+                .copied()
+                .collect::<Vec<u16>>();
+            let mut expected = vec![0u16; plane.len()];
+            morphology_non_flat_u16(
+                &plane,
+                &mut expected,
+                op,
+                size,
+                &weights,
+                kernel,
+                BorderMode::Constant,
+                MorphScalar::dup(border[channel]),
+                MorphologyThreadingPolicy::Single,
+            )
+            .unwrap();
+            let result = dst
+                .iter()
+                .skip(channel)
+                .step_by(3)
+                .copied()
+                .collect::<Vec<u16>>();
+            assert_eq!(result, expected, "op {:?}, channel {}", op, channel);
+        }
+    }
+}
+
+#[test]
+fn horizontal_periodic_line_cost_does_not_grow_with_length() {
+    let size = ImageSize::new(2400, 8);
+    let src = make_image(size.width * size.height, 5, 65536)
+        .iter()
+        .map(|&v| Level(v as u16))
+        .collect::<Vec<Level>>();
+    let mut items_per_pixel = vec![];
+    for length in [256, 512, 1024, 2048] {
+        // Sheared two rows are a horizontal periodic line and two points
+        let kernel = KernelShape::new(length + 1, 2);
+        let mut se = vec![0u8; kernel.width * kernel.height];
+        se[..length].fill(1);
+        se[kernel.width + 1..].fill(1);
+        let mut dst = vec![Level::default(); src.len()];
+        ROWS_ITEMS.with(|x| x.set(0));
+        generic::morphology::<Level, generic::Gray>(
+            &src,
+            &mut dst,
+            MorphExOp::Dilate,
+            size,
+            &se,
+            kernel,
+            BorderMode::Clamp,
+            MorphScalar::default(),
+            MorphologyThreadingPolicy::Single,
+        )
+        .unwrap();
+        // Line runs over the image padded by the kernel
+        let arena = (size.width + kernel.width - 1) * (size.height + kernel.height - 1);
+        items_per_pixel.push(ROWS_ITEMS.with(|x| x.get()) as f64 / arena as f64);
+    }
+    // Van Herk/Gil-Werman algorithm writes 3 items per pixel, passes of log2 of the length
+    // would write 8 to 11
+    assert!(
+        items_per_pixel.iter().all(|&x| x < 4.),
+        "Rows handlers wrote {:?} items per pixel",
+        items_per_pixel
+    );
+}
@@ -0,0 +1,378 @@
+mod common;
+
+use common::*;
+use fast_morphology::*;
+
+type InPlaceFn<T> = fn(
+    &mut [T],
+    MorphExOp,
+    ImageSize,
+    &[u8],
+    KernelShape,
+    BorderMode,
+    MorphScalar,
+    MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError>;
+
+type InPlaceOpFn<T> = fn(
+    &mut [T],
+    ImageSize,
+    &[u8],
+    KernelShape,
+    BorderMode,
+    MorphScalar,
+    MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError>;
+
+fn shapes() -> Vec<(Vec<u8>, KernelShape)> {
+    vec![
+        structuring_element::rect(7, 5),
+        structuring_element::circle(5),
+        structuring_element::disk(9, structuring_element::DiskAccuracy::default()),
+        (make_se((3, 3), 5), KernelShape::new(3, 3)),
+        (make_se((4, 6), 9), KernelShape::new_with_anchor(4, 6, 0, 5)),
+        (vec![0u8; 9], KernelShape::new(3, 3)),
+    ]
+}
+
+macro_rules! check_in_place {
+    ($t: ty, $layout: expr, $max: expr, $morphology: expr, $dilate: expr, $erode: expr) => {{
+        let layout: MorphologyLayout = $layout;
+        let morphology: InPlaceFn<$t> = $morphology;
+        let dilate: InPlaceOpFn<$t> = $dilate;
+        let erode: InPlaceOpFn<$t> = $erode;
+        let size = ImageSize::new(61, 27);
+        let src = make_image(size.width * size.height * layout.channels(), 23, $max)
+            .iter()
+            .map(|&v| <$t>::from_f64(v + <$t>::OFFSET))
+            .collect::<Vec<$t>>();
+        let scalar = MorphScalar::new(7., 20., 30., 255.);
+        for (se, kernel) in shapes() {
+            for border_mode in [BorderMode::Reflect101, BorderMode::Constant] {
+                for op in MORPH_OPS {
+                    for policy in [
+                        MorphologyThreadingPolicy::Single,
+                        MorphologyThreadingPolicy::Fixed(3),
+                    ] {
+                        let mut plan = MorphologyPlan::<$t>::new(
+                            size,
+                            layout,
+                            op,
+                            &se,
+                            kernel,
+                            border_mode,
+                            scalar,
+                            policy.clone(),
+                        )
+                        .unwrap();
+                        let mut expected = vec![<$t>::default(); src.len()];
+                        plan.execute(&src, &mut expected).unwrap();
+
+                        let mut image = src.clone();
+                        morphology(
+                            &mut image,
+                            op,
+                            size,
+                            &se,
+                            kernel,
+                            border_mode,
+                            scalar,
+                            policy.clone(),
+                        )
+                        .unwrap();
+                        assert!(
+                            image == expected,
+                            "In place result differs for kernel {:?}, op {:?}, border {:?}",
+                            kernel,
+                            op,
+                            border_mode
+                        );
+
+                        let mut image = src.clone();
+                        plan.execute_in_place(&mut image).unwrap();
+                        assert!(image == expected, "Plan in place differs for op {:?}", op);
+
+                        let single: Option<InPlaceOpFn<$t>> = match op {
+                            MorphExOp::Dilate => Some(dilate),
+                            MorphExOp::Erode => Some(erode),
+                            _ => None,
+                        };
+                        if let Some(single) = single {
+                            let mut image = src.clone();
+                            single(&mut image, size, &se, kernel, border_mode, scalar, policy)
+                                .unwrap();
+                            assert!(image == expected, "{:?} in place differs", op);
+                        }
+                    }
+                }
+            }
+        }
+    }};
+}
+
+#[test]
+fn in_place_u8() {
+    check_in_place!(
+        u8,
+        MorphologyLayout::Gray,
+        256,
+        morphology_in_place,
+        dilate_in_place,
+        erode_in_place
+    );
+    check_in_place!(
+        u8,
+        MorphologyLayout::GrayAlpha,
+        256,
+        morphology_gray_alpha_in_place,
+        dilate_gray_alpha_in_place,
+        erode_gray_alpha_in_place
+    );
+    check_in_place!(
+        u8,
+        MorphologyLayout::Rgb,
+        256,
+        morphology_rgb_in_place,
+        dilate_rgb_in_place,
+        erode_rgb_in_place
+    );
+    check_in_place!(
+        u8,
+        MorphologyLayout::Rgba,
+        256,
+        morphology_rgba_in_place,
+        dilate_rgba_in_place,
+        erode_rgba_in_place
+    );
+}
+
+#[test]
+fn in_place_u16() {
+    check_in_place!(
+        u16,
+        MorphologyLayout::Gray,
+        65536,
+        morphology_gray_u16_in_place,
+        dilate_u16_in_place,
+        erode_u16_in_place
+    );
+    check_in_place!(
+        u16,
+        MorphologyLayout::GrayAlpha,
+        65536,
+        morphology_gray_alpha_u16_in_place,
+        dilate_gray_alpha_u16_in_place,
+        erode_gray_alpha_u16_in_place
+    );
+    check_in_place!(
+        u16,
+        MorphologyLayout::Rgb,
+        65536,
+        morphology_rgb_u16_in_place,
+        dilate_rgb_u16_in_place,
+        erode_rgb_u16_in_place
+    );
+    check_in_place!(
+        u16,
+        MorphologyLayout::Rgba,
+        65536,
+        morphology_rgba_u16_in_place,
+        dilate_rgba_u16_in_place,
+        erode_rgba_u16_in_place
+    );
+}
+
+#[test]
+fn in_place_f32() {
+    check_in_place!(
+        f32,
+        MorphologyLayout::Gray,
+        1000,
+        morphology_gray_f32_in_place,
+        dilate_f32_in_place,
+        erode_f32_in_place
+    );
+    check_in_place!(
+        f32,
+        MorphologyLayout::GrayAlpha,
+        1000,
+        morphology_gray_alpha_f32_in_place,
+        dilate_gray_alpha_f32_in_place,
+        erode_gray_alpha_f32_in_place
+    );
+    check_in_place!(
+        f32,
+        MorphologyLayout::Rgb,
+        1000,
+        morphology_rgb_f32_in_place,
+        dilate_rgb_f32_in_place,
+        erode_rgb_f32_in_place
+    );
+    check_in_place!(
+        f32,
+        MorphologyLayout::Rgba,
+        1000,
+        morphology_rgba_f32_in_place,
+        dilate_rgba_f32_in_place,
+        erode_rgba_f32_in_place
+    );
+}
+
+#[test]
+fn in_place_f64() {
+    check_in_place!(
+        f64,
+        MorphologyLayout::Gray,
+        1000,
+        morphology_gray_f64_in_place,
+        dilate_f64_in_place,
+        erode_f64_in_place
+    );
+    check_in_place!(
+        f64,
+        MorphologyLayout::GrayAlpha,
+        1000,
+        morphology_gray_alpha_f64_in_place,
+        dilate_gray_alpha_f64_in_place,
+        erode_gray_alpha_f64_in_place
+    );
+    check_in_place!(
+        f64,
+        MorphologyLayout::Rgb,
+        1000,
+        morphology_rgb_f64_in_place,
+        dilate_rgb_f64_in_place,
+        erode_rgb_f64_in_place
+    );
+    check_in_place!(
+        f64,
+        MorphologyLayout::Rgba,
+        1000,
+        morphology_rgba_f64_in_place,
+        dilate_rgba_f64_in_place,
+        erode_rgba_f64_in_place
+    );
+}
+
+#[cfg(feature = "half")]
+#[test]
+fn in_place_f16() {
+    use half::f16;
+    check_in_place!(
+        f16,
+        MorphologyLayout::Gray,
+        1000,
+        morphology_gray_f16_in_place,
+        dilate_f16_in_place,
+        erode_f16_in_place
+    );
+    check_in_place!(
+        f16,
+        MorphologyLayout::GrayAlpha,
+        1000,
+        morphology_gray_alpha_f16_in_place,
+        dilate_gray_alpha_f16_in_place,
+        erode_gray_alpha_f16_in_place
+    );
+    check_in_place!(
+        f16,
+        MorphologyLayout::Rgb,
+        1000,
+        morphology_rgb_f16_in_place,
+        dilate_rgb_f16_in_place,
+        erode_rgb_f16_in_place
+    );
+    check_in_place!(
+        f16,
+        MorphologyLayout::Rgba,
+        1000,
+        morphology_rgba_f16_in_place,
+        dilate_rgba_f16_in_place,
+        erode_rgba_f16_in_place
+    );
+}
+
+#[test]
+fn in_place_int() {
+    check_in_place!(
+        i8,
+        MorphologyLayout::Gray,
+        256,
+        morphology_gray_i8_in_place,
+        dilate_i8_in_place,
+        erode_i8_in_place
+    );
+    check_in_place!(
+        i16,
+        MorphologyLayout::GrayAlpha,
+        65536,
+        morphology_gray_alpha_i16_in_place,
+        dilate_gray_alpha_i16_in_place,
+        erode_gray_alpha_i16_in_place
+    );
+    check_in_place!(
+        i32,
+        MorphologyLayout::Rgb,
+        u32::MAX,
+        morphology_rgb_i32_in_place,
+        dilate_rgb_i32_in_place,
+        erode_rgb_i32_in_place
+    );
+    check_in_place!(
+        u32,
+        MorphologyLayout::Rgba,
+        u32::MAX,
+        morphology_rgba_u32_in_place,
+        dilate_rgba_u32_in_place,
+        erode_rgba_u32_in_place
+    );
+}
+
+#[test]
+fn in_place_roi_with_stride() {
+    let size = ImageSize::new(45, 30);
+    let stride = size.width * 3 + 7;
+    let src = make_image(stride * size.height, 3, 256)
+        .iter()
+        .map(|&v| v as u8)
+        .collect::<Vec<u8>>();
+    let (se, kernel) = structuring_element::ellipse(7, 5);
+    for roi in [
+        Roi::new(10, 8, 20, 12),
+        Roi::new(0, 0, 45, 30),
+        Roi::new(40, 0, 5, 30),
+    ] {
+        for op in MORPH_OPS {
+            let mut plan = MorphologyPlan::<u8>::new_roi(
+                size,
+                roi,
+                MorphologyLayout::Rgb,
+                op,
+                &se,
+                kernel,
+                BorderMode::Reflect,
+                MorphScalar::default(),
+                MorphologyThreadingPolicy::Single,
+            )
+            .unwrap();
+            let mut expected = vec![0u8; roi.width * roi.height * 3];
+            plan.execute_with_stride(&src, stride, &mut expected, roi.width * 3)
+                .unwrap();
+
+            let mut image = src.clone();
+            plan.execute_in_place_with_stride(&mut image, stride)
+                .unwrap();
+            for (y, (row, src_row)) in image.chunks(stride).zip(src.chunks(stride)).enumerate() {
+                let (start, end) = (roi.x * 3, (roi.x + roi.width) * 3);
+                if y >= roi.y && y < roi.y + roi.height {
+                    let expected_row = &expected[(y - roi.y) * roi.width * 3..][..roi.width * 3];
+                    assert_eq!(&row[start..end], expected_row, "ROI {:?}, op {:?}", roi, op);
+                    assert_eq!(row[..start], src_row[..start]);
+                    assert_eq!(row[end..], src_row[end..]);
+                } else {
+                    assert_eq!(row, src_row, "Row {} outside of ROI {:?} changed", y, roi);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,167 @@
+mod common;
+
+use common::*;
+use fast_morphology::*;
+
+fn int_kernels() -> Vec<KernelShape> {
+    vec![
+        KernelShape::new(3, 3),
+        KernelShape::new(1, 1),
+        KernelShape::new(15, 5),
+        KernelShape::new(33, 3),
+        KernelShape::new(1, 9),
+        KernelShape::new_with_anchor(6, 4, 0, 3),
+    ]
+}
+
+fn full_se(kernel: KernelShape, _: u64) -> Vec<u8> {
+    vec![1u8; kernel.width * kernel.height]
+}
+
+macro_rules! check_layouts {
+    ($t: ty, $max: expr, $gray: ident, $gray_alpha: ident, $rgb: ident, $rgba: ident) => {{
+        let kernels = int_kernels();
+        let ops = &MORPH_OPS;
+        check_morphology::<$t, _>(&kernels, 1, $max, ops, morph_fn!($gray));
+        check_morphology::<$t, _>(&kernels, 2, $max, ops, morph_fn!($gray_alpha));
+        check_morphology::<$t, _>(&kernels, 3, $max, ops, morph_fn!($rgb));
+        check_morphology::<$t, _>(&kernels, 4, $max, ops, morph_fn!($rgba));
+        check_morphology_with_se::<$t, _, _>(&kernels, 1, $max, ops, full_se, morph_fn!($gray));
+    }};
+}
+
+#[test]
+fn int_morphology_i8() {
+    check_layouts!(
+        i8,
+        256,
+        morphology_gray_i8,
+        morphology_gray_alpha_i8,
+        morphology_rgb_i8,
+        morphology_rgba_i8
+    );
+}
+
+#[test]
+fn int_morphology_i16() {
+    check_layouts!(
+        i16,
+        65536,
+        morphology_gray_i16,
+        morphology_gray_alpha_i16,
+        morphology_rgb_i16,
+        morphology_rgba_i16
+    );
+}
+
+#[test]
+fn int_morphology_i32() {
+    check_layouts!(
+        i32,
+        u32::MAX,
+        morphology_gray_i32,
+        morphology_gray_alpha_i32,
+        morphology_rgb_i32,
+        morphology_rgba_i32
+    );
+}
+
+#[test]
+fn int_morphology_u32() {
+    check_layouts!(
+        u32,
+        u32::MAX,
+        morphology_gray_u32,
+        morphology_gray_alpha_u32,
+        morphology_rgb_u32,
+        morphology_rgba_u32
+    );
+}
+
+macro_rules! check_extremes {
+    ($t: ty, $dilate: ident, $erode: ident, $low: expr, $high: expr) => {{
+        // Wide enough for every vector width, values compare differently as signed and unsigned
+        let size = ImageSize::new(67, 3);
+        let src = (0..size.width * size.height)
+            .map(|i| if i % 2 == 0 { $low } else { $high })
+            .collect::<Vec<$t>>();
+        let (se, kernel) = structuring_element::rect(3, 1);
+        let mut dst = vec![<$t>::default(); src.len()];
+        $dilate(
+            &src,
+            &mut dst,
+            size,
+            &se,
+            kernel,
+            BorderMode::Clamp,
+            MorphScalar::default(),
+            MorphologyThreadingPolicy::Single,
+        )
+        .unwrap();
+        assert!(dst.iter().all(|&v| v == $high), "{:?}", dst);
+        $erode(
+            &src,
+            &mut dst,
+            size,
+            &se,
+            kernel,
+            BorderMode::Clamp,
+            MorphScalar::default(),
+            MorphologyThreadingPolicy::Single,
+        )
+        .unwrap();
+        assert!(dst.iter().all(|&v| v == $low), "{:?}", dst);
+    }};
+}
+
+#[test]
+fn int_signed_min_max() {
+    check_extremes!(i8, dilate_i8, erode_i8, -1, 1);
+    check_extremes!(i8, dilate_i8, erode_i8, i8::MIN, i8::MAX);
+    check_extremes!(i16, dilate_i16, erode_i16, -1, 1);
+    check_extremes!(i16, dilate_i16, erode_i16, i16::MIN, i16::MAX);
+    check_extremes!(i32, dilate_i32, erode_i32, -1, 1);
+    check_extremes!(i32, dilate_i32, erode_i32, i32::MIN, i32::MAX);
+    check_extremes!(u32, dilate_u32, erode_u32, 1, u32::MAX);
+    check_extremes!(
+        u32,
+        dilate_u32,
+        erode_u32,
+        i32::MAX as u32,
+        i32::MAX as u32 + 1
+    );
+}
+
+#[test]
+fn int_constant_border() {
+    let size = ImageSize::new(53, 17);
+    let src_f64 = make_image(size.width * size.height, 13, 65536)
+        .iter()
+        .map(|&v| v + i16::MIN as f64)
+        .collect::<Vec<f64>>();
+    let src = src_f64.iter().map(|&v| v as i16).collect::<Vec<i16>>();
+    let (se, kernel) = structuring_element::rect(5, 5);
+    for (dilate_op, border) in [
+        (true, -100.),
+        (false, 100.),
+        (true, 32767.),
+        (false, -32768.),
+    ] {
+        let mut dst = vec![0i16; src.len()];
+        let op = if dilate_op { dilate_i16 } else { erode_i16 };
+        op(
+            &src,
+            &mut dst,
+            size,
+            &se,
+            kernel,
+            BorderMode::Constant,
+            MorphScalar::dup(border),
+            MorphologyThreadingPolicy::Fixed(2),
+        )
+        .unwrap();
+        let expected = reference_op(&src_f64, size, 1, &se, kernel, dilate_op, Some(border));
+        let dst = dst.iter().map(|&v| v as f64).collect::<Vec<f64>>();
+        assert_eq!(dst, expected, "border {}", border);
+    }
+}
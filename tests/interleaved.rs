@@ -0,0 +1,286 @@
+mod common;
+
+use common::*;
+use fast_morphology::generic::{self, ChannelLayout, InterleavedPackable, UnpackedInterleaved};
+use fast_morphology::*;
+
+fn interleaved_kernels() -> Vec<KernelShape> {
+    vec![
+        KernelShape::new(3, 3),
+        KernelShape::new(9, 5),
+        KernelShape::new_with_anchor(4, 6, 3, 1),
+    ]
+}
+
+fn check_channels<T, L>(max: u32)
+where
+    T: Sample + MorphologySample,
+    L: ChannelLayout,
+    f64: num_traits::AsPrimitive<T>,
+{
+    check_morphology::<T, _>(
+        &interleaved_kernels(),
+        L::channels(),
+        max,
+        &MORPH_OPS,
+        |src, dst, op, size, se, kernel, policy| {
+            generic::morphology::<T, L>(
+                src,
+                dst,
+                op,
+                size,
+                se,
+                kernel,
+                BorderMode::Clamp,
+                MorphScalar::default(),
+                policy,
+            )
+        },
+    );
+}
+
+#[test]
+fn interleaved_const_channels() {
+    check_channels::<u8, generic::Channels<1>>(256);
+    check_channels::<u8, generic::Channels<2>>(256);
+    check_channels::<u16, generic::Channels<3>>(65536);
+    check_channels::<u8, generic::Channels<4>>(256);
+    check_channels::<u8, generic::Channels<5>>(256);
+    check_channels::<f32, generic::Channels<7>>(1000);
+    check_channels::<u16, generic::Channels<12>>(65536);
+}
+
+#[test]
+fn interleaved_runtime_channels() {
+    for channels in [2, 6, 9] {
+        let options = MorphologyOptions::default();
+        check_morphology::<u8, _>(
+            &interleaved_kernels(),
+            channels,
+            256,
+            &MORPH_OPS,
+            |src, dst, op, size, se, kernel, policy| {
+                generic::morphology_interleaved(
+                    src,
+                    dst,
+                    channels,
+                    op,
+                    size,
+                    se,
+                    kernel,
+                    &options.clone().with_threading_policy(policy),
+                )
+            },
+        );
+        check_morphology::<u8, _>(
+            &interleaved_kernels(),
+            channels,
+            256,
+            &MORPH_OPS,
+            |src, dst, op, size, se, kernel, policy| {
+                dst.copy_from_slice(src);
+                generic::morphology_interleaved_in_place(
+                    dst,
+                    channels,
+                    op,
+                    size,
+                    se,
+                    kernel,
+                    &options.clone().with_threading_policy(policy),
+                )
+            },
+        );
+    }
+}
+
+#[test]
+fn interleaved_matches_planes() {
+    let size = ImageSize::new(37, 19);
+    let channels = 6;
+    let src = make_image(size.width * size.height * channels, 8, 256)
+        .iter()
+        .map(|&v| v as u8)
+        .collect::<Vec<u8>>();
+    let kernel = KernelShape::new(5, 5);
+    let se = make_se((5, 5), 4);
+    let border = MorphScalar::new(10., 20., 30., 40.);
+    let options = MorphologyOptions::default()
+        .with_border_mode(BorderMode::Constant)
+        .with_border_scalar(border)
+        .with_threading_policy(MorphologyThreadingPolicy::Single);
+    let mut plan = MorphologyPlan::new_with_options(
+        size,
+        MorphologyLayout::Interleaved(channels),
+        MorphExOp::Dilate,
+        &se,
+        kernel,
+        &options,
+    )
+    .unwrap();
+    let mut dst = vec![0u8; src.len()];
+    plan.execute(&src, &mut dst).unwrap();
+    for channel in 0..channels {
+        let plane = src
+            .iter()
+            .skip(channel)
+            .step_by(channels)
+            .copied()
+            .collect::<Vec<u8>>();
+        let mut expected = vec![0u8; plane.len()];
+        dilate(
+            &plane,
+            &mut expected,
+            size,
+            &se,
+            kernel,
+            BorderMode::Constant,
+            MorphScalar::dup(border[channel.min(3)]),
+            MorphologyThreadingPolicy::Single,
+        )
+        .unwrap();
+        let result = dst
+            .iter()
+            .skip(channel)
+            .step_by(channels)
+            .copied()
+            .collect::<Vec<u8>>();
+        assert_eq!(result, expected, "channel {}", channel);
+    }
+}
+
+#[test]
+fn interleaved_border_values_per_channel() {
+    let size = ImageSize::new(29, 17);
+    let channels = 7;
+    let reference_src = make_image(size.width * size.height * channels, 12, 65536);
+    let src = reference_src
+        .iter()
+        .map(|&v| v as u16)
+        .collect::<Vec<u16>>();
+    let kernel = KernelShape::new_with_anchor(5, 3, 0, 2);
+    let se = make_se((5, 3), 6);
+    let border_values = [0., 65535., 100., 40000., 7., 65000., 300.];
+    let options = MorphologyOptions::default()
+        .with_border_mode(BorderMode::Constant)
+        .with_border_scalar(MorphScalar::dup(1.))
+        .with_border_values(&border_values);
+    for (op, dilate) in [(MorphExOp::Dilate, true), (MorphExOp::Erode, false)] {
+        let mut dst = vec![0u16; src.len()];
+        generic::morphology_with_options::<u16, generic::Channels<7>>(
+            &src, &mut dst, op, size, &se, kernel, &options,
+        )
+        .unwrap();
+        let mut image = src.clone();
+        generic::morphology_interleaved_in_place(
+            &mut image, channels, op, size, &se, kernel, &options,
+        )
+        .unwrap();
+        for (channel, &border) in border_values.iter().enumerate() {
+            let plane = reference_src
+                .iter()
+                .skip(channel)
+                .step_by(channels)
+                .copied()
+                .collect::<Vec<f64>>();
+            let expected = reference_op(&plane, size, 1, &se, kernel, dilate, Some(border))
+                .iter()
+                .map(|&v| v as u16)
+                .collect::<Vec<u16>>();
+            let channel_of = |image: &[u16]| {
+                image
+                    .iter()
+                    .skip(channel)
+                    .step_by(channels)
+                    .copied()
+                    .collect::<Vec<u16>>()
+            };
+            assert_eq!(channel_of(&dst), expected, "{:?} channel {}", op, channel);
+            assert_eq!(
+                channel_of(&image),
+                expected,
+                "in place {:?} channel {}",
+                op,
+                channel
+            );
+        }
+    }
+    let mut dst = vec![0u16; src.len()];
+    assert_eq!(
+        generic::morphology_with_options::<u16, generic::Channels<7>>(
+            &src,
+            &mut dst,
+            MorphExOp::Dilate,
+            size,
+            &se,
+            kernel,
+            &options.with_border_values(&[0.; 4]),
+        ),
+        Err(MorphologyError::BorderValuesMismatch {
+            expected: 7,
+            actual: 4
+        })
+    );
+}
+
+fn check_packing<T>(max: u32)
+where
+    T: Sample + MorphologySample + PartialEq,
+    f64: num_traits::AsPrimitive<T>,
+{
+    for channels in 1..=17 {
+        for width in [1, 2, 7, 16, 33] {
+            let size = ImageSize::new(width, 3);
+            let stride = width * channels + 5;
+            let src = make_image(stride * size.height, channels as u64, max)
+                .iter()
+                .map(|&v| T::from_f64(v))
+                .collect::<Vec<T>>();
+            let mut unpacked = UnpackedInterleaved::<T>::alloc(size, channels);
+            <T as InterleavedPackable<T>>::unpack(&src, stride, &mut unpacked, size);
+            for (channel, plane) in unpacked.planes.iter().enumerate() {
+                let expected = (0..size.height)
+                    .flat_map(|y| (0..width).map(move |x| y * stride + x * channels + channel))
+                    .map(|i| src[i])
+                    .collect::<Vec<T>>();
+                assert!(
+                    *plane == expected,
+                    "unpack channels {}, width {}",
+                    channels,
+                    width
+                );
+            }
+            let mut dst = src.clone();
+            for (y, row) in dst.chunks_mut(stride).enumerate() {
+                row[..width * channels].fill(T::from_f64(y as f64));
+            }
+            <T as InterleavedPackable<T>>::pack(&unpacked, &mut dst, stride, size);
+            assert!(dst == src, "pack channels {}, width {}", channels, width);
+        }
+    }
+}
+
+#[test]
+fn interleaved_packing_round_trips() {
+    check_packing::<u8>(256);
+    check_packing::<i8>(256);
+    check_packing::<u16>(65536);
+    check_packing::<i32>(1 << 31);
+    check_packing::<f32>(1000);
+    check_packing::<f64>(1000);
+}
+
+#[test]
+fn interleaved_zero_channels() {
+    let size = ImageSize::new(4, 4);
+    let result = MorphologyPlan::<u8>::new(
+        size,
+        MorphologyLayout::Interleaved(0),
+        MorphExOp::Dilate,
+        &[1; 9],
+        KernelShape::new(3, 3),
+        BorderMode::Clamp,
+        MorphScalar::default(),
+        MorphologyThreadingPolicy::Single,
+    );
+    assert!(matches!(result, Err(MorphologyError::ZeroChannels)));
+}
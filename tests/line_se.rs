@@ -0,0 +1,107 @@
+mod common;
+
+use common::*;
+use fast_morphology::*;
+
+fn lines() -> Vec<(Vec<u8>, KernelShape)> {
+    vec![
+        structuring_element::line(1, 0.),
+        structuring_element::line(25, 0.),
+        structuring_element::line(20, 90.),
+        structuring_element::line(31, 45.),
+        structuring_element::line(40, 30.),
+        structuring_element::line(33, 100.),
+        structuring_element::line(45, 160.),
+        structuring_element::line(27, -65.),
+        structuring_element::line(60, 12.5),
+    ]
+}
+
+#[test]
+fn line_shape() {
+    for length in [1usize, 2, 5, 17, 40] {
+        for angle in [0., 15., 30., 45., 60., 75., 90., 120., 160., 200., -30.] {
+            let (se, kernel) = structuring_element::line(length, angle);
+            assert_eq!(se.len(), kernel.width * kernel.height);
+            assert_eq!(se.iter().filter(|&&x| x != 0).count(), length);
+            assert_eq!(kernel.width.max(kernel.height), length);
+            assert_ne!(se[kernel.anchor_y * kernel.width + kernel.anchor_x], 0);
+            // Every point has a neighbour in 8-connectivity
+            if length > 1 {
+                for y in 0..kernel.height as i64 {
+                    for x in 0..kernel.width as i64 {
+                        if se[y as usize * kernel.width + x as usize] == 0 {
+                            continue;
+                        }
+                        let has_neighbour = (-1..=1).any(|dy| {
+                            (-1..=1).any(|dx| {
+                                let (nx, ny) = (x + dx, y + dy);
+                                (dx, dy) != (0, 0)
+                                    && nx >= 0
+                                    && ny >= 0
+                                    && nx < kernel.width as i64
+                                    && ny < kernel.height as i64
+                                    && se[ny as usize * kernel.width + nx as usize] != 0
+                            })
+                        });
+                        assert!(has_neighbour, "line {} at {}", length, angle);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn line_morphology_u8() {
+    let lines = lines();
+    let ops = &MORPH_OPS;
+    check_morphology_with_shapes::<u8, _>(&lines, 1, 256, ops, morph_fn!(morphology));
+    check_morphology_with_shapes::<u8, _>(&lines, 4, 256, ops, morph_fn!(morphology_rgba));
+}
+
+#[test]
+fn line_morphology_u16() {
+    let lines = lines();
+    let ops = &MORPH_OPS;
+    check_morphology_with_shapes::<u16, _>(&lines, 1, 65536, ops, morph_fn!(morphology_gray_u16));
+    check_morphology_with_shapes::<u16, _>(&lines, 3, 65536, ops, morph_fn!(morphology_rgb_u16));
+}
+
+#[test]
+fn line_morphology_f32() {
+    let lines = lines();
+    let ops = &MORPH_OPS;
+    check_morphology_with_shapes::<f32, _>(&lines, 3, 1000, ops, morph_fn!(morphology_rgb_f32));
+}
+
+#[test]
+fn line_border_modes() {
+    let size = ImageSize::new(73, 41);
+    let src_f64 = make_image(size.width * size.height, 23, 256);
+    let src = src_f64.iter().map(|&v| v as u8).collect::<Vec<u8>>();
+    for (se, kernel) in lines() {
+        for (dilate_op, border) in [(true, Some(255.)), (false, Some(0.)), (false, None)] {
+            let mut dst = vec![0u8; src.len()];
+            let op = if dilate_op { dilate } else { erode };
+            let (border_mode, scalar) = match border {
+                Some(v) => (BorderMode::Constant, MorphScalar::dup(v)),
+                None => (BorderMode::Clamp, MorphScalar::default()),
+            };
+            op(
+                &src,
+                &mut dst,
+                size,
+                &se,
+                kernel,
+                border_mode,
+                scalar,
+                MorphologyThreadingPolicy::Fixed(4),
+            )
+            .unwrap();
+            let expected = reference_op(&src_f64, size, 1, &se, kernel, dilate_op, border);
+            let dst = dst.iter().map(|&v| v as f64).collect::<Vec<f64>>();
+            assert_eq!(dst, expected, "kernel {:?}", kernel);
+        }
+    }
+}
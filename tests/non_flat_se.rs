@@ -0,0 +1,380 @@
+mod common;
+
+use common::*;
+use fast_morphology::*;
+
+fn weighted_elements() -> Vec<(Vec<f32>, KernelShape)> {
+    let mut rng = Lcg(17);
+    let asymmetric = (0..5 * 3)
+        .map(|i| match (i, rng.next() % 4) {
+            (0, _) => 0.,
+            (_, 0) => f32::NEG_INFINITY,
+            (_, v) => (rng.next() % 600) as f32 - 300. + v as f32,
+        })
+        .collect::<Vec<f32>>();
+    vec![
+        structuring_element::paraboloid(3, 2.),
+        structuring_element::ball(4, 40.),
+        (asymmetric, KernelShape::new_with_anchor(5, 3, 1, 2)),
+        (vec![7.], KernelShape::new(1, 1)),
+    ]
+}
+
+fn reference_weighted_op(
+    src: &[f64],
+    size: ImageSize,
+    weights: &[f64],
+    kernel: KernelShape,
+    dilate: bool,
+    border: Option<f64>,
+    max: Option<f64>,
+) -> Vec<f64> {
+    let (w, h) = (size.width as i64, size.height as i64);
+    let mut dst = vec![0f64; src.len()];
+    for y in 0..h {
+        for x in 0..w {
+            let mut acc = if dilate { f64::MIN } else { f64::MAX };
+            for ky in 0..kernel.height {
+                for kx in 0..kernel.width {
+                    let weight = weights[ky * kernel.width + kx];
+                    if !weight.is_finite() {
+                        continue;
+                    }
+                    let (dx, dy) = (
+                        kx as i64 - kernel.anchor_x as i64,
+                        ky as i64 - kernel.anchor_y as i64,
+                    );
+                    // Dilation reflects the element
+                    let (sx, sy) = if dilate {
+                        (x - dx, y - dy)
+                    } else {
+                        (x + dx, y + dy)
+                    };
+                    let v = if sy < 0 || sy >= h || sx < 0 || sx >= w {
+                        match border {
+                            Some(v) => v,
+                            None => src[(sy.clamp(0, h - 1) * w + sx.clamp(0, w - 1)) as usize],
+                        }
+                    } else {
+                        src[(sy * w + sx) as usize]
+                    };
+                    let v = if dilate { v + weight } else { v - weight };
+                    let v = match max {
+                        Some(max) => v.clamp(0., max),
+                        None => v,
+                    };
+                    acc = if dilate { acc.max(v) } else { acc.min(v) };
+                }
+            }
+            dst[(y * w + x) as usize] = acc;
+        }
+    }
+    dst
+}
+
+fn reference_weighted_morphology(
+    src: &[f64],
+    size: ImageSize,
+    weights: &[f64],
+    kernel: KernelShape,
+    op: MorphExOp,
+    border: Option<f64>,
+    max: Option<f64>,
+) -> Vec<f64> {
+    let dilate = |v: &[f64]| reference_weighted_op(v, size, weights, kernel, true, border, max);
+    let erode = |v: &[f64]| reference_weighted_op(v, size, weights, kernel, false, border, max);
+    let sub = |a: &[f64], b: &[f64]| {
+        a.iter()
+            .zip(b)
+            .map(|(a, b)| {
+                if max.is_some() {
+                    (a - b).max(0.)
+                } else {
+                    a - b
+                }
+            })
+            .collect::<Vec<_>>()
+    };
+    match op {
+        MorphExOp::Dilate => dilate(src),
+        MorphExOp::Erode => erode(src),
+        MorphExOp::Opening => dilate(&erode(src)),
+        MorphExOp::Closing => erode(&dilate(src)),
+        MorphExOp::Gradient => sub(&dilate(src), &erode(src)),
+        MorphExOp::TopHat => sub(src, &dilate(&erode(src))),
+        MorphExOp::BlackHat => sub(&erode(&dilate(src)), src),
+    }
+}
+
+fn check_non_flat<T, F>(max: u32, saturating: bool, tolerance: f64, func: F)
+where
+    T: Sample,
+    F: Fn(
+        &[T],
+        &mut [T],
+        MorphExOp,
+        ImageSize,
+        &[f32],
+        KernelShape,
+        BorderMode,
+        MorphScalar,
+        MorphologyThreadingPolicy,
+    ) -> Result<(), MorphologyError>,
+{
+    let size = ImageSize::new(77, 23);
+    for (i, (weights, kernel)) in weighted_elements().into_iter().enumerate() {
+        let reference_src = make_image(size.width * size.height, 7 + i as u64, max);
+        let src = reference_src
+            .iter()
+            .map(|&v| T::from_f64(v))
+            .collect::<Vec<T>>();
+        // Integer pixels use rounded weights
+        let reference_weights = weights
+            .iter()
+            .map(|&w| {
+                if saturating {
+                    w.round() as f64
+                } else {
+                    w as f64
+                }
+            })
+            .collect::<Vec<f64>>();
+        let saturation = saturating.then_some(max as f64 - 1.);
+        for &op in MORPH_OPS.iter() {
+            for (border_mode, border) in [
+                (BorderMode::Clamp, None),
+                (BorderMode::Constant, Some(max as f64 / 2.)),
+            ] {
+                let expected = reference_weighted_morphology(
+                    &reference_src,
+                    size,
+                    &reference_weights,
+                    kernel,
+                    op,
+                    border,
+                    saturation,
+                );
+                for policy in [
+                    MorphologyThreadingPolicy::Single,
+                    MorphologyThreadingPolicy::Fixed(3),
+                ] {
+                    let mut dst = vec![T::default(); src.len()];
+                    func(
+                        &src,
+                        &mut dst,
+                        op,
+                        size,
+                        &weights,
+                        kernel,
+                        border_mode,
+                        MorphScalar::dup(max as f64 / 2.),
+                        policy,
+                    )
+                    .unwrap();
+                    for (idx, (&v, &e)) in dst.iter().zip(expected.iter()).enumerate() {
+                        assert!(
+                            (v.into() - e).abs() <= tolerance,
+                            "Mismatch at {} for kernel {:?}, op {:?}, {:?} != {}",
+                            idx,
+                            kernel,
+                            op,
+                            v,
+                            e
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn non_flat_morphology_u8() {
+    check_non_flat::<u8, _>(256, true, 0., morphology_non_flat);
+}
+
+#[test]
+fn non_flat_morphology_u16() {
+    check_non_flat::<u16, _>(65536, true, 0., morphology_non_flat_u16);
+}
+
+#[test]
+fn non_flat_morphology_f32() {
+    check_non_flat::<f32, _>(1000, false, 1e-3, morphology_non_flat_f32);
+}
+
+#[test]
+fn non_flat_zero_weights_match_flat() {
+    let size = ImageSize::new(64, 31);
+    let src = make_image(size.width * size.height, 3, 256)
+        .iter()
+        .map(|&v| v as u8)
+        .collect::<Vec<u8>>();
+    let (se, kernel) = structuring_element::circle(4);
+    let weights = se
+        .iter()
+        .map(|&x| if x != 0 { 0. } else { f32::NEG_INFINITY })
+        .collect::<Vec<f32>>();
+    for op in MORPH_OPS {
+        let mut expected = vec![0u8; src.len()];
+        morphology(
+            &src,
+            &mut expected,
+            op,
+            size,
+            &se,
+            kernel,
+            BorderMode::Clamp,
+            MorphScalar::default(),
+            MorphologyThreadingPolicy::Single,
+        )
+        .unwrap();
+        let mut dst = vec![0u8; src.len()];
+        morphology_non_flat(
+            &src,
+            &mut dst,
+            op,
+            size,
+            &weights,
+            kernel,
+            BorderMode::Clamp,
+            MorphScalar::default(),
+            MorphologyThreadingPolicy::Single,
+        )
+        .unwrap();
+        assert_eq!(dst, expected, "op {:?}", op);
+    }
+}
+
+#[test]
+fn non_flat_zero_weights_match_flat_with_off_centre_anchor() {
+    let size = ImageSize::new(47, 29);
+    let src = make_image(size.width * size.height, 11, 65536)
+        .iter()
+        .map(|&v| v as u16)
+        .collect::<Vec<u16>>();
+    let kernel = KernelShape::new_with_anchor(5, 3, 0, 2);
+    let se = make_se((5, 3), 9);
+    let weights = se
+        .iter()
+        .map(|&x| if x != 0 { 0. } else { f32::NEG_INFINITY })
+        .collect::<Vec<f32>>();
+    // Non-flat dilation is flat dilation with the element reflected around its anchor
+    let reflected_se = se.iter().rev().copied().collect::<Vec<u8>>();
+    let reflected_kernel = KernelShape::new_with_anchor(5, 3, 4, 0);
+    for border_mode in [BorderMode::Clamp, BorderMode::Constant] {
+        let mut expected = vec![0u16; src.len()];
+        dilate_u16(
+            &src,
+            &mut expected,
+            size,
+            &reflected_se,
+            reflected_kernel,
+            border_mode,
+            MorphScalar::dup(700.),
+            MorphologyThreadingPolicy::Single,
+        )
+        .unwrap();
+        let mut dst = vec![0u16; src.len()];
+        morphology_non_flat_u16(
+            &src,
+            &mut dst,
+            MorphExOp::Dilate,
+            size,
+            &weights,
+            kernel,
+            border_mode,
+            MorphScalar::dup(700.),
+            MorphologyThreadingPolicy::Single,
+        )
+        .unwrap();
+        assert_eq!(dst, expected, "{:?}", border_mode);
+    }
+}
+
+#[test]
+fn non_flat_near_zero_weights_follow_reflected_flat_dilation() {
+    let size = ImageSize::new(9, 1);
+    let kernel = KernelShape::new(3, 1);
+    let mut row = [0u8; 9];
+    row[4] = 200;
+    let mut expected = [0u8; 9];
+    dilate(
+        &row,
+        &mut expected,
+        size,
+        &[0, 1, 1],
+        kernel,
+        BorderMode::Clamp,
+        MorphScalar::default(),
+        MorphologyThreadingPolicy::Single,
+    )
+    .unwrap();
+    assert_eq!(expected, [0, 0, 0, 200, 200, 0, 0, 0, 0]);
+    for weight in [0., 0.4, -0.4] {
+        let mut dst = [0u8; 9];
+        dilate_non_flat(
+            &row,
+            &mut dst,
+            size,
+            &[weight, weight, f32::NEG_INFINITY],
+            kernel,
+            BorderMode::Clamp,
+            MorphScalar::default(),
+            MorphologyThreadingPolicy::Single,
+        )
+        .unwrap();
+        assert_eq!(dst, expected, "weight {}", weight);
+    }
+
+    let row = row.map(|v| v as f32);
+    for weight in [0., 1e-6] {
+        let mut dst = [0f32; 9];
+        dilate_non_flat_f32(
+            &row,
+            &mut dst,
+            size,
+            &[weight, weight, f32::NEG_INFINITY],
+            kernel,
+            BorderMode::Clamp,
+            MorphScalar::default(),
+            MorphologyThreadingPolicy::Single,
+        )
+        .unwrap();
+        let dilated = dst.map(|v| v >= 100.);
+        assert_eq!(dilated, expected.map(|v| v != 0), "weight {}", weight);
+    }
+}
+
+#[test]
+fn non_flat_shapes() {
+    let (weights, kernel) = structuring_element::paraboloid(5, 4.);
+    assert_eq!(weights.len(), kernel.width * kernel.height);
+    assert_eq!(
+        weights[kernel.anchor_y * kernel.width + kernel.anchor_x],
+        0.
+    );
+    assert_eq!(weights[kernel.anchor_y * kernel.width], -25. / 8.);
+    assert_eq!(weights[0], f32::NEG_INFINITY);
+
+    let (weights, kernel) = structuring_element::ball(6, 30.);
+    let center = kernel.anchor_y * kernel.width + kernel.anchor_x;
+    assert_eq!(weights[center], 0.);
+    assert!((weights[kernel.anchor_y * kernel.width] + 30.).abs() < 1e-4);
+    assert!(weights
+        .iter()
+        .all(|&w| w == f32::NEG_INFINITY || (-30.0..=0.).contains(&w)));
+
+    let mut dst = vec![0u8; 4];
+    assert!(erode_non_flat(
+        &[0u8; 4],
+        &mut dst,
+        ImageSize::new(2, 2),
+        &weights[1..],
+        kernel,
+        BorderMode::Clamp,
+        MorphScalar::default(),
+        MorphologyThreadingPolicy::Single,
+    )
+    .is_err());
+}
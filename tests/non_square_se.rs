@@ -0,0 +1,220 @@
+mod common;
+
+use common::*;
+use fast_morphology::*;
+
+const KERNELS: [(usize, usize); 8] = [
+    (1, 7),
+    (7, 1),
+    (15, 5),
+    (5, 15),
+    (4, 9),
+    (2, 1),
+    (1, 1),
+    (3, 3),
+];
+
+fn kernels() -> Vec<KernelShape> {
+    KERNELS
+        .iter()
+        .map(|&(kw, kh)| KernelShape::new(kw, kh))
+        .collect()
+}
+
+const DILATE: &[MorphExOp] = &[MorphExOp::Dilate];
+const ERODE: &[MorphExOp] = &[MorphExOp::Erode];
+
+#[test]
+fn dilate_erode_u8_non_square() {
+    check_morphology::<u8, _>(&kernels(), 1, 256, DILATE, op_fn!(dilate));
+    check_morphology::<u8, _>(&kernels(), 1, 256, ERODE, op_fn!(erode));
+    check_morphology::<u8, _>(&kernels(), 2, 256, DILATE, op_fn!(dilate_gray_alpha));
+    check_morphology::<u8, _>(&kernels(), 2, 256, ERODE, op_fn!(erode_gray_alpha));
+    check_morphology::<u8, _>(&kernels(), 3, 256, DILATE, op_fn!(dilate_rgb));
+    check_morphology::<u8, _>(&kernels(), 3, 256, ERODE, op_fn!(erode_rgb));
+    check_morphology::<u8, _>(&kernels(), 4, 256, DILATE, op_fn!(dilate_rgba));
+    check_morphology::<u8, _>(&kernels(), 4, 256, ERODE, op_fn!(erode_rgba));
+}
+
+#[test]
+fn dilate_erode_u16_non_square() {
+    check_morphology::<u16, _>(&kernels(), 1, 65536, DILATE, op_fn!(dilate_u16));
+    check_morphology::<u16, _>(&kernels(), 1, 65536, ERODE, op_fn!(erode_u16));
+    check_morphology::<u16, _>(&kernels(), 2, 65536, DILATE, op_fn!(dilate_gray_alpha_u16));
+    check_morphology::<u16, _>(&kernels(), 2, 65536, ERODE, op_fn!(erode_gray_alpha_u16));
+    check_morphology::<u16, _>(&kernels(), 3, 65536, DILATE, op_fn!(dilate_rgb_u16));
+    check_morphology::<u16, _>(&kernels(), 3, 65536, ERODE, op_fn!(erode_rgb_u16));
+    check_morphology::<u16, _>(&kernels(), 4, 65536, DILATE, op_fn!(dilate_rgba_u16));
+    check_morphology::<u16, _>(&kernels(), 4, 65536, ERODE, op_fn!(erode_rgba_u16));
+}
+
+#[test]
+fn dilate_erode_f32_non_square() {
+    check_morphology::<f32, _>(&kernels(), 1, 1000, DILATE, op_fn!(dilate_f32));
+    check_morphology::<f32, _>(&kernels(), 1, 1000, ERODE, op_fn!(erode_f32));
+    check_morphology::<f32, _>(&kernels(), 2, 1000, DILATE, op_fn!(dilate_gray_alpha_f32));
+    check_morphology::<f32, _>(&kernels(), 2, 1000, ERODE, op_fn!(erode_gray_alpha_f32));
+    check_morphology::<f32, _>(&kernels(), 3, 1000, DILATE, op_fn!(dilate_rgb_f32));
+    check_morphology::<f32, _>(&kernels(), 3, 1000, ERODE, op_fn!(erode_rgb_f32));
+    check_morphology::<f32, _>(&kernels(), 4, 1000, DILATE, op_fn!(dilate_rgba_f32));
+    check_morphology::<f32, _>(&kernels(), 4, 1000, ERODE, op_fn!(erode_rgba_f32));
+}
+
+#[test]
+fn morphology_u8_non_square() {
+    let ops = &MORPH_OPS;
+    check_morphology::<u8, _>(&kernels(), 1, 256, ops, morph_fn!(morphology));
+    check_morphology::<u8, _>(&kernels(), 2, 256, ops, morph_fn!(morphology_gray_alpha));
+    check_morphology::<u8, _>(&kernels(), 3, 256, ops, morph_fn!(morphology_rgb));
+    check_morphology::<u8, _>(&kernels(), 4, 256, ops, morph_fn!(morphology_rgba));
+}
+
+#[test]
+fn morphology_u16_non_square() {
+    let ops = &MORPH_OPS;
+    check_morphology::<u16, _>(&kernels(), 1, 65536, ops, morph_fn!(morphology_gray_u16));
+    check_morphology::<u16, _>(
+        &kernels(),
+        2,
+        65536,
+        ops,
+        morph_fn!(morphology_gray_alpha_u16),
+    );
+    check_morphology::<u16, _>(&kernels(), 3, 65536, ops, morph_fn!(morphology_rgb_u16));
+    check_morphology::<u16, _>(&kernels(), 4, 65536, ops, morph_fn!(morphology_rgba_u16));
+}
+
+#[test]
+fn morphology_f32_non_square() {
+    let ops = &MORPH_OPS;
+    check_morphology::<f32, _>(&kernels(), 3, 1000, ops, morph_fn!(morphology_rgb_f32));
+    check_morphology::<f32, _>(&kernels(), 4, 1000, ops, morph_fn!(morphology_rgba_f32));
+}
+
+#[test]
+fn constant_border_non_square() {
+    let size = ImageSize::new(67, 13);
+    let kernel = KernelShape::new(9, 3);
+    let se = make_se((9, 3), 7);
+    let reference_src = make_image(size.width * size.height, 3, 200);
+    let src = reference_src.iter().map(|&v| v as u8).collect::<Vec<u8>>();
+    for (dilate_op, border) in [(true, 255f64), (false, 0f64), (true, 10f64)] {
+        let mut dst = vec![0u8; src.len()];
+        let func = if dilate_op { dilate } else { erode };
+        func(
+            &src,
+            &mut dst,
+            size,
+            &se,
+            kernel,
+            BorderMode::Constant,
+            MorphScalar::dup(border),
+            MorphologyThreadingPolicy::Single,
+        )
+        .unwrap();
+        let expected = reference_op(
+            &reference_src,
+            size,
+            1,
+            &se,
+            kernel,
+            dilate_op,
+            Some(border),
+        );
+        let dst = dst.iter().map(|&v| v as f64).collect::<Vec<f64>>();
+        assert_eq!(dst, expected);
+    }
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn morphology_image_non_square() {
+    use image::{DynamicImage, GrayAlphaImage, GrayImage, RgbImage, RgbaImage};
+
+    let size = ImageSize::new(77, 21);
+    let kernel = KernelShape::new(11, 3);
+    let se = make_se((11, 3), 11);
+    for channels in 1..=4usize {
+        let reference_src = make_image(size.width * size.height * channels, 5, 256);
+        let raw = reference_src.iter().map(|&v| v as u8).collect::<Vec<u8>>();
+        let (w, h) = (size.width as u32, size.height as u32);
+        let image = match channels {
+            1 => DynamicImage::from(GrayImage::from_raw(w, h, raw).unwrap()),
+            2 => DynamicImage::from(GrayAlphaImage::from_raw(w, h, raw).unwrap()),
+            3 => DynamicImage::from(RgbImage::from_raw(w, h, raw).unwrap()),
+            _ => DynamicImage::from(RgbaImage::from_raw(w, h, raw).unwrap()),
+        };
+        for op in MORPH_OPS {
+            let result = morphology_image(
+                image.clone(),
+                op,
+                &se,
+                kernel,
+                BorderMode::Clamp,
+                MorphScalar::default(),
+                MorphologyThreadingPolicy::default(),
+            )
+            .unwrap();
+            let expected =
+                reference_morphology(&reference_src, size, channels, &se, kernel, op, true);
+            let actual = result
+                .as_bytes()
+                .iter()
+                .map(|&v| v as f64)
+                .collect::<Vec<f64>>();
+            assert_eq!(actual, expected, "channels {}, op {:?}", channels, op);
+        }
+    }
+}
+
+#[cfg(feature = "image")]
+#[test]
+fn morphology_image_gray_f32() {
+    use image::{ImageBuffer, Luma, LumaA};
+
+    let size = ImageSize::new(53, 19);
+    let kernel = KernelShape::new(7, 3);
+    let se = make_se((7, 3), 17);
+    let (w, h) = (size.width as u32, size.height as u32);
+    for op in MORPH_OPS {
+        let reference_src = make_image(size.width * size.height, 9, 1000);
+        let raw = reference_src
+            .iter()
+            .map(|&v| v as f32)
+            .collect::<Vec<f32>>();
+        let image = ImageBuffer::<Luma<f32>, Vec<f32>>::from_raw(w, h, raw).unwrap();
+        let result = morph_gray_f32_image(
+            image,
+            op,
+            &se,
+            kernel,
+            BorderMode::Clamp,
+            MorphScalar::default(),
+            MorphologyThreadingPolicy::default(),
+        )
+        .unwrap();
+        let expected = reference_morphology(&reference_src, size, 1, &se, kernel, op, false);
+        let actual = result.iter().map(|&v| v as f64).collect::<Vec<f64>>();
+        assert_eq!(actual, expected, "gray, op {:?}", op);
+
+        let reference_src = make_image(size.width * size.height * 2, 9, 1000);
+        let raw = reference_src
+            .iter()
+            .map(|&v| v as f32)
+            .collect::<Vec<f32>>();
+        let image = ImageBuffer::<LumaA<f32>, Vec<f32>>::from_raw(w, h, raw).unwrap();
+        let result = morph_gray_alpha_f32_image(
+            image,
+            op,
+            &se,
+            kernel,
+            BorderMode::Clamp,
+            MorphScalar::default(),
+            MorphologyThreadingPolicy::default(),
+        )
+        .unwrap();
+        let expected = reference_morphology(&reference_src, size, 2, &se, kernel, op, false);
+        let actual = result.iter().map(|&v| v as f64).collect::<Vec<f64>>();
+        assert_eq!(actual, expected, "gray alpha, op {:?}", op);
+    }
+}
@@ -0,0 +1,119 @@
+use fast_morphology::*;
+
+type Factory = fn(usize, usize) -> (Vec<u8>, KernelShape);
+
+/// Ellipse takes radii, so only odd sizes exist, zero radius gives a single row or column
+fn ellipse_of_size(width: usize, height: usize) -> (Vec<u8>, KernelShape) {
+    structuring_element::ellipse(width / 2, height / 2)
+}
+
+/// Masks of OpenCV `getStructuringElement` with the default anchor, one string per row
+fn opencv_masks() -> Vec<(&'static str, Factory, usize, usize, Vec<&'static str>)> {
+    vec![
+        (
+            "rect",
+            structuring_element::rect,
+            3,
+            3,
+            vec!["111", "111", "111"],
+        ),
+        (
+            "rect",
+            structuring_element::rect,
+            4,
+            2,
+            vec!["1111", "1111"],
+        ),
+        ("rect", structuring_element::rect, 1, 1, vec!["1"]),
+        ("rect", structuring_element::rect, 5, 1, vec!["11111"]),
+        (
+            "cross",
+            structuring_element::cross,
+            5,
+            5,
+            vec!["00100", "00100", "11111", "00100", "00100"],
+        ),
+        (
+            "cross",
+            structuring_element::cross,
+            4,
+            4,
+            vec!["0010", "0010", "1111", "0010"],
+        ),
+        (
+            "cross",
+            structuring_element::cross,
+            6,
+            3,
+            vec!["000100", "111111", "000100"],
+        ),
+        ("cross", structuring_element::cross, 1, 1, vec!["1"]),
+        ("cross", structuring_element::cross, 5, 1, vec!["11111"]),
+        (
+            "cross",
+            structuring_element::cross,
+            1,
+            4,
+            vec!["1", "1", "1", "1"],
+        ),
+        ("ellipse", ellipse_of_size, 3, 3, vec!["010", "111", "010"]),
+        (
+            "ellipse",
+            ellipse_of_size,
+            5,
+            5,
+            vec!["00100", "11111", "11111", "11111", "00100"],
+        ),
+        (
+            "ellipse",
+            ellipse_of_size,
+            7,
+            7,
+            vec![
+                "0001000", "0111110", "1111111", "1111111", "1111111", "0111110", "0001000",
+            ],
+        ),
+        (
+            "ellipse",
+            ellipse_of_size,
+            7,
+            3,
+            vec!["0001000", "1111111", "0001000"],
+        ),
+        ("ellipse", ellipse_of_size, 1, 1, vec!["1"]),
+        // Zero vertical radius leaves only the center of a single row
+        ("ellipse", ellipse_of_size, 5, 1, vec!["00100"]),
+        (
+            "ellipse",
+            ellipse_of_size,
+            1,
+            5,
+            vec!["1", "1", "1", "1", "1"],
+        ),
+    ]
+}
+
+#[test]
+fn elements_match_opencv() {
+    for (name, factory, width, height, rows) in opencv_masks() {
+        let (se, kernel) = factory(width, height);
+        assert_eq!(
+            (
+                kernel.width,
+                kernel.height,
+                kernel.anchor_x,
+                kernel.anchor_y
+            ),
+            (width, height, width / 2, height / 2),
+            "{} {}x{}",
+            name,
+            width,
+            height
+        );
+        let expected = rows
+            .iter()
+            .flat_map(|row| row.bytes().map(|x| x - b'0'))
+            .collect::<Vec<u8>>();
+        assert_eq!(se, expected, "{} {}x{}", name, width, height);
+    }
+}
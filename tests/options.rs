@@ -0,0 +1,278 @@
+mod common;
+
+use common::*;
+use fast_morphology::generic::{self, ChannelLayout};
+use fast_morphology::*;
+
+fn option_kernels() -> Vec<(KernelShape, Vec<u8>)> {
+    vec![
+        (KernelShape::new(3, 3), vec![1; 9]),
+        (KernelShape::new(11, 7), vec![1; 77]),
+        (KernelShape::new(7, 5), make_se((7, 5), 3)),
+        (KernelShape::new_with_anchor(6, 4, 0, 3), make_se((6, 4), 5)),
+        (
+            KernelShape::new(15, 1),
+            vec![1, 0, 0, 1, 0, 0, 1, 0, 0, 1, 0, 0, 1, 0, 0],
+        ),
+    ]
+}
+
+/// Reference of `op` where every dilation and erosion is applied `iterations` times
+fn reference_iterated(
+    src: &[f64],
+    size: ImageSize,
+    channels: usize,
+    se: &[u8],
+    kernel: KernelShape,
+    op: MorphExOp,
+    iterations: usize,
+) -> Vec<f64> {
+    let repeated = |v: &[f64], dilate: bool| {
+        (0..iterations).fold(v.to_vec(), |acc, _| {
+            reference_op(&acc, size, channels, se, kernel, dilate, None)
+        })
+    };
+    let dilate = |v: &[f64]| repeated(v, true);
+    let erode = |v: &[f64]| repeated(v, false);
+    let sub = |a: &[f64], b: &[f64]| a.iter().zip(b).map(|(a, b)| a - b).collect::<Vec<_>>();
+    match op {
+        MorphExOp::Dilate => dilate(src),
+        MorphExOp::Erode => erode(src),
+        MorphExOp::Opening => dilate(&erode(src)),
+        MorphExOp::Closing => erode(&dilate(src)),
+        MorphExOp::Gradient => sub(&dilate(src), &erode(src)),
+        MorphExOp::TopHat => sub(src, &dilate(&erode(src))),
+        MorphExOp::BlackHat => sub(&erode(&dilate(src)), src),
+    }
+}
+
+fn check_iterations<T, L>(max: u32, iterations: usize)
+where
+    T: Sample + MorphologySample + PartialEq,
+    L: ChannelLayout,
+    f64: num_traits::AsPrimitive<T>,
+{
+    let size = ImageSize::new(47, 23);
+    let channels = L::channels();
+    let reference_src = make_image(size.width * size.height * channels, 17, max);
+    let src = reference_src
+        .iter()
+        .map(|&v| T::from_f64(v))
+        .collect::<Vec<T>>();
+    let options = MorphologyOptions::default().with_iterations(iterations);
+    for (kernel, se) in option_kernels() {
+        for op in MORPH_OPS {
+            let expected =
+                reference_iterated(&reference_src, size, channels, &se, kernel, op, iterations)
+                    .iter()
+                    .map(|&v| T::from_f64(v.clamp(T::MIN, T::MAX)))
+                    .collect::<Vec<T>>();
+            let mut dst = vec![T::default(); src.len()];
+            generic::morphology_with_options::<T, L>(
+                &src, &mut dst, op, size, &se, kernel, &options,
+            )
+            .unwrap();
+            assert!(dst == expected, "op {:?}, kernel {:?}", op, kernel);
+
+            let mut image = src.clone();
+            generic::morphology_in_place_with_options::<T, L>(
+                &mut image, op, size, &se, kernel, &options,
+            )
+            .unwrap();
+            assert!(
+                image == expected,
+                "in place op {:?}, kernel {:?}",
+                op,
+                kernel
+            );
+        }
+    }
+}
+
+#[test]
+fn options_iterations_match_repeated_passes() {
+    check_iterations::<u8, generic::Gray>(256, 3);
+    check_iterations::<u16, generic::Rgb>(65536, 2);
+    check_iterations::<f32, generic::GrayAlpha>(1000, 2);
+}
+
+#[test]
+fn options_defaults_match_positional_arguments() {
+    let size = ImageSize::new(53, 21);
+    let src = make_image(size.width * size.height * 4, 9, 256)
+        .iter()
+        .map(|&v| v as u8)
+        .collect::<Vec<u8>>();
+    let kernel = KernelShape::new(5, 5);
+    let se = make_se((5, 5), 1);
+    for op in MORPH_OPS {
+        let mut expected = vec![0u8; src.len()];
+        morphology_rgba(
+            &src,
+            &mut expected,
+            op,
+            size,
+            &se,
+            kernel,
+            BorderMode::Clamp,
+            MorphScalar::default(),
+            MorphologyThreadingPolicy::default(),
+        )
+        .unwrap();
+        let mut dst = vec![0u8; src.len()];
+        generic::morphology_with_options::<u8, generic::Rgba>(
+            &src,
+            &mut dst,
+            op,
+            size,
+            &se,
+            kernel,
+            &MorphologyOptions::default(),
+        )
+        .unwrap();
+        assert_eq!(dst, expected, "op {:?}", op);
+    }
+}
+
+#[test]
+fn options_anchor_overrides_kernel_anchor() {
+    let size = ImageSize::new(41, 29);
+    let src = make_image(size.width * size.height, 4, 65536)
+        .iter()
+        .map(|&v| v as u16)
+        .collect::<Vec<u16>>();
+    let se = make_se((5, 3), 2);
+    let options = MorphologyOptions::default()
+        .with_border_mode(BorderMode::Constant)
+        .with_border_scalar(MorphScalar::dup(300.))
+        .with_anchor(0, 2)
+        .with_threading_policy(MorphologyThreadingPolicy::Single);
+    for op in MORPH_OPS {
+        let mut expected = vec![0u16; src.len()];
+        morphology_gray_u16(
+            &src,
+            &mut expected,
+            op,
+            size,
+            &se,
+            KernelShape::new_with_anchor(5, 3, 0, 2),
+            BorderMode::Constant,
+            MorphScalar::dup(300.),
+            MorphologyThreadingPolicy::Single,
+        )
+        .unwrap();
+        let mut dst = vec![0u16; src.len()];
+        generic::morphology_with_options::<u16, generic::Gray>(
+            &src,
+            &mut dst,
+            op,
+            size,
+            &se,
+            KernelShape::new(5, 3),
+            &options,
+        )
+        .unwrap();
+        assert_eq!(dst, expected, "op {:?}", op);
+    }
+}
+
+fn check_scalar_backend<T>(max: u32)
+where
+    T: Sample + MorphologySample + PartialEq,
+    f64: num_traits::AsPrimitive<T>,
+{
+    let size = ImageSize::new(97, 31);
+    let src = make_image(size.width * size.height * 3, 23, max)
+        .iter()
+        .map(|&v| T::from_f64(v))
+        .collect::<Vec<T>>();
+    let auto =
+        MorphologyOptions::default().with_threading_policy(MorphologyThreadingPolicy::Fixed(3));
+    let scalar = auto.clone().with_backend(MorphologyBackend::Scalar);
+    let weights = (0..15).map(|i| (i % 3) as f32).collect::<Vec<f32>>();
+    for (kernel, se) in option_kernels() {
+        for op in MORPH_OPS {
+            let mut expected = vec![T::default(); src.len()];
+            generic::morphology_with_options::<T, generic::Rgb>(
+                &src,
+                &mut expected,
+                op,
+                size,
+                &se,
+                kernel,
+                &auto,
+            )
+            .unwrap();
+            let mut dst = vec![T::default(); src.len()];
+            generic::morphology_with_options::<T, generic::Rgb>(
+                &src, &mut dst, op, size, &se, kernel, &scalar,
+            )
+            .unwrap();
+            assert!(dst == expected, "op {:?}, kernel {:?}", op, kernel);
+        }
+    }
+    for op in MORPH_OPS {
+        let mut expected = vec![T::default(); src.len()];
+        generic::morphology_non_flat_with_options::<T, generic::Rgb>(
+            &src,
+            &mut expected,
+            op,
+            size,
+            &weights,
+            KernelShape::new(5, 3),
+            &auto,
+        )
+        .unwrap();
+        let mut dst = vec![T::default(); src.len()];
+        generic::morphology_non_flat_with_options::<T, generic::Rgb>(
+            &src,
+            &mut dst,
+            op,
+            size,
+            &weights,
+            KernelShape::new(5, 3),
+            &scalar,
+        )
+        .unwrap();
+        assert!(dst == expected, "non-flat op {:?}", op);
+    }
+}
+
+#[test]
+fn options_scalar_backend_matches_auto() {
+    check_scalar_backend::<u8>(256);
+    check_scalar_backend::<u16>(65536);
+    check_scalar_backend::<f32>(1000);
+}
+
+#[test]
+fn options_are_validated() {
+    let size = ImageSize::new(8, 8);
+    let src = vec![0u8; 64];
+    let mut dst = vec![0u8; 64];
+    let se = vec![1u8; 9];
+    let run = |options: &MorphologyOptions, dst: &mut [u8]| {
+        generic::morphology_with_options::<u8, generic::Gray>(
+            &src,
+            dst,
+            MorphExOp::Dilate,
+            size,
+            &se,
+            KernelShape::new(3, 3),
+            options,
+        )
+    };
+    assert_eq!(
+        run(&MorphologyOptions::default().with_iterations(0), &mut dst),
+        Err(MorphologyError::ZeroIterations)
+    );
+    assert_eq!(
+        run(&MorphologyOptions::default().with_anchor(3, 1), &mut dst),
+        Err(MorphologyError::AnchorOutOfBounds {
+            anchor_x: 3,
+            anchor_y: 1,
+            width: 3,
+            height: 3,
+        })
+    );
+}
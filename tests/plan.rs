@@ -0,0 +1,183 @@
+mod common;
+
+use common::*;
+use fast_morphology::*;
+
+fn shapes() -> Vec<(Vec<u8>, KernelShape)> {
+    vec![
+        structuring_element::rect(7, 5),
+        structuring_element::circle(7),
+        structuring_element::disk(9, structuring_element::DiskAccuracy::default()),
+        (make_se((3, 3), 5), KernelShape::new(3, 3)),
+        (vec![1u8], KernelShape::new(1, 1)),
+    ]
+}
+
+macro_rules! plan_fn {
+    ($t: ty, $layout: expr) => {
+        |src: &[$t], dst: &mut [$t], op, size, se: &[u8], kernel, policy| {
+            let mut plan = MorphologyPlan::<$t>::new(
+                size,
+                $layout,
+                op,
+                se,
+                kernel,
+                BorderMode::Clamp,
+                MorphScalar::default(),
+                policy,
+            )?;
+            // The previous frame must not leak into the next one.
+            let previous = src.iter().rev().copied().collect::<Vec<$t>>();
+            let mut scratch = vec![<$t>::default(); dst.len()];
+            plan.execute(&previous, &mut scratch)?;
+            plan.execute(src, dst)?;
+            plan.execute(src, dst)
+        }
+    };
+}
+
+#[test]
+fn plan_u8() {
+    let shapes = shapes();
+    check_morphology_with_shapes::<u8, _>(
+        &shapes,
+        1,
+        256,
+        &MORPH_OPS,
+        plan_fn!(u8, MorphologyLayout::Gray),
+    );
+    check_morphology_with_shapes::<u8, _>(
+        &shapes,
+        2,
+        256,
+        &MORPH_OPS,
+        plan_fn!(u8, MorphologyLayout::GrayAlpha),
+    );
+    check_morphology_with_shapes::<u8, _>(
+        &shapes,
+        3,
+        256,
+        &MORPH_OPS,
+        plan_fn!(u8, MorphologyLayout::Rgb),
+    );
+    check_morphology_with_shapes::<u8, _>(
+        &shapes,
+        4,
+        256,
+        &MORPH_OPS,
+        plan_fn!(u8, MorphologyLayout::Rgba),
+    );
+}
+
+#[test]
+fn plan_u16() {
+    let shapes = shapes();
+    check_morphology_with_shapes::<u16, _>(
+        &shapes,
+        1,
+        65536,
+        &MORPH_OPS,
+        plan_fn!(u16, MorphologyLayout::Gray),
+    );
+    check_morphology_with_shapes::<u16, _>(
+        &shapes,
+        4,
+        65536,
+        &MORPH_OPS,
+        plan_fn!(u16, MorphologyLayout::Rgba),
+    );
+}
+
+#[test]
+fn plan_f32() {
+    let shapes = shapes();
+    check_morphology_with_shapes::<f32, _>(
+        &shapes,
+        1,
+        1000,
+        &MORPH_OPS,
+        plan_fn!(f32, MorphologyLayout::Gray),
+    );
+    check_morphology_with_shapes::<f32, _>(
+        &shapes,
+        3,
+        1000,
+        &MORPH_OPS,
+        plan_fn!(f32, MorphologyLayout::Rgb),
+    );
+}
+
+#[test]
+fn plan_non_flat_matches_function() {
+    let size = ImageSize::new(93, 41);
+    let weights_size = KernelShape::new(5, 3);
+    let weights = (0..15)
+        .map(|i| {
+            if i % 4 == 0 {
+                f32::NEG_INFINITY
+            } else {
+                (i % 5) as f32 * 3.
+            }
+        })
+        .collect::<Vec<f32>>();
+    for op in MORPH_OPS {
+        let mut plan = MorphologyPlan::<u16>::new_non_flat(
+            size,
+            MorphologyLayout::Gray,
+            op,
+            &weights,
+            weights_size,
+            BorderMode::Clamp,
+            MorphScalar::default(),
+            MorphologyThreadingPolicy::Fixed(3),
+        )
+        .unwrap();
+        for seed in 1..4 {
+            let src = make_image(size.width * size.height, seed, 65536)
+                .iter()
+                .map(|&v| v as u16)
+                .collect::<Vec<u16>>();
+            let mut expected = vec![0u16; src.len()];
+            morphology_non_flat_u16(
+                &src,
+                &mut expected,
+                op,
+                size,
+                &weights,
+                weights_size,
+                BorderMode::Clamp,
+                MorphScalar::default(),
+                MorphologyThreadingPolicy::Single,
+            )
+            .unwrap();
+            let mut dst = vec![0u16; src.len()];
+            plan.execute(&src, &mut dst).unwrap();
+            assert_eq!(dst, expected, "op {:?}, frame {}", op, seed);
+        }
+    }
+}
+
+#[test]
+fn plan_rejects_mismatched_buffers() {
+    let size = ImageSize::new(16, 8);
+    let (se, kernel) = structuring_element::rect(3, 3);
+    let mut plan = MorphologyPlan::<u8>::new(
+        size,
+        MorphologyLayout::Rgb,
+        MorphExOp::Dilate,
+        &se,
+        kernel,
+        BorderMode::Clamp,
+        MorphScalar::default(),
+        MorphologyThreadingPolicy::Single,
+    )
+    .unwrap();
+    assert_eq!(plan.image_size(), size);
+    assert_eq!(plan.layout(), MorphologyLayout::Rgb);
+    let src = vec![0u8; 16 * 8 * 3];
+    let mut short = vec![0u8; 16 * 8];
+    assert!(plan.execute(&src, &mut short).is_err());
+    assert!(plan.execute(&src[..16 * 8], &mut short).is_err());
+    let mut dst = vec![0u8; src.len()];
+    assert!(plan.execute(&src, &mut dst).is_ok());
+}
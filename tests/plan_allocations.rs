@@ -0,0 +1,165 @@
+use fast_morphology::*;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+struct CountingAllocator;
+
+thread_local! {
+    static TRACKING: Cell<bool> = const { Cell::new(false) };
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    static LIVE_BYTES: Cell<isize> = const { Cell::new(0) };
+    static PEAK_BYTES: Cell<isize> = const { Cell::new(0) };
+}
+
+fn track_bytes(delta: isize) {
+    if TRACKING.with(|x| x.get()) {
+        let live = LIVE_BYTES.with(|x| {
+            x.set(x.get() + delta);
+            x.get()
+        });
+        PEAK_BYTES.with(|x| x.set(x.get().max(live)));
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if TRACKING.with(|x| x.get()) {
+            ALLOCATIONS.with(|x| x.set(x.get() + 1));
+        }
+        track_bytes(layout.size() as isize);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        track_bytes(-(layout.size() as isize));
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if TRACKING.with(|x| x.get()) {
+            ALLOCATIONS.with(|x| x.set(x.get() + 1));
+        }
+        track_bytes(new_size as isize - layout.size() as isize);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn count_allocations<F: FnMut()>(mut f: F) -> usize {
+    ALLOCATIONS.with(|x| x.set(0));
+    TRACKING.with(|x| x.set(true));
+    f();
+    TRACKING.with(|x| x.set(false));
+    ALLOCATIONS.with(|x| x.get())
+}
+
+/// Largest amount of heap memory held at once by `f`
+fn peak_bytes<F: FnMut()>(mut f: F) -> usize {
+    LIVE_BYTES.with(|x| x.set(0));
+    PEAK_BYTES.with(|x| x.set(0));
+    TRACKING.with(|x| x.set(true));
+    f();
+    TRACKING.with(|x| x.set(false));
+    PEAK_BYTES.with(|x| x.get()) as usize
+}
+
+#[test]
+fn plan_does_not_allocate_after_warm_up() {
+    let size = ImageSize::new(120, 64);
+    let shapes = [
+        structuring_element::rect(9, 7),
+        structuring_element::circle(6),
+        structuring_element::disk(12, structuring_element::DiskAccuracy::default()),
+        structuring_element::cross(3, 3),
+    ];
+    for (layout, channels) in [(MorphologyLayout::Gray, 1), (MorphologyLayout::Rgba, 4)] {
+        let src = (0..size.width * size.height * channels)
+            .map(|i| (i * 31 % 251) as u8)
+            .collect::<Vec<u8>>();
+        let mut dst = vec![0u8; src.len()];
+        for (se, kernel) in shapes.iter() {
+            for op in [MorphExOp::Dilate, MorphExOp::Gradient, MorphExOp::TopHat] {
+                let mut plan = MorphologyPlan::<u8>::new(
+                    size,
+                    layout,
+                    op,
+                    se,
+                    *kernel,
+                    BorderMode::Reflect101,
+                    MorphScalar::default(),
+                    MorphologyThreadingPolicy::Single,
+                )
+                .unwrap();
+                plan.execute(&src, &mut dst).unwrap();
+                let one_shot = count_allocations(|| {
+                    plan = MorphologyPlan::<u8>::new(
+                        size,
+                        layout,
+                        op,
+                        se,
+                        *kernel,
+                        BorderMode::Reflect101,
+                        MorphScalar::default(),
+                        MorphologyThreadingPolicy::Single,
+                    )
+                    .unwrap();
+                });
+                assert_ne!(one_shot, 0);
+                plan.execute(&src, &mut dst).unwrap();
+                let allocations = count_allocations(|| {
+                    for _ in 0..3 {
+                        plan.execute(&src, &mut dst).unwrap();
+                    }
+                });
+                assert_eq!(allocations, 0, "{:?} {:?} {:?}", layout, kernel, op);
+            }
+        }
+    }
+}
+
+#[test]
+fn in_place_memory() {
+    let size = ImageSize::new(512, 384);
+    let mut image = (0..size.width * size.height * 4)
+        .map(|i| (i * 31 % 251) as u8)
+        .collect::<Vec<u8>>();
+    let mut dst = vec![0u8; image.len()];
+    let (se, kernel) = structuring_element::rect(15, 15);
+    let in_place = peak_bytes(|| {
+        morphology_rgba_in_place(
+            &mut image,
+            MorphExOp::TopHat,
+            size,
+            &se,
+            kernel,
+            BorderMode::Clamp,
+            MorphScalar::default(),
+            MorphologyThreadingPolicy::Single,
+        )
+        .unwrap();
+    });
+    let out_of_place = peak_bytes(|| {
+        morphology_rgba(
+            &image,
+            &mut dst,
+            MorphExOp::TopHat,
+            size,
+            &se,
+            kernel,
+            BorderMode::Clamp,
+            MorphScalar::default(),
+            MorphologyThreadingPolicy::Single,
+        )
+        .unwrap();
+    });
+    // Two arenas and a single channel plane
+    assert!(
+        in_place < image.len(),
+        "In place peak {} exceeds image size {}",
+        in_place,
+        image.len()
+    );
+    assert!(in_place * 2 < out_of_place, "{} {}", in_place, out_of_place);
+}
@@ -0,0 +1,150 @@
+mod common;
+
+use common::*;
+use fast_morphology::*;
+
+fn rect_kernels() -> Vec<KernelShape> {
+    vec![
+        KernelShape::new(3, 3),
+        KernelShape::new(1, 1),
+        KernelShape::new(1, 13),
+        KernelShape::new(17, 1),
+        KernelShape::new(4, 6),
+        KernelShape::new(15, 5),
+        KernelShape::new(40, 3),
+        KernelShape::new_with_anchor(6, 4, 0, 3),
+        KernelShape::new_with_anchor(5, 9, 4, 0),
+    ]
+}
+
+fn large_rect_kernels() -> Vec<KernelShape> {
+    vec![
+        KernelShape::new(31, 31),
+        KernelShape::new(61, 61),
+        KernelShape::new(300, 2),
+        KernelShape::new(2, 45),
+    ]
+}
+
+fn full_se(kernel: KernelShape, _: u64) -> Vec<u8> {
+    vec![1u8; kernel.width * kernel.height]
+}
+
+#[test]
+fn rect_morphology_u8() {
+    let kernels = rect_kernels();
+    let ops = &MORPH_OPS;
+    check_morphology_with_se::<u8, _, _>(&kernels, 1, 256, ops, full_se, morph_fn!(morphology));
+    check_morphology_with_se::<u8, _, _>(&kernels, 3, 256, ops, full_se, morph_fn!(morphology_rgb));
+    check_morphology_with_se::<u8, _, _>(
+        &kernels,
+        4,
+        256,
+        ops,
+        full_se,
+        morph_fn!(morphology_rgba),
+    );
+}
+
+#[test]
+fn large_rect_morphology() {
+    let kernels = large_rect_kernels();
+    let ops = &[MorphExOp::Dilate, MorphExOp::Erode, MorphExOp::Gradient];
+    check_morphology_with_se::<u8, _, _>(&kernels, 1, 256, ops, full_se, morph_fn!(morphology));
+    check_morphology_with_se::<f32, _, _>(
+        &kernels,
+        3,
+        1000,
+        ops,
+        full_se,
+        morph_fn!(morphology_rgb_f32),
+    );
+}
+
+#[test]
+fn rect_morphology_u16() {
+    let kernels = rect_kernels();
+    let ops = &MORPH_OPS;
+    check_morphology_with_se::<u16, _, _>(
+        &kernels,
+        1,
+        65536,
+        ops,
+        full_se,
+        morph_fn!(morphology_gray_u16),
+    );
+    check_morphology_with_se::<u16, _, _>(
+        &kernels,
+        4,
+        65536,
+        ops,
+        full_se,
+        morph_fn!(morphology_rgba_u16),
+    );
+}
+
+#[test]
+fn rect_morphology_f32() {
+    let kernels = rect_kernels();
+    let ops = &MORPH_OPS;
+    check_morphology_with_se::<f32, _, _>(
+        &kernels,
+        1,
+        1000,
+        ops,
+        full_se,
+        morph_fn!(morphology_gray_f32),
+    );
+    check_morphology_with_se::<f32, _, _>(
+        &kernels,
+        2,
+        1000,
+        ops,
+        full_se,
+        morph_fn!(morphology_gray_alpha_f32),
+    );
+    check_morphology_with_se::<f32, _, _>(
+        &kernels,
+        4,
+        1000,
+        ops,
+        full_se,
+        morph_fn!(morphology_rgba_f32),
+    );
+    check_morphology_with_se::<f32, _, _>(
+        &kernels,
+        3,
+        1000,
+        ops,
+        full_se,
+        morph_fn!(morphology_rgb_f32),
+    );
+}
+
+#[test]
+fn rect_constant_border() {
+    let size = ImageSize::new(83, 27);
+    let src_f64 = make_image(size.width * size.height, 5, 256);
+    let src = src_f64.iter().map(|&v| v as u8).collect::<Vec<u8>>();
+    for kernel in rect_kernels() {
+        let se = full_se(kernel, 0);
+        for (dilate_op, border) in [(true, 255.), (false, 0.), (true, 0.), (false, 255.)] {
+            let mut dst = vec![0u8; src.len()];
+            let op = if dilate_op { dilate } else { erode };
+            op(
+                &src,
+                &mut dst,
+                size,
+                &se,
+                kernel,
+                BorderMode::Constant,
+                MorphScalar::dup(border),
+                MorphologyThreadingPolicy::Fixed(4),
+            )
+            .unwrap();
+            let expected = reference_op(&src_f64, size, 1, &se, kernel, dilate_op, Some(border));
+            let dst = dst.iter().map(|&v| v as f64).collect::<Vec<f64>>();
+            assert_eq!(dst, expected, "kernel {:?}", kernel);
+        }
+    }
+}
@@ -0,0 +1,272 @@
+mod common;
+
+use common::*;
+use fast_morphology::*;
+
+const BORDER_MODES: [BorderMode; 5] = [
+    BorderMode::Clamp,
+    BorderMode::Wrap,
+    BorderMode::Reflect,
+    BorderMode::Reflect101,
+    BorderMode::Constant,
+];
+
+/// Mask of runs with random lengths, like scanned documents or board layers
+fn make_runs_mask(size: ImageSize, seed: u64, density: u32) -> Vec<u8> {
+    let lengths = make_image(size.width * size.height, seed, 10);
+    let mut mask = vec![0u8; size.width * size.height];
+    let mut x = 0usize;
+    let mut value = 0u8;
+    for &length in lengths.iter() {
+        if x >= mask.len() {
+            break;
+        }
+        let length = if value == 0 {
+            length as usize * density as usize + 1
+        } else {
+            length as usize + 1
+        };
+        let end = (x + length).min(mask.len());
+        mask[x..end].fill(value);
+        x = end;
+        value = 255 - value;
+    }
+    mask
+}
+
+fn rle_kernels() -> Vec<(Vec<u8>, KernelShape)> {
+    let mut random_se = make_image(5 * 4, 3, 2)
+        .iter()
+        .map(|&v| v as u8)
+        .collect::<Vec<u8>>();
+    random_se[7] = 1;
+    vec![
+        structuring_element::rect(3, 3),
+        structuring_element::rect(1, 1),
+        structuring_element::rect(15, 5),
+        structuring_element::rect(1, 9),
+        structuring_element::ellipse(7, 5),
+        structuring_element::cross(5, 5),
+        (vec![1u8; 24], KernelShape::new_with_anchor(6, 4, 0, 3)),
+        (random_se, KernelShape::new_with_anchor(5, 4, 2, 1)),
+        (vec![0u8; 9], KernelShape::new(3, 3)),
+    ]
+}
+
+fn byte_morphology(
+    mask: &[u8],
+    size: ImageSize,
+    op: MorphExOp,
+    se: &[u8],
+    kernel: KernelShape,
+    border_mode: BorderMode,
+    border_value: bool,
+) -> Vec<u8> {
+    let mut dst = vec![0u8; mask.len()];
+    morphology(
+        mask,
+        &mut dst,
+        op,
+        size,
+        se,
+        kernel,
+        border_mode,
+        MorphScalar::dup(if border_value { 255. } else { 0. }),
+        MorphologyThreadingPolicy::Single,
+    )
+    .unwrap();
+    dst
+}
+
+#[test]
+fn rle_matches_byte_masks() {
+    for (i, size) in [
+        ImageSize::new(1, 7),
+        ImageSize::new(2, 5),
+        ImageSize::new(37, 9),
+        ImageSize::new(150, 13),
+    ]
+    .iter()
+    .enumerate()
+    {
+        let mask = make_runs_mask(*size, i as u64 + 1, 1);
+        let src = RleMask::from_mask(&mask, *size).unwrap();
+        for (se, kernel) in rle_kernels() {
+            for op in MORPH_OPS {
+                for border_mode in BORDER_MODES {
+                    for border_value in [false, true] {
+                        let expected = byte_morphology(
+                            &mask,
+                            *size,
+                            op,
+                            &se,
+                            kernel,
+                            border_mode,
+                            border_value,
+                        );
+                        let mut dst = RleMask::new(*size);
+                        rle_morphology(
+                            &src,
+                            &mut dst,
+                            op,
+                            &se,
+                            kernel,
+                            border_mode,
+                            border_value,
+                            MorphologyThreadingPolicy::Fixed(3),
+                        )
+                        .unwrap();
+                        let mut result = vec![0u8; mask.len()];
+                        dst.to_mask(&mut result).unwrap();
+                        assert_eq!(
+                            result, expected,
+                            "size {:?}, kernel {:?}, op {:?}, border {:?} {}",
+                            size, kernel, op, border_mode, border_value
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn rle_dilate_erode_sparse_mask() {
+    let size = ImageSize::new(1000, 40);
+    let mask = make_runs_mask(size, 17, 40);
+    let src = RleMask::from_mask(&mask, size).unwrap();
+    let (se, kernel) = structuring_element::ellipse(9, 7);
+    let mut dilated = RleMask::new(size);
+    rle_dilate(
+        &src,
+        &mut dilated,
+        &se,
+        kernel,
+        BorderMode::Constant,
+        false,
+        MorphologyThreadingPolicy::Single,
+    )
+    .unwrap();
+    let mut eroded = RleMask::new(size);
+    rle_erode(
+        &src,
+        &mut eroded,
+        &se,
+        kernel,
+        BorderMode::Constant,
+        false,
+        MorphologyThreadingPolicy::Single,
+    )
+    .unwrap();
+    for (result, op) in [(dilated, MorphExOp::Dilate), (eroded, MorphExOp::Erode)] {
+        let expected = byte_morphology(&mask, size, op, &se, kernel, BorderMode::Constant, false);
+        assert_eq!(RleMask::from_mask(&expected, size).unwrap(), result);
+    }
+}
+
+#[test]
+fn rle_mask_conversion() {
+    let size = ImageSize::new(20, 3);
+    let stride = 23;
+    let mut mask = vec![7u8; stride * (size.height - 1) + size.width];
+    for y in 0..size.height {
+        for x in 0..size.width {
+            mask[y * stride + x] = if (x / 3 + y) % 2 == 0 { 1 } else { 0 };
+        }
+    }
+    let rle = RleMask::from_mask_with_stride(&mask, stride, size).unwrap();
+    assert_eq!(
+        rle.row(0),
+        &[
+            MaskRun::new(0, 3),
+            MaskRun::new(6, 9),
+            MaskRun::new(12, 15),
+            MaskRun::new(18, 20)
+        ]
+    );
+    for y in 0..size.height {
+        for x in 0..size.width {
+            assert_eq!(rle.get(x, y), (x / 3 + y) % 2 == 0);
+        }
+    }
+    assert_eq!(rle.count_ones(), 11 + 9 + 11);
+    assert_eq!(rle.runs().len(), 4 + 3 + 4);
+
+    let mut unpacked = vec![7u8; mask.len()];
+    rle.to_mask_with_stride(&mut unpacked, stride).unwrap();
+    for y in 0..size.height - 1 {
+        // Bytes between rows are left untouched
+        assert!(unpacked[y * stride + size.width..(y + 1) * stride]
+            .iter()
+            .all(|&v| v == 7));
+    }
+    for y in 0..size.height {
+        for x in 0..size.width {
+            let expected = if mask[y * stride + x] != 0 { 255 } else { 0 };
+            assert_eq!(unpacked[y * stride + x], expected);
+        }
+    }
+
+    // Overlapping and touching runs are merged, runs past the width are clipped
+    let built = RleMask::from_rows(
+        ImageSize::new(10, 2),
+        [
+            vec![MaskRun::new(4, 6), MaskRun::new(0, 2), MaskRun::new(2, 3)],
+            vec![
+                MaskRun::new(8, 15),
+                MaskRun::new(5, 9),
+                MaskRun::new(12, 13),
+            ],
+        ],
+    );
+    assert_eq!(built.row(0), &[MaskRun::new(0, 3), MaskRun::new(4, 6)]);
+    assert_eq!(built.row(1), &[MaskRun::new(5, 10)]);
+}
+
+#[test]
+fn rle_errors() {
+    let src = RleMask::new(ImageSize::new(4, 3));
+    let mut dst = RleMask::new(ImageSize::new(3, 4));
+    let result = rle_dilate(
+        &src,
+        &mut dst,
+        &[1; 9],
+        KernelShape::new(3, 3),
+        BorderMode::Clamp,
+        false,
+        MorphologyThreadingPolicy::Single,
+    );
+    assert_eq!(
+        result,
+        Err(MorphologyError::ImageDimensionsMismatch {
+            src_width: 4,
+            src_height: 3,
+            dst_width: 3,
+            dst_height: 4
+        })
+    );
+    let mut dst = RleMask::new(ImageSize::new(4, 3));
+    let result = rle_erode(
+        &src,
+        &mut dst,
+        &[1; 8],
+        KernelShape::new(3, 3),
+        BorderMode::Clamp,
+        false,
+        MorphologyThreadingPolicy::Single,
+    );
+    assert_eq!(
+        result,
+        Err(MorphologyError::StructuringElementSizeMismatch {
+            expected: 9,
+            actual: 8
+        })
+    );
+    assert_eq!(
+        RleMask::from_mask(&[0u8; 11], ImageSize::new(4, 3)),
+        Err(MorphologyError::ImageSizeMismatch {
+            expected: 12,
+            actual: 11
+        })
+    );
+}
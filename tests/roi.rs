@@ -0,0 +1,262 @@
+mod common;
+
+use common::*;
+use fast_morphology::*;
+
+const BORDERS: [BorderMode; 5] = [
+    BorderMode::Clamp,
+    BorderMode::Wrap,
+    BorderMode::Reflect,
+    BorderMode::Reflect101,
+    BorderMode::Constant,
+];
+
+fn shapes() -> Vec<(Vec<u8>, KernelShape)> {
+    vec![
+        structuring_element::rect(7, 5),
+        structuring_element::circle(5),
+        structuring_element::disk(7, structuring_element::DiskAccuracy::default()),
+        (make_se((3, 3), 5), KernelShape::new(3, 3)),
+        (vec![1u8; 9], KernelShape::new_with_anchor(9, 1, 8, 0)),
+        (make_se((4, 6), 9), KernelShape::new_with_anchor(4, 6, 0, 5)),
+    ]
+}
+
+fn rois() -> [Roi; 7] {
+    [
+        Roi::new(17, 9, 20, 11),
+        Roi::new(0, 0, 12, 7),
+        Roi::new(41, 24, 12, 7),
+        Roi::new(0, 13, 53, 4),
+        Roi::new(30, 0, 1, 31),
+        Roi::new(52, 30, 1, 1),
+        Roi::new(0, 0, 53, 31),
+    ]
+}
+
+fn crop<T: Copy>(image: &[T], stride: usize, roi: Roi, channels: usize) -> Vec<T> {
+    image
+        .chunks_exact(stride)
+        .skip(roi.y)
+        .take(roi.height)
+        .flat_map(|row| {
+            row[roi.x * channels..(roi.x + roi.width) * channels]
+                .iter()
+                .copied()
+        })
+        .collect()
+}
+
+macro_rules! check_roi {
+    ($t: ty, $layout: expr, $max: expr, $new: expr) => {{
+        let layout: MorphologyLayout = $layout;
+        let channels = layout.channels();
+        let size = ImageSize::new(53, 31);
+        let stride = size.width * channels;
+        let src = make_image(stride * size.height, 17, $max)
+            .iter()
+            .map(|&v| <$t>::from_f64(v))
+            .collect::<Vec<$t>>();
+        let scalar = MorphScalar::new(3., 100., 11., 250.);
+        for (se, kernel) in shapes() {
+            for op in MORPH_OPS {
+                for border_mode in BORDERS {
+                    let mut whole = $new(size, Roi::whole(size), op, &se, kernel, border_mode, scalar)
+                        .unwrap();
+                    let mut expected = vec![<$t>::default(); src.len()];
+                    whole.execute(&src, &mut expected).unwrap();
+                    for roi in rois() {
+                        let mut plan =
+                            $new(size, roi, op, &se, kernel, border_mode, scalar).unwrap();
+                        let mut dst = vec![<$t>::default(); roi.width * roi.height * channels];
+                        plan.execute(&src, &mut dst).unwrap();
+                        assert!(
+                            dst == crop(&expected, stride, roi, channels),
+                            "ROI {:?} differs from the whole image for kernel {:?}, op {:?}, border {:?}",
+                            roi,
+                            kernel,
+                            op,
+                            border_mode
+                        );
+                    }
+                }
+            }
+        }
+    }};
+}
+
+macro_rules! flat_plan {
+    ($t: ty, $layout: expr, $policy: expr) => {
+        |size, roi, op, se: &[u8], kernel, border_mode, scalar| {
+            MorphologyPlan::<$t>::new_roi(
+                size,
+                roi,
+                $layout,
+                op,
+                se,
+                kernel,
+                border_mode,
+                scalar,
+                $policy,
+            )
+        }
+    };
+}
+
+#[test]
+fn roi_u8() {
+    check_roi!(
+        u8,
+        MorphologyLayout::Gray,
+        256,
+        flat_plan!(
+            u8,
+            MorphologyLayout::Gray,
+            MorphologyThreadingPolicy::Single
+        )
+    );
+    check_roi!(
+        u8,
+        MorphologyLayout::Rgba,
+        256,
+        flat_plan!(
+            u8,
+            MorphologyLayout::Rgba,
+            MorphologyThreadingPolicy::Fixed(3)
+        )
+    );
+}
+
+#[test]
+fn roi_u16() {
+    check_roi!(
+        u16,
+        MorphologyLayout::GrayAlpha,
+        65536,
+        flat_plan!(
+            u16,
+            MorphologyLayout::GrayAlpha,
+            MorphologyThreadingPolicy::Single
+        )
+    );
+}
+
+#[test]
+fn roi_f32() {
+    check_roi!(
+        f32,
+        MorphologyLayout::Rgb,
+        1000,
+        flat_plan!(
+            f32,
+            MorphologyLayout::Rgb,
+            MorphologyThreadingPolicy::Single
+        )
+    );
+}
+
+#[test]
+fn roi_non_flat() {
+    let (weights, weights_kernel) = structuring_element::ball(3, 20.);
+    let new = |size, roi, op, _se: &[u8], _kernel, border_mode, scalar| {
+        MorphologyPlan::<u8>::new_non_flat_roi(
+            size,
+            roi,
+            MorphologyLayout::Gray,
+            op,
+            &weights,
+            weights_kernel,
+            border_mode,
+            scalar,
+            MorphologyThreadingPolicy::Single,
+        )
+    };
+    check_roi!(u8, MorphologyLayout::Gray, 256, new);
+}
+
+#[test]
+fn roi_with_stride() -> Result<(), MorphologyError> {
+    let size = ImageSize::new(40, 20);
+    let roi = Roi::new(8, 4, 16, 9);
+    let src = make_image(size.width * size.height, 5, 256)
+        .iter()
+        .map(|&v| v as u8)
+        .collect::<Vec<u8>>();
+    let (se, kernel) = structuring_element::circle(3);
+    let mut plan = MorphologyPlan::<u8>::new_roi(
+        size,
+        roi,
+        MorphologyLayout::Gray,
+        MorphExOp::Closing,
+        &se,
+        kernel,
+        BorderMode::Reflect101,
+        MorphScalar::default(),
+        MorphologyThreadingPolicy::Single,
+    )?;
+    let mut expected = vec![0u8; roi.width * roi.height];
+    plan.execute(&src, &mut expected)?;
+
+    // Destination is a view into a larger image
+    let mut canvas = vec![0u8; size.width * size.height];
+    plan.execute_with_stride(
+        &src,
+        size.width,
+        &mut canvas[roi.y * size.width + roi.x..],
+        size.width,
+    )?;
+    assert_eq!(crop(&canvas, size.width, roi, 1), expected);
+    assert_eq!(
+        canvas.iter().map(|&v| v as usize).sum::<usize>(),
+        expected.iter().map(|&v| v as usize).sum::<usize>()
+    );
+    Ok(())
+}
+
+#[test]
+fn roi_errors() {
+    let size = ImageSize::new(40, 20);
+    let (se, kernel) = structuring_element::rect(3, 3);
+    let plan = |roi| {
+        MorphologyPlan::<u8>::new_roi(
+            size,
+            roi,
+            MorphologyLayout::Gray,
+            MorphExOp::Dilate,
+            &se,
+            kernel,
+            BorderMode::Clamp,
+            MorphScalar::default(),
+            MorphologyThreadingPolicy::Single,
+        )
+    };
+    assert_eq!(
+        plan(Roi::new(30, 5, 11, 5)).err(),
+        Some(MorphologyError::RoiOutOfBounds {
+            x: 30,
+            y: 5,
+            width: 11,
+            height: 5,
+            image_width: 40,
+            image_height: 20
+        })
+    );
+    assert_eq!(
+        plan(Roi::new(3, 5, 0, 5)).err(),
+        Some(MorphologyError::ZeroSizedImage {
+            width: 0,
+            height: 5
+        })
+    );
+
+    let mut plan = plan(Roi::new(3, 5, 10, 5)).unwrap();
+    let src = vec![0u8; size.width * size.height];
+    let mut dst = vec![0u8; src.len()];
+    assert_eq!(
+        plan.execute(&src, &mut dst),
+        Err(MorphologyError::ImageSizeMismatch {
+            expected: 50,
+            actual: 800
+        })
+    );
+}
@@ -0,0 +1,385 @@
+mod common;
+
+use common::*;
+use fast_morphology::*;
+
+type MorphFn<T> = fn(
+    &[T],
+    &mut [T],
+    MorphExOp,
+    ImageSize,
+    &[u8],
+    KernelShape,
+    BorderMode,
+    MorphScalar,
+    MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError>;
+
+type StridedMorphFn<T> = fn(
+    &[T],
+    usize,
+    &mut [T],
+    usize,
+    MorphExOp,
+    ImageSize,
+    &[u8],
+    KernelShape,
+    BorderMode,
+    MorphScalar,
+    MorphologyThreadingPolicy,
+) -> Result<(), MorphologyError>;
+
+fn shapes() -> Vec<(Vec<u8>, KernelShape)> {
+    vec![
+        structuring_element::rect(7, 5),
+        structuring_element::circle(6),
+        structuring_element::disk(9, structuring_element::DiskAccuracy::default()),
+        (make_se((3, 3), 7), KernelShape::new(3, 3)),
+    ]
+}
+
+/// Copies packed image into rows `stride` items apart, padding is filled with `fill`,
+/// the last row is not padded
+fn pad_rows<T: Copy>(packed: &[T], row: usize, stride: usize, fill: T) -> Vec<T> {
+    let height = packed.len() / row;
+    let mut padded = vec![fill; stride * (height - 1) + row];
+    for (dst, src) in padded.chunks_mut(stride).zip(packed.chunks_exact(row)) {
+        dst[..row].copy_from_slice(src);
+    }
+    padded
+}
+
+fn check_strides<T: Sample + PartialEq>(
+    channels: usize,
+    max: u32,
+    fill: T,
+    packed_fn: MorphFn<T>,
+    strided_fn: StridedMorphFn<T>,
+) {
+    let size = ImageSize::new(67, 23);
+    let row = size.width * channels;
+    let src = make_image(row * size.height, 11, max)
+        .iter()
+        .map(|&v| T::from_f64(v + T::OFFSET))
+        .collect::<Vec<T>>();
+    for (se, kernel) in shapes() {
+        for op in MORPH_OPS {
+            let mut expected = vec![T::default(); src.len()];
+            packed_fn(
+                &src,
+                &mut expected,
+                op,
+                size,
+                &se,
+                kernel,
+                BorderMode::Reflect101,
+                MorphScalar::default(),
+                MorphologyThreadingPolicy::Single,
+            )
+            .unwrap();
+            for (src_pad, dst_pad) in [(5, 0), (0, 9), (13, 3)] {
+                for policy in [
+                    MorphologyThreadingPolicy::Single,
+                    MorphologyThreadingPolicy::Fixed(3),
+                ] {
+                    let src_stride = row + src_pad;
+                    let dst_stride = row + dst_pad;
+                    let padded_src = pad_rows(&src, row, src_stride, fill);
+                    let mut padded_dst =
+                        pad_rows(&vec![T::default(); src.len()], row, dst_stride, fill);
+                    strided_fn(
+                        &padded_src,
+                        src_stride,
+                        &mut padded_dst,
+                        dst_stride,
+                        op,
+                        size,
+                        &se,
+                        kernel,
+                        BorderMode::Reflect101,
+                        MorphScalar::default(),
+                        policy,
+                    )
+                    .unwrap();
+                    for (y, dst_row) in padded_dst.chunks(dst_stride).enumerate() {
+                        assert!(
+                            dst_row[..row] == expected[y * row..(y + 1) * row],
+                            "Mismatch at row {} for kernel {:?}, op {:?}, strides {} {}",
+                            y,
+                            kernel,
+                            op,
+                            src_stride,
+                            dst_stride
+                        );
+                        assert!(
+                            dst_row[row..].iter().all(|&v| v == fill),
+                            "Padding is overwritten at row {}",
+                            y
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn stride_u8() {
+    check_strides::<u8>(1, 256, 77, morphology, morphology_with_stride);
+    check_strides::<u8>(
+        2,
+        256,
+        77,
+        morphology_gray_alpha,
+        morphology_gray_alpha_with_stride,
+    );
+    check_strides::<u8>(3, 256, 77, morphology_rgb, morphology_rgb_with_stride);
+    check_strides::<u8>(4, 256, 77, morphology_rgba, morphology_rgba_with_stride);
+}
+
+#[test]
+fn stride_u16() {
+    check_strides::<u16>(
+        1,
+        65536,
+        7,
+        morphology_gray_u16,
+        morphology_gray_u16_with_stride,
+    );
+    check_strides::<u16>(
+        2,
+        65536,
+        7,
+        morphology_gray_alpha_u16,
+        morphology_gray_alpha_u16_with_stride,
+    );
+    check_strides::<u16>(
+        3,
+        65536,
+        7,
+        morphology_rgb_u16,
+        morphology_rgb_u16_with_stride,
+    );
+    check_strides::<u16>(
+        4,
+        65536,
+        7,
+        morphology_rgba_u16,
+        morphology_rgba_u16_with_stride,
+    );
+}
+
+#[test]
+fn stride_f32() {
+    check_strides::<f32>(
+        1,
+        1000,
+        -1.,
+        morphology_gray_f32,
+        morphology_gray_f32_with_stride,
+    );
+    check_strides::<f32>(
+        2,
+        1000,
+        -1.,
+        morphology_gray_alpha_f32,
+        morphology_gray_alpha_f32_with_stride,
+    );
+    check_strides::<f32>(
+        3,
+        1000,
+        -1.,
+        morphology_rgb_f32,
+        morphology_rgb_f32_with_stride,
+    );
+    check_strides::<f32>(
+        4,
+        1000,
+        -1.,
+        morphology_rgba_f32,
+        morphology_rgba_f32_with_stride,
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn deprecated_float_names_match_gray() {
+    check_strides::<f32>(
+        1,
+        1000,
+        -1.,
+        morphology_f32,
+        morphology_gray_f32_with_stride,
+    );
+    check_strides::<f32>(
+        1,
+        1000,
+        -1.,
+        morphology_gray_f32,
+        morphology_f32_with_stride,
+    );
+    check_strides::<f64>(
+        1,
+        1000,
+        -1.,
+        morphology_f64,
+        morphology_gray_f64_with_stride,
+    );
+    check_strides::<f64>(
+        1,
+        1000,
+        -1.,
+        morphology_gray_f64,
+        morphology_f64_with_stride,
+    );
+}
+
+#[test]
+fn stride_f64() {
+    check_strides::<f64>(
+        1,
+        1000,
+        -1.,
+        morphology_gray_f64,
+        morphology_gray_f64_with_stride,
+    );
+    check_strides::<f64>(
+        2,
+        1000,
+        -1.,
+        morphology_gray_alpha_f64,
+        morphology_gray_alpha_f64_with_stride,
+    );
+    check_strides::<f64>(
+        3,
+        1000,
+        -1.,
+        morphology_rgb_f64,
+        morphology_rgb_f64_with_stride,
+    );
+    check_strides::<f64>(
+        4,
+        1000,
+        -1.,
+        morphology_rgba_f64,
+        morphology_rgba_f64_with_stride,
+    );
+}
+
+#[cfg(feature = "half")]
+#[test]
+fn stride_f16() {
+    use half::f16;
+    let pad = f16::from_f32(-1.);
+    check_strides::<f16>(
+        1,
+        1000,
+        pad,
+        morphology_gray_f16,
+        morphology_gray_f16_with_stride,
+    );
+    check_strides::<f16>(
+        2,
+        1000,
+        pad,
+        morphology_gray_alpha_f16,
+        morphology_gray_alpha_f16_with_stride,
+    );
+    check_strides::<f16>(
+        3,
+        1000,
+        pad,
+        morphology_rgb_f16,
+        morphology_rgb_f16_with_stride,
+    );
+    check_strides::<f16>(
+        4,
+        1000,
+        pad,
+        morphology_rgba_f16,
+        morphology_rgba_f16_with_stride,
+    );
+}
+
+#[test]
+fn stride_int() {
+    check_strides::<i8>(
+        1,
+        256,
+        -7,
+        morphology_gray_i8,
+        morphology_gray_i8_with_stride,
+    );
+    check_strides::<i16>(
+        2,
+        65536,
+        -7,
+        morphology_gray_alpha_i16,
+        morphology_gray_alpha_i16_with_stride,
+    );
+    check_strides::<i32>(
+        3,
+        u32::MAX,
+        -7,
+        morphology_rgb_i32,
+        morphology_rgb_i32_with_stride,
+    );
+    check_strides::<u32>(
+        4,
+        u32::MAX,
+        7,
+        morphology_rgba_u32,
+        morphology_rgba_u32_with_stride,
+    );
+}
+
+#[test]
+fn plan_with_stride() {
+    let size = ImageSize::new(40, 12);
+    let (se, kernel) = structuring_element::circle(3);
+    let mut plan = MorphologyPlan::<u8>::new(
+        size,
+        MorphologyLayout::Rgba,
+        MorphExOp::TopHat,
+        &se,
+        kernel,
+        BorderMode::Clamp,
+        MorphScalar::default(),
+        MorphologyThreadingPolicy::Single,
+    )
+    .unwrap();
+    let row = size.width * 4;
+    let src = make_image(row * size.height, 3, 256)
+        .iter()
+        .map(|&v| v as u8)
+        .collect::<Vec<u8>>();
+    let mut expected = vec![0u8; src.len()];
+    plan.execute(&src, &mut expected).unwrap();
+
+    // Buffers are allowed to be longer than needed, as full last rows of padded frames are
+    let stride = row + 32;
+    let mut padded_src = pad_rows(&src, row, stride, 0);
+    padded_src.resize(stride * size.height, 0);
+    let mut padded_dst = vec![0u8; stride * size.height];
+    plan.execute_with_stride(&padded_src, stride, &mut padded_dst, stride)
+        .unwrap();
+    for (y, dst_row) in padded_dst.chunks(stride).enumerate() {
+        assert_eq!(dst_row[..row], expected[y * row..(y + 1) * row]);
+        assert!(dst_row[row..].iter().all(|&v| v == 0));
+    }
+
+    assert_eq!(
+        plan.execute_with_stride(&padded_src, row - 1, &mut padded_dst, stride),
+        Err(MorphologyError::StrideTooSmall {
+            stride: row - 1,
+            min_stride: row
+        })
+    );
+    assert_eq!(
+        plan.execute_with_stride(&padded_src, stride, &mut padded_dst[..stride * 11], stride),
+        Err(MorphologyError::ImageSizeMismatch {
+            expected: stride * 11 + row,
+            actual: stride * 11
+        })
+    );
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use common::*;
+use fast_morphology::*;
+use std::sync::Arc;
+
+fn kernels() -> Vec<KernelShape> {
+    vec![
+        KernelShape::new(3, 3),
+        KernelShape::new(5, 7),
+        KernelShape::new(15, 9),
+    ]
+}
+
+#[test]
+fn caller_supplied_pool() {
+    let pool = Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(3)
+            .build()
+            .unwrap(),
+    );
+    let policy = MorphologyThreadingPolicy::Pool(pool.clone());
+    assert_eq!(policy.get_threads_count(ImageSize::new(10, 10)), 3);
+    assert_eq!(policy, MorphologyThreadingPolicy::Pool(pool.clone()));
+    let ops = &MORPH_OPS;
+    check_morphology::<u8, _>(
+        &kernels(),
+        1,
+        256,
+        ops,
+        morph_fn!(morphology, BorderMode::Clamp, policy.clone()),
+    );
+    check_morphology::<u8, _>(
+        &kernels(),
+        3,
+        256,
+        ops,
+        morph_fn!(morphology_rgb, BorderMode::Clamp, policy.clone()),
+    );
+    check_morphology::<u16, _>(
+        &kernels(),
+        4,
+        65536,
+        ops,
+        morph_fn!(morphology_rgba_u16, BorderMode::Clamp, policy.clone()),
+    );
+    check_morphology::<f32, _>(
+        &kernels(),
+        3,
+        1000,
+        ops,
+        morph_fn!(morphology_rgb_f32, BorderMode::Clamp, policy.clone()),
+    );
+}
+
+#[test]
+fn global_pool() {
+    let policy = MorphologyThreadingPolicy::Global;
+    let ops = &MORPH_OPS;
+    check_morphology::<u8, _>(
+        &kernels(),
+        1,
+        256,
+        ops,
+        morph_fn!(morphology, BorderMode::Clamp, policy.clone()),
+    );
+    check_morphology::<u8, _>(
+        &kernels(),
+        2,
+        256,
+        ops,
+        morph_fn!(morphology_gray_alpha, BorderMode::Clamp, policy.clone()),
+    );
+}
+
+#[test]
+fn global_policy_inside_application_pool() {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(2)
+        .build()
+        .unwrap();
+    pool.install(|| {
+        assert_eq!(
+            MorphologyThreadingPolicy::Global.get_threads_count(ImageSize::new(10, 10)),
+            2
+        );
+        check_morphology::<u8, _>(
+            &kernels(),
+            4,
+            256,
+            &MORPH_OPS,
+            morph_fn!(
+                morphology_rgba,
+                BorderMode::Clamp,
+                MorphologyThreadingPolicy::Global
+            ),
+        );
+    });
+}
+
+#[test]
+#[allow(deprecated)]
+fn deprecated_get_pool_builds_pools_as_before() {
+    let size = ImageSize::new(1024, 1024);
+    assert!(MorphologyThreadingPolicy::Single.get_pool(size).is_none());
+    let pool = MorphologyThreadingPolicy::Fixed(3).get_pool(size).unwrap();
+    assert_eq!(pool.current_num_threads(), 3);
+    let pool = MorphologyThreadingPolicy::Adaptive.get_pool(size).unwrap();
+    assert_eq!(pool.current_num_threads(), 16);
+}